@@ -0,0 +1,14 @@
+//! Host-side helpers to provide the secret inputs to the Miden VM
+
+use miden_processor::AdviceInputs;
+
+use crate::MidenError;
+
+/// Build the advice inputs holding the secret inputs that are read by
+/// `ozk_stdlib::secret_input` and `ozk_stdlib::secret_input_slice` in the vector order
+/// (the same as `ozk_stdlib::io_native::init_io`).
+pub fn secret_input_advice(secret_input: Vec<u64>) -> Result<AdviceInputs, MidenError> {
+    AdviceInputs::default()
+        .with_stack_values(secret_input)
+        .map_err(|err| MidenError::SecretInput(format!("{err:?}")))
+}
@@ -0,0 +1,70 @@
+//! Assembling the emitted MASM with `miden-assembly`
+
+use miden_assembly::Assembler;
+use miden_assembly::ProgramAst;
+use miden_stdlib::StdLibrary;
+use ozk_miden_dialect::ops::ProgramOp;
+use pliron::context::Context;
+
+use crate::emit_prog;
+use crate::InstBuffer;
+use crate::MidenError;
+use crate::MidenOutputFormat;
+use crate::MidenTargetConfig;
+
+/// The compiled Miden program
+pub struct MidenProgram {
+    /// The program in the output format of the target config
+    pub output: MidenProgramOutput,
+    /// The MAST root hash of the program (hex). Verifiers pin the proven program by it.
+    pub hash: String,
+}
+
+/// The program in one of the [MidenOutputFormat]s
+pub enum MidenProgramOutput {
+    /// MASM source
+    Source(String),
+    /// The program AST serialized with `ProgramAst::to_bytes` (not the MAST). It assembles
+    /// to the MAST with the [MidenProgram::hash] root, verifiers should check it against that.
+    Binary(Vec<u8>),
+}
+
+/// Emit the program and assemble it, see [assemble_prog].
+pub fn compile_prog(
+    ctx: &Context,
+    prog_op: &ProgramOp,
+    target_config: &MidenTargetConfig,
+) -> Result<MidenProgram, MidenError> {
+    let inst_buf = emit_prog(ctx, prog_op, target_config)?;
+    assemble_prog(&inst_buf, target_config)
+}
+
+/// Assemble the emitted program (linked with the Miden standard library) to get its
+/// MAST root hash and serialize it if the binary output is requested.
+pub fn assemble_prog(
+    inst_buf: &InstBuffer,
+    target_config: &MidenTargetConfig,
+) -> Result<MidenProgram, MidenError> {
+    let source = inst_buf.pretty_print();
+    let assembler = Assembler::default()
+        .with_library(&StdLibrary::default())
+        .map_err(|err| MidenError::Assembly(err.to_string()))?;
+    let program = assembler
+        .compile(&source)
+        .map_err(|err| MidenError::Assembly(err.to_string()))?;
+    let hash = program
+        .hash()
+        .as_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    let output = match target_config.output_format {
+        MidenOutputFormat::Source => MidenProgramOutput::Source(source),
+        MidenOutputFormat::Binary => {
+            let ast =
+                ProgramAst::parse(&source).map_err(|err| MidenError::Assembly(err.to_string()))?;
+            MidenProgramOutput::Binary(ast.to_bytes())
+        }
+    };
+    Ok(MidenProgram { output, hash })
+}
@@ -3,18 +3,23 @@
 
 mod inst_buf;
 
+use std::collections::BTreeSet;
+
 pub use inst_buf::InstBuffer;
 mod emit;
 pub use emit::*;
 mod miden_inst;
 pub use miden_inst::*;
 use ozk_miden_dialect::ops::*;
+use pliron::common_traits::Verify;
 use pliron::context::Context;
 use pliron::dialects::builtin::op_interfaces::get_callees_syms;
 use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
 use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
 use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
 use rustc_hash::FxHashMap;
 use thiserror::Error;
 use topological_sort::TopologicalSort;
@@ -28,6 +33,9 @@ pub fn emit_prog(
     prog_op: &ProgramOp,
     target_config: &MidenTargetConfig,
 ) -> Result<InstBuffer, MidenError> {
+    prog_op
+        .verify(ctx)
+        .map_err(|err| MidenError::InvalidProgram(format!("{err:?}")))?;
     let body = prog_op.get_body(ctx, 0);
     let mut procs = Vec::new();
     for op in body.deref(ctx).iter(ctx) {
@@ -35,7 +43,9 @@ pub fn emit_prog(
                     .deref(ctx)
                     .get_op(ctx)
                     .downcast::<ProcOp>() else {
-                todo!("error. there should be only miden.proc ops in miden.program body");
+                return Err(MidenError::InvalidProgram(
+                    "there should be only miden.proc ops in miden.program body".to_string(),
+                ));
             };
         procs.push(*proc_op);
     }
@@ -45,6 +55,7 @@ pub fn emit_prog(
         .collect();
     let sorted_procs = topo_sort_procedures(ctx, procs.into_iter())?;
     let mut b = MidenAssemblyBuilder::new(InstBuffer::new(target_config));
+    emit_use_directives(ctx, prog_op, &mut b)?;
     for proc_name in sorted_procs {
         #[allow(clippy::unwrap_used)] // topo sort should not introduce new proc syms
         let proc_op = proc_map.get(&proc_name).unwrap();
@@ -54,6 +65,127 @@ pub fn emit_prog(
     Ok(b.build())
 }
 
+/// Emit a library module with the exported procs as `export` procedures (named after
+/// the export) and the rest as internal procedures.
+/// The main proc is the program initialization (start function, public inputs/outputs,
+/// recursion frames) that a library cannot run, so it must be empty.
+pub fn emit_lib(
+    ctx: &Context,
+    prog_op: &ProgramOp,
+    target_config: &MidenTargetConfig,
+) -> Result<InstBuffer, MidenError> {
+    prog_op
+        .verify(ctx)
+        .map_err(|err| MidenError::InvalidProgram(format!("{err:?}")))?;
+    let main_proc_sym = prog_op.get_main_proc_sym(ctx);
+    let body = prog_op.get_body(ctx, 0);
+    let mut procs = Vec::new();
+    for op in body.deref(ctx).iter(ctx) {
+        let Ok(proc_op) = op
+                    .deref(ctx)
+                    .get_op(ctx)
+                    .downcast::<ProcOp>() else {
+                return Err(MidenError::InvalidProgram(
+                    "there should be only miden.proc ops in miden.program body".to_string(),
+                ));
+            };
+        if proc_op.get_symbol_name(ctx) == main_proc_sym {
+            if proc_op
+                .get_entry_block(ctx)
+                .deref(ctx)
+                .iter(ctx)
+                .next()
+                .is_some()
+            {
+                return Err(MidenError::InvalidProgram(
+                    "a library cannot have the start function, public inputs/outputs or recursion"
+                        .to_string(),
+                ));
+            }
+            continue;
+        }
+        procs.push(*proc_op);
+    }
+    let exports = prog_op.get_exports(ctx);
+    if exports.is_empty() {
+        return Err(MidenError::InvalidProgram(
+            "a library should have exported procs".to_string(),
+        ));
+    }
+    let proc_map: FxHashMap<String, ProcOp> = procs
+        .iter()
+        .map(|proc| (proc.get_symbol_name(ctx), *proc))
+        .collect();
+    let sorted_procs = topo_sort_procedures(ctx, procs.into_iter())?;
+    let mut b = MidenAssemblyBuilder::new(InstBuffer::new(target_config));
+    emit_use_directives(ctx, prog_op, &mut b)?;
+    for proc_name in sorted_procs {
+        #[allow(clippy::unwrap_used)] // topo sort should not introduce new proc syms
+        let proc_op = proc_map.get(&proc_name).unwrap();
+        let num_locals = proc_op.get_num_locals(ctx);
+        if exports
+            .iter()
+            .any(|(export_name, proc_sym)| *export_name == proc_name && *proc_sym == proc_name)
+        {
+            b.export(proc_name, num_locals);
+        } else {
+            b.proc(proc_name, num_locals);
+        }
+        emit_proc_body(ctx, proc_op, target_config, &mut b)?;
+        b.end();
+    }
+    // the exports named other than their procs call them
+    for (export_name, proc_sym) in exports {
+        if export_name == proc_sym {
+            continue;
+        }
+        if proc_map.contains_key(&export_name) {
+            return Err(MidenError::InvalidProgram(format!(
+                "export {export_name} clashes with the proc of the same name"
+            )));
+        }
+        b.export(export_name, 0);
+        b.exec(proc_sym);
+        b.end();
+    }
+    Ok(b.build())
+}
+
+/// Emit the `use` directives for the modules of the library procedures called in the program.
+fn emit_use_directives(
+    ctx: &Context,
+    prog_op: &ProgramOp,
+    b: &mut MidenAssemblyBuilder,
+) -> Result<(), MidenError> {
+    let mut modules = BTreeSet::new();
+    prog_op
+        .get_operation()
+        .walk_only::<ExecOp>(ctx, WalkOrder::PreOrder, &mut |exec_op| {
+            if let Some(module) = exec_op.get_callee_module(ctx) {
+                modules.insert(module);
+            }
+            WalkResult::Advance
+        });
+    let mut aliases: FxHashMap<&str, &str> = FxHashMap::default();
+    for module in modules.iter() {
+        if let Some(other_module) = aliases.insert(module_alias(module), module) {
+            return Err(MidenError::InvalidProgram(format!(
+                "library modules {other_module} and {module} have the same name"
+            )));
+        }
+    }
+    for module in modules {
+        b.use_module(module);
+    }
+    Ok(())
+}
+
+/// The name the procedures of a `use`d module are invoked with
+/// (the last component of the module path, e.g. `u64` for `std::math::u64`).
+pub(crate) fn module_alias(module: &str) -> &str {
+    module.rsplit("::").next().unwrap_or(module)
+}
+
 // TODO: move to EmitMasm impl for ProcOp?
 pub fn emit_proc(
     ctx: &Context,
@@ -65,12 +197,22 @@ pub fn emit_proc(
     if is_main_proc {
         b.begin();
     } else {
-        b.proc(proc_op.get_symbol_name(ctx), 0);
+        b.proc(proc_op.get_symbol_name(ctx), proc_op.get_num_locals(ctx));
     }
+    emit_proc_body(ctx, proc_op, target_config, b)?;
+    b.end();
+    Ok(())
+}
+
+fn emit_proc_body(
+    ctx: &Context,
+    proc_op: &ProcOp,
+    target_config: &MidenTargetConfig,
+    b: &mut MidenAssemblyBuilder,
+) -> Result<(), MidenError> {
     for op in proc_op.get_entry_block(ctx).deref(ctx).iter(ctx) {
         emit_op(ctx, op, target_config, b)?;
     }
-    b.end();
     Ok(())
 }
 
@@ -90,6 +232,10 @@ pub fn topo_sort_procedures(
         let proc_name = proc.get_symbol_name(ctx);
         topo_sort.insert(proc_name.clone());
         for dep in get_callees_syms(ctx, proc.get_operation()) {
+            if library_proc_module(&dep).is_some() {
+                // library procedures are not emitted
+                continue;
+            }
             topo_sort.add_dependency(dep, proc_name.clone());
         }
     }
@@ -1,11 +1,60 @@
 use intertrait::cast_to;
 use ozk_miden_dialect::ops::AddOp;
+use ozk_miden_dialect::ops::AdvLoadWOp;
+use ozk_miden_dialect::ops::AdvPushOp;
+use ozk_miden_dialect::ops::AssertOp;
 use ozk_miden_dialect::ops::ConstantOp;
+use ozk_miden_dialect::ops::DropOp;
+use ozk_miden_dialect::ops::DupOp;
+use ozk_miden_dialect::ops::EqOp;
 use ozk_miden_dialect::ops::ExecOp;
+use ozk_miden_dialect::ops::GtOp;
+use ozk_miden_dialect::ops::IfOp;
+use ozk_miden_dialect::ops::LocAddrOp;
 use ozk_miden_dialect::ops::LocLoadOp;
+use ozk_miden_dialect::ops::LocStoreOp;
+use ozk_miden_dialect::ops::LocStoreWOp;
+use ozk_miden_dialect::ops::LtOp;
+use ozk_miden_dialect::ops::MemLoadOp;
+use ozk_miden_dialect::ops::MemLoadWOp;
+use ozk_miden_dialect::ops::MemStoreOp;
+use ozk_miden_dialect::ops::MemStoreWOp;
+use ozk_miden_dialect::ops::MovDnOp;
+use ozk_miden_dialect::ops::MovUpOp;
+use ozk_miden_dialect::ops::MulOp;
+use ozk_miden_dialect::ops::NeqOp;
+use ozk_miden_dialect::ops::PadWOp;
+use ozk_miden_dialect::ops::RepeatOp;
+use ozk_miden_dialect::ops::SdepthOp;
+use ozk_miden_dialect::ops::SubOp;
+use ozk_miden_dialect::ops::SwapOp;
+use ozk_miden_dialect::ops::U32CheckedAndOp;
+use ozk_miden_dialect::ops::U32CheckedDivOp;
+use ozk_miden_dialect::ops::U32CheckedGtOp;
+use ozk_miden_dialect::ops::U32CheckedGteOp;
+use ozk_miden_dialect::ops::U32CheckedLtOp;
+use ozk_miden_dialect::ops::U32CheckedLteOp;
+use ozk_miden_dialect::ops::U32CheckedModOp;
+use ozk_miden_dialect::ops::U32CheckedNotOp;
+use ozk_miden_dialect::ops::U32CheckedOrOp;
+use ozk_miden_dialect::ops::U32CheckedRotlOp;
+use ozk_miden_dialect::ops::U32CheckedRotrOp;
+use ozk_miden_dialect::ops::U32CheckedShlOp;
+use ozk_miden_dialect::ops::U32CheckedShrOp;
+use ozk_miden_dialect::ops::U32CheckedXorOp;
+use ozk_miden_dialect::ops::U32SplitOp;
+use ozk_miden_dialect::ops::U32WrappingAddOp;
+use ozk_miden_dialect::ops::U32WrappingMulOp;
+use ozk_miden_dialect::ops::U32WrappingSubOp;
+use ozk_miden_dialect::ops::WhileOp;
 use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::op_cast;
 use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::with_context::AttachContext;
 
+use crate::codegen::module_alias;
 use crate::MidenAssemblyBuilder;
 
 pub trait EmitMasm: Op {
@@ -35,6 +84,112 @@ macro_rules! emit_masm_param {
 }
 
 emit_masm!(AddOp, add);
+emit_masm!(SubOp, sub);
+emit_masm!(MulOp, mul);
+emit_masm!(U32WrappingAddOp, u32wrapping_add);
+emit_masm!(U32WrappingSubOp, u32wrapping_sub);
+emit_masm!(U32WrappingMulOp, u32wrapping_mul);
+emit_masm!(U32CheckedAndOp, u32checked_and);
+emit_masm!(U32CheckedXorOp, u32checked_xor);
+emit_masm!(U32CheckedShlOp, u32checked_shl);
+emit_masm!(U32CheckedShrOp, u32checked_shr);
+emit_masm!(U32CheckedOrOp, u32checked_or);
+emit_masm!(U32CheckedNotOp, u32checked_not);
+emit_masm!(U32CheckedDivOp, u32checked_div);
+emit_masm!(U32CheckedModOp, u32checked_mod);
+emit_masm!(U32CheckedRotlOp, u32checked_rotl);
+emit_masm!(U32CheckedRotrOp, u32checked_rotr);
+emit_masm!(U32CheckedLtOp, u32checked_lt);
+emit_masm!(U32CheckedLteOp, u32checked_lte);
+emit_masm!(U32CheckedGtOp, u32checked_gt);
+emit_masm!(U32CheckedGteOp, u32checked_gte);
+emit_masm!(U32SplitOp, u32split);
+emit_masm!(NeqOp, neq);
+emit_masm!(EqOp, eq);
+emit_masm!(LtOp, lt);
+emit_masm!(GtOp, gt);
+emit_masm!(AssertOp, assert);
+emit_masm!(SdepthOp, sdepth);
+emit_masm!(DropOp, drop);
+emit_masm!(MemLoadOp, mem_load);
+emit_masm!(MemStoreOp, mem_store);
+emit_masm!(MemLoadWOp, mem_loadw);
+emit_masm!(MemStoreWOp, mem_storew);
 emit_masm_param!(ConstantOp, push, get_value);
-emit_masm_param!(ExecOp, exec, get_callee_sym);
-emit_masm_param!(LocLoadOp, loc_load, get_index_as_u32);
+emit_masm_param!(LocLoadOp, loc_load, get_index);
+emit_masm_param!(LocStoreOp, loc_store, get_index);
+emit_masm_param!(LocStoreWOp, loc_storew, get_index);
+emit_masm_param!(LocAddrOp, locaddr, get_index);
+emit_masm_param!(DupOp, dup, get_index);
+emit_masm_param!(SwapOp, swap, get_index);
+emit_masm_param!(MovUpOp, movup, get_index);
+emit_masm_param!(MovDnOp, movdn, get_index);
+emit_masm_param!(AdvPushOp, adv_push, get_count);
+emit_masm!(AdvLoadWOp, adv_loadw);
+emit_masm!(PadWOp, padw);
+
+#[cast_to]
+impl EmitMasm for ExecOp {
+    fn emit_masm(&self, ctx: &Context, builder: &mut MidenAssemblyBuilder) {
+        let callee_sym = self.get_callee_sym(ctx);
+        match self.get_callee_module(ctx) {
+            // library procedures are invoked via the alias of their `use`d module
+            Some(module) => {
+                let proc_name = callee_sym
+                    .strip_prefix(&format!("{module}::"))
+                    .unwrap_or(&callee_sym);
+                builder.exec(format!("{}::{proc_name}", module_alias(&module)))
+            }
+            None => builder.exec(callee_sym),
+        }
+    }
+}
+
+#[cast_to]
+impl EmitMasm for WhileOp {
+    fn emit_masm(&self, ctx: &Context, builder: &mut MidenAssemblyBuilder) {
+        builder.while_true();
+        emit_nested_ops(ctx, self.op_iter(ctx), builder);
+        builder.end();
+    }
+}
+
+#[cast_to]
+impl EmitMasm for RepeatOp {
+    fn emit_masm(&self, ctx: &Context, builder: &mut MidenAssemblyBuilder) {
+        builder.repeat(self.get_count(ctx));
+        emit_nested_ops(ctx, self.op_iter(ctx), builder);
+        builder.end();
+    }
+}
+
+#[cast_to]
+impl EmitMasm for IfOp {
+    fn emit_masm(&self, ctx: &Context, builder: &mut MidenAssemblyBuilder) {
+        builder.if_true();
+        emit_nested_ops(ctx, self.then_op_iter(ctx), builder);
+        if self.else_op_iter(ctx).next().is_some() {
+            builder.if_else();
+            emit_nested_ops(ctx, self.else_op_iter(ctx), builder);
+        }
+        builder.end();
+    }
+}
+
+fn emit_nested_ops(
+    ctx: &Context,
+    ops: impl Iterator<Item = Ptr<Operation>>,
+    builder: &mut MidenAssemblyBuilder,
+) {
+    for op in ops {
+        #[allow(clippy::panic)] // all ops should be emitable
+        if let Some(emitable_op) = op_cast::<dyn EmitMasm>(op.deref(ctx).get_op(ctx).as_ref()) {
+            emitable_op.emit_masm(ctx, builder);
+        } else {
+            panic!(
+                "missing EmitMasm impl for op: {}",
+                op.deref(ctx).get_opid().with_ctx(ctx)
+            );
+        }
+    }
+}
@@ -2,7 +2,6 @@
 #![allow(dead_code)]
 
 use crate::MidenInst;
-use crate::MidenOutputFormat;
 use crate::MidenTargetConfig;
 
 pub struct InstBuffer {
@@ -10,10 +9,8 @@ pub struct InstBuffer {
 }
 impl InstBuffer {
     pub(crate) fn new(config: &MidenTargetConfig) -> Self {
-        match config.output_format {
-            MidenOutputFormat::Binary => todo!(),
-            MidenOutputFormat::Source => Self { inner: Vec::new() },
-        }
+        // the binary output is assembled from the source, see `assemble_prog`
+        Self { inner: Vec::new() }
     }
 
     pub fn pretty_print(&self) -> String {
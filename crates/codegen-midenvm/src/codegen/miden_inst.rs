@@ -25,11 +25,20 @@ impl MidenAssemblyBuilder {
         self.sink.push("begin".to_string().into());
     }
 
-    pub fn proc(&mut self, name: String, num_of_locals: usize) {
+    pub fn proc(&mut self, name: String, num_of_locals: u32) {
         self.sink
             .push(format!("proc.{name}.{num_of_locals}").into());
     }
 
+    pub fn export(&mut self, name: String, num_of_locals: u32) {
+        self.sink
+            .push(format!("export.{name}.{num_of_locals}").into());
+    }
+
+    pub fn use_module(&mut self, path: String) {
+        self.sink.push(format!("use.{path}").into());
+    }
+
     pub fn exec(&mut self, name: String) {
         self.sink.push(format!("exec.{name}").into());
     }
@@ -42,6 +51,14 @@ impl MidenAssemblyBuilder {
         self.sink.push(format!("adv_push.{num}").into());
     }
 
+    pub(crate) fn adv_loadw(&mut self) {
+        self.sink.push("adv_loadw".to_string().into());
+    }
+
+    pub(crate) fn padw(&mut self) {
+        self.sink.push("padw".to_string().into());
+    }
+
     pub fn end(&mut self) {
         self.sink.push("end".to_string().into());
     }
@@ -54,6 +71,10 @@ impl MidenAssemblyBuilder {
         self.sink.push("while.true".to_string().into());
     }
 
+    pub(crate) fn repeat(&mut self, count: u32) {
+        self.sink.push(format!("repeat.{count}").into());
+    }
+
     pub fn sdepth(&mut self) {
         self.sink.push("sdepth".to_string().into());
     }
@@ -78,10 +99,98 @@ impl MidenAssemblyBuilder {
         self.sink.push("mem_load".to_string().into());
     }
 
+    pub(crate) fn mem_loadw(&mut self) {
+        self.sink.push("mem_loadw".to_string().into());
+    }
+
+    pub(crate) fn mem_storew(&mut self) {
+        self.sink.push("mem_storew".to_string().into());
+    }
+
     pub(crate) fn sub(&mut self) {
         self.sink.push("sub".to_string().into());
     }
 
+    pub(crate) fn u32wrapping_add(&mut self) {
+        self.sink.push("u32wrapping_add".to_string().into());
+    }
+
+    pub(crate) fn u32wrapping_sub(&mut self) {
+        self.sink.push("u32wrapping_sub".to_string().into());
+    }
+
+    pub(crate) fn u32wrapping_mul(&mut self) {
+        self.sink.push("u32wrapping_mul".to_string().into());
+    }
+
+    pub(crate) fn u32checked_and(&mut self) {
+        self.sink.push("u32checked_and".to_string().into());
+    }
+
+    pub(crate) fn u32checked_xor(&mut self) {
+        self.sink.push("u32checked_xor".to_string().into());
+    }
+
+    pub(crate) fn u32checked_shl(&mut self) {
+        self.sink.push("u32checked_shl".to_string().into());
+    }
+
+    pub(crate) fn u32checked_shr(&mut self) {
+        self.sink.push("u32checked_shr".to_string().into());
+    }
+
+    pub(crate) fn u32checked_or(&mut self) {
+        self.sink.push("u32checked_or".to_string().into());
+    }
+
+    pub(crate) fn u32checked_not(&mut self) {
+        self.sink.push("u32checked_not".to_string().into());
+    }
+
+    pub(crate) fn u32checked_div(&mut self) {
+        self.sink.push("u32checked_div".to_string().into());
+    }
+
+    pub(crate) fn u32checked_mod(&mut self) {
+        self.sink.push("u32checked_mod".to_string().into());
+    }
+
+    pub(crate) fn u32checked_rotl(&mut self) {
+        self.sink.push("u32checked_rotl".to_string().into());
+    }
+
+    pub(crate) fn u32checked_rotr(&mut self) {
+        self.sink.push("u32checked_rotr".to_string().into());
+    }
+
+    pub(crate) fn u32checked_lt(&mut self) {
+        self.sink.push("u32checked_lt".to_string().into());
+    }
+
+    pub(crate) fn u32checked_lte(&mut self) {
+        self.sink.push("u32checked_lte".to_string().into());
+    }
+
+    pub(crate) fn u32checked_gt(&mut self) {
+        self.sink.push("u32checked_gt".to_string().into());
+    }
+
+    pub(crate) fn u32checked_gte(&mut self) {
+        self.sink.push("u32checked_gte".to_string().into());
+    }
+
+    pub(crate) fn u32split(&mut self) {
+        self.sink.push("u32split".to_string().into());
+    }
+
+    pub(crate) fn movup(&mut self, idx: u8) {
+        self.sink.push(format!("movup.{idx}").into());
+    }
+
+    pub(crate) fn movdn(&mut self, idx: u8) {
+        self.sink.push(format!("movdn.{idx}").into());
+    }
+
     pub(crate) fn neq_imm(&mut self, imm: i32) {
         self.sink.push(format!("neq.{imm}").into());
     }
@@ -94,10 +203,34 @@ impl MidenAssemblyBuilder {
         self.sink.push(format!("loc_store.{local_idx}").into());
     }
 
+    pub(crate) fn loc_storew(&mut self, local_idx: u32) {
+        self.sink.push(format!("loc_storew.{local_idx}").into());
+    }
+
+    pub(crate) fn locaddr(&mut self, local_idx: u32) {
+        self.sink.push(format!("locaddr.{local_idx}").into());
+    }
+
     pub(crate) fn neq(&mut self) {
         self.sink.push("neq".to_string().into());
     }
 
+    pub(crate) fn eq(&mut self) {
+        self.sink.push("eq".to_string().into());
+    }
+
+    pub(crate) fn lt(&mut self) {
+        self.sink.push("lt".to_string().into());
+    }
+
+    pub(crate) fn gt(&mut self) {
+        self.sink.push("gt".to_string().into());
+    }
+
+    pub(crate) fn assert(&mut self) {
+        self.sink.push("assert".to_string().into());
+    }
+
     pub(crate) fn drop(&mut self) {
         self.sink.push("drop".to_string().into());
     }
@@ -1,13 +1,26 @@
 #![allow(unused_imports)]
 
+use ozk_ir_transform::dce::DceUnusedFunctionsPass;
 use ozk_ir_transform::miden::lowering::call_op_lowering::WasmToMidenCallOpLoweringPass;
 use ozk_ir_transform::miden::lowering::WasmToMidenArithLoweringPass;
 use ozk_ir_transform::miden::lowering::WasmToMidenCFLoweringPass;
 use ozk_ir_transform::miden::lowering::WasmToMidenFinalLoweringPass;
+use ozk_ir_transform::miden::lowering::WasmToMidenLocalsLoweringPass;
+use ozk_ir_transform::miden::lowering::WasmToMidenMemLoweringPass;
+use ozk_ir_transform::miden::recursion_to_loop::MidenRecursionToLoopPass;
+use ozk_ir_transform::miden::save_stack_pub_inputs::MidenSaveStackPubInputsPass;
+use ozk_ir_transform::miden::secret_inputs::MidenSecretInputsPass;
+use ozk_ir_transform::ozk::legalize_i64::LegalizeI64Pass;
+use ozk_ir_transform::ozk::lowering::WasmToOzkLoweringPass;
+use ozk_ir_transform::pass_manager::PassManager;
 use ozk_ir_transform::wasm::explicit_func_args_pass::WasmExplicitFuncArgsPass;
 use ozk_ir_transform::wasm::globals_to_mem::WasmGlobalsToMemPass;
+use ozk_ir_transform::wasm::inline::InlineCostModel;
+use ozk_ir_transform::wasm::inline::WasmInlinePass;
+use ozk_ir_transform::wasm::pseudo_ops::PseudoOp;
+use ozk_ir_transform::wasm::pseudo_ops::WasmExpandPseudoOpsPass;
+use ozk_ir_transform::wasm::spill_stack::WasmSpillStackPass;
 use pliron::context::Context;
-use pliron::pass::PassManager;
 
 use crate::MidenMemoryLayout;
 
@@ -17,42 +30,88 @@ pub struct MidenTargetConfig {
     pub memory_layout: MidenMemoryLayout,
 }
 
+/// The default pipeline does not inline on purpose: every Wasm function keeps its own
+/// procedure, so the output maps back to the source functions. Use
+/// [MidenTargetConfig::with_inlining] to opt in.
 impl Default for MidenTargetConfig {
     fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl MidenTargetConfig {
+    /// Inline the small functions with the Miden cost model before the lowering.
+    /// The [Default] pipeline does not inline.
+    pub fn with_inlining() -> Self {
+        Self::new(Some(InlineCostModel::miden()))
+    }
+
+    fn new(inline_cost_model: Option<InlineCostModel>) -> Self {
         let memory_layout = MidenMemoryLayout::default();
         let mut pass_manager = PassManager::new();
-        pass_manager.add_pass(Box::<WasmExplicitFuncArgsPass>::default());
-        pass_manager.add_pass(Box::<WasmToMidenCallOpLoweringPass>::default());
-        pass_manager.add_pass(Box::<WasmToMidenCFLoweringPass>::default());
+        // the arith lowering has no division and popcnt, expanded before inlining so that
+        // the helpers can be inlined too
+        pass_manager.add_pass(Box::new(WasmExpandPseudoOpsPass::new(vec![
+            PseudoOp::I32DivU,
+            PseudoOp::I32DivS,
+            PseudoOp::I32RemU,
+            PseudoOp::I32RemS,
+            PseudoOp::I32Popcnt,
+        ])));
+        if let Some(inline_cost_model) = inline_cost_model {
+            pass_manager.add_pass(Box::new(WasmInlinePass::new(inline_cost_model)));
+        }
+        // the ozk lowering expects no Wasm globals left
         pass_manager.add_pass(Box::new(WasmGlobalsToMemPass::new(
             memory_layout.globals_start_address,
         )));
+        pass_manager.add_pass(Box::<WasmToOzkLoweringPass>::default());
+        // i64 is split into the u32 limbs for the Miden `u32` ops
+        pass_manager.add_pass(Box::<LegalizeI64Pass>::default());
+        pass_manager.add_pass(Box::<DceUnusedFunctionsPass>::default());
+        pass_manager.add_pass(Box::<WasmExplicitFuncArgsPass>::default());
+        // only the top 16 stack values are addressable
+        pass_manager.add_pass(Box::<WasmSpillStackPass>::default());
+        pass_manager.add_pass(Box::<WasmToMidenCallOpLoweringPass>::default());
+        pass_manager.add_pass(Box::new(WasmToMidenCFLoweringPass::new(
+            memory_layout.br_depth_address,
+        )));
+        pass_manager.add_pass(Box::<WasmToMidenMemLoweringPass>::default());
         pass_manager.add_pass(Box::<WasmToMidenArithLoweringPass>::default());
+        pass_manager.add_pass(Box::<WasmToMidenLocalsLoweringPass>::default());
+        // MASM has no recursion
+        pass_manager.add_pass(Box::new(MidenRecursionToLoopPass::new(
+            u32::from(memory_layout.frame_pointer_address),
+            u32::from(memory_layout.recursion_frames_start_address),
+        )));
+        pass_manager.add_pass(Box::new(MidenSaveStackPubInputsPass::new(
+            memory_layout.pub_inputs_start_address,
+            memory_layout.pub_outputs_start_address,
+        )));
+        pass_manager.add_pass(Box::<MidenSecretInputsPass>::default());
         // pass_manager.add_pass(Box::<WasmToMidenFinalLoweringPass>::default());
         Self {
             output_format: MidenOutputFormat::Source,
             // ir_passes: vec![
-            // Box::new(SaveStackPubInputsPass::new(
-            //     memory_layout.pub_inputs_start_address,
-            //     memory_layout.pub_outputs_start_address,
-            // )),
             // Box::<BlocksToFuncPass>::default(),
             // Box::new(GlobalsToMemPass::new(memory_layout.globals_start_address)),
-            // Box::<DceUnusedFunctionsPass>::default(),
             // ],
             memory_layout,
             pass_manager,
         }
     }
-}
 
-impl MidenTargetConfig {
     pub fn register(&self, ctx: &mut Context) {
         ozk_miden_dialect::register(ctx);
     }
 }
 
+/// The output of the Miden backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MidenOutputFormat {
+    /// The program AST serialized with `miden-assembly` (the MAST root hash is reported
+    /// alongside, see [crate::MidenProgram])
     Binary,
+    /// MASM source
     Source,
 }
@@ -7,8 +7,14 @@ use crate::TopoSortError;
 pub enum MidenError {
     #[error("Invalid instruction: {0}")]
     InvalidInst(String),
+    #[error("Invalid program: {0}")]
+    InvalidProgram(String),
     #[error("Emit error: {0:?}")]
     Emit(#[from] EmitError),
     #[error("Topological sort error: {0:?}")]
     TopoSortError(#[from] TopoSortError),
+    #[error("Assembly error: {0}")]
+    Assembly(String),
+    #[error("Invalid secret input: {0}")]
+    SecretInput(String),
 }
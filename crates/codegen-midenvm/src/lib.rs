@@ -18,11 +18,15 @@
 #![deny(clippy::unimplemented)]
 #![deny(clippy::panic)]
 
+mod advice;
+mod assembly;
 mod codegen;
 mod config;
 mod error;
 mod memory;
 
+pub use crate::advice::*;
+pub use crate::assembly::*;
 pub use crate::codegen::*;
 pub use crate::config::*;
 pub use crate::error::*;
@@ -0,0 +1,82 @@
+//! Tests of the assembled (binary) output and the program hash.
+
+use miden_assembly::Assembler;
+use miden_assembly::ProgramAst;
+use miden_stdlib::StdLibrary;
+use ozk_codegen_midenvm::compile_prog;
+use ozk_codegen_midenvm::emit_prog;
+use ozk_codegen_midenvm::MidenOutputFormat;
+use ozk_codegen_midenvm::MidenProgram;
+use ozk_codegen_midenvm::MidenProgramOutput;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use sem_tests::compile_to_miden_dialect;
+
+mod sem_tests;
+
+const WAT: &str = r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 1
+        i32.const 2
+        i32.add
+        return)
+)"#;
+
+fn compile_with_format(output_format: MidenOutputFormat) -> (String, MidenProgram) {
+    let mut ctx = pliron::context::Context::default();
+    let mut target_config = MidenTargetConfig::default();
+    target_config.output_format = output_format;
+    let miden_prog =
+        compile_to_miden_dialect(&mut ctx, &wat::parse_str(WAT).unwrap(), &target_config);
+    let source = emit_prog(&ctx, &miden_prog, &target_config)
+        .unwrap()
+        .pretty_print();
+    let program = compile_prog(&ctx, &miden_prog, &target_config).unwrap();
+    (source, program)
+}
+
+fn hash_of(source: &str) -> String {
+    let assembler = Assembler::default()
+        .with_library(&StdLibrary::default())
+        .unwrap();
+    let program = assembler.compile(source).unwrap();
+    program
+        .hash()
+        .as_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[test]
+fn test_source_output_hash() {
+    let (source, program) = compile_with_format(MidenOutputFormat::Source);
+    let MidenProgramOutput::Source(output) = program.output else {
+        panic!("expected source output");
+    };
+    assert_eq!(output, source);
+    assert_eq!(program.hash, hash_of(&source));
+}
+
+#[test]
+fn test_binary_output_hash() {
+    let (source, program) = compile_with_format(MidenOutputFormat::Binary);
+    let MidenProgramOutput::Binary(bytes) = program.output else {
+        panic!("expected binary output");
+    };
+    assert_eq!(program.hash, hash_of(&source));
+    // the deserialized program assembles to the same MAST
+    let ast = ProgramAst::from_bytes(&bytes).unwrap();
+    let assembler = Assembler::default()
+        .with_library(&StdLibrary::default())
+        .unwrap();
+    let deserialized = assembler.compile_ast(&ast).unwrap();
+    let deserialized_hash: String = deserialized
+        .hash()
+        .as_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    assert_eq!(program.hash, deserialized_hash);
+}
@@ -0,0 +1,12 @@
+#[path = "../sem_tests.rs"]
+mod sem_tests;
+
+mod loop_in_block;
+mod loop_single;
+mod loop_with_block;
+mod nested_block;
+mod nested_block_br;
+mod nested_block_br_if;
+mod one_block;
+mod one_block_br;
+mod one_block_br_if;
@@ -1,6 +1,6 @@
 use expect_test::expect;
 
-use crate::codegen::sem_tests::check_wat;
+use crate::sem_tests::check_wat;
 
 #[test]
 fn test_nested_block() {
@@ -33,93 +33,65 @@ fn test_nested_block() {
         secret_input,
         expected_output,
         expect![[r#"
-            call main
-            halt
-            ozk_stdlib_pub_input:
-            read_io
-            return
-            ozk_stdlib_pub_output:
-            push -1
-            call globals_get
-            dup 0
-            swap 2
-            write_mem
-            pop
-            push -4
-            add
-            push -1
-            call globals_set
-            push -1
-            call globals_get
-            push 4
-            add
-            read_mem
-            swap 1
-            pop
-            write_io
-            push -1
-            call globals_get
-            push 4
-            add
-            push -1
-            call globals_set
-            return
-            ozk_stdlib_secret_input:
-            divine
-            return
-            main:
-            call init_mem_for_locals
-            call main_l0_b0
-            return
-            return
-            init_mem_for_locals:
-            push 00000000002147483635
-            push -1
-            call globals_set
-            return
-            globals_get:
-            push 4
-            mul
-            push 00000000002147483647
-            add
-            read_mem
-            swap 1
-            pop
-            return
-            globals_set:
-            push 4
+            proc.init_pub_outputs.0
+            push.2147475447
+            push.2147475455
+            mem_store
+            end
+
+            proc.load_pub_outputs_on_stack.0
+            push.2147475455
+            mem_load
+            push.2147475447
+            dup.1
+            dup.1
+            neq
+            while.true
+            dup.0
+            mem_load
+            movup.2
+            movup.2
+            push.8
+            sub
+            dup.1
+            dup.1
+            neq
+            end
+
+            drop
+            drop
+            end
+
+            proc.ozk_stdlib_pub_output.0
+            push.4294967296
             mul
-            push 00000000002147483647
             add
-            swap 1
-            write_mem
-            pop
-            return
-            main_l0_b0:
-            push 3
-            call ozk_stdlib_pub_output
-            call main_l0_b0_l1_b0
-            call next_br_propagation
-            skiz
-            return
-            return
-            main_l0_b0_l1_b0:
-            push 8
-            call ozk_stdlib_pub_output
-            return
-            next_br_propagation:
-            push -2
-            call globals_get
-            dup 0
-            push 0
-            eq
-            skiz
-            return
-            push -1
-            add
-            dup 0
-            push -2
-            call globals_set
-            return"#]],
+            push.2147475455
+            mem_load
+            dup.0
+            movup.2
+            swap.1
+            mem_store
+            push.8
+            sub
+            push.2147475455
+            mem_store
+            end
+
+            proc.main.0
+            push.3
+            push.0
+            exec.ozk_stdlib_pub_output
+            push.8
+            push.0
+            exec.ozk_stdlib_pub_output
+            end
+
+            begin
+            exec.init_pub_outputs
+            exec.main
+            exec.load_pub_outputs_on_stack
+            end
+        "#]],
     );
 }
@@ -1,6 +1,5 @@
 use expect_test::expect;
 
-mod sem_tests;
 use crate::sem_tests::check_wat;
 
 #[test]
@@ -32,142 +31,80 @@ fn test_one_block() {
         secret_input,
         expected_output,
         expect![[r#"
-            proc.globals_get.0
-            push.18446744069414584317
-            mul
-            push.2147467263
-            add
-            mem_load
-            end
-
-            proc.globals_set.0
-            push.18446744069414584317
-            mul
-            push.2147467263
-            add
-            swap.1
-            swap.1
+            proc.init_pub_outputs.0
+            push.2147475447
+            push.2147475455
             mem_store
             end
 
-            proc.save_pub_inputs.2
-            push.2147483647
-            loc_store.0
-            sdepth
-            loc_store.1
-            push.1
+            proc.load_pub_outputs_on_stack.0
+            push.2147475455
+            mem_load
+            push.2147475447
+            dup.1
+            dup.1
+            neq
             while.true
             dup.0
-            neq.0
-            if.true
-            loc_load.0
-            dup.0
-            swap.2
-            swap.1
-            mem_store
+            mem_load
+            movup.2
+            movup.2
             push.8
             sub
-            loc_store.0
-            else
-            drop
-            end
-
-            loc_load.1
-            push.1
-            sub
-            dup.0
-            loc_store.1
-            neq.0
+            dup.1
+            dup.1
+            neq
             end
 
-            loc_load.0
-            push.0
-            exec.globals_set
+            drop
+            drop
             end
 
-            proc.omni_miden_pub_input.0
-            push.0
-            exec.globals_get
-            push.8
+            proc.ozk_stdlib_pub_output.0
+            push.4294967296
+            mul
             add
-            dup.0
+            push.2147475455
             mem_load
-            swap.1
-            push.0
-            exec.globals_set
-            end
-
-            proc.init_pub_outputs.0
-            push.2147483647
-            push.1
-            exec.globals_set
-            end
-
-            proc.omni_miden_pub_output.0
-            push.1
-            exec.globals_get
             dup.0
-            swap.2
+            movup.2
             swap.1
             mem_store
             push.8
             sub
-            push.1
-            exec.globals_set
+            push.2147475455
+            mem_store
             end
 
-            proc.load_pub_outputs_on_stack.2
-            push.2147483647
-            dup.0
-            loc_store.0
+            proc.main.5
             push.1
-            exec.globals_get
-            dup.0
+            push.0
+            push.2
+            push.0
+            loc_store.3
+            loc_store.2
             loc_store.1
-            sub
-            neq.0
-            while.true
-            loc_load.0
-            mem_load
+            loc_store.0
             loc_load.0
-            push.8
-            sub
+            loc_load.2
+            u32wrapping_add
             dup.0
-            loc_store.0
-            loc_load.1
-            sub
-            neq.0
-            end
-
-            end
-
-            proc.ozk_stdlib_pub_output.1
-            loc_store.0
+            loc_store.4
+            loc_load.4
             loc_load.0
-            exec.omni_miden_pub_output
-            end
-
-            proc.main_l0_b0.0
-            push.1
-            push.2
-            add
+            u32checked_lt
+            loc_load.1
+            u32wrapping_add
+            loc_load.3
+            u32wrapping_add
             exec.ozk_stdlib_pub_output
             end
 
-            proc.main.0
-            exec.main_l0_b0
-            end
-
-            proc.start_with_miden_io_persistent.0
-            exec.save_pub_inputs
+            begin
             exec.init_pub_outputs
             exec.main
             exec.load_pub_outputs_on_stack
             end
-
-            begin
-            exec.start_with_miden_io_persistent
-            end
         "#]],
     );
 }
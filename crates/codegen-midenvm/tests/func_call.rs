@@ -33,7 +33,7 @@ fn test_func_call_no_args() {
             proc.get.0
             push.1
             push.2
-            add
+            u32wrapping_add
             end
 
             proc.main.0
@@ -76,11 +76,11 @@ fn test_ir_func_call_w_args() {
                 }
                 miden.proc @add {
                   entry():
-                    wasm.local.set 0x0: ui32
-                    wasm.local.set 0x1: ui32
-                    wasm.local.get 0
-                    wasm.local.get 1
-                    miden.add
+                    miden.loc_store 1
+                    miden.loc_store 0
+                    miden.loc_load 0
+                    miden.loc_load 1
+                    miden.u32wrapping_add
                 }
                 miden.proc @main {
                   entry():
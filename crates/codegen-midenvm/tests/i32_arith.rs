@@ -0,0 +1,147 @@
+//! Differential tests of the i32 arithmetic against wasmtime.
+
+use sem_tests::compile;
+use sem_tests::run_miden_program;
+use wasmtime::*;
+
+mod sem_tests;
+
+/// Operands covering the overflow edge cases.
+const OPERANDS: [i32; 9] = [
+    0,
+    1,
+    2,
+    -1,
+    -2,
+    i32::MAX,
+    i32::MIN,
+    0x1234_5678,
+    -0x1234_5678,
+];
+
+/// Shift amounts, including the ones wasm takes modulo 32.
+const SHIFT_AMOUNTS: [i32; 9] = [0, 1, 4, 31, 32, 33, 63, -1, i32::MIN];
+
+/// Evaluate `a <op> b` for every pair of operands on wasmtime and on Miden VM
+/// and check that the results are the same.
+fn check_binary_op(op: &str, lhs: &[i32], rhs: &[i32]) {
+    let wat = format!(
+        r#"
+(module
+    (func (export "f") (param i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        {op})
+)"#
+    );
+    let mut store = Store::new(&Engine::default(), ());
+    let module = Module::from_binary(store.engine(), &wat::parse_str(wat).unwrap()).unwrap();
+    let instance = Instance::new(&mut store, &module, &[]).unwrap();
+    let func = instance
+        .get_typed_func::<(i32, i32), i32>(&mut store, "f")
+        .unwrap();
+    for a in lhs {
+        for b in rhs {
+            let expected = func.call(&mut store, (*a, *b)).unwrap();
+            let actual = run_on_miden(op, *a, *b);
+            assert_eq!(
+                actual, expected as u32 as u64,
+                "{op} {a} {b}: expected {expected}"
+            );
+        }
+    }
+}
+
+fn run_on_miden(op: &str, a: i32, b: i32) -> u64 {
+    let wat = format!(
+        r#"
+(module
+    (start $main)
+    (func $main
+        i32.const {a}
+        i32.const {b}
+        {op}
+        return)
+)"#
+    );
+    let mut ctx = pliron::context::Context::default();
+    let program = compile(&mut ctx, &wat::parse_str(wat).unwrap());
+    let stack = run_miden_program(program, vec![], vec![]);
+    stack[0]
+}
+
+#[test]
+fn test_i32_add() {
+    check_binary_op("i32.add", &OPERANDS, &OPERANDS);
+}
+
+#[test]
+fn test_i32_sub() {
+    check_binary_op("i32.sub", &OPERANDS, &OPERANDS);
+}
+
+#[test]
+fn test_i32_mul() {
+    check_binary_op("i32.mul", &OPERANDS, &OPERANDS);
+}
+
+#[test]
+fn test_i32_shl() {
+    check_binary_op("i32.shl", &OPERANDS, &SHIFT_AMOUNTS);
+}
+
+#[test]
+fn test_i32_shr_u() {
+    check_binary_op("i32.shr_u", &OPERANDS, &SHIFT_AMOUNTS);
+}
+
+#[test]
+fn test_i32_shr_s() {
+    check_binary_op("i32.shr_s", &OPERANDS, &SHIFT_AMOUNTS);
+}
+
+#[test]
+fn test_i32_bitwise() {
+    for op in ["i32.and", "i32.or", "i32.xor"] {
+        check_binary_op(op, &OPERANDS, &OPERANDS);
+    }
+}
+
+#[test]
+fn test_i32_cmp() {
+    for op in [
+        "i32.lt_u", "i32.lt_s", "i32.gt_u", "i32.gt_s", "i32.le_u", "i32.le_s", "i32.ge_u",
+        "i32.ge_s",
+    ] {
+        check_binary_op(op, &OPERANDS, &OPERANDS);
+    }
+}
+
+#[test]
+fn test_i32_eqz() {
+    // eqz(a - b)
+    check_binary_op("i32.sub i32.eqz", &OPERANDS, &OPERANDS);
+}
+
+/// Divisors without the ones wasm traps on (zero and `-1` for `i32::MIN / -1`).
+const DIVISORS: [i32; 7] = [1, 2, -2, 3, i32::MAX, i32::MIN, 0x1234_5678];
+
+#[test]
+fn test_i32_div() {
+    check_binary_op("i32.div_u", &OPERANDS, &DIVISORS);
+    check_binary_op("i32.div_s", &OPERANDS, &DIVISORS);
+}
+
+#[test]
+fn test_i32_rem() {
+    check_binary_op("i32.rem_u", &OPERANDS, &DIVISORS);
+    // `i32::MIN % -1` is 0 and does not trap
+    check_binary_op("i32.rem_s", &OPERANDS, &[-1]);
+    check_binary_op("i32.rem_s", &OPERANDS, &DIVISORS);
+}
+
+#[test]
+fn test_i32_popcnt() {
+    // popcnt(a)
+    check_binary_op("drop i32.popcnt", &OPERANDS, &[0]);
+}
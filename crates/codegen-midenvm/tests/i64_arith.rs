@@ -0,0 +1,137 @@
+//! Differential tests of the i64 arithmetic (legalized to i32 limbs) against wasmtime.
+
+use sem_tests::compile;
+use sem_tests::run_miden_program;
+use wasmtime::*;
+
+mod sem_tests;
+
+/// Operands covering the carries between the limbs and the overflow edge cases.
+const OPERANDS: [i64; 11] = [
+    0,
+    1,
+    2,
+    -1,
+    -2,
+    0xFFFF_FFFF,
+    0x1_0000_0000,
+    i64::MAX,
+    i64::MIN,
+    0x1234_5678_9ABC_DEF0,
+    -0x1234_5678_9ABC_DEF0,
+];
+
+/// Shift amounts, including the ones crossing the limbs and the ones wasm takes modulo 64.
+const SHIFT_AMOUNTS: [i64; 10] = [0, 1, 4, 31, 32, 33, 63, 64, 65, -1];
+
+/// Evaluate `a <op> b` for every pair of operands on wasmtime and on Miden VM
+/// and check that the results are the same.
+fn check_binary_op(op: &str, result_ty: &str, lhs: &[i64], rhs: &[i64]) {
+    let wat = format!(
+        r#"
+(module
+    (func (export "f") (param i64 i64) (result {result_ty})
+        local.get 0
+        local.get 1
+        {op})
+)"#
+    );
+    let mut store = Store::new(&Engine::default(), ());
+    let module = Module::from_binary(store.engine(), &wat::parse_str(wat).unwrap()).unwrap();
+    let instance = Instance::new(&mut store, &module, &[]).unwrap();
+    let func = instance.get_func(&mut store, "f").unwrap();
+    let is_i64 = result_ty == "i64";
+    for a in lhs {
+        for b in rhs {
+            let mut results = [if is_i64 { Val::I64(0) } else { Val::I32(0) }];
+            func.call(&mut store, &[Val::I64(*a), Val::I64(*b)], &mut results)
+                .unwrap();
+            let expected = if is_i64 {
+                results[0].unwrap_i64() as u64
+            } else {
+                results[0].unwrap_i32() as u32 as u64
+            };
+            let actual = run_on_miden(op, is_i64, *a, *b);
+            assert_eq!(
+                actual, expected,
+                "{op} {a:#x} {b:#x}: expected {expected:#x}"
+            );
+        }
+    }
+}
+
+fn run_on_miden(op: &str, is_i64: bool, a: i64, b: i64) -> u64 {
+    let wat = format!(
+        r#"
+(module
+    (start $main)
+    (func $main
+        i64.const {a}
+        i64.const {b}
+        {op}
+        return)
+)"#
+    );
+    let mut ctx = pliron::context::Context::default();
+    let program = compile(&mut ctx, &wat::parse_str(wat).unwrap());
+    let stack = run_miden_program(program, vec![], vec![]);
+    if is_i64 {
+        // the high limb is on top
+        (stack[0] << 32) | stack[1]
+    } else {
+        stack[0]
+    }
+}
+
+#[test]
+fn test_i64_add() {
+    check_binary_op("i64.add", "i64", &OPERANDS, &OPERANDS);
+}
+
+#[test]
+fn test_i64_sub() {
+    check_binary_op("i64.sub", "i64", &OPERANDS, &OPERANDS);
+}
+
+#[test]
+fn test_i64_mul() {
+    check_binary_op("i64.mul", "i64", &OPERANDS, &OPERANDS);
+}
+
+#[test]
+fn test_i64_shl() {
+    check_binary_op("i64.shl", "i64", &OPERANDS, &SHIFT_AMOUNTS);
+}
+
+#[test]
+fn test_i64_shr_u() {
+    check_binary_op("i64.shr_u", "i64", &OPERANDS, &SHIFT_AMOUNTS);
+}
+
+#[test]
+fn test_i64_shr_s() {
+    check_binary_op("i64.shr_s", "i64", &OPERANDS, &SHIFT_AMOUNTS);
+}
+
+#[test]
+fn test_i64_bitwise() {
+    for op in ["i64.and", "i64.or", "i64.xor"] {
+        check_binary_op(op, "i64", &OPERANDS, &OPERANDS);
+    }
+}
+
+#[test]
+fn test_i64_cmp() {
+    for op in [
+        "i64.eq", "i64.ne", "i64.lt_u", "i64.lt_s", "i64.gt_u", "i64.gt_s", "i64.le_u", "i64.le_s",
+        "i64.ge_u", "i64.ge_s",
+    ] {
+        check_binary_op(op, "i32", &OPERANDS, &OPERANDS);
+    }
+}
+
+#[test]
+fn test_i64_eqz() {
+    // eqz(a - b)
+    check_binary_op("i64.sub i64.eqz", "i32", &OPERANDS, &OPERANDS);
+}
@@ -0,0 +1,70 @@
+//! Tests of the MASM library emitted from the exported functions.
+
+use ozk_codegen_midenvm::emit_lib;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use sem_tests::compile_to_miden_dialect;
+use sem_tests::run_miden_program;
+
+mod sem_tests;
+
+/// `sum` is exported under its own name, `sum_mul` as `fma`, `double` is internal
+const LIB_WAT: &str = r#"
+(module
+    (func $double (param i32) (result i32)
+        local.get 0
+        i32.const 2
+        i32.mul)
+    (func $sum (param i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        i32.add)
+    (func $sum_mul (param i32 i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        call $sum
+        local.get 2
+        i32.mul
+        call $double)
+    (func $unused (result i32)
+        i32.const 7)
+    (export "sum" (func $sum))
+    (export "fma" (func $sum_mul))
+)"#;
+
+fn compile_lib(wat: &str) -> String {
+    let mut ctx = pliron::context::Context::default();
+    let target_config = MidenTargetConfig::default();
+    let miden_prog =
+        compile_to_miden_dialect(&mut ctx, &wat::parse_str(wat).unwrap(), &target_config);
+    emit_lib(&ctx, &miden_prog, &target_config)
+        .unwrap()
+        .pretty_print()
+}
+
+/// Run the exported procedure by making the library a program
+/// (the exported procedures become local ones).
+fn run_export(lib: &str, export_name: &str, args: &[u64]) -> u64 {
+    let pushes = args
+        .iter()
+        .map(|arg| format!("push.{arg}"))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let program = format!(
+        "{}\nbegin\n{pushes}\nexec.{export_name}\nend\n",
+        lib.replace("export.", "proc.")
+    );
+    run_miden_program(program, vec![], vec![])[0]
+}
+
+#[test]
+fn test_exports() {
+    let lib = compile_lib(LIB_WAT);
+    assert!(!lib.contains("begin"), "{lib}");
+    assert!(lib.contains("export.sum."), "{lib}");
+    assert!(lib.contains("proc.sum_mul."), "{lib}");
+    assert!(lib.contains("proc.double."), "{lib}");
+    assert!(lib.contains("export.fma.0\nexec.sum_mul\nend"), "{lib}");
+    assert!(!lib.contains("unused"), "{lib}");
+    assert_eq!(run_export(&lib, "sum", &[3, 4]), 7);
+    assert_eq!(run_export(&lib, "fma", &[3, 4, 5]), 70);
+}
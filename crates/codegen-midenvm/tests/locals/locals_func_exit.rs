@@ -1,6 +1,6 @@
 use expect_test::expect;
 
-use crate::codegen::sem_tests::check_wat;
+use crate::sem_tests::check_wat;
 
 #[test]
 fn test_locals_func_exit() {
@@ -38,152 +38,99 @@ fn test_locals_func_exit() {
         secret_input,
         expected_output,
         expect![[r#"
-            call main
-            halt
-            ozk_stdlib_pub_input:
-            read_io
-            return
-            ozk_stdlib_pub_output:
-            push -1
-            call globals_get
-            dup 0
-            swap 2
-            write_mem
-            pop
-            push -4
-            add
-            push -1
-            call globals_set
-            push -1
-            call globals_get
-            push 4
-            add
-            read_mem
-            swap 1
-            pop
-            write_io
-            push -1
-            call globals_get
-            push 4
-            add
-            push -1
-            call globals_set
-            return
-            ozk_stdlib_secret_input:
-            divine
-            return
-            add:
-            push -1
-            call globals_get
-            dup 0
-            swap 2
-            write_mem
-            pop
-            push -4
-            add
-            dup 0
-            swap 2
-            write_mem
-            pop
-            push -4
-            add
-            push -1
-            call globals_set
-            push -1
-            call globals_get
-            push 8
-            add
-            read_mem
-            swap 1
-            pop
-            push -1
-            call globals_get
-            push 4
-            add
-            read_mem
-            swap 1
-            pop
-            add
-            push -1
-            call globals_get
-            push 8
-            add
-            push -1
-            call globals_set
-            return
-            push -1
-            call globals_get
-            push 8
-            add
-            push -1
-            call globals_set
-            return
-            main:
-            call init_mem_for_locals
-            push -1
-            call globals_get
-            push -4
-            add
-            push -1
-            call globals_set
-            push 5
-            push -1
-            call globals_get
-            swap 1
-            swap 1
-            push 4
-            add
-            swap 1
-            write_mem
-            pop
-            push 9
-            push 7
-            call add
-            push -1
-            call globals_get
-            push 4
-            add
-            read_mem
-            swap 1
-            pop
-            call ozk_stdlib_pub_output
-            call ozk_stdlib_pub_output
-            push -1
-            call globals_get
-            push 4
-            add
-            push -1
-            call globals_set
-            return
-            push -1
-            call globals_get
-            push 4
-            add
-            push -1
-            call globals_set
-            return
-            init_mem_for_locals:
-            push 00000000002147483635
-            push -1
-            call globals_set
-            return
-            globals_get:
-            push 4
-            mul
-            push 00000000002147483647
-            add
-            read_mem
-            swap 1
-            pop
-            return
-            globals_set:
-            push 4
+            proc.add.9
+            loc_store.3
+            loc_store.2
+            loc_store.1
+            loc_store.0
+            loc_load.0
+            loc_load.1
+            loc_load.2
+            loc_load.3
+            loc_store.7
+            loc_store.6
+            loc_store.5
+            loc_store.4
+            loc_load.4
+            loc_load.6
+            u32wrapping_add
+            dup.0
+            loc_store.8
+            loc_load.8
+            loc_load.4
+            u32checked_lt
+            loc_load.5
+            u32wrapping_add
+            loc_load.7
+            u32wrapping_add
+            end
+
+            proc.init_pub_outputs.0
+            push.2147475447
+            push.2147475455
+            mem_store
+            end
+
+            proc.load_pub_outputs_on_stack.0
+            push.2147475455
+            mem_load
+            push.2147475447
+            dup.1
+            dup.1
+            neq
+            while.true
+            dup.0
+            mem_load
+            movup.2
+            movup.2
+            push.8
+            sub
+            dup.1
+            dup.1
+            neq
+            end
+
+            drop
+            drop
+            end
+
+            proc.ozk_stdlib_pub_output.0
+            push.4294967296
             mul
-            push 00000000002147483647
             add
-            swap 1
-            write_mem
-            pop
-            return"#]],
+            push.2147475455
+            mem_load
+            dup.0
+            movup.2
+            swap.1
+            mem_store
+            push.8
+            sub
+            push.2147475455
+            mem_store
+            end
+
+            proc.main.2
+            push.5
+            push.0
+            loc_store.1
+            loc_store.0
+            push.9
+            push.0
+            push.7
+            push.0
+            exec.add
+            loc_load.0
+            loc_load.1
+            exec.ozk_stdlib_pub_output
+            exec.ozk_stdlib_pub_output
+            end
+
+            begin
+            exec.init_pub_outputs
+            exec.main
+            exec.load_pub_outputs_on_stack
+            end
+        "#]],
     );
 }
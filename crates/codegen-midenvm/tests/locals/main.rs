@@ -0,0 +1,5 @@
+#[path = "../sem_tests.rs"]
+mod sem_tests;
+
+mod locals_func_exit;
+mod locals_set_get;
@@ -0,0 +1,110 @@
+//! Differential tests of the loops against wasmtime.
+
+use sem_tests::compile;
+use sem_tests::run_miden_program;
+use wasmtime::*;
+
+mod sem_tests;
+
+/// f(n) = n * 0 + n * 1 + ... + n * 9, the loop runs a constant number of iterations
+const COUNTED: &str = r#"
+    (func $f (param i32) (result i32) (local i32 i32)
+        i32.const 0
+        local.set 1
+        loop
+            local.get 2
+            local.get 0
+            local.get 1
+            i32.mul
+            i32.add
+            local.set 2
+            local.get 1
+            i32.const 1
+            i32.add
+            local.tee 1
+            i32.const 10
+            i32.ne
+            br_if 0
+        end
+        local.get 2)
+"#;
+
+/// f(n) = 1 + 2 + ... + n (n > 0), the trip count depends on the argument
+const NOT_COUNTED: &str = r#"
+    (func $f (param i32) (result i32) (local i32 i32)
+        i32.const 0
+        local.set 1
+        loop
+            local.get 1
+            i32.const 1
+            i32.add
+            local.tee 1
+            local.get 2
+            i32.add
+            local.set 2
+            local.get 1
+            local.get 0
+            i32.ne
+            br_if 0
+        end
+        local.get 2)
+"#;
+
+/// Call `$f` with every argument on wasmtime and on Miden VM and check that the results
+/// are the same. Returns the compiled Miden program.
+fn check_func(func: &str, args: &[i32]) -> String {
+    let wat = format!(
+        r#"
+(module
+    {func}
+    (export "f" (func $f))
+)"#
+    );
+    let mut store = Store::new(&Engine::default(), ());
+    let module = Module::from_binary(store.engine(), &wat::parse_str(wat).unwrap()).unwrap();
+    let instance = Instance::new(&mut store, &module, &[]).unwrap();
+    let func_wasmtime = instance
+        .get_typed_func::<i32, i32>(&mut store, "f")
+        .unwrap();
+    let mut program = String::new();
+    for arg in args {
+        let expected = func_wasmtime.call(&mut store, *arg).unwrap();
+        program = compile_func(func, *arg);
+        let stack = run_miden_program(program.clone(), vec![], vec![]);
+        assert_eq!(
+            stack[0], expected as u32 as u64,
+            "f({arg}): expected {expected}"
+        );
+    }
+    program
+}
+
+fn compile_func(func: &str, arg: i32) -> String {
+    let wat = format!(
+        r#"
+(module
+    (start $main)
+    {func}
+    (func $main
+        i32.const {arg}
+        call $f
+        return)
+)"#
+    );
+    let mut ctx = pliron::context::Context::default();
+    compile(&mut ctx, &wat::parse_str(wat).unwrap())
+}
+
+#[test]
+fn test_counted_loop_to_repeat() {
+    let program = check_func(COUNTED, &[0, 1, 7]);
+    assert!(program.contains("repeat.10"), "{program}");
+    assert!(!program.contains("while.true"), "{program}");
+}
+
+#[test]
+fn test_not_counted_loop_to_while() {
+    let program = check_func(NOT_COUNTED, &[1, 2, 10]);
+    assert!(!program.contains("repeat."), "{program}");
+    assert!(program.contains("while.true"), "{program}");
+}
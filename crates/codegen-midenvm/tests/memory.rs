@@ -0,0 +1,210 @@
+//! Differential tests of the Wasm linear memory access against wasmtime.
+
+use sem_tests::compile;
+use sem_tests::run_miden_program;
+use wasmtime::*;
+
+mod sem_tests;
+
+/// Run `$f` with the given body and result type on wasmtime and on Miden VM
+/// and check that the results are the same.
+fn check_func(body: &str, result_ty: &str) {
+    let func = format!(
+        r#"
+    (func $f (result {result_ty})
+        {body})
+"#
+    );
+    let wat = format!(
+        r#"
+(module
+    (memory 1)
+    {func}
+    (export "f" (func $f))
+)"#
+    );
+    let mut store = Store::new(&Engine::default(), ());
+    let module = Module::from_binary(store.engine(), &wat::parse_str(wat).unwrap()).unwrap();
+    let instance = Instance::new(&mut store, &module, &[]).unwrap();
+    let func_wasmtime = instance.get_func(&mut store, "f").unwrap();
+    let is_i64 = result_ty == "i64";
+    let mut results = [if is_i64 { Val::I64(0) } else { Val::I32(0) }];
+    func_wasmtime.call(&mut store, &[], &mut results).unwrap();
+    let expected = if is_i64 {
+        results[0].unwrap_i64() as u64
+    } else {
+        results[0].unwrap_i32() as u32 as u64
+    };
+    let wat = format!(
+        r#"
+(module
+    (memory 1)
+    (start $main)
+    {func}
+    (func $main
+        call $f
+        return)
+)"#
+    );
+    let mut ctx = pliron::context::Context::default();
+    let program = compile(&mut ctx, &wat::parse_str(wat).unwrap());
+    let stack = run_miden_program(program, vec![], vec![]);
+    let actual = if is_i64 {
+        // the high limb is on top
+        (stack[0] << 32) | stack[1]
+    } else {
+        stack[0]
+    };
+    assert_eq!(actual, expected, "{body}");
+}
+
+#[test]
+fn test_i32_load_store_aligned() {
+    check_func(
+        r#"
+        i32.const 8
+        i32.const 0x12345678
+        i32.store
+        i32.const 12
+        i32.const -7
+        i32.store
+        i32.const 8
+        i32.load
+        i32.const 12
+        i32.load
+        i32.add"#,
+        "i32",
+    );
+}
+
+#[test]
+fn test_i32_load_store_unaligned() {
+    // the store spans two Miden words, the load overlaps it
+    for store_addr in 0..4 {
+        for load_addr in 0..8 {
+            check_func(
+                &format!(
+                    r#"
+        i32.const 0
+        i32.const 0x7f6e5d4c
+        i32.store
+        i32.const 4
+        i32.const 0x8899aabb
+        i32.store
+        i32.const {store_addr}
+        i32.const 0x11223344
+        i32.store align=1
+        i32.const {load_addr}
+        i32.load align=1"#
+                ),
+                "i32",
+            );
+        }
+    }
+}
+
+#[test]
+fn test_i32_load_store_offset() {
+    check_func(
+        r#"
+        i32.const 3
+        i32.const 0x01020304
+        i32.store offset=6 align=1
+        i32.const 1
+        i32.load offset=8 align=1"#,
+        "i32",
+    );
+}
+
+#[test]
+fn test_i32_sub_word_load() {
+    for op in ["i32.load8_u", "i32.load8_s", "i32.load16_u", "i32.load16_s"] {
+        for addr in 0..7 {
+            let align = if op.contains("16") && addr % 2 == 0 {
+                ""
+            } else {
+                "align=1"
+            };
+            check_func(
+                &format!(
+                    r#"
+        i32.const 0
+        i32.const 0x80ff7f01
+        i32.store
+        i32.const 4
+        i32.const 0x00fe8081
+        i32.store
+        i32.const {addr}
+        {op} {align}"#
+                ),
+                "i32",
+            );
+        }
+    }
+}
+
+#[test]
+fn test_i32_sub_word_store() {
+    for op in ["i32.store8", "i32.store16"] {
+        for addr in 0..7 {
+            for load_addr in [0, 4] {
+                check_func(
+                    &format!(
+                        r#"
+        i32.const 0
+        i32.const -1
+        i32.store
+        i32.const 4
+        i32.const 0x01020304
+        i32.store
+        i32.const {addr}
+        i32.const 0xabcdef12
+        {op} align=1
+        i32.const {load_addr}
+        i32.load"#
+                    ),
+                    "i32",
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_i64_load_store() {
+    for addr in [0, 3, 8] {
+        check_func(
+            &format!(
+                r#"
+        i32.const {addr}
+        i64.const 0x0102030405060708
+        i64.store align=1
+        i32.const {addr}
+        i64.load align=1"#
+            ),
+            "i64",
+        );
+    }
+}
+
+#[test]
+fn test_i64_sub_word_load_store() {
+    for (store_op, load_op) in [
+        ("i64.store8", "i64.load8_u"),
+        ("i64.store16", "i64.load16_u"),
+        ("i64.store32", "i64.load32_u"),
+        ("i64.store32", "i64.load"),
+    ] {
+        check_func(
+            &format!(
+                r#"
+        i32.const 2
+        i64.const 0x0102030405060708
+        {store_op} align=1
+        i32.const 2
+        {load_op} align=1"#
+            ),
+            "i64",
+        );
+    }
+}
@@ -2,7 +2,6 @@ mod sem_tests;
 use crate::sem_tests::check_miden;
 use expect_test::expect;
 
-#[ignore]
 #[test]
 fn test_pub_inputs() {
     // let input = vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2];
@@ -10,7 +9,8 @@ fn test_pub_inputs() {
     // prepend with 16 zeroes
     // let input = vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2];
     let secret_input = vec![];
-    let expected_output = vec![7, 5];
+    // the inputs are split into the u32 limbs, the high one on top
+    let expected_output = vec![0, 7, 0, 5];
     check_miden(
         r#"
 (module
@@ -31,117 +31,55 @@ fn test_pub_inputs() {
         secret_input,
         expected_output,
         expect![[r#"
-            proc.globals_get.0
-            push.18446744069414584317
-            mul
-            push.2147467263
-            add
-            mem_load
-            end
-
-            proc.globals_set.0
-            push.18446744069414584317
-            mul
-            push.2147467263
-            add
-            swap.1
-            swap.1
-            mem_store
-            end
-
-            proc.save_pub_inputs.2
+            proc.ozk_stdlib_pub_input.0
             push.2147483647
-            loc_store.0
-            sdepth
-            loc_store.1
-            push.1
-            while.true
-            dup.0
-            neq.0
-            if.true
-            loc_load.0
+            mem_load
             dup.0
-            swap.2
+            mem_load
             swap.1
-            mem_store
             push.8
             sub
-            loc_store.0
-            else
-            drop
-            end
-
-            loc_load.1
-            push.1
-            sub
-            dup.0
-            loc_store.1
-            neq.0
-            end
-
-            loc_load.0
-            push.0
-            exec.globals_set
-            end
-
-            proc.omni_miden_pub_input.0
-            push.0
-            exec.globals_get
-            push.8
-            add
-            dup.0
-            mem_load
-            swap.1
-            push.0
-            exec.globals_set
-            end
-
-            proc.init_pub_outputs.0
             push.2147483647
-            push.1
-            exec.globals_set
-            end
-
-            proc.omni_miden_pub_output.0
-            push.1
-            exec.globals_get
-            dup.0
-            swap.2
-            swap.1
             mem_store
-            push.8
-            sub
-            push.1
-            exec.globals_set
+            u32split
             end
 
-            proc.load_pub_outputs_on_stack.2
+            proc.save_pub_inputs.0
+            push.2147483639
+            mem_store
+            push.2147483631
+            mem_store
+            push.2147483623
+            mem_store
+            push.2147483615
+            mem_store
+            push.2147483607
+            mem_store
+            push.2147483599
+            mem_store
+            push.2147483591
+            mem_store
+            push.2147483583
+            mem_store
+            push.2147483575
+            mem_store
+            push.2147483567
+            mem_store
+            push.2147483559
+            mem_store
+            push.2147483551
+            mem_store
+            push.2147483543
+            mem_store
+            push.2147483535
+            mem_store
+            push.2147483527
+            mem_store
+            push.2147483519
+            mem_store
+            push.2147483639
             push.2147483647
-            dup.0
-            loc_store.0
-            push.1
-            exec.globals_get
-            dup.0
-            loc_store.1
-            sub
-            neq.0
-            while.true
-            loc_load.0
-            mem_load
-            loc_load.0
-            push.8
-            sub
-            dup.0
-            loc_store.0
-            loc_load.1
-            sub
-            neq.0
-            end
-
-            end
-
-            proc.ozk_stdlib_pub_input.0
-            exec.omni_miden_pub_input
+            mem_store
             end
 
             proc.main.0
@@ -149,15 +87,9 @@ fn test_pub_inputs() {
             exec.ozk_stdlib_pub_input
             end
 
-            proc.start_with_miden_io_persistent.0
+            begin
             exec.save_pub_inputs
-            exec.init_pub_outputs
             exec.main
-            exec.load_pub_outputs_on_stack
-            end
-
-            begin
-            exec.start_with_miden_io_persistent
             end
         "#]],
     );
@@ -0,0 +1,167 @@
+//! Differential tests of the recursive functions against wasmtime.
+
+use sem_tests::compile;
+use sem_tests::run_miden_program;
+use wasmtime::*;
+
+mod sem_tests;
+
+const FACT: &str = r#"
+    (func $f (param i32) (result i32)
+        block
+            local.get 0
+            br_if 0
+            i32.const 1
+            return
+        end
+        local.get 0
+        local.get 0
+        i32.const 1
+        i32.sub
+        call $f
+        i32.mul)
+"#;
+
+const FIB: &str = r#"
+    (func $f (param i32) (result i32)
+        block
+            local.get 0
+            br_if 0
+            i32.const 0
+            return
+        end
+        block
+            local.get 0
+            i32.const 1
+            i32.ne
+            br_if 0
+            i32.const 1
+            return
+        end
+        local.get 0
+        i32.const 1
+        i32.sub
+        call $f
+        local.get 0
+        i32.const 2
+        i32.sub
+        call $f
+        i32.add)
+"#;
+
+const EVEN_ODD: &str = r#"
+    (func $f (param i32) (result i32)
+        block
+            local.get 0
+            br_if 0
+            i32.const 1
+            return
+        end
+        local.get 0
+        i32.const 1
+        i32.sub
+        call $odd)
+    (func $odd (param i32) (result i32)
+        block
+            local.get 0
+            br_if 0
+            i32.const 0
+            return
+        end
+        local.get 0
+        i32.const 1
+        i32.sub
+        call $f)
+"#;
+
+/// f(n) = 1 + f(0) + ... + f(n - 1), the recursive call is in a loop
+const CALL_IN_LOOP: &str = r#"
+    (func $f (param i32) (result i32) (local i32 i32)
+        i32.const 1
+        local.set 1
+        block
+            local.get 0
+            i32.const 0
+            i32.eq
+            br_if 0
+            loop
+                local.get 1
+                local.get 2
+                call $f
+                i32.add
+                local.set 1
+                local.get 2
+                i32.const 1
+                i32.add
+                local.tee 2
+                local.get 0
+                i32.ne
+                br_if 0
+            end
+        end
+        local.get 1)
+"#;
+
+/// Call `$f` with every argument on wasmtime and on Miden VM and check that the results
+/// are the same.
+fn check_recursive_func(funcs: &str, args: &[i32]) {
+    let wat = format!(
+        r#"
+(module
+    {funcs}
+    (export "f" (func $f))
+)"#
+    );
+    let mut store = Store::new(&Engine::default(), ());
+    let module = Module::from_binary(store.engine(), &wat::parse_str(wat).unwrap()).unwrap();
+    let instance = Instance::new(&mut store, &module, &[]).unwrap();
+    let func = instance
+        .get_typed_func::<i32, i32>(&mut store, "f")
+        .unwrap();
+    for arg in args {
+        let expected = func.call(&mut store, *arg).unwrap();
+        let actual = run_on_miden(funcs, *arg);
+        assert_eq!(
+            actual, expected as u32 as u64,
+            "f({arg}): expected {expected}"
+        );
+    }
+}
+
+fn run_on_miden(funcs: &str, arg: i32) -> u64 {
+    let wat = format!(
+        r#"
+(module
+    (start $main)
+    {funcs}
+    (func $main
+        i32.const {arg}
+        call $f
+        return)
+)"#
+    );
+    let mut ctx = pliron::context::Context::default();
+    let program = compile(&mut ctx, &wat::parse_str(wat).unwrap());
+    let stack = run_miden_program(program, vec![], vec![]);
+    stack[0]
+}
+
+#[test]
+fn test_self_recursion() {
+    check_recursive_func(FACT, &[0, 1, 5, 10]);
+}
+
+#[test]
+fn test_two_recursive_calls() {
+    check_recursive_func(FIB, &[0, 1, 2, 10]);
+}
+
+#[test]
+fn test_mutual_recursion() {
+    check_recursive_func(EVEN_ODD, &[0, 1, 6, 9]);
+}
+
+#[test]
+fn test_recursive_call_in_loop() {
+    check_recursive_func(CALL_IN_LOOP, &[0, 1, 4, 6]);
+}
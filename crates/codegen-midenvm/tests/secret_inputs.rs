@@ -0,0 +1,64 @@
+//! Tests of the secret inputs read from the advice stack.
+
+use sem_tests::compile;
+use sem_tests::run_miden_program;
+
+mod sem_tests;
+
+#[test]
+fn test_secret_input() {
+    let wat = r#"
+(module
+    (import "env" "ozk_stdlib_secret_input" (func $ozk_stdlib_secret_input (result i64)))
+    (start $main)
+    (func $main
+        call $ozk_stdlib_secret_input
+        call $ozk_stdlib_secret_input
+        i64.sub
+        return)
+)"#;
+    let mut ctx = pliron::context::Context::default();
+    let program = compile(&mut ctx, &wat::parse_str(wat).unwrap());
+    assert!(program.contains("adv_push.1"), "{program}");
+    assert!(!program.contains("ozk_stdlib_secret_input"), "{program}");
+    let stack = run_miden_program(program, vec![], vec![10, 3]);
+    // the high limb is on top
+    assert_eq!(stack[..2], [0, 7]);
+}
+
+#[test]
+fn test_secret_input_slice() {
+    // 4 values are read with `adv_loadw` and the last 2 with `adv_push.1`
+    let wat = r#"
+(module
+    (import "env" "ozk_stdlib_secret_input_slice" (func $ozk_stdlib_secret_input_slice (param i32 i32)))
+    (memory 1)
+    (start $main)
+    (func $main
+        i32.const 16
+        i32.const 6
+        call $ozk_stdlib_secret_input_slice
+        i32.const 16
+        i64.load
+        i32.const 24
+        i64.load
+        i32.const 44
+        i32.load
+        i32.const 40
+        i32.load
+        i32.const 48
+        i64.load
+        i32.const 56
+        i64.load
+        i32.const 64
+        i64.load
+        return)
+)"#;
+    let mut ctx = pliron::context::Context::default();
+    let program = compile(&mut ctx, &wat::parse_str(wat).unwrap());
+    assert!(program.contains("adv_loadw"), "{program}");
+    let secret_input = vec![1, 0x1_0000_0002, 3, 0x4_0000_0005, 6, 7, 8];
+    let stack = run_miden_program(program, vec![], secret_input);
+    // the u64 after the slice is not written, the high limbs of i64 are on top
+    assert_eq!(stack[..12], [0, 0, 0, 7, 0, 6, 5, 4, 1, 2, 0, 1]);
+}
@@ -8,15 +8,17 @@ use std::ops::RangeFrom;
 
 use miden_assembly::Assembler;
 use miden_processor::math::Felt;
-use miden_processor::AdviceInputs;
 use miden_processor::MemAdviceProvider;
 use miden_processor::StackInputs;
 use miden_processor::VmState;
 use miden_processor::VmStateIterator;
 use miden_stdlib::StdLibrary;
-use ozk_codegen_midenvm::emit_prog;
+use ozk_codegen_midenvm::compile_prog;
+use ozk_codegen_midenvm::secret_input_advice;
+use ozk_codegen_midenvm::MidenProgramOutput;
 use ozk_codegen_midenvm::MidenTargetConfig;
 use ozk_frontend_wasm::WasmFrontendConfig;
+use ozk_ir_transform::pass_manager::PassManager;
 use ozk_miden_dialect::ops::ProgramOp;
 use ozk_wasm_dialect::ops::ModuleOp;
 use pliron::context::Context;
@@ -39,7 +41,7 @@ pub fn check_ir(input: &str, expected_tree: expect_test::Expect) {
     target_config.register(&mut ctx);
     let wasm_module_op =
         ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
-    let miden_prog = run_conversion_passes(&mut ctx, wasm_module_op, &target_config);
+    let miden_prog = run_conversion_passes(&mut ctx, wasm_module_op, None, &target_config);
     expected_tree.assert_eq(miden_prog.with_ctx(&ctx).to_string().as_str());
 }
 
@@ -52,19 +54,38 @@ pub fn compile_to_miden_dialect(
     frontend_config.register(ctx);
     target_config.register(ctx);
     let wasm_module_op = ozk_frontend_wasm::parse_module(ctx, source, &frontend_config).unwrap();
-    run_conversion_passes(ctx, wasm_module_op, target_config)
+    run_conversion_passes(ctx, wasm_module_op, None, target_config)
 }
 
 pub fn compile(ctx: &mut Context, source: &[u8]) -> String {
     let target_config = MidenTargetConfig::default();
     let miden_prog = compile_to_miden_dialect(ctx, source, &target_config);
-    let inst_buf = emit_prog(ctx, &miden_prog, &target_config).unwrap();
-    inst_buf.pretty_print()
+    emit_source(ctx, &miden_prog, &target_config)
+}
+
+/// Compile to MASM running the `pre_passes` on the Wasm module before the Miden pipeline.
+pub fn compile_after_passes(ctx: &mut Context, source: &[u8], pre_passes: &PassManager) -> String {
+    let target_config = MidenTargetConfig::default();
+    let frontend_config = WasmFrontendConfig::default();
+    frontend_config.register(ctx);
+    target_config.register(ctx);
+    let wasm_module_op = ozk_frontend_wasm::parse_module(ctx, source, &frontend_config).unwrap();
+    let miden_prog = run_conversion_passes(ctx, wasm_module_op, Some(pre_passes), &target_config);
+    emit_source(ctx, &miden_prog, &target_config)
+}
+
+fn emit_source(ctx: &Context, miden_prog: &ProgramOp, target_config: &MidenTargetConfig) -> String {
+    let program = compile_prog(ctx, miden_prog, target_config).unwrap();
+    let MidenProgramOutput::Source(source) = program.output else {
+        panic!("expected MASM source output");
+    };
+    source
 }
 
 fn run_conversion_passes(
     ctx: &mut Context,
     wasm_module: ModuleOp,
+    pre_passes: Option<&PassManager>,
     target_config: &MidenTargetConfig,
 ) -> ProgramOp {
     // we need to wrap the wasm in an op because passes cannot replace the root op
@@ -72,6 +93,9 @@ fn run_conversion_passes(
     wasm_module
         .get_operation()
         .insert_at_back(wrapper_module.get_body(ctx, 0), ctx);
+    if let Some(pre_passes) = pre_passes {
+        pre_passes.run(ctx, wrapper_module.get_operation()).unwrap();
+    }
     target_config
         .pass_manager
         .run(ctx, wrapper_module.get_operation())
@@ -116,15 +140,24 @@ pub fn check_miden(
     let mut ctx = Context::default();
     let program = compile(&mut ctx, &wasm);
     expected_miden.assert_eq(&program);
+    let stack = run_miden_program(program, input, secret_input);
+    // fill expected_output with zeros if it's shorter than stack
+    let expected_output = expected_output
+        .into_iter()
+        .chain(std::iter::repeat(0))
+        .take(stack.len())
+        .collect::<Vec<_>>();
+    assert_eq!(stack, expected_output);
+}
+
+/// Assemble and run the Miden program, returns the stack at the end of the execution.
+pub fn run_miden_program(program: String, input: Vec<u64>, secret_input: Vec<u64>) -> Vec<u64> {
     let assembler = Assembler::default()
         .with_library(&StdLibrary::default())
         .unwrap();
     let program = assembler.compile(program).unwrap();
     let stack_inputs = StackInputs::try_from_values(input).unwrap();
-    let adv_provider: MemAdviceProvider = AdviceInputs::default()
-        .with_stack_values(secret_input)
-        .unwrap()
-        .into();
+    let adv_provider: MemAdviceProvider = secret_input_advice(secret_input).unwrap().into();
     dbg!(&program);
     // let trace = miden_processor::execute(&program, stack_inputs, adv_provider).unwrap();
     let e_iter = miden_processor::execute_iter(&program, stack_inputs, adv_provider);
@@ -139,14 +172,7 @@ pub fn check_miden(
     );
     // assert_eq!(0, 1);
     // let stack = pretty_stack(trace.stack_outputs().stack());
-    let stack = pretty_stack_felt(&vm_state.last().unwrap().stack);
-    // fill expected_output with zeros if it's shorter than stack
-    let expected_output = expected_output
-        .into_iter()
-        .chain(std::iter::repeat(0))
-        .take(stack.len())
-        .collect::<Vec<_>>();
-    assert_eq!(stack, expected_output);
+    pretty_stack_felt(&vm_state.last().unwrap().stack)
 }
 
 pub fn check_wat(
@@ -192,7 +218,9 @@ pub fn check_wat(
     let _ = Instance::new(&mut store, &module, &imports).unwrap();
 
     assert_eq!(store.data().output, expected_output);
-    check_miden(source, input, secret_input, expected_output, expected_miden);
+    // public outputs are loaded on the stack with the last one on top
+    let expected_stack = expected_output.into_iter().rev().collect();
+    check_miden(source, input, secret_input, expected_stack, expected_miden);
 }
 
 fn pretty_stack_felt(stack: &[Felt]) -> Vec<u64> {
@@ -52,7 +52,7 @@ fn test_smoke_add_wo_imports() {
             proc.main.0
             push.1
             push.2
-            add
+            u32wrapping_add
             end
 
             begin
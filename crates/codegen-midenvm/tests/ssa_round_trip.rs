@@ -0,0 +1,132 @@
+//! Differential test of the SSA round trip (Wasm -> SSA -> Wasm) against wasmtime.
+
+use ozk_ir_transform::pass_manager::PassManager;
+use ozk_ir_transform::ssa::ssa_to_wasm::SsaToWasmPass;
+use ozk_ir_transform::ssa::wasm_to_ssa::WasmToSsaPass;
+use sem_tests::compile_after_passes;
+use sem_tests::run_miden_program;
+use wasmtime::*;
+
+mod sem_tests;
+
+/// `$main` returns `$g + 2 * (n + (n - 1) + ... + 1) + $mix(n)` computed with a block, a loop,
+/// locals, a global and memory. `$mix` covers the i64, comparison, shift, conversion
+/// and sized memory access ops.
+fn round_trip_wat(entry: &str, n: i32) -> String {
+    format!(
+        r#"
+(module
+    (memory 1)
+    (global $g (mut i32) (i32.const 0))
+    {entry}
+    (func $sum (param i32) (result i32)
+        (local i32)
+        block
+            local.get 0
+            i32.eqz
+            br_if 0
+            loop
+                local.get 1
+                local.get 0
+                i32.add
+                local.set 1
+                local.get 0
+                i32.const 1
+                i32.sub
+                local.tee 0
+                i32.eqz
+                i32.eqz
+                br_if 0
+            end
+        end
+        local.get 1)
+    (func $mix (param i32) (result i32)
+        (local i64)
+        local.get 0
+        i64.extend_i32_u
+        i64.const 3
+        i64.mul
+        local.get 0
+        i64.extend_i32_s
+        i64.const 33
+        i64.shl
+        i64.xor
+        local.set 1
+        i32.const 32
+        local.get 1
+        i64.store16
+        i32.const 32
+        i32.load8_s
+        local.get 1
+        i64.const 32
+        i64.shr_u
+        i32.wrap_i64
+        local.get 0
+        i32.const 5
+        i32.mul
+        i32.lt_s
+        i32.const 4
+        i32.shl
+        i32.add
+        local.get 1
+        i64.eqz
+        i32.add
+        i32.const 100
+        drop)
+    (func $main (result i32)
+        (local i32)
+        i32.const 16
+        i32.const {n}
+        call $sum
+        i32.store
+        i32.const 7
+        global.set $g
+        i32.const 16
+        i32.load
+        local.tee 0
+        local.get 0
+        i32.add
+        global.get $g
+        i32.add
+        i32.const {n}
+        call $mix
+        i32.add)
+)"#
+    )
+}
+
+fn run_on_wasmtime(n: i32) -> i32 {
+    let wat = round_trip_wat(r#"(export "main" (func $main))"#, n);
+    let mut store = Store::new(&Engine::default(), ());
+    let module = Module::from_binary(store.engine(), &wat::parse_str(wat).unwrap()).unwrap();
+    let instance = Instance::new(&mut store, &module, &[]).unwrap();
+    let main = instance
+        .get_typed_func::<(), i32>(&mut store, "main")
+        .unwrap();
+    main.call(&mut store, ()).unwrap()
+}
+
+fn run_round_trip_on_miden(n: i32) -> u64 {
+    let wat = round_trip_wat("(start $main)", n);
+    let mut ctx = pliron::context::Context::default();
+    ozk_ssa_dialect::register(&mut ctx);
+    let mut pre_passes = PassManager::new();
+    pre_passes.set_verify_after_each_pass(true);
+    pre_passes.add_pass(Box::<WasmToSsaPass>::default());
+    pre_passes.add_pass(Box::<SsaToWasmPass>::default());
+    let program = compile_after_passes(&mut ctx, &wat::parse_str(wat).unwrap(), &pre_passes);
+    let stack = run_miden_program(program, vec![], vec![]);
+    stack[0]
+}
+
+#[test]
+fn test_ssa_round_trip_matches_wasmtime() {
+    for n in [0, 1, 2, 10] {
+        let expected = run_on_wasmtime(n);
+        assert_eq!(
+            run_round_trip_on_miden(n),
+            expected as u32 as u64,
+            "n = {n}"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! Tests of the Wasm imports lowered to the Miden standard library procedure calls.
+
+use sem_tests::compile;
+use sem_tests::run_miden_program;
+
+mod sem_tests;
+
+#[test]
+fn test_u64_wrapping_add() {
+    // 0x1_ffffffff + 0x2_00000001 = 0x4_00000000
+    // u64 values are passed as u32 limbs: [b_hi, b_lo, a_hi, a_lo] -> [c_hi, c_lo]
+    let wat = r#"
+(module
+    (import "miden::std::math::u64" "wrapping_add"
+        (func $u64_wrapping_add (param i32 i32 i32 i32) (result i32 i32)))
+    (start $main)
+    (func $main
+        i32.const -1
+        i32.const 1
+        i32.const 1
+        i32.const 2
+        call $u64_wrapping_add
+        return)
+)"#;
+    let mut ctx = pliron::context::Context::default();
+    let program = compile(&mut ctx, &wat::parse_str(wat).unwrap());
+    assert!(program.starts_with("use.std::math::u64\n"), "{program}");
+    assert!(program.contains("exec.u64::wrapping_add"), "{program}");
+    assert!(!program.contains("proc.wrapping_add"), "{program}");
+    let stack = run_miden_program(program, vec![], vec![]);
+    assert_eq!(stack[..2], [4, 0]);
+}
@@ -1,24 +1,49 @@
 #![allow(unused_imports)]
 
+use ozk_ir_transform::dce::DceUnusedFunctionsPass;
+use ozk_ir_transform::ozk::legalize_i64::LegalizeI64Pass;
+use ozk_ir_transform::ozk::lowering::WasmToOzkLoweringPass;
+use ozk_ir_transform::pass_manager::PassManager;
 use ozk_ir_transform::valida::lowering::arith_op_lowering::WasmToValidaArithLoweringPass;
 use ozk_ir_transform::valida::lowering::func_lowering::WasmToValidaFuncLoweringPass;
 use ozk_ir_transform::valida::lowering::module_lowering::WasmToValidaModuleLoweringPass;
 use ozk_ir_transform::valida::lowering::resolve_target_sym_to_pc::ValidaResolveTargetSymToPcPass;
 use ozk_ir_transform::valida::lowering::WasmToValidaFinalLoweringPass;
 use ozk_ir_transform::valida::track_pc::ValidaTrackProgramCounterPass;
-use ozk_ir_transform::wasm::resolve_call_op::WasmCallOpToOzkCallOpPass;
+use ozk_ir_transform::wasm::inline::InlineCostModel;
+use ozk_ir_transform::wasm::inline::WasmInlinePass;
 use ozk_ir_transform::wasm::track_stack_depth::WasmTrackStackDepthPass;
 use pliron::context::Context;
-use pliron::pass::PassManager;
 
 pub struct ValidaTargetConfig {
     pub pass_manager: PassManager,
 }
 
+/// The default pipeline does not inline on purpose: every Wasm function keeps its own
+/// procedure, so the output maps back to the source functions. Use
+/// [ValidaTargetConfig::with_inlining] to opt in.
 impl Default for ValidaTargetConfig {
     fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl ValidaTargetConfig {
+    /// Inline the small functions with the Valida cost model before the lowering.
+    /// The [Default] pipeline does not inline.
+    pub fn with_inlining() -> Self {
+        Self::new(Some(InlineCostModel::valida()))
+    }
+
+    fn new(inline_cost_model: Option<InlineCostModel>) -> Self {
         let mut pass_manager = PassManager::new();
-        pass_manager.add_pass(Box::<WasmCallOpToOzkCallOpPass>::default());
+        if let Some(inline_cost_model) = inline_cost_model {
+            pass_manager.add_pass(Box::new(WasmInlinePass::new(inline_cost_model)));
+        }
+        pass_manager.add_pass(Box::<WasmToOzkLoweringPass>::default());
+        // Valida operates on 32-bit words
+        pass_manager.add_pass(Box::<LegalizeI64Pass>::default());
+        pass_manager.add_pass(Box::<DceUnusedFunctionsPass>::default());
         pass_manager.add_pass(Box::new(
             WasmTrackStackDepthPass::new_reserve_space_for_locals(),
         ));
@@ -30,9 +55,7 @@ impl Default for ValidaTargetConfig {
         pass_manager.add_pass(Box::<WasmToValidaFinalLoweringPass>::default());
         Self { pass_manager }
     }
-}
 
-impl ValidaTargetConfig {
     pub fn register(&self, ctx: &mut Context) {
         ozk_valida_dialect::register(ctx);
     }
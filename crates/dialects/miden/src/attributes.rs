@@ -17,6 +17,7 @@ use pliron::r#type::TypeObj;
 use intertrait::cast_to;
 use pliron::with_context::AttachContext;
 use thiserror::Error;
+use winter_math::StarkField;
 
 use crate::types::FieldElemType;
 
@@ -42,8 +43,13 @@ impl DisplayWithContext for FieldElemAttr {
 }
 
 impl Verify for FieldElemAttr {
-    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
-        todo!()
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        if !self.ty.deref(ctx).is::<FieldElemType>() {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected felt type for FieldElem attribute".to_string(),
+            });
+        }
+        Ok(())
     }
 }
 
@@ -53,21 +59,44 @@ impl FieldElemAttr {
         FieldElemAttr { ty, val }
     }
 
+    /// Convert an i32 integer attribute to a field element holding its u32 bit pattern,
+    /// the representation expected by the Miden `u32*` instructions.
+    /// An i64 attribute is converted to its u64 bit pattern (e.g. public inputs and outputs)
+    /// if it fits in a field element.
     pub fn from_integer_attr(
         ctx: &mut Context,
         int_attr: IntegerAttr,
     ) -> Result<FieldElemAttr, FieldElemError> {
         let ty = FieldElemType::get(ctx);
         if int_attr.get_type() == IntegerType::get(ctx, 32, Signedness::Signed) {
-            Ok(FieldElemAttr::create(ty, apint_to_oxfoi(int_attr.into())))
+            Ok(FieldElemAttr::create(
+                ty,
+                apint_to_u32_felt(int_attr.into()),
+            ))
+        } else if int_attr.get_type() == IntegerType::get(ctx, 64, Signedness::Signed) {
+            let value: ApInt = int_attr.into();
+            match value.try_to_u64() {
+                Ok(raw) if raw < FieldElem::MODULUS => {
+                    Ok(FieldElemAttr::create(ty, FieldElem::new(raw)))
+                }
+                Ok(_) | Err(_) => Err(FieldElemError::TooLarge(value)),
+            }
         } else {
             Err(FieldElemError::TooLarge(int_attr.into()))
         }
     }
 }
 
+/// Field element with the bit pattern of the 32-bit integer as u32 value.
+pub fn apint_to_u32_felt(value: ApInt) -> FieldElem {
+    assert!(value.width() <= 32.into());
+    let i = Int::from(value);
+    #[allow(clippy::expect_used)]
+    let raw = i.try_to_i32().expect("failed to get 32-bit integer");
+    FieldElem::new(raw as u32 as u64)
+}
+
 pub fn apint_to_oxfoi(value: ApInt) -> FieldElem {
-    use winter_math::StarkField;
     assert!(value.width() <= 64.into());
     let i = Int::from(value);
     #[allow(clippy::expect_used)]
@@ -4,6 +4,7 @@ use std::ops::Deref;
 
 use apint::ApInt;
 use intertrait::cast_to;
+use ozk_ozk_dialect::attributes::u32_attr;
 use ozk_ozk_dialect::types::FuncSym;
 use pliron::attribute;
 use pliron::attribute::attr_cast;
@@ -20,6 +21,7 @@ use pliron::dialects::builtin::attributes::FloatAttr;
 use pliron::dialects::builtin::attributes::IntegerAttr;
 use pliron::dialects::builtin::attributes::StringAttr;
 use pliron::dialects::builtin::attributes::TypeAttr;
+use pliron::dialects::builtin::attributes::VecAttr;
 use pliron::dialects::builtin::op_interfaces::CallOpInterface;
 use pliron::dialects::builtin::op_interfaces::OneRegionInterface;
 use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
@@ -31,6 +33,8 @@ use pliron::error::CompilerError;
 use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
 use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
 use pliron::r#type::TypeObj;
 use pliron::with_context::AttachContext;
 
@@ -38,6 +42,14 @@ use crate::attributes::FieldElemAttr;
 
 declare_op!(
     /// Represents a Miden program
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_MAIN_PROC_SYM](ProgramOp::ATTR_KEY_MAIN_PROC_SYM) | [StringAttr] |
+    /// | [ATTR_KEY_EXPORT_NAMES](ProgramOp::ATTR_KEY_EXPORT_NAMES) (optional) | [VecAttr] of [StringAttr] |
+    /// | [ATTR_KEY_EXPORT_PROC_SYMS](ProgramOp::ATTR_KEY_EXPORT_PROC_SYMS) (optional) | [VecAttr] of [StringAttr] |
     ProgramOp,
     "program",
     "miden"
@@ -57,7 +69,79 @@ impl DisplayWithContext for ProgramOp {
 
 impl Verify for ProgramOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_MAIN_PROC_SYM)
+            .map_or(false, |attr| attr.is::<StringAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "miden.program has no main proc symbol".to_string(),
+            });
+        }
         self.verify_interfaces(ctx)?;
+        if self.get_region(ctx).deref(ctx).iter(ctx).count() != 1 {
+            return Err(CompilerError::VerificationError {
+                msg: "miden.program must have a single block".to_string(),
+            });
+        }
+        let mut proc_syms = Vec::new();
+        for op in self.get_body(ctx, 0).deref(ctx).iter(ctx) {
+            let Some(proc_op) = op.deref(ctx).get_op(ctx).downcast_ref::<ProcOp>().cloned() else {
+                return Err(CompilerError::VerificationError {
+                    msg: format!(
+                        "miden.program body must contain only miden.proc, found: {}",
+                        op.deref(ctx).with_ctx(ctx)
+                    ),
+                });
+            };
+            let sym = proc_op.get_symbol_name(ctx);
+            if proc_syms.contains(&sym) {
+                return Err(CompilerError::VerificationError {
+                    msg: format!("duplicate miden.proc symbol: {sym}"),
+                });
+            }
+            proc_syms.push(sym);
+        }
+        let main_proc_sym = self.get_main_proc_sym(ctx);
+        if !proc_syms.contains(&main_proc_sym) {
+            return Err(CompilerError::VerificationError {
+                msg: format!("main proc {main_proc_sym} is not defined in miden.program"),
+            });
+        }
+        for (export_name, proc_sym) in self.get_exports(ctx) {
+            if !proc_syms.contains(&proc_sym) {
+                return Err(CompilerError::VerificationError {
+                    msg: format!(
+                        "exported proc {export_name} ({proc_sym}) is not defined in miden.program"
+                    ),
+                });
+            }
+        }
+        let mut unresolved_callees = Vec::new();
+        self.get_operation()
+            .walk_only::<ExecOp>(ctx, WalkOrder::PreOrder, &mut |exec_op| {
+                let callee_sym = exec_op.get_callee_sym(ctx);
+                if exec_op.get_callee_module(ctx).is_none() && !proc_syms.contains(&callee_sym) {
+                    unresolved_callees.push(callee_sym);
+                }
+                WalkResult::Advance
+            });
+        if let Some(callee_sym) = unresolved_callees.first() {
+            return Err(CompilerError::VerificationError {
+                msg: format!("miden.exec callee {callee_sym} is not defined in miden.program"),
+            });
+        }
         self.get_region(ctx).deref(ctx).verify(ctx)
     }
 }
@@ -65,6 +149,10 @@ impl Verify for ProgramOp {
 impl ProgramOp {
     /// Attribute key for the main proc symbol.
     pub const ATTR_KEY_MAIN_PROC_SYM: &'static str = "program.main_proc_sym";
+    /// Attribute key for the names of the exported procs.
+    pub const ATTR_KEY_EXPORT_NAMES: &'static str = "program.export_names";
+    /// Attribute key for the symbols of the exported procs (in the order of the names).
+    pub const ATTR_KEY_EXPORT_PROC_SYMS: &'static str = "program.export_proc_syms";
 
     /// Create a new [ProgramOP].
     /// The returned programm has a single [crate::region::Region] with a single (BasicBlock)[crate::basic_block::BasicBlock].
@@ -97,6 +185,43 @@ impl ProgramOp {
         String::from(attr.downcast_ref::<StringAttr>().unwrap().clone())
     }
 
+    /// Set the exported procs (export name, proc symbol).
+    pub fn set_exports(&self, ctx: &mut Context, exports: Vec<(String, String)>) {
+        let (names, proc_syms): (Vec<String>, Vec<String>) = exports.into_iter().unzip();
+        let mut self_op = self.get_operation().deref_mut(ctx);
+        self_op.attributes.insert(
+            Self::ATTR_KEY_EXPORT_NAMES,
+            VecAttr::create(names.into_iter().map(StringAttr::create).collect()),
+        );
+        self_op.attributes.insert(
+            Self::ATTR_KEY_EXPORT_PROC_SYMS,
+            VecAttr::create(proc_syms.into_iter().map(StringAttr::create).collect()),
+        );
+    }
+
+    /// Return the exported procs (export name, proc symbol).
+    pub fn get_exports(&self, ctx: &Context) -> Vec<(String, String)> {
+        let self_op = self.get_operation().deref(ctx);
+        let strings = |key: &str| -> Vec<String> {
+            self_op
+                .attributes
+                .get(key)
+                .and_then(|attr| attr.downcast_ref::<VecAttr>())
+                .map_or(Vec::new(), |v_attr| {
+                    v_attr
+                        .0
+                        .iter()
+                        .filter_map(|attr| attr.downcast_ref::<StringAttr>())
+                        .map(|s_attr| String::from(s_attr.clone()))
+                        .collect()
+                })
+        };
+        strings(Self::ATTR_KEY_EXPORT_NAMES)
+            .into_iter()
+            .zip(strings(Self::ATTR_KEY_EXPORT_PROC_SYMS))
+            .collect()
+    }
+
     /// Add an [ProcOp] into this program.
     pub fn add_proc_op(&self, ctx: &mut Context, proc_op: ProcOp) {
         // TODO: check for procedure name clashes with existing procedures?
@@ -116,7 +241,12 @@ declare_op!(
 );
 
 impl ProcOp {
-    /// Create a new [FuncOp].
+    /// Attribute key for the number of the procedure locals
+    pub const ATTR_KEY_NUM_LOCALS: &str = "proc.num_locals";
+    /// Maximum number of the locals in a Miden procedure
+    pub const MAX_LOCALS: u32 = 1 << 16;
+
+    /// Create a new [ProcOp] without locals.
     /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
     /// The returned function has a single region with an empty `entry` block.
     pub fn new_unlinked(ctx: &mut Context, name: &str) -> ProcOp {
@@ -128,9 +258,37 @@ impl ProcOp {
         let body = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
         body.insert_at_front(region, ctx);
         opop.set_symbol_name(ctx, name);
+        opop.set_num_locals(ctx, 0);
         opop
     }
 
+    /// Get the number of the procedure locals.
+    pub fn get_num_locals(&self, ctx: &Context) -> u32 {
+        let op = self.get_operation().deref(ctx);
+        #[allow(clippy::expect_used)]
+        let value = op
+            .attributes
+            .get(Self::ATTR_KEY_NUM_LOCALS)
+            .expect("no attribute found");
+        #[allow(clippy::expect_used)]
+        let apint: ApInt = value
+            .downcast_ref::<IntegerAttr>()
+            .expect("number of locals is not an IntegerAttr")
+            .clone()
+            .into();
+        #[allow(clippy::expect_used)]
+        apint.try_to_u32().expect("number of locals is not u32")
+    }
+
+    /// Set the number of the procedure locals.
+    pub fn set_num_locals(&self, ctx: &mut Context, num_locals: u32) {
+        let attr = u32_attr(ctx, num_locals);
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_NUM_LOCALS, attr);
+    }
+
     /// Get the entry block of this function.
     pub fn get_entry_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
         #[allow(clippy::unwrap_used)]
@@ -177,6 +335,37 @@ impl Verify for ProcOp {
             });
         }
         self.verify_interfaces(ctx)?;
+        let Some(num_locals) = op
+            .attributes
+            .get(Self::ATTR_KEY_NUM_LOCALS)
+            .and_then(|attr| attr.downcast_ref::<IntegerAttr>()) else {
+            return Err(CompilerError::VerificationError {
+                msg: format!(
+                    "miden.proc {} has no number of locals",
+                    self.get_symbol_name(ctx)
+                ),
+            });
+        };
+        let num_locals: ApInt = num_locals.clone().into();
+        if num_locals
+            .try_to_u32()
+            .map_or(true, |num_locals| num_locals > Self::MAX_LOCALS)
+        {
+            return Err(CompilerError::VerificationError {
+                msg: format!(
+                    "miden.proc {} has too many locals",
+                    self.get_symbol_name(ctx)
+                ),
+            });
+        }
+        if self.get_region(ctx).deref(ctx).iter(ctx).count() != 1 {
+            return Err(CompilerError::VerificationError {
+                msg: format!(
+                    "miden.proc {} must have a single block",
+                    self.get_symbol_name(ctx)
+                ),
+            });
+        }
         self.get_entry_block(ctx).verify(ctx)?;
         Ok(())
     }
@@ -230,6 +419,17 @@ impl DisplayWithContext for ConstantOp {
 impl Verify for ConstantOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
         let op = &*self.get_operation().deref(ctx);
+        let Some(value) = op.attributes.get(Self::ATTR_KEY_VALUE) else {
+            return Err(CompilerError::VerificationError {
+                msg: "miden.constant has no value".to_string(),
+            });
+        };
+        if !value.is::<FieldElemAttr>() {
+            return Err(CompilerError::VerificationError {
+                msg: "Unexpected constant type".to_string(),
+            });
+        }
+        value.verify(ctx)?;
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -288,6 +488,8 @@ impl Verify for AddOp {
 
 declare_op!(
     /// Call miden exec on provided symbol.
+    /// The callee is either a proc of the program or a library procedure
+    /// given by its full path (e.g. `std::math::u64::wrapping_add`).
     ///
     /// Attributes:
     ///
@@ -315,6 +517,11 @@ impl ExecOp {
         callee_sym.with_ctx(ctx).to_string()
     }
 
+    /// Get the module path of the callee if it is a library procedure.
+    pub fn get_callee_module(&self, ctx: &Context) -> Option<String> {
+        library_proc_module(&self.get_callee_sym(ctx)).map(str::to_string)
+    }
+
     /// Create a new [CallOp]. The underlying [Operation] is not linked to a
     /// [BasicBlock](crate::basic_block::BasicBlock).
     pub fn new_unlinked(ctx: &mut Context, callee_name: FuncSym) -> ExecOp {
@@ -341,6 +548,15 @@ impl DisplayWithContext for ExecOp {
 impl Verify for ExecOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
         let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_CALLEE_SYM)
+            .map_or(false, |attr| attr.is::<StringAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "miden.exec has no callee symbol".to_string(),
+            });
+        }
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -362,102 +578,828 @@ impl CallOpInterface for ExecOp {
     }
 }
 
+/// Return the module path of a library procedure symbol (`std::math::u64` for
+/// `std::math::u64::wrapping_add`) or `None` for a proc symbol of the program.
+pub fn library_proc_module(proc_sym: &str) -> Option<&str> {
+    proc_sym.rsplit_once("::").map(|(module, _)| module)
+}
+
+/// Check that the op has no operands and results (the values are on the stack).
+fn verify_stack_op(op: &Operation, opid: pliron::op::OpId) -> Result<(), CompilerError> {
+    if op.get_opid() != opid {
+        return Err(CompilerError::VerificationError {
+            msg: "Incorrect OpId".to_string(),
+        });
+    }
+    if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+        return Err(CompilerError::VerificationError {
+            msg: "Incorrect number of results or operands".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Declares an op without attributes (the operands are on the stack).
+macro_rules! stack_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal) => {
+        declare_op!($(#[$outer])* $name, $op_name, "miden");
+
+        impl $name {
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock](crate::basic_block::BasicBlock).
+            pub fn new_unlinked(ctx: &mut Context) -> $name {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                $name { op }
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", self.get_opid().with_ctx(ctx))
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                verify_stack_op(&self.get_operation().deref(ctx), Self::get_opid_static())
+            }
+        }
+    };
+}
+
+/// Declares an op with the index of the stack item as an attribute.
+macro_rules! stack_index_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal, $attr_key:literal) => {
+        declare_op!($(#[$outer])* $name, $op_name, "miden");
+
+        impl $name {
+            /// Attribute key for the index of the stack item
+            pub const ATTR_KEY_INDEX: &str = $attr_key;
+
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock](crate::basic_block::BasicBlock).
+            pub fn new_unlinked(ctx: &mut Context, index: u8) -> $name {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                let attr = u32_attr(ctx, index.into());
+                op.deref_mut(ctx)
+                    .attributes
+                    .insert(Self::ATTR_KEY_INDEX, attr);
+                $name { op }
+            }
+
+            /// Get the index of the stack item.
+            pub fn get_index(&self, ctx: &Context) -> u8 {
+                let op = self.get_operation().deref(ctx);
+                #[allow(clippy::expect_used)]
+                let value = op
+                    .attributes
+                    .get(Self::ATTR_KEY_INDEX)
+                    .expect("no attribute found");
+                #[allow(clippy::expect_used)]
+                let apint: ApInt = value
+                    .downcast_ref::<IntegerAttr>()
+                    .expect("index is not an IntegerAttr")
+                    .clone()
+                    .into();
+                #[allow(clippy::expect_used)]
+                apint.try_to_u8().expect("index is not u8")
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{} {}", self.get_opid().with_ctx(ctx), self.get_index(ctx))
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                let op = &*self.get_operation().deref(ctx);
+                let Some(index) = op
+                    .attributes
+                    .get(Self::ATTR_KEY_INDEX)
+                    .and_then(|attr| attr.downcast_ref::<IntegerAttr>()) else {
+                    return Err(CompilerError::VerificationError {
+                        msg: format!("{} has no index", self.get_opid().with_ctx(ctx)),
+                    });
+                };
+                let index: ApInt = index.clone().into();
+                if index.try_to_u8().map_or(true, |index| index > 15) {
+                    return Err(CompilerError::VerificationError {
+                        msg: format!(
+                            "{} index is out of the top 16 stack items",
+                            self.get_opid().with_ctx(ctx)
+                        ),
+                    });
+                }
+                verify_stack_op(op, Self::get_opid_static())
+            }
+        }
+    };
+}
+
+/// Declares an op with the index of the procedure local as an attribute.
+macro_rules! local_index_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal, $attr_key:literal) => {
+        declare_op!($(#[$outer])* $name, $op_name, "miden");
+
+        impl $name {
+            /// Attribute key for the index of the local
+            pub const ATTR_KEY_INDEX: &str = $attr_key;
+
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock](crate::basic_block::BasicBlock).
+            pub fn new_unlinked(ctx: &mut Context, index: u32) -> $name {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                let attr = u32_attr(ctx, index);
+                op.deref_mut(ctx)
+                    .attributes
+                    .insert(Self::ATTR_KEY_INDEX, attr);
+                $name { op }
+            }
+
+            /// Get the index of the local.
+            pub fn get_index(&self, ctx: &Context) -> u32 {
+                let op = self.get_operation().deref(ctx);
+                #[allow(clippy::expect_used)]
+                let value = op
+                    .attributes
+                    .get(Self::ATTR_KEY_INDEX)
+                    .expect("no attribute found");
+                #[allow(clippy::expect_used)]
+                let apint: ApInt = value
+                    .downcast_ref::<IntegerAttr>()
+                    .expect("index is not an IntegerAttr")
+                    .clone()
+                    .into();
+                #[allow(clippy::expect_used)]
+                apint.try_to_u32().expect("index is not u32")
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{} {}", self.get_opid().with_ctx(ctx), self.get_index(ctx))
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                let op = &*self.get_operation().deref(ctx);
+                let Some(index) = op
+                    .attributes
+                    .get(Self::ATTR_KEY_INDEX)
+                    .and_then(|attr| attr.downcast_ref::<IntegerAttr>()) else {
+                    return Err(CompilerError::VerificationError {
+                        msg: format!("{} has no index", self.get_opid().with_ctx(ctx)),
+                    });
+                };
+                let index: ApInt = index.clone().into();
+                if index
+                    .try_to_u32()
+                    .map_or(true, |index| index >= ProcOp::MAX_LOCALS)
+                {
+                    return Err(CompilerError::VerificationError {
+                        msg: format!(
+                            "{} index is out of the procedure locals",
+                            self.get_opid().with_ctx(ctx)
+                        ),
+                    });
+                }
+                verify_stack_op(op, Self::get_opid_static())
+            }
+        }
+    };
+}
+
+stack_op!(
+    /// Pops `b` and `a`, pushes `a - b` (field subtraction)
+    SubOp,
+    "sub"
+);
+
+stack_op!(
+    /// Pops `b` and `a`, pushes `a * b` (field multiplication)
+    MulOp,
+    "mul"
+);
+
+stack_op!(
+    /// Pops two u32 values, pushes their sum modulo 2^32
+    U32WrappingAddOp,
+    "u32wrapping_add"
+);
+
+stack_op!(
+    /// Pops `b` and `a` (u32), pushes `a - b` modulo 2^32
+    U32WrappingSubOp,
+    "u32wrapping_sub"
+);
+
+stack_op!(
+    /// Pops two u32 values, pushes their product modulo 2^32
+    U32WrappingMulOp,
+    "u32wrapping_mul"
+);
+
+stack_op!(
+    /// Pops two u32 values, pushes their bitwise AND (fails if the values are not u32)
+    U32CheckedAndOp,
+    "u32checked_and"
+);
+
+stack_op!(
+    /// Pops two u32 values, pushes their bitwise XOR (fails if the values are not u32)
+    U32CheckedXorOp,
+    "u32checked_xor"
+);
+
+stack_op!(
+    /// Pops `b` and `a` (u32), pushes `(a << b) mod 2^32` (fails if `b` > 31)
+    U32CheckedShlOp,
+    "u32checked_shl"
+);
+
+stack_op!(
+    /// Pops `b` and `a` (u32), pushes `a >> b` (fails if `b` > 31)
+    U32CheckedShrOp,
+    "u32checked_shr"
+);
+
+stack_op!(
+    /// Pops two u32 values, pushes their bitwise OR (fails if the values are not u32)
+    U32CheckedOrOp,
+    "u32checked_or"
+);
+
+stack_op!(
+    /// Pops the u32 value, pushes its bitwise NOT (fails if the value is not u32)
+    U32CheckedNotOp,
+    "u32checked_not"
+);
+
+stack_op!(
+    /// Pops `b` and `a` (u32), pushes `a / b` rounded down (fails if `b` is 0)
+    U32CheckedDivOp,
+    "u32checked_div"
+);
+
+stack_op!(
+    /// Pops `b` and `a` (u32), pushes `a mod b` (fails if `b` is 0)
+    U32CheckedModOp,
+    "u32checked_mod"
+);
+
+stack_op!(
+    /// Pops `b` and `a` (u32), pushes `a` rotated left by `b` bits (fails if `b` > 31)
+    U32CheckedRotlOp,
+    "u32checked_rotl"
+);
+
+stack_op!(
+    /// Pops `b` and `a` (u32), pushes `a` rotated right by `b` bits (fails if `b` > 31)
+    U32CheckedRotrOp,
+    "u32checked_rotr"
+);
+
+stack_op!(
+    /// Pops `b` and `a` (u32), pushes 1 if `a < b`, otherwise 0
+    U32CheckedLtOp,
+    "u32checked_lt"
+);
+
+stack_op!(
+    /// Pops `b` and `a` (u32), pushes 1 if `a <= b`, otherwise 0
+    U32CheckedLteOp,
+    "u32checked_lte"
+);
+
+stack_op!(
+    /// Pops `b` and `a` (u32), pushes 1 if `a > b`, otherwise 0
+    U32CheckedGtOp,
+    "u32checked_gt"
+);
+
+stack_op!(
+    /// Pops `b` and `a` (u32), pushes 1 if `a >= b`, otherwise 0
+    U32CheckedGteOp,
+    "u32checked_gte"
+);
+
+stack_op!(
+    /// Pops `a`, pushes its low 32 bits and then its high 32 bits (on top)
+    U32SplitOp,
+    "u32split"
+);
+
+stack_op!(
+    /// Pops `b` and `a`, pushes 1 if `a != b`, otherwise 0
+    NeqOp,
+    "neq"
+);
+
+stack_op!(
+    /// Pops `b` and `a`, pushes 1 if `a == b`, otherwise 0
+    EqOp,
+    "eq"
+);
+
+stack_op!(
+    /// Pops `b` and `a`, pushes 1 if `a < b` (as integers in `[0, p)`), otherwise 0
+    LtOp,
+    "lt"
+);
+
+stack_op!(
+    /// Pops `b` and `a`, pushes 1 if `a > b` (as integers in `[0, p)`), otherwise 0
+    GtOp,
+    "gt"
+);
+
+stack_op!(
+    /// Pops the top stack item, fails if it's not 1
+    AssertOp,
+    "assert"
+);
+
+stack_op!(
+    /// Pushes the current depth of the stack
+    SdepthOp,
+    "sdepth"
+);
+
 declare_op!(
-    /// Push local variable value with the given index onto the stack.
-    ///
-    /// Attributes:
-    ///
-    /// | key | value |
-    /// |-----|-------|
-    /// |[ATTR_KEY_INDEX](Self::ATTR_KEY_INDEX) | [IntegerAttr] |
-    ///
-    LocLoadOp,
-    "loc.load",
+    /// Pushes the given number of values from the advice stack (`adv_push.count`)
+    AdvPushOp,
+    "adv_push",
     "miden"
 );
 
-impl LocLoadOp {
-    /// Attribute key for the index
-    pub const ATTR_KEY_INDEX: &str = "loc.load.index";
+impl AdvPushOp {
+    /// Attribute key for the number of values
+    pub const ATTR_KEY_COUNT: &str = "adv_push.count";
+
+    /// Max number of values pushed by a single `adv_push`
+    pub const MAX_COUNT: u32 = 16;
+
+    /// Create a new [AdvPushOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context, count: u32) -> AdvPushOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        let attr = u32_attr(ctx, count);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_COUNT, attr);
+        AdvPushOp { op }
+    }
 
-    /// Get the index of the local variable.
-    pub fn get_index(&self, ctx: &Context) -> AttrObj {
+    /// Get the number of values.
+    pub fn get_count(&self, ctx: &Context) -> u32 {
         let op = self.get_operation().deref(ctx);
         #[allow(clippy::expect_used)]
         let value = op
             .attributes
-            .get(Self::ATTR_KEY_INDEX)
+            .get(Self::ATTR_KEY_COUNT)
             .expect("no attribute found");
-        attribute::clone::<IntegerAttr>(value)
+        #[allow(clippy::expect_used)]
+        let apint: ApInt = value
+            .downcast_ref::<IntegerAttr>()
+            .expect("count is not an IntegerAttr")
+            .clone()
+            .into();
+        #[allow(clippy::expect_used)]
+        apint.try_to_u32().expect("count is not u32")
     }
+}
 
-    /// Get the index of the local variable as u32.
-    #[allow(clippy::unwrap_used)]
-    pub fn get_index_as_u32(&self, ctx: &Context) -> u32 {
-        let attr = self.get_index(ctx);
-        #[allow(clippy::unwrap_used)]
-        let apint: ApInt = attr.downcast_ref::<IntegerAttr>().unwrap().clone().into();
-        apint.try_to_u32().unwrap()
+impl DisplayWithContext for AdvPushOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_count(ctx)
+        )
     }
+}
 
-    /// Create a new [LocalGetOp].
-    pub fn new_unlinked(ctx: &mut Context, index: AttrObj) -> LocLoadOp {
-        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-        op.deref_mut(ctx)
+impl Verify for AdvPushOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        let count = op
             .attributes
-            .insert(Self::ATTR_KEY_INDEX, index);
-        LocLoadOp { op }
+            .get(Self::ATTR_KEY_COUNT)
+            .and_then(|attr| attr.downcast_ref::<IntegerAttr>())
+            .and_then(|count| ApInt::from(count.clone()).try_to_u32().ok());
+        if count.map_or(true, |count| count == 0 || count > Self::MAX_COUNT) {
+            return Err(CompilerError::VerificationError {
+                msg: format!("miden.adv_push count must be in 1..={}", Self::MAX_COUNT),
+            });
+        }
+        verify_stack_op(op, Self::get_opid_static())
     }
 }
 
-impl DisplayWithContext for LocLoadOp {
+stack_op!(
+    /// Overwrites the top 4 stack items with the next word from the advice stack
+    /// (the first popped value ends up the deepest)
+    AdvLoadWOp,
+    "adv_loadw"
+);
+
+stack_op!(
+    /// Pushes 4 zeros
+    PadWOp,
+    "padw"
+);
+
+stack_op!(
+    /// Pops the top stack item
+    DropOp,
+    "drop"
+);
+
+stack_op!(
+    /// Pops the address `a`, pushes the first element of the memory word at `a`
+    MemLoadOp,
+    "mem_load"
+);
+
+stack_op!(
+    /// Pops the address `a` and the value `v`, stores `v` as the first element
+    /// of the memory word at `a`
+    MemStoreOp,
+    "mem_store"
+);
+
+stack_op!(
+    /// Pops the address `a` and the top word, pushes the memory word at `a`
+    /// (overwrites the top word)
+    MemLoadWOp,
+    "mem_loadw"
+);
+
+stack_op!(
+    /// Pops the address `a`, stores the top word as the memory word at `a`
+    /// (the word stays on the stack)
+    MemStoreWOp,
+    "mem_storew"
+);
+
+stack_index_op!(
+    /// Pushes a copy of the stack item at the given index
+    DupOp,
+    "dup",
+    "dup.index"
+);
+
+stack_index_op!(
+    /// Swaps the top stack item with the item at the given index
+    SwapOp,
+    "swap",
+    "swap.index"
+);
+
+stack_index_op!(
+    /// Moves the stack item at the given index to the top
+    MovUpOp,
+    "movup",
+    "movup.index"
+);
+
+stack_index_op!(
+    /// Moves the top stack item to the given index
+    MovDnOp,
+    "movdn",
+    "movdn.index"
+);
+
+local_index_op!(
+    /// Pushes the first element of the procedure local with the given index
+    LocLoadOp,
+    "loc_load",
+    "loc_load.index"
+);
+
+local_index_op!(
+    /// Pops the top stack item and stores it as the first element of the procedure local
+    /// with the given index
+    LocStoreOp,
+    "loc_store",
+    "loc_store.index"
+);
+
+local_index_op!(
+    /// Stores the top stack word (without popping it) into the procedure local
+    /// with the given index
+    LocStoreWOp,
+    "loc_storew",
+    "loc_storew.index"
+);
+
+local_index_op!(
+    /// Pushes the memory address of the procedure local with the given index
+    LocAddrOp,
+    "locaddr",
+    "locaddr.index"
+);
+
+declare_op!(
+    /// Pops the condition and executes the body while the condition is 1.
+    /// The body must leave the next condition on top of the stack (`while.true`).
+    WhileOp,
+    "while",
+    "miden"
+);
+
+impl WhileOp {
+    /// Create a new [WhileOp] with an empty body.
+    /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> WhileOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 1);
+        let opop = WhileOp { op };
+        let region = opop.get_region(ctx);
+        let body = BasicBlock::new(ctx, Some("body".to_string()), vec![]);
+        body.insert_at_front(region, ctx);
+        opop
+    }
+
+    /// Get the body block.
+    pub fn get_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        #[allow(clippy::unwrap_used)]
+        self.get_region(ctx).deref(ctx).get_head().unwrap()
+    }
+
+    /// Get an iterator over all operations of the body.
+    pub fn op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
+        self.get_region(ctx)
+            .deref(ctx)
+            .iter(ctx)
+            .flat_map(|bb| bb.deref(ctx).iter(ctx))
+    }
+}
+
+impl OneRegionInterface for WhileOp {}
+
+impl DisplayWithContext for WhileOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let region = self.get_region(ctx).with_ctx(ctx).to_string();
         write!(
             f,
-            "{} {}",
+            "{} {{\n{}}}",
             self.get_opid().with_ctx(ctx),
-            self.get_index(ctx).with_ctx(ctx)
+            indent::indent_all_by(2, region),
         )
     }
 }
 
-impl Verify for LocLoadOp {
+impl Verify for WhileOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
-        let index = self.get_index(ctx);
-        if let Ok(index_attr) = index.downcast::<IntegerAttr>() {
-            #[allow(clippy::unwrap_used)]
-            if index_attr.get_type()
-                != IntegerType::get_existing(ctx, 32, Signedness::Unsigned).unwrap()
-            {
-                return Err(CompilerError::VerificationError {
-                    msg: "Expected u32 for index".to_string(),
-                });
-            }
-        } else {
+        verify_stack_op(&self.get_operation().deref(ctx), Self::get_opid_static())?;
+        self.verify_interfaces(ctx)?;
+        if self.get_region(ctx).deref(ctx).iter(ctx).count() != 1 {
             return Err(CompilerError::VerificationError {
-                msg: "Unexpected index type".to_string(),
+                msg: "miden.while must have a single block".to_string(),
             });
-        };
+        }
+        self.get_block(ctx).verify(ctx)
+    }
+}
+
+declare_op!(
+    /// Executes the body the given number of times (`repeat.count`).
+    RepeatOp,
+    "repeat",
+    "miden"
+);
+
+impl RepeatOp {
+    /// Attribute key for the number of iterations
+    pub const ATTR_KEY_COUNT: &str = "repeat.count";
+
+    /// Create a new [RepeatOp] with an empty body.
+    /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context, count: u32) -> RepeatOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 1);
+        let attr = u32_attr(ctx, count);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_COUNT, attr);
+        let opop = RepeatOp { op };
+        let region = opop.get_region(ctx);
+        let body = BasicBlock::new(ctx, Some("body".to_string()), vec![]);
+        body.insert_at_front(region, ctx);
+        opop
+    }
+
+    /// Get the number of iterations.
+    pub fn get_count(&self, ctx: &Context) -> u32 {
+        let op = self.get_operation().deref(ctx);
+        #[allow(clippy::expect_used)]
+        let value = op
+            .attributes
+            .get(Self::ATTR_KEY_COUNT)
+            .expect("no attribute found");
+        #[allow(clippy::expect_used)]
+        let apint: ApInt = value
+            .downcast_ref::<IntegerAttr>()
+            .expect("count is not an IntegerAttr")
+            .clone()
+            .into();
+        #[allow(clippy::expect_used)]
+        apint.try_to_u32().expect("count is not u32")
+    }
+
+    /// Get the body block.
+    pub fn get_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        #[allow(clippy::unwrap_used)]
+        self.get_region(ctx).deref(ctx).get_head().unwrap()
+    }
+
+    /// Get an iterator over all operations of the body.
+    pub fn op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
+        self.get_region(ctx)
+            .deref(ctx)
+            .iter(ctx)
+            .flat_map(|bb| bb.deref(ctx).iter(ctx))
+    }
+}
+
+impl OneRegionInterface for RepeatOp {}
+
+impl DisplayWithContext for RepeatOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let region = self.get_region(ctx).with_ctx(ctx).to_string();
+        write!(
+            f,
+            "{} {} {{\n{}}}",
+            self.get_opid().with_ctx(ctx),
+            self.get_count(ctx),
+            indent::indent_all_by(2, region),
+        )
+    }
+}
+
+impl Verify for RepeatOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
         let op = &*self.get_operation().deref(ctx);
-        if op.get_opid() != Self::get_opid_static() {
+        verify_stack_op(op, Self::get_opid_static())?;
+        let count = op
+            .attributes
+            .get(Self::ATTR_KEY_COUNT)
+            .and_then(|attr| attr.downcast_ref::<IntegerAttr>())
+            .and_then(|count| ApInt::from(count.clone()).try_to_u32().ok());
+        if count.map_or(true, |count| count == 0) {
             return Err(CompilerError::VerificationError {
-                msg: "Incorrect OpId".to_string(),
+                msg: "miden.repeat must have a positive count".to_string(),
             });
         }
-        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+        self.verify_interfaces(ctx)?;
+        if self.get_region(ctx).deref(ctx).iter(ctx).count() != 1 {
             return Err(CompilerError::VerificationError {
-                msg: "Incorrect number of results or operands".to_string(),
+                msg: "miden.repeat must have a single block".to_string(),
             });
         }
-        Ok(())
+        self.get_block(ctx).verify(ctx)
+    }
+}
+
+declare_op!(
+    /// Pops the condition and executes the "then" block if it's 1, the "else" block if it's 0
+    /// (`if.true`). The "else" block can be empty.
+    IfOp,
+    "if",
+    "miden"
+);
+
+impl IfOp {
+    /// Create a new [IfOp] with empty "then" and "else" blocks.
+    /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> IfOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 2);
+        for (index, label) in ["then", "else"].into_iter().enumerate() {
+            #[allow(clippy::expect_used)]
+            let region = op.deref(ctx).get_region(index).expect("no region");
+            let block = BasicBlock::new(ctx, Some(label.to_string()), vec![]);
+            block.insert_at_front(region, ctx);
+        }
+        IfOp { op }
+    }
+
+    /// Get the block executed if the condition is 1.
+    pub fn get_then_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        self.get_block(ctx, 0)
+    }
+
+    /// Get the block executed if the condition is 0.
+    pub fn get_else_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        self.get_block(ctx, 1)
+    }
+
+    #[allow(clippy::expect_used)]
+    fn get_block(&self, ctx: &Context, region_index: usize) -> Ptr<BasicBlock> {
+        self.get_operation()
+            .deref(ctx)
+            .get_region(region_index)
+            .and_then(|region| region.deref(ctx).get_head())
+            .expect("miden.if has no block")
+    }
+
+    /// Get an iterator over the operations of the "then" block.
+    pub fn then_op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
+        self.get_then_block(ctx).deref(ctx).iter(ctx)
+    }
+
+    /// Get an iterator over the operations of the "else" block.
+    pub fn else_op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
+        self.get_else_block(ctx).deref(ctx).iter(ctx)
+    }
+}
+
+impl DisplayWithContext for IfOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let then_block = self.get_then_block(ctx).with_ctx(ctx).to_string();
+        let else_block = self.get_else_block(ctx).with_ctx(ctx).to_string();
+        write!(
+            f,
+            "{} {{\n{}}} {{\n{}}}",
+            self.get_opid().with_ctx(ctx),
+            indent::indent_all_by(2, then_block),
+            indent::indent_all_by(2, else_block),
+        )
+    }
+}
+
+impl Verify for IfOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_stack_op(&self.get_operation().deref(ctx), Self::get_opid_static())?;
+        let op = &*self.get_operation().deref(ctx);
+        for region_index in 0..2 {
+            let Some(region) = op.get_region(region_index) else {
+                return Err(CompilerError::VerificationError {
+                    msg: "miden.if must have the then and else regions".to_string(),
+                });
+            };
+            if region.deref(ctx).iter(ctx).count() != 1 {
+                return Err(CompilerError::VerificationError {
+                    msg: "miden.if regions must have a single block".to_string(),
+                });
+            }
+        }
+        self.get_then_block(ctx).verify(ctx)?;
+        self.get_else_block(ctx).verify(ctx)
     }
 }
 
 pub(crate) fn register(ctx: &mut Context, dialect: &mut Dialect) {
     ConstantOp::register(ctx, dialect);
     AddOp::register(ctx, dialect);
+    SubOp::register(ctx, dialect);
+    MulOp::register(ctx, dialect);
+    U32WrappingAddOp::register(ctx, dialect);
+    U32WrappingSubOp::register(ctx, dialect);
+    U32WrappingMulOp::register(ctx, dialect);
+    U32CheckedAndOp::register(ctx, dialect);
+    U32CheckedXorOp::register(ctx, dialect);
+    U32CheckedShlOp::register(ctx, dialect);
+    U32CheckedShrOp::register(ctx, dialect);
+    U32CheckedOrOp::register(ctx, dialect);
+    U32CheckedNotOp::register(ctx, dialect);
+    U32CheckedDivOp::register(ctx, dialect);
+    U32CheckedModOp::register(ctx, dialect);
+    U32CheckedRotlOp::register(ctx, dialect);
+    U32CheckedRotrOp::register(ctx, dialect);
+    U32CheckedLtOp::register(ctx, dialect);
+    U32CheckedLteOp::register(ctx, dialect);
+    U32CheckedGtOp::register(ctx, dialect);
+    U32CheckedGteOp::register(ctx, dialect);
+    U32SplitOp::register(ctx, dialect);
+    DupOp::register(ctx, dialect);
+    SwapOp::register(ctx, dialect);
+    MovUpOp::register(ctx, dialect);
+    MovDnOp::register(ctx, dialect);
+    NeqOp::register(ctx, dialect);
+    EqOp::register(ctx, dialect);
+    LtOp::register(ctx, dialect);
+    GtOp::register(ctx, dialect);
+    AssertOp::register(ctx, dialect);
+    SdepthOp::register(ctx, dialect);
+    AdvPushOp::register(ctx, dialect);
+    AdvLoadWOp::register(ctx, dialect);
+    PadWOp::register(ctx, dialect);
+    DropOp::register(ctx, dialect);
+    MemLoadOp::register(ctx, dialect);
+    MemStoreOp::register(ctx, dialect);
+    MemLoadWOp::register(ctx, dialect);
+    MemStoreWOp::register(ctx, dialect);
+    WhileOp::register(ctx, dialect);
+    RepeatOp::register(ctx, dialect);
+    IfOp::register(ctx, dialect);
     ExecOp::register(ctx, dialect);
     LocLoadOp::register(ctx, dialect);
+    LocStoreOp::register(ctx, dialect);
+    LocStoreWOp::register(ctx, dialect);
+    LocAddrOp::register(ctx, dialect);
     ProgramOp::register(ctx, dialect);
     ProcOp::register(ctx, dialect);
 }
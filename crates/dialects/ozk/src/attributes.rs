@@ -54,8 +54,23 @@ impl DisplayWithContext for FieldElemAttr {
 }
 
 impl Verify for FieldElemAttr {
-    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
-        todo!()
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let ty = self.ty.deref(ctx);
+        let Some(felt_type) = ty.downcast_ref::<FieldElemType>() else {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected felt type for FieldElem attribute".to_string(),
+            });
+        };
+        let field_matches = match self.val {
+            FieldElem::Oxfoi(_) => felt_type.get_field() == Field::Oxfoi,
+            FieldElem::P231m1(_) => felt_type.get_field() == Field::P231m1,
+        };
+        if !field_matches {
+            return Err(CompilerError::VerificationError {
+                msg: "FieldElem value does not belong to the field of its type".to_string(),
+            });
+        }
+        Ok(())
     }
 }
 
@@ -185,3 +200,9 @@ pub fn apint_to_u32(value: ApInt) -> u32 {
     #[allow(clippy::expect_used)]
     i.try_to_u32().expect("unsigned 32-bit integer")
 }
+
+pub fn apint_to_i64(value: ApInt) -> i64 {
+    let i = Int::from(value);
+    #[allow(clippy::expect_used)]
+    i.try_to_i64().expect("64-bit integer")
+}
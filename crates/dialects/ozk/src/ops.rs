@@ -1,8 +1,13 @@
 #![allow(clippy::expect_used)]
 
+//! The mid-level `ozk` dialect: target-independent stack ops that Wasm is lowered to
+//! (integer ops with explicit wrap semantics, field ops, memory, locals,
+//! structured control flow and calls) before lowering to a particular VM.
+
 use pliron::attribute;
 use pliron::attribute::attr_cast;
 use pliron::attribute::AttrObj;
+use pliron::basic_block::BasicBlock;
 use pliron::common_traits::DisplayWithContext;
 use pliron::common_traits::Verify;
 use pliron::context::Context;
@@ -13,9 +18,13 @@ use pliron::dialects::builtin::attr_interfaces::TypedAttrInterface;
 use pliron::dialects::builtin::attributes::IntegerAttr;
 use pliron::dialects::builtin::attributes::StringAttr;
 use pliron::dialects::builtin::attributes::TypeAttr;
+use pliron::dialects::builtin::op_interfaces::OneRegionInterface;
 use pliron::dialects::builtin::types::FunctionType;
+use pliron::dialects::builtin::types::IntegerType;
 use pliron::error::CompilerError;
+use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
+use pliron::op::OpId;
 use pliron::operation::Operation;
 use pliron::r#type::Type;
 use pliron::r#type::TypeObj;
@@ -162,6 +171,15 @@ impl DisplayWithContext for SwapOp {
 impl Verify for SwapOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
         let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_INDEX)
+            .map_or(false, |attr| attr.is::<IntegerAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "ozk.swap has no index".to_string(),
+            });
+        }
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -176,6 +194,96 @@ impl Verify for SwapOp {
     }
 }
 
+/// Declares an op moving the stack item with the given index (see [SwapOp]).
+macro_rules! ord16_index_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal, $attr_key:literal) => {
+        declare_op!($(#[$outer])* $name, $op_name, "ozk");
+
+        impl $name {
+            pub const ATTR_KEY_INDEX: &str = $attr_key;
+
+            /// Get the index
+            pub fn get_index(&self, ctx: &Context) -> Ord16 {
+                let op = self.get_operation().deref(ctx);
+                #[allow(clippy::expect_used)]
+                let value = op
+                    .attributes
+                    .get(Self::ATTR_KEY_INDEX)
+                    .expect("no attribute for index found");
+                let value_u32 = apint_to_i32(
+                    value
+                        .downcast_ref::<IntegerAttr>()
+                        .expect("index is not an IntegerAttr")
+                        .clone()
+                        .into(),
+                ) as u32;
+                value_u32.try_into().expect("index is not an Ord16")
+            }
+
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock](crate::basic_block::BasicBlock).
+            pub fn new_unlinked(ctx: &mut Context, index: Ord16) -> $name {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                let attr = u32_attr(ctx, index.into());
+                op.deref_mut(ctx)
+                    .attributes
+                    .insert(Self::ATTR_KEY_INDEX, attr);
+                $name { op }
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{} {}", self.get_opid().with_ctx(ctx), self.get_index(ctx))
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                verify_stack_op(
+                    ctx,
+                    self.get_operation(),
+                    Self::get_opid_static(),
+                    &[Self::ATTR_KEY_INDEX],
+                )?;
+                let op = self.get_operation().deref(ctx);
+                let Some(index) = op
+                    .attributes
+                    .get(Self::ATTR_KEY_INDEX)
+                    .and_then(|attr| attr.downcast_ref::<IntegerAttr>()) else {
+                    return Err(CompilerError::VerificationError {
+                        msg: format!("{} has no index", self.get_opid().with_ctx(ctx)),
+                    });
+                };
+                let index = apint_to_i32(index.clone().into()) as u32;
+                if Ord16::try_from(index).is_err() {
+                    return Err(CompilerError::VerificationError {
+                        msg: format!(
+                            "{} index {index} is out of the top 16 stack values",
+                            self.get_opid().with_ctx(ctx)
+                        ),
+                    });
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+ord16_index_op!(
+    /// Move the value at the given index to the top of the stack.
+    MovUpOp,
+    "movup",
+    "movup.index"
+);
+
+ord16_index_op!(
+    /// Move the top value on the stack to the given index.
+    MovDnOp,
+    "movdn",
+    "movdn.index"
+);
+
 declare_op!(
     /// Call a function
     ///
@@ -255,6 +363,28 @@ impl DisplayWithContext for CallOp {
 impl Verify for CallOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
         let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_SYM)
+            .map_or(false, |attr| attr.is::<StringAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "ozk.call has no callee symbol".to_string(),
+            });
+        }
+        let Some(func_type_attr) = op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_TYPE)
+            .and_then(|attr| attr.downcast_ref::<TypeAttr>()) else {
+            return Err(CompilerError::VerificationError {
+                msg: "ozk.call has no callee type".to_string(),
+            });
+        };
+        if !func_type_attr.get_type().deref(ctx).is::<FunctionType>() {
+            return Err(CompilerError::VerificationError {
+                msg: "Unexpected ozk.call callee type".to_string(),
+            });
+        }
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -269,8 +399,982 @@ impl Verify for CallOp {
     }
 }
 
+/// Get the type stored in the [TypeAttr] attribute of the op.
+fn get_type_attr(ctx: &Context, op: Ptr<Operation>, key: &'static str) -> Ptr<TypeObj> {
+    let opref = op.deref(ctx);
+    let ty_attr = opref.attributes.get(key).expect("no type attribute");
+    attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+        .expect("invalid type attribute")
+        .get_type()
+}
+
+/// Get the value of the u32 [IntegerAttr] attribute of the op.
+fn get_u32_attr(ctx: &Context, op: Ptr<Operation>, key: &'static str) -> u32 {
+    let opref = op.deref(ctx);
+    let attr = opref.attributes.get(key).expect("no attribute found");
+    apint_to_i32(
+        attr.downcast_ref::<IntegerAttr>()
+            .expect("expected IntegerAttr")
+            .clone()
+            .into(),
+    ) as u32
+}
+
+/// Check the OpId, that the op has no operands and results (the values are on the stack)
+/// and that it has the given attributes.
+fn verify_stack_op(
+    ctx: &Context,
+    op: Ptr<Operation>,
+    opid: OpId,
+    attr_keys: &[&'static str],
+) -> Result<(), CompilerError> {
+    let op = &*op.deref(ctx);
+    if op.get_opid() != opid {
+        return Err(CompilerError::VerificationError {
+            msg: "Incorrect OpId".to_string(),
+        });
+    }
+    if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+        return Err(CompilerError::VerificationError {
+            msg: "Incorrect number of results or operands".to_string(),
+        });
+    }
+    for attr_key in attr_keys {
+        if !op.attributes.contains_key(attr_key) {
+            return Err(CompilerError::VerificationError {
+                msg: format!("{} has no {attr_key} attribute", opid.with_ctx(ctx)),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Declares an op that takes its operands from the stack and has a single [TypeAttr]
+/// attribute with the (integer) type of the operands.
+macro_rules! int_typed_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal, $attr_key:literal) => {
+        declare_op!($(#[$outer])* $name, $op_name, "ozk");
+
+        impl $name {
+            /// Attribute key for the type of the operands
+            pub const ATTR_KEY_OP_TYPE: &str = $attr_key;
+
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock].
+            pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> $name {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                op.deref_mut(ctx)
+                    .attributes
+                    .insert(Self::ATTR_KEY_OP_TYPE, TypeAttr::create(ty));
+                $name { op }
+            }
+
+            /// Get the type of the operands
+            pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
+                get_type_attr(ctx, self.get_operation(), Self::ATTR_KEY_OP_TYPE)
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(
+                    f,
+                    "{} {}",
+                    self.get_opid().with_ctx(ctx),
+                    self.get_type(ctx).with_ctx(ctx)
+                )
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                verify_stack_op(
+                    ctx,
+                    self.get_operation(),
+                    Self::get_opid_static(),
+                    &[Self::ATTR_KEY_OP_TYPE],
+                )?;
+                if !self.get_type(ctx).deref(ctx).is::<IntegerType>() {
+                    return Err(CompilerError::VerificationError {
+                        msg: format!("{} expects an integer type", self.get_opid().with_ctx(ctx)),
+                    });
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Declares an op that has a single u32 [IntegerAttr] attribute.
+macro_rules! u32_attr_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal, $attr_key:literal, $getter:ident) => {
+        declare_op!($(#[$outer])* $name, $op_name, "ozk");
+
+        impl $name {
+            /// Attribute key for the u32 parameter (index or relative depth)
+            pub const ATTR_KEY_INDEX: &str = $attr_key;
+
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock].
+            pub fn new_unlinked(ctx: &mut Context, index: u32) -> $name {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                let attr = u32_attr(ctx, index);
+                op.deref_mut(ctx)
+                    .attributes
+                    .insert(Self::ATTR_KEY_INDEX, attr);
+                $name { op }
+            }
+
+            pub fn $getter(&self, ctx: &Context) -> u32 {
+                get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_INDEX)
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{} {}", self.get_opid().with_ctx(ctx), self.$getter(ctx))
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                verify_stack_op(
+                    ctx,
+                    self.get_operation(),
+                    Self::get_opid_static(),
+                    &[Self::ATTR_KEY_INDEX],
+                )?;
+                let op = self.get_operation().deref(ctx);
+                if !op
+                    .attributes
+                    .get(Self::ATTR_KEY_INDEX)
+                    .map_or(false, |attr| attr.is::<IntegerAttr>())
+                {
+                    return Err(CompilerError::VerificationError {
+                        msg: format!("{} has no index", self.get_opid().with_ctx(ctx)),
+                    });
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Declares an op without attributes.
+macro_rules! plain_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal) => {
+        declare_op!($(#[$outer])* $name, $op_name, "ozk");
+
+        impl $name {
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock].
+            pub fn new_unlinked(ctx: &mut Context) -> $name {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                $name { op }
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", self.get_opid().with_ctx(ctx))
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                verify_stack_op(ctx, self.get_operation(), Self::get_opid_static(), &[])
+            }
+        }
+    };
+}
+
+int_typed_op!(
+    /// Pops two integers, pushes their sum wrapped around modulo 2^N (N is the type width).
+    WrappingAddOp,
+    "wrapping_add",
+    "wrapping_add.type"
+);
+
+int_typed_op!(
+    /// Pops `b` and `a`, pushes `a - b` wrapped around modulo 2^N (N is the type width).
+    WrappingSubOp,
+    "wrapping_sub",
+    "wrapping_sub.type"
+);
+
+int_typed_op!(
+    /// Pops two integers, pushes their product wrapped around modulo 2^N (N is the type width).
+    WrappingMulOp,
+    "wrapping_mul",
+    "wrapping_mul.type"
+);
+
+int_typed_op!(
+    /// Pops the shift amount `b` and `a`, pushes `a << (b mod N)` (N is the type width).
+    ShlOp,
+    "shl",
+    "shl.type"
+);
+
+int_typed_op!(
+    /// Pops the shift amount `b` and `a`, pushes `a >> (b mod N)` filling with zeros.
+    ShrUOp,
+    "shr_u",
+    "shr_u.type"
+);
+
+int_typed_op!(
+    /// Pops the shift amount `b` and `a`, pushes `a >> (b mod N)` filling with the sign bit.
+    ShrSOp,
+    "shr_s",
+    "shr_s.type"
+);
+
+int_typed_op!(
+    /// Pops an integer, pushes 1 (i32) if it is zero, otherwise 0.
+    EqzOp,
+    "eqz",
+    "eqz.type"
+);
+
+int_typed_op!(
+    /// Pops two integers, pushes their bitwise AND.
+    AndOp,
+    "and",
+    "and.type"
+);
+
+int_typed_op!(
+    /// Pops two integers, pushes their bitwise OR.
+    OrOp,
+    "or",
+    "or.type"
+);
+
+int_typed_op!(
+    /// Pops two integers, pushes their bitwise XOR.
+    XorOp,
+    "xor",
+    "xor.type"
+);
+
+int_typed_op!(
+    /// Pops two integers, pushes 1 (i32) if they are equal, otherwise 0.
+    EqOp,
+    "eq",
+    "eq.type"
+);
+
+int_typed_op!(
+    /// Pops two integers, pushes 1 (i32) if they are not equal, otherwise 0.
+    NeOp,
+    "ne",
+    "ne.type"
+);
+
+int_typed_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a < b` as unsigned integers, otherwise 0.
+    LtUOp,
+    "lt_u",
+    "lt_u.type"
+);
+
+int_typed_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a < b` as signed integers, otherwise 0.
+    LtSOp,
+    "lt_s",
+    "lt_s.type"
+);
+
+int_typed_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a > b` as unsigned integers, otherwise 0.
+    GtUOp,
+    "gt_u",
+    "gt_u.type"
+);
+
+int_typed_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a > b` as signed integers, otherwise 0.
+    GtSOp,
+    "gt_s",
+    "gt_s.type"
+);
+
+int_typed_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a <= b` as unsigned integers, otherwise 0.
+    LeUOp,
+    "le_u",
+    "le_u.type"
+);
+
+int_typed_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a <= b` as signed integers, otherwise 0.
+    LeSOp,
+    "le_s",
+    "le_s.type"
+);
+
+int_typed_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a >= b` as unsigned integers, otherwise 0.
+    GeUOp,
+    "ge_u",
+    "ge_u.type"
+);
+
+int_typed_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a >= b` as signed integers, otherwise 0.
+    GeSOp,
+    "ge_s",
+    "ge_s.type"
+);
+
+plain_op!(
+    /// Pops an i32, pushes it zero-extended to i64.
+    ExtendI32UOp,
+    "extend_i32_u"
+);
+
+plain_op!(
+    /// Pops an i32, pushes it sign-extended to i64.
+    ExtendI32SOp,
+    "extend_i32_s"
+);
+
+plain_op!(
+    /// Pops an i64, pushes its low 32 bits as i32.
+    WrapI64Op,
+    "wrap_i64"
+);
+
+plain_op!(
+    /// Pops the top value from the stack.
+    DropOp,
+    "drop"
+);
+
+plain_op!(
+    /// Pops two field elements, pushes their sum.
+    FeltAddOp,
+    "felt.add"
+);
+
+plain_op!(
+    /// Pops `b` and `a` field elements, pushes `a - b`.
+    FeltSubOp,
+    "felt.sub"
+);
+
+plain_op!(
+    /// Pops two field elements, pushes their product.
+    FeltMulOp,
+    "felt.mul"
+);
+
+/// The `memarg` immediate of a [LoadOp] or [StoreOp]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MemArg {
+    /// The offset added to the address operand
+    pub offset: u32,
+    /// The alignment hint (the exponent of 2)
+    pub align: u32,
+}
+
+impl MemArg {
+    /// No offset and the natural alignment of the access of `size` bytes.
+    pub fn natural(size: u32) -> MemArg {
+        MemArg {
+            offset: 0,
+            align: size.trailing_zeros(),
+        }
+    }
+}
+
+/// The size of the integer type in bytes.
+fn int_type_size(ctx: &Context, ty: Ptr<TypeObj>) -> Option<u32> {
+    ty.deref(ctx)
+        .downcast_ref::<IntegerType>()
+        .map(|int_ty| int_ty.get_width() as u32 / 8)
+}
+
+/// Check the type, the access size (bytes) and the memarg of a [LoadOp] or [StoreOp].
+fn verify_mem_access(
+    ctx: &Context,
+    op: Ptr<Operation>,
+    ty: Ptr<TypeObj>,
+    size: u32,
+    memarg: MemArg,
+) -> Result<(), CompilerError> {
+    let opid = op.deref(ctx).get_opid();
+    let Some(ty_size) = int_type_size(ctx, ty) else {
+        return Err(CompilerError::VerificationError {
+            msg: format!("{} expects an integer type", opid.with_ctx(ctx)),
+        });
+    };
+    if !size.is_power_of_two() || size > ty_size {
+        return Err(CompilerError::VerificationError {
+            msg: format!(
+                "{} has invalid access size {size} for {}",
+                opid.with_ctx(ctx),
+                ty.with_ctx(ctx)
+            ),
+        });
+    }
+    if memarg.align > size.trailing_zeros() {
+        return Err(CompilerError::VerificationError {
+            msg: format!(
+                "{} alignment {} is larger than the access size {size}",
+                opid.with_ctx(ctx),
+                1u64 << memarg.align
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Format the type, the access size, the sign extension and the memarg. The size and the memarg
+/// are omitted if they are the default (the whole value, no offset and the natural alignment).
+fn fmt_mem_access(
+    ctx: &Context,
+    op: Ptr<Operation>,
+    ty: Ptr<TypeObj>,
+    size: u32,
+    signed: Option<bool>,
+    memarg: MemArg,
+    f: &mut core::fmt::Formatter<'_>,
+) -> core::fmt::Result {
+    write!(
+        f,
+        "{} {}",
+        op.deref(ctx).get_opid().with_ctx(ctx),
+        ty.with_ctx(ctx)
+    )?;
+    if Some(size) != int_type_size(ctx, ty) {
+        match signed {
+            Some(signed) => write!(f, " {}_{}", size * 8, if signed { "s" } else { "u" })?,
+            None => write!(f, " {}", size * 8)?,
+        }
+    }
+    if memarg.offset != 0 {
+        write!(f, " offset={}", memarg.offset)?;
+    }
+    if memarg.align != size.trailing_zeros() {
+        write!(f, " align={}", 1u64 << memarg.align)?;
+    }
+    Ok(())
+}
+
+declare_op!(
+    /// Pops the address (i32), pushes the value of the given type loaded from memory
+    /// at the address plus the offset. A sub-word load (e.g. Wasm's `i32.load8_s`) loads
+    /// `size` bytes and sign or zero extends them to the value type.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_OP_TYPE](LoadOp::ATTR_KEY_OP_TYPE) | [TypeAttr] |
+    /// | [ATTR_KEY_SIZE](LoadOp::ATTR_KEY_SIZE) | [IntegerAttr] |
+    /// | [ATTR_KEY_SIGNED](LoadOp::ATTR_KEY_SIGNED) | [IntegerAttr] |
+    /// | [ATTR_KEY_OFFSET](LoadOp::ATTR_KEY_OFFSET) | [IntegerAttr] |
+    /// | [ATTR_KEY_ALIGN](LoadOp::ATTR_KEY_ALIGN) | [IntegerAttr] |
+    LoadOp,
+    "load",
+    "ozk"
+);
+
+impl LoadOp {
+    /// Attribute key for the value type
+    pub const ATTR_KEY_OP_TYPE: &str = "load.type";
+    /// Attribute key for the number of the loaded bytes
+    pub const ATTR_KEY_SIZE: &str = "load.size";
+    /// Attribute key for the sign extension of the loaded bytes (1 - sign extend, 0 - zero extend)
+    pub const ATTR_KEY_SIGNED: &str = "load.signed";
+    /// Attribute key for the memarg offset
+    pub const ATTR_KEY_OFFSET: &str = "load.offset";
+    /// Attribute key for the memarg alignment
+    pub const ATTR_KEY_ALIGN: &str = "load.align";
+
+    /// Create a new [LoadOp] loading the whole value with no offset and the natural alignment.
+    /// The underlying [Operation] is not linked to a [BasicBlock].
+    pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> LoadOp {
+        let size = int_type_size(ctx, ty).expect("integer type");
+        Self::new_sized_unlinked(ctx, ty, size, false, MemArg::natural(size))
+    }
+
+    /// Create a new [LoadOp] loading `size` bytes extended to the value type.
+    /// The underlying [Operation] is not linked to a [BasicBlock].
+    pub fn new_sized_unlinked(
+        ctx: &mut Context,
+        ty: Ptr<TypeObj>,
+        size: u32,
+        signed: bool,
+        memarg: MemArg,
+    ) -> LoadOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        let size_attr = u32_attr(ctx, size);
+        let signed_attr = u32_attr(ctx, signed as u32);
+        let offset_attr = u32_attr(ctx, memarg.offset);
+        let align_attr = u32_attr(ctx, memarg.align);
+        let attributes = &mut op.deref_mut(ctx).attributes;
+        attributes.insert(Self::ATTR_KEY_OP_TYPE, TypeAttr::create(ty));
+        attributes.insert(Self::ATTR_KEY_SIZE, size_attr);
+        attributes.insert(Self::ATTR_KEY_SIGNED, signed_attr);
+        attributes.insert(Self::ATTR_KEY_OFFSET, offset_attr);
+        attributes.insert(Self::ATTR_KEY_ALIGN, align_attr);
+        LoadOp { op }
+    }
+
+    /// Get the type of the value
+    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
+        get_type_attr(ctx, self.get_operation(), Self::ATTR_KEY_OP_TYPE)
+    }
+
+    /// Get the number of the loaded bytes.
+    pub fn get_size(&self, ctx: &Context) -> u32 {
+        get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_SIZE)
+    }
+
+    /// Returns true if the loaded bytes are sign extended.
+    pub fn is_signed(&self, ctx: &Context) -> bool {
+        get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_SIGNED) != 0
+    }
+
+    /// Get the offset and the alignment hint.
+    pub fn get_memarg(&self, ctx: &Context) -> MemArg {
+        MemArg {
+            offset: get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_OFFSET),
+            align: get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_ALIGN),
+        }
+    }
+}
+
+impl DisplayWithContext for LoadOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_mem_access(
+            ctx,
+            self.get_operation(),
+            self.get_type(ctx),
+            self.get_size(ctx),
+            Some(self.is_signed(ctx)),
+            self.get_memarg(ctx),
+            f,
+        )
+    }
+}
+
+impl Verify for LoadOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_stack_op(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            &[
+                Self::ATTR_KEY_OP_TYPE,
+                Self::ATTR_KEY_SIZE,
+                Self::ATTR_KEY_SIGNED,
+                Self::ATTR_KEY_OFFSET,
+                Self::ATTR_KEY_ALIGN,
+            ],
+        )?;
+        verify_mem_access(
+            ctx,
+            self.get_operation(),
+            self.get_type(ctx),
+            self.get_size(ctx),
+            self.get_memarg(ctx),
+        )
+    }
+}
+
+declare_op!(
+    /// Pops the value of the given type and the address (i32), stores the value in memory
+    /// at the address plus the offset. A sub-word store (e.g. Wasm's `i32.store8`) stores
+    /// the `size` low bytes of the value.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_OP_TYPE](StoreOp::ATTR_KEY_OP_TYPE) | [TypeAttr] |
+    /// | [ATTR_KEY_SIZE](StoreOp::ATTR_KEY_SIZE) | [IntegerAttr] |
+    /// | [ATTR_KEY_OFFSET](StoreOp::ATTR_KEY_OFFSET) | [IntegerAttr] |
+    /// | [ATTR_KEY_ALIGN](StoreOp::ATTR_KEY_ALIGN) | [IntegerAttr] |
+    StoreOp,
+    "store",
+    "ozk"
+);
+
+impl StoreOp {
+    /// Attribute key for the value type
+    pub const ATTR_KEY_OP_TYPE: &str = "store.type";
+    /// Attribute key for the number of the stored bytes (the low bytes of the value)
+    pub const ATTR_KEY_SIZE: &str = "store.size";
+    /// Attribute key for the memarg offset
+    pub const ATTR_KEY_OFFSET: &str = "store.offset";
+    /// Attribute key for the memarg alignment
+    pub const ATTR_KEY_ALIGN: &str = "store.align";
+
+    /// Create a new [StoreOp] storing the whole value with no offset and the natural alignment.
+    /// The underlying [Operation] is not linked to a [BasicBlock].
+    pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> StoreOp {
+        let size = int_type_size(ctx, ty).expect("integer type");
+        Self::new_sized_unlinked(ctx, ty, size, MemArg::natural(size))
+    }
+
+    /// Create a new [StoreOp] storing the `size` low bytes of the value.
+    /// The underlying [Operation] is not linked to a [BasicBlock].
+    pub fn new_sized_unlinked(
+        ctx: &mut Context,
+        ty: Ptr<TypeObj>,
+        size: u32,
+        memarg: MemArg,
+    ) -> StoreOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        let size_attr = u32_attr(ctx, size);
+        let offset_attr = u32_attr(ctx, memarg.offset);
+        let align_attr = u32_attr(ctx, memarg.align);
+        let attributes = &mut op.deref_mut(ctx).attributes;
+        attributes.insert(Self::ATTR_KEY_OP_TYPE, TypeAttr::create(ty));
+        attributes.insert(Self::ATTR_KEY_SIZE, size_attr);
+        attributes.insert(Self::ATTR_KEY_OFFSET, offset_attr);
+        attributes.insert(Self::ATTR_KEY_ALIGN, align_attr);
+        StoreOp { op }
+    }
+
+    /// Get the type of the value
+    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
+        get_type_attr(ctx, self.get_operation(), Self::ATTR_KEY_OP_TYPE)
+    }
+
+    /// Get the number of the stored bytes.
+    pub fn get_size(&self, ctx: &Context) -> u32 {
+        get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_SIZE)
+    }
+
+    /// Get the offset and the alignment hint.
+    pub fn get_memarg(&self, ctx: &Context) -> MemArg {
+        MemArg {
+            offset: get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_OFFSET),
+            align: get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_ALIGN),
+        }
+    }
+}
+
+impl DisplayWithContext for StoreOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_mem_access(
+            ctx,
+            self.get_operation(),
+            self.get_type(ctx),
+            self.get_size(ctx),
+            None,
+            self.get_memarg(ctx),
+            f,
+        )
+    }
+}
+
+impl Verify for StoreOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_stack_op(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            &[
+                Self::ATTR_KEY_OP_TYPE,
+                Self::ATTR_KEY_SIZE,
+                Self::ATTR_KEY_OFFSET,
+                Self::ATTR_KEY_ALIGN,
+            ],
+        )?;
+        verify_mem_access(
+            ctx,
+            self.get_operation(),
+            self.get_type(ctx),
+            self.get_size(ctx),
+            self.get_memarg(ctx),
+        )
+    }
+}
+
+u32_attr_op!(
+    /// Pushes the value of the local variable.
+    LocalGetOp,
+    "local.get",
+    "local.get.index",
+    get_index
+);
+
+u32_attr_op!(
+    /// Pops the value into the local variable.
+    LocalSetOp,
+    "local.set",
+    "local.set.index",
+    get_index
+);
+
+u32_attr_op!(
+    /// Copies the top stack value into the local variable.
+    LocalTeeOp,
+    "local.tee",
+    "local.tee.index",
+    get_index
+);
+
+u32_attr_op!(
+    /// Branch to the enclosing [BlockOp] (exit) or [LoopOp] (next iteration)
+    /// at the given relative depth.
+    BrOp,
+    "br",
+    "br.relative_depth",
+    get_relative_depth
+);
+
+u32_attr_op!(
+    /// Pops the i32 condition and performs [BrOp] if it is not zero.
+    BrIfOp,
+    "br_if",
+    "br_if.relative_depth",
+    get_relative_depth
+);
+
+plain_op!(
+    /// Return from the function.
+    ReturnOp,
+    "return"
+);
+
+/// Create a structured op with the type attribute and an empty `entry` block.
+fn new_structured_op(
+    ctx: &mut Context,
+    opid: OpId,
+    attr_key: &'static str,
+    ty: Ptr<TypeObj>,
+) -> Ptr<Operation> {
+    let op = Operation::new(ctx, opid, vec![], vec![], 1);
+    op.deref_mut(ctx)
+        .attributes
+        .insert(attr_key, TypeAttr::create(ty));
+    let region = op.deref(ctx).get_region(0).expect("no region");
+    let body = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+    body.insert_at_front(region, ctx);
+    op
+}
+
+/// Verify a structured op with the block type attribute and a region.
+fn verify_structured_op(
+    ctx: &Context,
+    op: Ptr<Operation>,
+    opid: OpId,
+    attr_key: &'static str,
+) -> Result<(), CompilerError> {
+    verify_stack_op(ctx, op, opid, &[attr_key])?;
+    if !get_type_attr(ctx, op, attr_key)
+        .deref(ctx)
+        .is::<FunctionType>()
+    {
+        return Err(CompilerError::VerificationError {
+            msg: format!("{} type is not a function type", opid.with_ctx(ctx)),
+        });
+    }
+    let region = op.deref(ctx).get_region(0).expect("no region");
+    region.deref(ctx).verify(ctx)
+}
+
+fn fmt_structured_op(
+    ctx: &Context,
+    op: Ptr<Operation>,
+    ty: Ptr<TypeObj>,
+    f: &mut core::fmt::Formatter<'_>,
+) -> core::fmt::Result {
+    let region = op
+        .deref(ctx)
+        .get_region(0)
+        .expect("no region")
+        .with_ctx(ctx)
+        .to_string();
+    write!(
+        f,
+        "{} {} {{\n{}}}",
+        op.deref(ctx).get_opid().with_ctx(ctx),
+        ty.with_ctx(ctx),
+        indent::indent_all_by(2, region),
+    )
+}
+
+declare_op!(
+    /// A block of ops (see Wasm's `block`). A branch to it jumps to its end.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_BLOCK_TYPE](BlockOp::ATTR_KEY_BLOCK_TYPE) | [TypeAttr] |
+    BlockOp,
+    "block",
+    "ozk"
+);
+
+impl BlockOp {
+    /// Attribute key for the block type
+    pub const ATTR_KEY_BLOCK_TYPE: &str = "block.type";
+
+    /// Create a new [BlockOp] with an empty `entry` block.
+    pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> BlockOp {
+        BlockOp {
+            op: new_structured_op(ctx, Self::get_opid_static(), Self::ATTR_KEY_BLOCK_TYPE, ty),
+        }
+    }
+
+    /// Get the signature (type).
+    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
+        get_type_attr(ctx, self.get_operation(), Self::ATTR_KEY_BLOCK_TYPE)
+    }
+
+    /// Get the bb of this block.
+    pub fn get_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        self.get_region(ctx)
+            .deref(ctx)
+            .get_head()
+            .expect("no entry block")
+    }
+
+    /// Get an iterator over all operations.
+    pub fn op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
+        self.get_region(ctx)
+            .deref(ctx)
+            .iter(ctx)
+            .flat_map(|bb| bb.deref(ctx).iter(ctx))
+    }
+}
+
+impl OneRegionInterface for BlockOp {}
+
+impl DisplayWithContext for BlockOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_structured_op(ctx, self.get_operation(), self.get_type(ctx), f)
+    }
+}
+
+impl Verify for BlockOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        self.verify_interfaces(ctx)?;
+        verify_structured_op(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            Self::ATTR_KEY_BLOCK_TYPE,
+        )
+    }
+}
+
+declare_op!(
+    /// A loop (see Wasm's `loop`). A branch to it jumps to its start.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_BLOCK_TYPE](LoopOp::ATTR_KEY_BLOCK_TYPE) | [TypeAttr] |
+    LoopOp,
+    "loop",
+    "ozk"
+);
+
+impl LoopOp {
+    /// Attribute key for the loop type
+    pub const ATTR_KEY_BLOCK_TYPE: &str = "loop.type";
+
+    /// Create a new [LoopOp] with an empty `entry` block.
+    pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> LoopOp {
+        LoopOp {
+            op: new_structured_op(ctx, Self::get_opid_static(), Self::ATTR_KEY_BLOCK_TYPE, ty),
+        }
+    }
+
+    /// Get the signature (type).
+    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
+        get_type_attr(ctx, self.get_operation(), Self::ATTR_KEY_BLOCK_TYPE)
+    }
+
+    /// Get the bb of this loop.
+    pub fn get_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        self.get_region(ctx)
+            .deref(ctx)
+            .get_head()
+            .expect("no entry block")
+    }
+
+    /// Get an iterator over all operations.
+    pub fn op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
+        self.get_region(ctx)
+            .deref(ctx)
+            .iter(ctx)
+            .flat_map(|bb| bb.deref(ctx).iter(ctx))
+    }
+}
+
+impl OneRegionInterface for LoopOp {}
+
+impl DisplayWithContext for LoopOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_structured_op(ctx, self.get_operation(), self.get_type(ctx), f)
+    }
+}
+
+impl Verify for LoopOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        self.verify_interfaces(ctx)?;
+        verify_structured_op(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            Self::ATTR_KEY_BLOCK_TYPE,
+        )
+    }
+}
+
 pub(crate) fn register(ctx: &mut Context, dialect: &mut Dialect) {
     ConstantOp::register(ctx, dialect);
     SwapOp::register(ctx, dialect);
+    MovUpOp::register(ctx, dialect);
+    MovDnOp::register(ctx, dialect);
     CallOp::register(ctx, dialect);
+    WrappingAddOp::register(ctx, dialect);
+    WrappingSubOp::register(ctx, dialect);
+    WrappingMulOp::register(ctx, dialect);
+    ShlOp::register(ctx, dialect);
+    ShrUOp::register(ctx, dialect);
+    ShrSOp::register(ctx, dialect);
+    EqzOp::register(ctx, dialect);
+    AndOp::register(ctx, dialect);
+    OrOp::register(ctx, dialect);
+    XorOp::register(ctx, dialect);
+    EqOp::register(ctx, dialect);
+    NeOp::register(ctx, dialect);
+    LtUOp::register(ctx, dialect);
+    LtSOp::register(ctx, dialect);
+    GtUOp::register(ctx, dialect);
+    GtSOp::register(ctx, dialect);
+    LeUOp::register(ctx, dialect);
+    LeSOp::register(ctx, dialect);
+    GeUOp::register(ctx, dialect);
+    GeSOp::register(ctx, dialect);
+    ExtendI32UOp::register(ctx, dialect);
+    ExtendI32SOp::register(ctx, dialect);
+    WrapI64Op::register(ctx, dialect);
+    DropOp::register(ctx, dialect);
+    FeltAddOp::register(ctx, dialect);
+    FeltSubOp::register(ctx, dialect);
+    FeltMulOp::register(ctx, dialect);
+    LoadOp::register(ctx, dialect);
+    StoreOp::register(ctx, dialect);
+    LocalGetOp::register(ctx, dialect);
+    LocalSetOp::register(ctx, dialect);
+    LocalTeeOp::register(ctx, dialect);
+    BlockOp::register(ctx, dialect);
+    LoopOp::register(ctx, dialect);
+    BrOp::register(ctx, dialect);
+    BrIfOp::register(ctx, dialect);
+    ReturnOp::register(ctx, dialect);
 }
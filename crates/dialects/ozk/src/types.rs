@@ -53,7 +53,7 @@ impl DisplayWithContext for FieldElemType {
 
 impl Verify for FieldElemType {
     fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
-        todo!()
+        Ok(())
     }
 }
 
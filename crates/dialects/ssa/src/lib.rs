@@ -0,0 +1,50 @@
+//! SSA dialect
+//!
+//! A value-based mid-level dialect. Unlike the stack machine dialects (wasm, miden, valida)
+//! the operands and results of the ops are explicit pliron `Value`s.
+//! Control flow is structured (`ssa.block`, `ssa.loop`) and values flowing into
+//! a block are passed as its region entry block arguments.
+
+// Coding conventions
+// #![deny(unsafe_code)]
+#![deny(non_upper_case_globals)]
+#![deny(non_camel_case_types)]
+#![deny(non_snake_case)]
+#![deny(unused_mut)]
+// #![deny(dead_code)]
+#![deny(unused_imports)]
+// #![deny(missing_docs)]
+// Clippy exclusions
+#![deny(rustdoc::broken_intra_doc_links)]
+#![deny(clippy::wildcard_enum_match_arm)]
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+// #![deny(clippy::todo)]
+#![deny(clippy::unimplemented)]
+#![deny(clippy::panic)]
+
+pub mod ops;
+
+use pliron::context::Context;
+use pliron::dialect::Dialect;
+use pliron::dialect::DialectName;
+
+/// Register the dialect in the context.
+pub fn register(ctx: &mut Context) {
+    let mut dialect = Dialect::new(SSA_DIALECT_NAME());
+    ops::register(ctx, &mut dialect);
+    dialect.register(ctx);
+}
+
+/// Returns the name of the dialect.
+#[allow(non_snake_case)]
+pub fn SSA_DIALECT_NAME() -> DialectName {
+    DialectName::new("ssa")
+}
+
+/// Returns the dialect.
+#[allow(non_snake_case)]
+pub fn SSA_DIALECT(ctx: &Context) -> &Dialect {
+    #[allow(clippy::expect_used)]
+    Dialect::get_ref(ctx, SSA_DIALECT_NAME()).expect("ssa dialect not registered")
+}
@@ -0,0 +1,1543 @@
+//! This module contains the definitions of the operations of the SSA dialect.
+
+#![allow(clippy::expect_used)]
+
+use intertrait::cast_to;
+use ozk_ozk_dialect::attributes::apint_to_i32;
+use ozk_ozk_dialect::attributes::u32_attr;
+use ozk_ozk_dialect::ops::MemArg;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::i64_type;
+use ozk_ozk_dialect::types::FuncSym;
+use pliron::attribute;
+use pliron::attribute::attr_cast;
+use pliron::attribute::AttrObj;
+use pliron::basic_block::BasicBlock;
+use pliron::common_traits::DisplayWithContext;
+use pliron::common_traits::Verify;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::declare_op;
+use pliron::dialect::Dialect;
+use pliron::dialects::builtin::attr_interfaces::TypedAttrInterface;
+use pliron::dialects::builtin::attributes::FloatAttr;
+use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::dialects::builtin::attributes::StringAttr;
+use pliron::dialects::builtin::attributes::TypeAttr;
+use pliron::dialects::builtin::op_interfaces::OneRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::dialects::builtin::types::IntegerType;
+use pliron::error::CompilerError;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::op::OpId;
+use pliron::operation::Operation;
+use pliron::r#type::TypeObj;
+use pliron::r#type::Typed;
+use pliron::value::Value;
+use pliron::with_context::AttachContext;
+
+/// All operands of the operation.
+pub fn op_operands(ctx: &Context, op: Ptr<Operation>) -> Vec<Value> {
+    let op = op.deref(ctx);
+    (0..op.get_num_operands())
+        .filter_map(|idx| op.get_operand(idx))
+        .collect()
+}
+
+/// All results of the operation.
+pub fn op_results(ctx: &Context, op: Ptr<Operation>) -> Vec<Value> {
+    let op = op.deref(ctx);
+    (0..op.get_num_results())
+        .filter_map(|idx| op.get_result(idx))
+        .collect()
+}
+
+/// All arguments of the block.
+pub fn block_arguments(ctx: &Context, block: Ptr<BasicBlock>) -> Vec<Value> {
+    let block = block.deref(ctx);
+    (0..block.get_num_arguments())
+        .filter_map(|idx| block.get_argument(idx))
+        .collect()
+}
+
+fn fmt_values(ctx: &Context, values: &[Value]) -> String {
+    values
+        .iter()
+        .map(|value| value.with_ctx(ctx).to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Format as `results = opid attrs operands`
+fn fmt_op(ctx: &Context, op: Ptr<Operation>, attrs: &str) -> String {
+    let results = op_results(ctx, op);
+    let operands = op_operands(ctx, op);
+    let mut out = String::new();
+    if !results.is_empty() {
+        out.push_str(&format!("{} = ", fmt_values(ctx, &results)));
+    }
+    out.push_str(&op.deref(ctx).get_opid().with_ctx(ctx).to_string());
+    if !attrs.is_empty() {
+        out.push(' ');
+        out.push_str(attrs);
+    }
+    if !operands.is_empty() {
+        out.push(' ');
+        out.push_str(&fmt_values(ctx, &operands));
+    }
+    out
+}
+
+/// Check the OpId and the number of operands and results (if given).
+fn verify_shape(
+    ctx: &Context,
+    op: Ptr<Operation>,
+    opid: OpId,
+    num_operands: Option<usize>,
+    num_results: Option<usize>,
+) -> Result<(), CompilerError> {
+    let op = &*op.deref(ctx);
+    if op.get_opid() != opid {
+        return Err(CompilerError::VerificationError {
+            msg: "Incorrect OpId".to_string(),
+        });
+    }
+    if num_operands.map_or(false, |num| num != op.get_num_operands())
+        || num_results.map_or(false, |num| num != op.get_num_results())
+    {
+        return Err(CompilerError::VerificationError {
+            msg: format!(
+                "Incorrect number of results or operands in {}",
+                opid.with_ctx(ctx)
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn get_u32_attr(ctx: &Context, op: Ptr<Operation>, key: &'static str) -> u32 {
+    let op = op.deref(ctx);
+    let attr = op.attributes.get(key).expect("no attribute found");
+    apint_to_i32(
+        attr.downcast_ref::<IntegerAttr>()
+            .expect("expected IntegerAttr")
+            .clone()
+            .into(),
+    ) as u32
+}
+
+fn verify_u32_attr(
+    ctx: &Context,
+    op: Ptr<Operation>,
+    key: &'static str,
+) -> Result<(), CompilerError> {
+    if !op
+        .deref(ctx)
+        .attributes
+        .get(key)
+        .map_or(false, |attr| attr.is::<IntegerAttr>())
+    {
+        return Err(CompilerError::VerificationError {
+            msg: format!("no {key} attribute"),
+        });
+    }
+    Ok(())
+}
+
+declare_op!(
+    /// A function with a value-based body.
+    /// The arguments of the entry block are the function parameters.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_FUNC_TYPE](FuncOp::ATTR_KEY_FUNC_TYPE) | [TypeAttr] |
+    FuncOp,
+    "func",
+    "ssa"
+);
+
+impl FuncOp {
+    /// Attribute key for the function type
+    pub const ATTR_KEY_FUNC_TYPE: &str = "func.type";
+
+    /// Create a new [FuncOp].
+    /// The underlying [Operation] is not linked to a [BasicBlock].
+    /// The returned function has a single region with an `entry` block
+    /// that has an argument for each function parameter.
+    pub fn new_unlinked(ctx: &mut Context, name: FuncSym, ty: Ptr<TypeObj>) -> FuncOp {
+        let inputs = ty
+            .deref(ctx)
+            .downcast_ref::<FunctionType>()
+            .map(|func_type| func_type.get_inputs().clone())
+            .unwrap_or_default();
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 1);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_TYPE, TypeAttr::create(ty));
+        let opop = FuncOp { op };
+        let region = opop.get_region(ctx);
+        let entry = BasicBlock::new(ctx, Some("entry".to_string()), inputs);
+        entry.insert_at_front(region, ctx);
+        opop.set_symbol_name(ctx, name.as_ref());
+        opop
+    }
+
+    /// Get the function signature (type).
+    pub fn get_type_attr(&self, ctx: &Context) -> Ptr<TypeObj> {
+        let opref = self.get_operation().deref(ctx);
+        let ty_attr = opref
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_TYPE)
+            .expect("no type attribute");
+        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .expect("invalid type attribute")
+            .get_type()
+    }
+
+    /// Get the function signature (type).
+    pub fn get_type(&self, ctx: &Context) -> FunctionType {
+        let func_type_obj = self.get_type_attr(ctx).deref(ctx);
+        #[allow(clippy::panic)]
+        let Some(func_type) = func_type_obj.downcast_ref::<FunctionType>() else {
+            panic!("FuncOp type is not a FunctionType");
+        };
+        func_type.clone()
+    }
+
+    /// Get the entry block of this function.
+    pub fn get_entry_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        #[allow(clippy::unwrap_used)]
+        self.get_region(ctx).deref(ctx).get_head().unwrap()
+    }
+}
+
+impl OneRegionInterface for FuncOp {}
+#[cast_to]
+impl SymbolOpInterface for FuncOp {}
+
+impl DisplayWithContext for FuncOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let region = self.get_region(ctx).with_ctx(ctx).to_string();
+        write!(
+            f,
+            "{} @{}{} {{\n{}}}",
+            self.get_opid().with_ctx(ctx),
+            self.get_symbol_name(ctx),
+            self.get_type_attr(ctx).with_ctx(ctx),
+            indent::indent_all_by(2, region),
+        )
+    }
+}
+
+impl Verify for FuncOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            Some(0),
+            Some(0),
+        )?;
+        let Some(func_type) = self
+            .get_type_attr(ctx)
+            .deref(ctx)
+            .downcast_ref::<FunctionType>()
+            .cloned() else {
+            return Err(CompilerError::VerificationError {
+                msg: "Unexpected Func type".to_string(),
+            });
+        };
+        let entry_block = self.get_entry_block(ctx);
+        if entry_block.deref(ctx).get_num_arguments() != func_type.get_inputs().len() {
+            return Err(CompilerError::VerificationError {
+                msg: format!(
+                    "ssa.func @{}: entry block arguments do not match the function parameters",
+                    self.get_symbol_name(ctx)
+                ),
+            });
+        }
+        self.verify_interfaces(ctx)?;
+        entry_block.verify(ctx)?;
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Numeric constant.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_VALUE](ConstantOp::ATTR_KEY_VALUE) | [IntegerAttr] or [FloatAttr] |
+    ///
+    ConstantOp,
+    "const",
+    "ssa"
+);
+
+impl ConstantOp {
+    /// Attribute key for the constant value.
+    pub const ATTR_KEY_VALUE: &str = "const.value";
+
+    /// Create a new [ConstantOp]. The result has the type of the value attribute.
+    pub fn new_unlinked(ctx: &mut Context, value: AttrObj) -> ConstantOp {
+        let result_types = attr_cast::<dyn TypedAttrInterface>(&*value)
+            .map(|attr| attr.get_type())
+            .into_iter()
+            .collect();
+        let op = Operation::new(ctx, Self::get_opid_static(), result_types, vec![], 0);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_VALUE, value);
+        ConstantOp { op }
+    }
+
+    /// Get the constant value that this Op defines.
+    pub fn get_value(&self, ctx: &Context) -> AttrObj {
+        let op = self.get_operation().deref(ctx);
+        let value = op
+            .attributes
+            .get(Self::ATTR_KEY_VALUE)
+            .expect("no attribute found");
+        if value.is::<IntegerAttr>() {
+            attribute::clone::<IntegerAttr>(value)
+        } else {
+            attribute::clone::<FloatAttr>(value)
+        }
+    }
+
+    /// Get the defined value.
+    pub fn get_result(&self, ctx: &Context) -> Value {
+        self.get_operation()
+            .deref(ctx)
+            .get_result(0)
+            .expect("no result")
+    }
+}
+
+impl DisplayWithContext for ConstantOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let value = self.get_value(ctx).with_ctx(ctx).to_string();
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), &value))
+    }
+}
+
+impl Verify for ConstantOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            Some(0),
+            Some(1),
+        )?;
+        let op = self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_VALUE)
+            .map_or(false, |attr| {
+                attr_cast::<dyn TypedAttrInterface>(&**attr).is_some()
+            })
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "ssa.const has no typed value".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Check that the operands of the op (and the result if `result_too`) have the same type.
+fn verify_same_type(
+    ctx: &Context,
+    op: Ptr<Operation>,
+    opid: OpId,
+    result_too: bool,
+) -> Result<(), CompilerError> {
+    let mut values = op_operands(ctx, op);
+    if result_too {
+        values.extend(op_results(ctx, op));
+    }
+    let types: Vec<Ptr<TypeObj>> = values.iter().map(|value| value.get_type(ctx)).collect();
+    if types.iter().any(|ty| *ty != types[0]) {
+        return Err(CompilerError::VerificationError {
+            msg: format!(
+                "{} operands{} must have the same type",
+                opid.with_ctx(ctx),
+                if result_too { " and result" } else { "" }
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Integer op on two values of the same type, the result has the type of the operands.
+macro_rules! binary_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal) => {
+        declare_op!($(#[$outer])* $name, $op_name, "ssa");
+
+        impl $name {
+            /// Create a new op. The result has the type of the `lhs`.
+            pub fn new_unlinked(ctx: &mut Context, lhs: Value, rhs: Value) -> $name {
+                let ty = lhs.get_type(ctx);
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![ty], vec![lhs, rhs], 0);
+                $name { op }
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", fmt_op(ctx, self.get_operation(), ""))
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                verify_shape(
+                    ctx,
+                    self.get_operation(),
+                    Self::get_opid_static(),
+                    Some(2),
+                    Some(1),
+                )?;
+                verify_same_type(ctx, self.get_operation(), Self::get_opid_static(), true)
+            }
+        }
+    };
+}
+
+/// Comparison of two values of the same type, the result is 1 (i32) if it holds, 0 otherwise.
+macro_rules! compare_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal) => {
+        declare_op!($(#[$outer])* $name, $op_name, "ssa");
+
+        impl $name {
+            /// Create a new op.
+            pub fn new_unlinked(ctx: &mut Context, lhs: Value, rhs: Value) -> $name {
+                let ty = i32_type(ctx);
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![ty], vec![lhs, rhs], 0);
+                $name { op }
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", fmt_op(ctx, self.get_operation(), ""))
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                verify_shape(
+                    ctx,
+                    self.get_operation(),
+                    Self::get_opid_static(),
+                    Some(2),
+                    Some(1),
+                )?;
+                verify_same_type(ctx, self.get_operation(), Self::get_opid_static(), false)
+            }
+        }
+    };
+}
+
+/// Integer conversion of a single value, the result type is fixed.
+macro_rules! conversion_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal, $result_type:path) => {
+        declare_op!($(#[$outer])* $name, $op_name, "ssa");
+
+        impl $name {
+            /// Create a new op.
+            pub fn new_unlinked(ctx: &mut Context, value: Value) -> $name {
+                let ty = $result_type(ctx);
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![ty], vec![value], 0);
+                $name { op }
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", fmt_op(ctx, self.get_operation(), ""))
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                verify_shape(
+                    ctx,
+                    self.get_operation(),
+                    Self::get_opid_static(),
+                    Some(1),
+                    Some(1),
+                )
+            }
+        }
+    };
+}
+
+binary_op!(
+    /// Integer addition (wrapping around).
+    AddOp,
+    "add"
+);
+
+binary_op!(
+    /// Integer subtraction `lhs - rhs` (wrapping around).
+    SubOp,
+    "sub"
+);
+
+binary_op!(
+    /// Integer multiplication (wrapping around).
+    MulOp,
+    "mul"
+);
+
+binary_op!(
+    /// Bitwise and.
+    AndOp,
+    "and"
+);
+
+binary_op!(
+    /// Bitwise or.
+    OrOp,
+    "or"
+);
+
+binary_op!(
+    /// Bitwise xor.
+    XorOp,
+    "xor"
+);
+
+binary_op!(
+    /// `lhs << (rhs mod N)` (N is the type width).
+    ShlOp,
+    "shl"
+);
+
+binary_op!(
+    /// Logical `lhs >> (rhs mod N)` (N is the type width).
+    ShrUOp,
+    "shr_u"
+);
+
+binary_op!(
+    /// Arithmetic `lhs >> (rhs mod N)` (N is the type width).
+    ShrSOp,
+    "shr_s"
+);
+
+compare_op!(
+    /// `lhs == rhs`
+    EqOp,
+    "eq"
+);
+
+compare_op!(
+    /// `lhs != rhs`
+    NeOp,
+    "ne"
+);
+
+compare_op!(
+    /// Unsigned `lhs < rhs`
+    LtUOp,
+    "lt_u"
+);
+
+compare_op!(
+    /// Signed `lhs < rhs`
+    LtSOp,
+    "lt_s"
+);
+
+compare_op!(
+    /// Unsigned `lhs > rhs`
+    GtUOp,
+    "gt_u"
+);
+
+compare_op!(
+    /// Signed `lhs > rhs`
+    GtSOp,
+    "gt_s"
+);
+
+compare_op!(
+    /// Unsigned `lhs <= rhs`
+    LeUOp,
+    "le_u"
+);
+
+compare_op!(
+    /// Signed `lhs <= rhs`
+    LeSOp,
+    "le_s"
+);
+
+compare_op!(
+    /// Unsigned `lhs >= rhs`
+    GeUOp,
+    "ge_u"
+);
+
+compare_op!(
+    /// Signed `lhs >= rhs`
+    GeSOp,
+    "ge_s"
+);
+
+conversion_op!(
+    /// Zero extend the i32 operand to i64.
+    ExtendI32UOp,
+    "extend_i32_u",
+    i64_type
+);
+
+conversion_op!(
+    /// Sign extend the i32 operand to i64.
+    ExtendI32SOp,
+    "extend_i32_s",
+    i64_type
+);
+
+conversion_op!(
+    /// The low 32 bits of the i64 operand.
+    WrapI64Op,
+    "wrap_i64",
+    i32_type
+);
+
+declare_op!(
+    /// Returns 1 (i32) if the operand is zero, 0 otherwise.
+    EqzOp,
+    "eqz",
+    "ssa"
+);
+
+impl EqzOp {
+    /// Create a new [EqzOp].
+    pub fn new_unlinked(ctx: &mut Context, value: Value) -> EqzOp {
+        let ty = i32_type(ctx);
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![ty], vec![value], 0);
+        EqzOp { op }
+    }
+}
+
+impl DisplayWithContext for EqzOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), ""))
+    }
+}
+
+impl Verify for EqzOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            Some(1),
+            Some(1),
+        )
+    }
+}
+
+declare_op!(
+    /// Discards the operand.
+    DropOp,
+    "drop",
+    "ssa"
+);
+
+impl DropOp {
+    /// Create a new [DropOp].
+    pub fn new_unlinked(ctx: &mut Context, value: Value) -> DropOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![value], 0);
+        DropOp { op }
+    }
+}
+
+impl DisplayWithContext for DropOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), ""))
+    }
+}
+
+impl Verify for DropOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            Some(1),
+            Some(0),
+        )
+    }
+}
+
+declare_op!(
+    /// Call a function. The operands are the arguments, the results are the returned values.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_FUNC_SYM](CallOp::ATTR_KEY_FUNC_SYM) | [StringAttr] |
+    /// | [ATTR_KEY_FUNC_TYPE](CallOp::ATTR_KEY_FUNC_TYPE) | [TypeAttr] |
+    CallOp,
+    "call",
+    "ssa"
+);
+
+impl CallOp {
+    /// Attribute key for the callee symbol
+    pub const ATTR_KEY_FUNC_SYM: &str = "call.func_sym";
+    /// Attribute key for the callee type
+    pub const ATTR_KEY_FUNC_TYPE: &str = "call.func_type";
+
+    /// Create a new [CallOp].
+    pub fn new_unlinked(
+        ctx: &mut Context,
+        func_sym: FuncSym,
+        func_type: Ptr<TypeObj>,
+        args: Vec<Value>,
+    ) -> CallOp {
+        let result_types = func_type
+            .deref(ctx)
+            .downcast_ref::<FunctionType>()
+            .map(|func_type| func_type.get_results().clone())
+            .unwrap_or_default();
+        let op = Operation::new(ctx, Self::get_opid_static(), result_types, args, 0);
+        {
+            let opref = &mut *op.deref_mut(ctx);
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_FUNC_SYM, StringAttr::create(func_sym.into()));
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_FUNC_TYPE, TypeAttr::create(func_type));
+        }
+        CallOp { op }
+    }
+
+    /// Get the callee symbol
+    pub fn get_func_sym(&self, ctx: &Context) -> FuncSym {
+        let op = self.get_operation().deref(ctx);
+        let func_sym: String = op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_SYM)
+            .expect("no attribute found")
+            .downcast_ref::<StringAttr>()
+            .expect("expected StringAttr")
+            .clone()
+            .into();
+        func_sym.into()
+    }
+
+    /// Get the callee signature (type).
+    pub fn get_func_type_attr(&self, ctx: &Context) -> Ptr<TypeObj> {
+        let opref = self.get_operation().deref(ctx);
+        let ty_attr = opref
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_TYPE)
+            .expect("no type attribute");
+        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .expect("invalid type attribute")
+            .get_type()
+    }
+
+    /// Get the callee signature (type).
+    pub fn get_func_type(&self, ctx: &Context) -> FunctionType {
+        let func_type_obj = self.get_func_type_attr(ctx).deref(ctx);
+        #[allow(clippy::panic)]
+        let Some(func_type) = func_type_obj.downcast_ref::<FunctionType>() else {
+            panic!("CallOp type is not a FunctionType");
+        };
+        func_type.clone()
+    }
+}
+
+impl DisplayWithContext for CallOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let callee = format!("@{}", self.get_func_sym(ctx).as_ref());
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), &callee))
+    }
+}
+
+impl Verify for CallOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_SYM)
+            .map_or(false, |attr| attr.is::<StringAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "ssa.call has no callee".to_string(),
+            });
+        }
+        let Some(func_type) = op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_TYPE)
+            .and_then(|attr| attr.downcast_ref::<TypeAttr>())
+            .and_then(|attr| attr.get_type().deref(ctx).downcast_ref::<FunctionType>().cloned()) else {
+            return Err(CompilerError::VerificationError {
+                msg: "ssa.call has no function type".to_string(),
+            });
+        };
+        drop(op);
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            Some(func_type.get_inputs().len()),
+            Some(func_type.get_results().len()),
+        )
+    }
+}
+
+/// The size of the integer type in bytes.
+fn int_type_size(ctx: &Context, ty: Ptr<TypeObj>) -> u32 {
+    ty.deref(ctx)
+        .downcast_ref::<IntegerType>()
+        .map_or(0, |int_ty| int_ty.get_width() as u32 / 8)
+}
+
+/// Format the access size, the sign extension and the memarg if they are not the default
+/// (the whole value, no offset and the natural alignment).
+fn fmt_mem_access(value_size: u32, size: u32, signed: Option<bool>, memarg: MemArg) -> String {
+    let mut attrs = Vec::new();
+    if size != value_size {
+        match signed {
+            Some(signed) => attrs.push(format!("{}_{}", size * 8, if signed { "s" } else { "u" })),
+            None => attrs.push(format!("{}", size * 8)),
+        }
+    }
+    if memarg.offset != 0 {
+        attrs.push(format!("offset={}", memarg.offset));
+    }
+    if memarg.align != size.trailing_zeros() {
+        attrs.push(format!("align={}", 1u64 << memarg.align));
+    }
+    attrs.join(" ")
+}
+
+declare_op!(
+    /// Load `size` bytes from the memory address (i32 operand) plus the offset
+    /// and extend them to the result type.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_SIZE](LoadOp::ATTR_KEY_SIZE) | [IntegerAttr] |
+    /// |[ATTR_KEY_SIGNED](LoadOp::ATTR_KEY_SIGNED) | [IntegerAttr] |
+    /// |[ATTR_KEY_OFFSET](LoadOp::ATTR_KEY_OFFSET) | [IntegerAttr] |
+    /// |[ATTR_KEY_ALIGN](LoadOp::ATTR_KEY_ALIGN) | [IntegerAttr] |
+    LoadOp,
+    "load",
+    "ssa"
+);
+
+impl LoadOp {
+    /// Attribute key for the number of the loaded bytes
+    pub const ATTR_KEY_SIZE: &str = "load.size";
+    /// Attribute key for the sign extension of the loaded bytes (1 - sign extend, 0 - zero extend)
+    pub const ATTR_KEY_SIGNED: &str = "load.signed";
+    /// Attribute key for the memarg offset
+    pub const ATTR_KEY_OFFSET: &str = "load.offset";
+    /// Attribute key for the memarg alignment
+    pub const ATTR_KEY_ALIGN: &str = "load.align";
+
+    /// Create a new [LoadOp] loading the whole value with no offset and the natural alignment.
+    pub fn new_unlinked(ctx: &mut Context, address: Value, ty: Ptr<TypeObj>) -> LoadOp {
+        let size = int_type_size(ctx, ty);
+        Self::new_sized_unlinked(ctx, address, ty, size, false, MemArg::natural(size))
+    }
+
+    /// Create a new [LoadOp] loading `size` bytes extended to the type `ty`.
+    pub fn new_sized_unlinked(
+        ctx: &mut Context,
+        address: Value,
+        ty: Ptr<TypeObj>,
+        size: u32,
+        signed: bool,
+        memarg: MemArg,
+    ) -> LoadOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![ty], vec![address], 0);
+        let size_attr = u32_attr(ctx, size);
+        let signed_attr = u32_attr(ctx, signed as u32);
+        let offset_attr = u32_attr(ctx, memarg.offset);
+        let align_attr = u32_attr(ctx, memarg.align);
+        let attributes = &mut op.deref_mut(ctx).attributes;
+        attributes.insert(Self::ATTR_KEY_SIZE, size_attr);
+        attributes.insert(Self::ATTR_KEY_SIGNED, signed_attr);
+        attributes.insert(Self::ATTR_KEY_OFFSET, offset_attr);
+        attributes.insert(Self::ATTR_KEY_ALIGN, align_attr);
+        LoadOp { op }
+    }
+
+    /// Get the number of the loaded bytes.
+    pub fn get_size(&self, ctx: &Context) -> u32 {
+        get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_SIZE)
+    }
+
+    /// Returns true if the loaded bytes are sign extended.
+    pub fn is_signed(&self, ctx: &Context) -> bool {
+        get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_SIGNED) != 0
+    }
+
+    /// Get the offset and the alignment hint.
+    pub fn get_memarg(&self, ctx: &Context) -> MemArg {
+        MemArg {
+            offset: get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_OFFSET),
+            align: get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_ALIGN),
+        }
+    }
+}
+
+impl DisplayWithContext for LoadOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let value_size = op_results(ctx, self.get_operation())
+            .first()
+            .map_or(0, |value| int_type_size(ctx, value.get_type(ctx)));
+        let attrs = fmt_mem_access(
+            value_size,
+            self.get_size(ctx),
+            Some(self.is_signed(ctx)),
+            self.get_memarg(ctx),
+        );
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), &attrs))
+    }
+}
+
+impl Verify for LoadOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        for key in [
+            Self::ATTR_KEY_SIZE,
+            Self::ATTR_KEY_SIGNED,
+            Self::ATTR_KEY_OFFSET,
+            Self::ATTR_KEY_ALIGN,
+        ] {
+            verify_u32_attr(ctx, self.get_operation(), key)?;
+        }
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            Some(1),
+            Some(1),
+        )
+    }
+}
+
+declare_op!(
+    /// Store the `size` low bytes of the value (second operand) at the memory address
+    /// (first operand) plus the offset.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_SIZE](StoreOp::ATTR_KEY_SIZE) | [IntegerAttr] |
+    /// |[ATTR_KEY_OFFSET](StoreOp::ATTR_KEY_OFFSET) | [IntegerAttr] |
+    /// |[ATTR_KEY_ALIGN](StoreOp::ATTR_KEY_ALIGN) | [IntegerAttr] |
+    StoreOp,
+    "store",
+    "ssa"
+);
+
+impl StoreOp {
+    /// Attribute key for the number of the stored bytes (the low bytes of the value)
+    pub const ATTR_KEY_SIZE: &str = "store.size";
+    /// Attribute key for the memarg offset
+    pub const ATTR_KEY_OFFSET: &str = "store.offset";
+    /// Attribute key for the memarg alignment
+    pub const ATTR_KEY_ALIGN: &str = "store.align";
+
+    /// Create a new [StoreOp] storing the whole value with no offset and the natural alignment.
+    pub fn new_unlinked(ctx: &mut Context, address: Value, value: Value) -> StoreOp {
+        let size = int_type_size(ctx, value.get_type(ctx));
+        Self::new_sized_unlinked(ctx, address, value, size, MemArg::natural(size))
+    }
+
+    /// Create a new [StoreOp] storing the `size` low bytes of the value.
+    pub fn new_sized_unlinked(
+        ctx: &mut Context,
+        address: Value,
+        value: Value,
+        size: u32,
+        memarg: MemArg,
+    ) -> StoreOp {
+        let op = Operation::new(
+            ctx,
+            Self::get_opid_static(),
+            vec![],
+            vec![address, value],
+            0,
+        );
+        let size_attr = u32_attr(ctx, size);
+        let offset_attr = u32_attr(ctx, memarg.offset);
+        let align_attr = u32_attr(ctx, memarg.align);
+        let attributes = &mut op.deref_mut(ctx).attributes;
+        attributes.insert(Self::ATTR_KEY_SIZE, size_attr);
+        attributes.insert(Self::ATTR_KEY_OFFSET, offset_attr);
+        attributes.insert(Self::ATTR_KEY_ALIGN, align_attr);
+        StoreOp { op }
+    }
+
+    /// Get the number of the stored bytes.
+    pub fn get_size(&self, ctx: &Context) -> u32 {
+        get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_SIZE)
+    }
+
+    /// Get the offset and the alignment hint.
+    pub fn get_memarg(&self, ctx: &Context) -> MemArg {
+        MemArg {
+            offset: get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_OFFSET),
+            align: get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_ALIGN),
+        }
+    }
+}
+
+impl DisplayWithContext for StoreOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let value_size = op_operands(ctx, self.get_operation())
+            .get(1)
+            .map_or(0, |value| int_type_size(ctx, value.get_type(ctx)));
+        let attrs = fmt_mem_access(value_size, self.get_size(ctx), None, self.get_memarg(ctx));
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), &attrs))
+    }
+}
+
+impl Verify for StoreOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        for key in [
+            Self::ATTR_KEY_SIZE,
+            Self::ATTR_KEY_OFFSET,
+            Self::ATTR_KEY_ALIGN,
+        ] {
+            verify_u32_attr(ctx, self.get_operation(), key)?;
+        }
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            Some(2),
+            Some(0),
+        )
+    }
+}
+
+declare_op!(
+    /// Read the global variable.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_INDEX](GlobalGetOp::ATTR_KEY_INDEX) | [IntegerAttr] |
+    GlobalGetOp,
+    "global.get",
+    "ssa"
+);
+
+impl GlobalGetOp {
+    /// Attribute key for the global index
+    pub const ATTR_KEY_INDEX: &str = "global.get.index";
+
+    /// Create a new [GlobalGetOp].
+    pub fn new_unlinked(ctx: &mut Context, index: u32, ty: Ptr<TypeObj>) -> GlobalGetOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![ty], vec![], 0);
+        let index_attr = u32_attr(ctx, index);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_INDEX, index_attr);
+        GlobalGetOp { op }
+    }
+
+    /// Get the global index
+    pub fn get_index(&self, ctx: &Context) -> u32 {
+        get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_INDEX)
+    }
+}
+
+impl DisplayWithContext for GlobalGetOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let index = self.get_index(ctx).to_string();
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), &index))
+    }
+}
+
+impl Verify for GlobalGetOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_INDEX)?;
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            Some(0),
+            Some(1),
+        )
+    }
+}
+
+declare_op!(
+    /// Write the operand to the global variable.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_INDEX](GlobalSetOp::ATTR_KEY_INDEX) | [IntegerAttr] |
+    GlobalSetOp,
+    "global.set",
+    "ssa"
+);
+
+impl GlobalSetOp {
+    /// Attribute key for the global index
+    pub const ATTR_KEY_INDEX: &str = "global.set.index";
+
+    /// Create a new [GlobalSetOp].
+    pub fn new_unlinked(ctx: &mut Context, index: u32, value: Value) -> GlobalSetOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![value], 0);
+        let index_attr = u32_attr(ctx, index);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_INDEX, index_attr);
+        GlobalSetOp { op }
+    }
+
+    /// Get the global index
+    pub fn get_index(&self, ctx: &Context) -> u32 {
+        get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_INDEX)
+    }
+}
+
+impl DisplayWithContext for GlobalSetOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let index = self.get_index(ctx).to_string();
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), &index))
+    }
+}
+
+impl Verify for GlobalSetOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_INDEX)?;
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            Some(1),
+            Some(0),
+        )
+    }
+}
+
+/// Create a structured control flow op with a single region
+/// whose entry block arguments have the types of the `inputs`.
+fn new_structured_op(
+    ctx: &mut Context,
+    opid: OpId,
+    inputs: Vec<Value>,
+    result_types: Vec<Ptr<TypeObj>>,
+) -> Ptr<Operation> {
+    let arg_types = inputs.iter().map(|value| value.get_type(ctx)).collect();
+    let op = Operation::new(ctx, opid, result_types, inputs, 1);
+    let region = op.deref(ctx).get_region(0).expect("no region");
+    let entry = BasicBlock::new(ctx, Some("entry".to_string()), arg_types);
+    entry.insert_at_front(region, ctx);
+    op
+}
+
+/// Check that the entry block arguments of a structured op match its operands.
+fn verify_structured_op(
+    ctx: &Context,
+    op: Ptr<Operation>,
+    opid: OpId,
+) -> Result<(), CompilerError> {
+    verify_shape(ctx, op, opid, None, None)?;
+    let Some(entry) = op
+        .deref(ctx)
+        .get_region(0)
+        .and_then(|region| region.deref(ctx).get_head()) else {
+        return Err(CompilerError::VerificationError {
+            msg: format!("{} has no entry block", opid.with_ctx(ctx)),
+        });
+    };
+    let arg_types: Vec<Ptr<TypeObj>> = block_arguments(ctx, entry)
+        .into_iter()
+        .map(|value| value.get_type(ctx))
+        .collect();
+    let operand_types: Vec<Ptr<TypeObj>> = op_operands(ctx, op)
+        .into_iter()
+        .map(|value| value.get_type(ctx))
+        .collect();
+    if arg_types != operand_types {
+        return Err(CompilerError::VerificationError {
+            msg: format!(
+                "{} entry block arguments do not match the operands",
+                opid.with_ctx(ctx)
+            ),
+        });
+    }
+    entry.verify(ctx)
+}
+
+fn fmt_structured_op(
+    ctx: &Context,
+    op: Ptr<Operation>,
+    f: &mut core::fmt::Formatter<'_>,
+) -> core::fmt::Result {
+    let region = op
+        .deref(ctx)
+        .get_region(0)
+        .map(|region| region.with_ctx(ctx).to_string())
+        .unwrap_or_default();
+    write!(
+        f,
+        "{} {{\n{}}}",
+        fmt_op(ctx, op, ""),
+        indent::indent_all_by(2, region),
+    )
+}
+
+declare_op!(
+    /// A block (see Wasm's `block`).
+    /// The operands are passed as the entry block arguments of the region.
+    /// The results are the operands of the [YieldOp] at the end of the region
+    /// or of a [BrOp]/[BrIfOp] that targets this block.
+    BlockOp,
+    "block",
+    "ssa"
+);
+
+impl BlockOp {
+    /// Create a new [BlockOp] with an `entry` block that has an argument for each input.
+    pub fn new_unlinked(
+        ctx: &mut Context,
+        inputs: Vec<Value>,
+        result_types: Vec<Ptr<TypeObj>>,
+    ) -> BlockOp {
+        BlockOp {
+            op: new_structured_op(ctx, Self::get_opid_static(), inputs, result_types),
+        }
+    }
+
+    /// Get the entry block of the region.
+    pub fn get_entry_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        #[allow(clippy::unwrap_used)]
+        self.get_region(ctx).deref(ctx).get_head().unwrap()
+    }
+}
+
+impl OneRegionInterface for BlockOp {}
+
+impl DisplayWithContext for BlockOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_structured_op(ctx, self.get_operation(), f)
+    }
+}
+
+impl Verify for BlockOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        self.verify_interfaces(ctx)?;
+        verify_structured_op(ctx, self.get_operation(), Self::get_opid_static())
+    }
+}
+
+declare_op!(
+    /// A loop (see Wasm's `loop`).
+    /// The operands are passed as the entry block arguments of the region.
+    /// A [BrOp]/[BrIfOp] that targets this loop starts the next iteration
+    /// passing its operands as the entry block arguments.
+    /// The results are the operands of the [YieldOp] at the end of the region.
+    LoopOp,
+    "loop",
+    "ssa"
+);
+
+impl LoopOp {
+    /// Create a new [LoopOp] with an `entry` block that has an argument for each input.
+    pub fn new_unlinked(
+        ctx: &mut Context,
+        inputs: Vec<Value>,
+        result_types: Vec<Ptr<TypeObj>>,
+    ) -> LoopOp {
+        LoopOp {
+            op: new_structured_op(ctx, Self::get_opid_static(), inputs, result_types),
+        }
+    }
+
+    /// Get the entry block of the region.
+    pub fn get_entry_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        #[allow(clippy::unwrap_used)]
+        self.get_region(ctx).deref(ctx).get_head().unwrap()
+    }
+}
+
+impl OneRegionInterface for LoopOp {}
+
+impl DisplayWithContext for LoopOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_structured_op(ctx, self.get_operation(), f)
+    }
+}
+
+impl Verify for LoopOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        self.verify_interfaces(ctx)?;
+        verify_structured_op(ctx, self.get_operation(), Self::get_opid_static())
+    }
+}
+
+declare_op!(
+    /// Branch to the enclosing [BlockOp] (exit), [LoopOp] (next iteration)
+    /// or function (return) at the given relative depth passing the operands.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_RELATIVE_DEPTH](BrOp::ATTR_KEY_RELATIVE_DEPTH) | [IntegerAttr] |
+    BrOp,
+    "br",
+    "ssa"
+);
+
+impl BrOp {
+    /// Attribute key for the relative depth
+    pub const ATTR_KEY_RELATIVE_DEPTH: &str = "br.relative_depth";
+
+    /// Create a new [BrOp].
+    pub fn new_unlinked(ctx: &mut Context, relative_depth: u32, args: Vec<Value>) -> BrOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], args, 0);
+        let attr = u32_attr(ctx, relative_depth);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_RELATIVE_DEPTH, attr);
+        BrOp { op }
+    }
+
+    /// Get the relative depth of the target
+    pub fn get_relative_depth(&self, ctx: &Context) -> u32 {
+        get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_RELATIVE_DEPTH)
+    }
+}
+
+impl DisplayWithContext for BrOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let depth = self.get_relative_depth(ctx).to_string();
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), &depth))
+    }
+}
+
+impl Verify for BrOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_RELATIVE_DEPTH)?;
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            None,
+            Some(0),
+        )
+    }
+}
+
+declare_op!(
+    /// Conditional [BrOp]. The first operand is the (i32) condition,
+    /// the rest are passed to the target if the condition is not zero.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_RELATIVE_DEPTH](BrIfOp::ATTR_KEY_RELATIVE_DEPTH) | [IntegerAttr] |
+    BrIfOp,
+    "br_if",
+    "ssa"
+);
+
+impl BrIfOp {
+    /// Attribute key for the relative depth
+    pub const ATTR_KEY_RELATIVE_DEPTH: &str = "br_if.relative_depth";
+
+    /// Create a new [BrIfOp].
+    pub fn new_unlinked(
+        ctx: &mut Context,
+        relative_depth: u32,
+        condition: Value,
+        args: Vec<Value>,
+    ) -> BrIfOp {
+        let mut operands = vec![condition];
+        operands.extend(args);
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], operands, 0);
+        let attr = u32_attr(ctx, relative_depth);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_RELATIVE_DEPTH, attr);
+        BrIfOp { op }
+    }
+
+    /// Get the relative depth of the target
+    pub fn get_relative_depth(&self, ctx: &Context) -> u32 {
+        get_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_RELATIVE_DEPTH)
+    }
+
+    /// Get the condition
+    pub fn get_condition(&self, ctx: &Context) -> Value {
+        self.get_operation()
+            .deref(ctx)
+            .get_operand(0)
+            .expect("no condition")
+    }
+
+    /// Get the values passed to the target
+    pub fn get_args(&self, ctx: &Context) -> Vec<Value> {
+        op_operands(ctx, self.get_operation())
+            .into_iter()
+            .skip(1)
+            .collect()
+    }
+}
+
+impl DisplayWithContext for BrIfOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let depth = self.get_relative_depth(ctx).to_string();
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), &depth))
+    }
+}
+
+impl Verify for BrIfOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_u32_attr(ctx, self.get_operation(), Self::ATTR_KEY_RELATIVE_DEPTH)?;
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            None,
+            Some(0),
+        )?;
+        if self.get_operation().deref(ctx).get_num_operands() == 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "ssa.br_if has no condition".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Ends the region of a [BlockOp] or [LoopOp], the operands become its results.
+    YieldOp,
+    "yield",
+    "ssa"
+);
+
+impl YieldOp {
+    /// Create a new [YieldOp].
+    pub fn new_unlinked(ctx: &mut Context, values: Vec<Value>) -> YieldOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], values, 0);
+        YieldOp { op }
+    }
+}
+
+impl DisplayWithContext for YieldOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), ""))
+    }
+}
+
+impl Verify for YieldOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            None,
+            Some(0),
+        )
+    }
+}
+
+declare_op!(
+    /// Return the operands from the function.
+    ReturnOp,
+    "return",
+    "ssa"
+);
+
+impl ReturnOp {
+    /// Create a new [ReturnOp].
+    pub fn new_unlinked(ctx: &mut Context, values: Vec<Value>) -> ReturnOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], values, 0);
+        ReturnOp { op }
+    }
+}
+
+impl DisplayWithContext for ReturnOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", fmt_op(ctx, self.get_operation(), ""))
+    }
+}
+
+impl Verify for ReturnOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        verify_shape(
+            ctx,
+            self.get_operation(),
+            Self::get_opid_static(),
+            None,
+            Some(0),
+        )
+    }
+}
+
+pub(crate) fn register(ctx: &mut Context, dialect: &mut Dialect) {
+    FuncOp::register(ctx, dialect);
+    ConstantOp::register(ctx, dialect);
+    AddOp::register(ctx, dialect);
+    SubOp::register(ctx, dialect);
+    MulOp::register(ctx, dialect);
+    AndOp::register(ctx, dialect);
+    OrOp::register(ctx, dialect);
+    XorOp::register(ctx, dialect);
+    ShlOp::register(ctx, dialect);
+    ShrUOp::register(ctx, dialect);
+    ShrSOp::register(ctx, dialect);
+    EqOp::register(ctx, dialect);
+    NeOp::register(ctx, dialect);
+    LtUOp::register(ctx, dialect);
+    LtSOp::register(ctx, dialect);
+    GtUOp::register(ctx, dialect);
+    GtSOp::register(ctx, dialect);
+    LeUOp::register(ctx, dialect);
+    LeSOp::register(ctx, dialect);
+    GeUOp::register(ctx, dialect);
+    GeSOp::register(ctx, dialect);
+    EqzOp::register(ctx, dialect);
+    ExtendI32UOp::register(ctx, dialect);
+    ExtendI32SOp::register(ctx, dialect);
+    WrapI64Op::register(ctx, dialect);
+    DropOp::register(ctx, dialect);
+    CallOp::register(ctx, dialect);
+    LoadOp::register(ctx, dialect);
+    StoreOp::register(ctx, dialect);
+    GlobalGetOp::register(ctx, dialect);
+    GlobalSetOp::register(ctx, dialect);
+    BlockOp::register(ctx, dialect);
+    LoopOp::register(ctx, dialect);
+    BrOp::register(ctx, dialect);
+    BrIfOp::register(ctx, dialect);
+    YieldOp::register(ctx, dialect);
+    ReturnOp::register(ctx, dialect);
+}
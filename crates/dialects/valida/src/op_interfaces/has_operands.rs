@@ -37,10 +37,21 @@ pub trait HasOperands: Op {
     }
 
     /// Verify that the operation is valid.
-    fn verify(_op: &dyn Op, _ctx: &Context) -> Result<(), CompilerError>
+    fn verify(op: &dyn Op, ctx: &Context) -> Result<(), CompilerError>
     where
         Self: Sized,
     {
-        todo!()
+        let self_op = op.get_operation().deref(ctx);
+        let Some(vec_attr) = self_op
+            .attributes
+            .get(ATTR_KEY_HAS_OPERANDS)
+            .and_then(|attr_obj| attr_obj.downcast_ref::<VecAttr>()) else {
+            return Err(CompilerError::VerificationError {
+                msg: "no operands attribute found".to_string(),
+            });
+        };
+        Operands::try_from(vec_attr)
+            .map_err(|msg| CompilerError::VerificationError { msg })?;
+        Ok(())
     }
 }
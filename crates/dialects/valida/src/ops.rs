@@ -13,6 +13,8 @@ use pliron::error::CompilerError;
 use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
 use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
 use pliron::with_context::AttachContext;
 
 use crate::op_interfaces::HasOperands;
@@ -58,8 +60,19 @@ impl DisplayWithContext for Imm32Op {
 }
 
 impl Verify for Imm32Op {
-    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
-        todo!()
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        <Self as HasOperands>::verify(self, ctx)
     }
 }
 
@@ -84,7 +97,47 @@ impl DisplayWithContext for ProgramOp {
 
 impl Verify for ProgramOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
         self.verify_interfaces(ctx)?;
+        if self.get_region(ctx).deref(ctx).iter(ctx).count() != 2 {
+            return Err(CompilerError::VerificationError {
+                msg: "valida.program must have an entry block and a functions block".to_string(),
+            });
+        }
+        for op in self.get_funcs_block(ctx).deref(ctx).iter(ctx) {
+            if op.deref(ctx).get_op(ctx).downcast_ref::<FuncOp>().is_none() {
+                return Err(CompilerError::VerificationError {
+                    msg: format!(
+                        "valida.program functions block must contain only valida.func, found: {}",
+                        op.deref(ctx).with_ctx(ctx)
+                    ),
+                });
+            }
+        }
+        let mut unresolved_targets = Vec::new();
+        self.get_operation()
+            .walk_only::<JalSymOp>(ctx, WalkOrder::PreOrder, &mut |jalsym_op| {
+                let target_sym = jalsym_op.get_target_sym(ctx);
+                if self.get_func(ctx, &target_sym).is_none() {
+                    unresolved_targets.push(target_sym);
+                }
+                WalkResult::Advance
+            });
+        if let Some(target_sym) = unresolved_targets.first() {
+            return Err(CompilerError::VerificationError {
+                msg: format!("valida.jalsym target {target_sym} is not defined in valida.program"),
+            });
+        }
         self.get_region(ctx).deref(ctx).verify(ctx)
     }
 }
@@ -260,8 +313,19 @@ impl DisplayWithContext for AddOp {
 }
 
 impl Verify for AddOp {
-    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
-        todo!()
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        <Self as HasOperands>::verify(self, ctx)
     }
 }
 
@@ -313,8 +377,19 @@ impl DisplayWithContext for JalvOp {
 }
 
 impl Verify for JalvOp {
-    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
-        todo!()
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        <Self as HasOperands>::verify(self, ctx)
     }
 }
 
@@ -359,8 +434,19 @@ impl DisplayWithContext for SwOp {
 }
 
 impl Verify for SwOp {
-    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
-        todo!()
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        <Self as HasOperands>::verify(self, ctx)
     }
 }
 
@@ -420,8 +506,19 @@ impl DisplayWithContext for JalOp {
 }
 
 impl Verify for JalOp {
-    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
-        todo!()
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        <Self as HasOperands>::verify(self, ctx)
     }
 }
 
@@ -497,8 +594,28 @@ impl DisplayWithContext for JalSymOp {
 }
 
 impl Verify for JalSymOp {
-    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
-        todo!()
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_TARGET_SYM)
+            .map_or(false, |attr| attr.is::<StringAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "valida.jalsym has no target symbol".to_string(),
+            });
+        }
+        <Self as HasOperands>::verify(self, ctx)
     }
 }
 
@@ -528,7 +645,18 @@ impl DisplayWithContext for ExitOp {
 }
 
 impl Verify for ExitOp {
-    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
         Ok(())
     }
 }
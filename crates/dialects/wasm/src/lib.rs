@@ -21,6 +21,7 @@
 pub mod attributes;
 pub mod op_interfaces;
 pub mod ops;
+pub mod typed_stack;
 pub mod types;
 
 use pliron::context::Context;
@@ -4,6 +4,7 @@
 
 use apint::ApInt;
 use ozk_ozk_dialect::attributes::u32_attr;
+use ozk_ozk_dialect::ops as ozk;
 use pliron::attribute;
 use pliron::context::Context;
 use pliron::dialects::builtin::attributes::IntegerAttr;
@@ -11,10 +12,39 @@ use pliron::error::CompilerError;
 use pliron::op::Op;
 
 use crate::ops::AddOp;
+use crate::ops::AndOp;
 use crate::ops::ConstantOp;
+use crate::ops::DivSOp;
+use crate::ops::DivUOp;
+use crate::ops::DropOp;
+use crate::ops::EqOp;
+use crate::ops::GeSOp;
+use crate::ops::GeUOp;
+use crate::ops::GtSOp;
+use crate::ops::GtUOp;
+use crate::ops::I32EqzOp;
+use crate::ops::I32WrapI64Op;
+use crate::ops::I64EqzOp;
+use crate::ops::I64ExtendI32SOp;
+use crate::ops::I64ExtendI32UOp;
+use crate::ops::LeSOp;
+use crate::ops::LeUOp;
 use crate::ops::LocalGetOp;
 use crate::ops::LocalSetOp;
+use crate::ops::LtSOp;
+use crate::ops::LtUOp;
+use crate::ops::MulOp;
+use crate::ops::NeOp;
+use crate::ops::OrOp;
+use crate::ops::PopcntOp;
+use crate::ops::RemSOp;
+use crate::ops::RemUOp;
 use crate::ops::ReturnOp;
+use crate::ops::ShlOp;
+use crate::ops::ShrSOp;
+use crate::ops::ShrUOp;
+use crate::ops::SubOp;
+use crate::ops::XorOp;
 use crate::types::StackDepth;
 
 /// The attribute key for the stack depth.
@@ -61,10 +91,10 @@ pub trait StackDepthChange: Op {
 }
 
 #[intertrait::cast_to]
-impl TrackedStackDepth for ozk_ozk_dialect::ops::CallOp {}
+impl TrackedStackDepth for ozk::CallOp {}
 
 #[intertrait::cast_to]
-impl StackDepthChange for ozk_ozk_dialect::ops::CallOp {
+impl StackDepthChange for ozk::CallOp {
     fn get_stack_depth_change(&self, ctx: &Context) -> i32 {
         let func_type = self.get_func_type(ctx);
         let change = -(func_type.get_inputs().len() as i32) + func_type.get_results().len() as i32;
@@ -89,6 +119,70 @@ macro_rules! stack_depth_change {
 
 stack_depth_change!(ConstantOp, 1);
 stack_depth_change!(AddOp, -1);
+stack_depth_change!(SubOp, -1);
+stack_depth_change!(MulOp, -1);
+stack_depth_change!(ShlOp, -1);
+stack_depth_change!(ShrUOp, -1);
+stack_depth_change!(ShrSOp, -1);
+stack_depth_change!(DivUOp, -1);
+stack_depth_change!(DivSOp, -1);
+stack_depth_change!(RemUOp, -1);
+stack_depth_change!(RemSOp, -1);
+stack_depth_change!(PopcntOp, 0);
+stack_depth_change!(AndOp, -1);
+stack_depth_change!(OrOp, -1);
+stack_depth_change!(XorOp, -1);
+stack_depth_change!(EqOp, -1);
+stack_depth_change!(NeOp, -1);
+stack_depth_change!(LtUOp, -1);
+stack_depth_change!(LtSOp, -1);
+stack_depth_change!(GtUOp, -1);
+stack_depth_change!(GtSOp, -1);
+stack_depth_change!(LeUOp, -1);
+stack_depth_change!(LeSOp, -1);
+stack_depth_change!(GeUOp, -1);
+stack_depth_change!(GeSOp, -1);
+stack_depth_change!(I32EqzOp, 0);
+stack_depth_change!(I64EqzOp, 0);
+stack_depth_change!(I64ExtendI32UOp, 0);
+stack_depth_change!(I64ExtendI32SOp, 0);
+stack_depth_change!(I32WrapI64Op, 0);
+stack_depth_change!(DropOp, -1);
 stack_depth_change!(ReturnOp, 0);
 stack_depth_change!(LocalGetOp, 1);
 stack_depth_change!(LocalSetOp, -1);
+
+stack_depth_change!(ozk::ConstantOp, 1);
+stack_depth_change!(ozk::WrappingAddOp, -1);
+stack_depth_change!(ozk::WrappingSubOp, -1);
+stack_depth_change!(ozk::WrappingMulOp, -1);
+stack_depth_change!(ozk::ShlOp, -1);
+stack_depth_change!(ozk::ShrUOp, -1);
+stack_depth_change!(ozk::ShrSOp, -1);
+stack_depth_change!(ozk::AndOp, -1);
+stack_depth_change!(ozk::OrOp, -1);
+stack_depth_change!(ozk::XorOp, -1);
+stack_depth_change!(ozk::EqOp, -1);
+stack_depth_change!(ozk::NeOp, -1);
+stack_depth_change!(ozk::LtUOp, -1);
+stack_depth_change!(ozk::LtSOp, -1);
+stack_depth_change!(ozk::GtUOp, -1);
+stack_depth_change!(ozk::GtSOp, -1);
+stack_depth_change!(ozk::LeUOp, -1);
+stack_depth_change!(ozk::LeSOp, -1);
+stack_depth_change!(ozk::GeUOp, -1);
+stack_depth_change!(ozk::GeSOp, -1);
+stack_depth_change!(ozk::EqzOp, 0);
+stack_depth_change!(ozk::ExtendI32UOp, 0);
+stack_depth_change!(ozk::ExtendI32SOp, 0);
+stack_depth_change!(ozk::WrapI64Op, 0);
+stack_depth_change!(ozk::FeltAddOp, -1);
+stack_depth_change!(ozk::FeltSubOp, -1);
+stack_depth_change!(ozk::FeltMulOp, -1);
+stack_depth_change!(ozk::LoadOp, 0);
+stack_depth_change!(ozk::StoreOp, -2);
+stack_depth_change!(ozk::DropOp, -1);
+stack_depth_change!(ozk::ReturnOp, 0);
+stack_depth_change!(ozk::LocalGetOp, 1);
+stack_depth_change!(ozk::LocalSetOp, -1);
+stack_depth_change!(ozk::LocalTeeOp, 0);
@@ -13,6 +13,7 @@ use intertrait::cast_to;
 use ozk_ozk_dialect::attributes::apint_to_i32;
 use ozk_ozk_dialect::attributes::i32_attr;
 use ozk_ozk_dialect::attributes::u32_attr;
+pub use ozk_ozk_dialect::ops::MemArg;
 use ozk_ozk_dialect::types::i32_type;
 use ozk_ozk_dialect::types::i64_type;
 use ozk_ozk_dialect::types::u32_type_unwrapped;
@@ -42,6 +43,7 @@ use pliron::dialects::builtin::types::IntegerType;
 use pliron::dialects::builtin::types::Signedness;
 use pliron::error::CompilerError;
 use pliron::linked_list::ContainsLinkedList;
+use pliron::op::op_cast;
 use pliron::op::Op;
 use pliron::operation::Operation;
 use pliron::operation::WalkOrder;
@@ -67,7 +69,10 @@ declare_op!(
     /// | key | value |
     /// |-----|-------|
     /// | [ATTR_KEY_SYM_NAME](super::ATTR_KEY_SYM_NAME) | [StringAttr](super::attributes::StringAttr) |
-    /// | [ATTR_KEY_START_FUNC_SYM](ModuleOp::ATTR_KEY_START_FUNC_SYM) | [StringAttr](super::attributes::StringAttr) |
+    /// | [ATTR_KEY_START_FUNC_SYM](ModuleOp::ATTR_KEY_START_FUNC_SYM) (optional) | [StringAttr](super::attributes::StringAttr) |
+    /// | [ATTR_KEY_GLOBAL_TYPES](ModuleOp::ATTR_KEY_GLOBAL_TYPES) | [VecAttr] of [TypeAttr] |
+    /// | [ATTR_KEY_EXPORT_NAMES](ModuleOp::ATTR_KEY_EXPORT_NAMES) (optional) | [VecAttr] of [StringAttr](super::attributes::StringAttr) |
+    /// | [ATTR_KEY_EXPORT_FUNC_SYMS](ModuleOp::ATTR_KEY_EXPORT_FUNC_SYMS) (optional) | [VecAttr] of [StringAttr](super::attributes::StringAttr) |
     ModuleOp,
     "module",
     "wasm"
@@ -88,9 +93,69 @@ impl DisplayWithContext for ModuleOp {
 
 impl Verify for ModuleOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
-        // TODO: check that the start function is defined.
+        let op = &*self.get_operation().deref(ctx);
+        for attr_key in [
+            Self::ATTR_KEY_FUNC_INDICES,
+            Self::ATTR_KEY_IMPORT_FUNC_TYPES,
+            Self::ATTR_KEY_IMPORT_FUNC_MODULES,
+            Self::ATTR_KEY_GLOBAL_TYPES,
+        ] {
+            if !op.attributes.contains_key(attr_key) {
+                return Err(CompilerError::VerificationError {
+                    msg: format!("wasm.module has no {attr_key} attribute"),
+                });
+            }
+        }
         self.verify_interfaces(ctx)?;
-        self.get_region(ctx).deref(ctx).verify(ctx)
+        if let Some(start_func_sym) = self.get_start_func_sym(ctx) {
+            if !self.has_symbol(ctx, &start_func_sym) {
+                return Err(CompilerError::VerificationError {
+                    msg: format!(
+                        "start function {} is not defined in wasm.module",
+                        start_func_sym.as_ref()
+                    ),
+                });
+            }
+        }
+        for (export_name, func_sym) in self.get_exports(ctx) {
+            if !self.has_symbol(ctx, &func_sym) {
+                return Err(CompilerError::VerificationError {
+                    msg: format!(
+                        "exported function {export_name} ({}) is not defined in wasm.module",
+                        func_sym.as_ref()
+                    ),
+                });
+            }
+        }
+        let mut unresolved_callees = Vec::new();
+        self.get_operation()
+            .walk(ctx, WalkOrder::PreOrder, &mut |op| {
+                let opop = op.deref(ctx).get_op(ctx);
+                if let Some(call_op) = opop.downcast_ref::<CallOp>() {
+                    let func_index = call_op.get_func_index(ctx);
+                    if self.get_func_sym(ctx, func_index).is_none() {
+                        unresolved_callees.push(format!("wasm.call {func_index}"));
+                    }
+                } else if let Some(call_op) = opop.downcast_ref::<ozk_ozk_dialect::ops::CallOp>() {
+                    let func_sym = call_op.get_func_sym(ctx);
+                    if self.get_func(ctx, &func_sym.clone().into()).is_none() {
+                        unresolved_callees.push(format!("ozk.call {func_sym}"));
+                    }
+                }
+                WalkResult::Advance
+            });
+        if let Some(callee) = unresolved_callees.first() {
+            return Err(CompilerError::VerificationError {
+                msg: format!("unresolved callee in wasm.module: {callee}"),
+            });
+        }
+        self.get_region(ctx).deref(ctx).verify(ctx)?;
+        for op in self.get_body(ctx, 0).deref(ctx).iter(ctx) {
+            if let Some(func_op) = op.deref(ctx).get_op(ctx).downcast_ref::<FuncOp>() {
+                crate::typed_stack::verify_stack_types(ctx, self, func_op)?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -107,6 +172,12 @@ impl ModuleOp {
     pub const ATTR_KEY_IMPORT_FUNC_TYPES: &str = "module.import_func_types";
     /// Attribute key for the import function modules.
     pub const ATTR_KEY_IMPORT_FUNC_MODULES: &str = "module.import_func_modules";
+    /// Attribute key for the global variable types (indexed by the global index)
+    pub const ATTR_KEY_GLOBAL_TYPES: &str = "module.global_types";
+    /// Attribute key for the names of the exported functions.
+    pub const ATTR_KEY_EXPORT_NAMES: &str = "module.export_names";
+    /// Attribute key for the symbols of the exported functions (in the order of the names).
+    pub const ATTR_KEY_EXPORT_FUNC_SYMS: &str = "module.export_func_syms";
 
     /// Create a new [ModuleOp].
     /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
@@ -114,20 +185,23 @@ impl ModuleOp {
     pub fn new(
         ctx: &mut Context,
         name: &str,
-        start_func_name: FuncSym,
+        start_func_name: Option<FuncSym>,
         all_func_syms: Vec<FuncSym>,
         functions: Vec<FuncOp>,
         import_func_types: Vec<Ptr<TypeObj>>,
         import_func_modules: Vec<String>,
+        global_types: Vec<Ptr<TypeObj>>,
     ) -> ModuleOp {
         let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 1);
         {
             let opref = &mut *op.deref_mut(ctx);
             // Set function type attributes.
-            opref.attributes.insert(
-                Self::ATTR_KEY_START_FUNC_SYM,
-                StringAttr::create(start_func_name.into()),
-            );
+            if let Some(start_func_name) = start_func_name {
+                opref.attributes.insert(
+                    Self::ATTR_KEY_START_FUNC_SYM,
+                    StringAttr::create(start_func_name.into()),
+                );
+            }
             opref.attributes.insert(
                 Self::ATTR_KEY_FUNC_INDICES,
                 VecAttr::create(
@@ -155,6 +229,10 @@ impl ModuleOp {
                         .collect(),
                 ),
             );
+            opref.attributes.insert(
+                Self::ATTR_KEY_GLOBAL_TYPES,
+                VecAttr::create(global_types.into_iter().map(TypeAttr::create).collect()),
+            );
         }
 
         let opop = ModuleOp { op };
@@ -192,20 +270,67 @@ impl ModuleOp {
         func_index.into()
     }
 
-    /// Return the start function symbol name
-    pub fn get_start_func_sym(&self, ctx: &Context) -> FuncSym {
+    /// Return the start function symbol name (if the module has a start function)
+    pub fn get_start_func_sym(&self, ctx: &Context) -> Option<FuncSym> {
         let self_op = self.get_operation().deref(ctx);
-        let s_attr = self_op
-            .attributes
-            .get(Self::ATTR_KEY_START_FUNC_SYM)
-            .expect("ModuleOp has no start function symbol attribute");
-        String::from(
-            s_attr
-                .downcast_ref::<StringAttr>()
-                .expect("ModuleOp start function symbol attribute is not a StringAttr")
-                .clone(),
+        let s_attr = self_op.attributes.get(Self::ATTR_KEY_START_FUNC_SYM)?;
+        Some(
+            String::from(
+                s_attr
+                    .downcast_ref::<StringAttr>()
+                    .expect("ModuleOp start function symbol attribute is not a StringAttr")
+                    .clone(),
+            )
+            .into(),
         )
-        .into()
+    }
+
+    /// Set the exported functions (export name, function symbol).
+    pub fn set_exports(&self, ctx: &mut Context, exports: Vec<(String, FuncSym)>) {
+        let (names, func_syms): (Vec<String>, Vec<FuncSym>) = exports.into_iter().unzip();
+        let mut self_op = self.get_operation().deref_mut(ctx);
+        self_op.attributes.insert(
+            Self::ATTR_KEY_EXPORT_NAMES,
+            VecAttr::create(names.into_iter().map(StringAttr::create).collect()),
+        );
+        self_op.attributes.insert(
+            Self::ATTR_KEY_EXPORT_FUNC_SYMS,
+            VecAttr::create(
+                func_syms
+                    .into_iter()
+                    .map(|func_sym| StringAttr::create(func_sym.into()))
+                    .collect(),
+            ),
+        );
+    }
+
+    /// Return the exported functions (export name, function symbol).
+    pub fn get_exports(&self, ctx: &Context) -> Vec<(String, FuncSym)> {
+        let self_op = self.get_operation().deref(ctx);
+        let strings = |key: &str| -> Vec<String> {
+            self_op.attributes.get(key).map_or(Vec::new(), |v_attr| {
+                v_attr
+                    .downcast_ref::<VecAttr>()
+                    .expect("ModuleOp exports attribute is not a VecAttr")
+                    .0
+                    .iter()
+                    .map(|attr: &AttrObj| {
+                        attr.downcast_ref::<StringAttr>()
+                            .expect("ModuleOp export is not a StringAttr")
+                            .clone()
+                            .into()
+                    })
+                    .collect()
+            })
+        };
+        strings(Self::ATTR_KEY_EXPORT_NAMES)
+            .into_iter()
+            .zip(
+                strings(Self::ATTR_KEY_EXPORT_FUNC_SYMS)
+                    .into_iter()
+                    .map(FuncSym::from),
+            )
+            .collect()
     }
 
     fn get_func_syms(&self, ctx: &Context) -> Vec<FuncSym> {
@@ -245,6 +370,100 @@ impl ModuleOp {
             .map(Into::into)
     }
 
+    /// Return the type of the imported function with the given index
+    /// (the imported functions come first in the function index space).
+    pub fn get_import_func_type(
+        &self,
+        ctx: &Context,
+        func_index: FuncIndex,
+    ) -> Option<FunctionType> {
+        let self_op = self.get_operation().deref(ctx);
+        let v_attr = self_op
+            .attributes
+            .get(Self::ATTR_KEY_IMPORT_FUNC_TYPES)
+            .expect("ModuleOp has no import function types attribute");
+        let ty = v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("ModuleOp import function types attribute is not a VecAttr")
+            .0
+            .get(usize::from(func_index))
+            .map(|attr: &AttrObj| {
+                attr.downcast_ref::<TypeAttr>()
+                    .expect("ModuleOp import function type is not a TypeAttr")
+                    .get_type()
+            })?;
+        ty.deref(ctx).downcast_ref::<FunctionType>().cloned()
+    }
+
+    /// Return the module name of the imported function with the given index
+    /// (the imported functions come first in the function index space).
+    pub fn get_import_func_module(&self, ctx: &Context, func_index: FuncIndex) -> Option<String> {
+        let self_op = self.get_operation().deref(ctx);
+        let v_attr = self_op
+            .attributes
+            .get(Self::ATTR_KEY_IMPORT_FUNC_MODULES)
+            .expect("ModuleOp has no import function modules attribute");
+        v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("ModuleOp import function modules attribute is not a VecAttr")
+            .0
+            .get(usize::from(func_index))
+            .map(|attr: &AttrObj| {
+                attr.downcast_ref::<StringAttr>()
+                    .expect("ModuleOp import function module is not a StringAttr")
+                    .clone()
+                    .into()
+            })
+    }
+
+    /// Return the type of the global variable with the given index.
+    pub fn get_global_type(
+        &self,
+        ctx: &Context,
+        global_index: GlobalIndex,
+    ) -> Option<Ptr<TypeObj>> {
+        let self_op = self.get_operation().deref(ctx);
+        let v_attr = self_op
+            .attributes
+            .get(Self::ATTR_KEY_GLOBAL_TYPES)
+            .expect("ModuleOp has no global types attribute");
+        v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("ModuleOp global types attribute is not a VecAttr")
+            .0
+            .get(u32::from(global_index) as usize)
+            .map(|attr: &AttrObj| {
+                attr.downcast_ref::<TypeAttr>()
+                    .expect("ModuleOp global type is not a TypeAttr")
+                    .get_type()
+            })
+    }
+
+    /// Add a global variable of the given type. Returns its index.
+    pub fn append_global(&self, ctx: &mut Context, ty: Ptr<TypeObj>) -> GlobalIndex {
+        let mut self_op = self.get_operation().deref_mut(ctx);
+        #[allow(clippy::expect_used)]
+        let global_types_attr = self_op
+            .attributes
+            .get_mut(Self::ATTR_KEY_GLOBAL_TYPES)
+            .expect("ModuleOp has no global types attribute")
+            .downcast_mut::<VecAttr>()
+            .expect("ModuleOp global types attribute is not a VecAttr");
+        global_types_attr.0.push(TypeAttr::create(ty));
+        (global_types_attr.0.len() as u32 - 1).into()
+    }
+
+    /// Returns true if the module body has an op (e.g. a function in another dialect)
+    /// with the given symbol name.
+    fn has_symbol(&self, ctx: &Context, func_sym: &FuncSym) -> bool {
+        self.get_body(ctx, 0).deref(ctx).iter(ctx).any(|op| {
+            op_cast::<dyn SymbolOpInterface>(op.deref(ctx).get_op(ctx).as_ref())
+                .map_or(false, |sym_op| {
+                    sym_op.get_symbol_name(ctx) == func_sym.as_ref()
+                })
+        })
+    }
+
     pub fn get_func(&self, ctx: &Context, func_sym: &FuncSym) -> Option<FuncOp> {
         for op in self.get_body(ctx, 0).deref(ctx).iter(ctx) {
             let deref_op = &op.deref(ctx).get_op(ctx);
@@ -275,6 +494,10 @@ impl FuncOp {
     /// Attribute key for the function type
     pub const ATTR_KEY_FUNC_TYPE: &str = "func.type";
     pub const ATTR_KEY_FUNC_LOCALS: &str = "func.locals";
+    /// Attribute key for the marker that the function arguments are on the stack at the entry
+    pub const ATTR_KEY_ARGS_ON_STACK: &str = "func.args_on_stack";
+    /// Attribute key for the marker that the function body is lowered to the `ozk` dialect
+    pub const ATTR_KEY_BODY_IN_OZK: &str = "func.body_in_ozk";
 
     /// Create a new [FuncOp].
     /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
@@ -361,6 +584,58 @@ impl FuncOp {
             })
             .collect()
     }
+
+    /// Set the function signature (type).
+    pub fn set_type(&self, ctx: &mut Context, ty: Ptr<TypeObj>) {
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_TYPE, TypeAttr::create(ty));
+    }
+
+    /// Set the local variables types
+    pub fn set_locals(&self, ctx: &mut Context, locals: Vec<Ptr<TypeObj>>) {
+        self.get_operation().deref_mut(ctx).attributes.insert(
+            Self::ATTR_KEY_FUNC_LOCALS,
+            VecAttr::create(locals.into_iter().map(TypeAttr::create).collect()),
+        );
+    }
+
+    /// Mark the function as taking its arguments on the stack (instead of in the locals).
+    pub fn set_args_on_stack(&self, ctx: &mut Context) {
+        let attr = u32_attr(ctx, 1);
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_ARGS_ON_STACK, attr);
+    }
+
+    /// Returns true if the function arguments are on the stack at the function entry
+    /// (the first argument on top).
+    pub fn has_args_on_stack(&self, ctx: &Context) -> bool {
+        self.get_operation()
+            .deref(ctx)
+            .attributes
+            .contains_key(Self::ATTR_KEY_ARGS_ON_STACK)
+    }
+
+    /// Mark the function body as lowered to the `ozk` dialect.
+    pub fn set_body_in_ozk(&self, ctx: &mut Context) {
+        let attr = u32_attr(ctx, 1);
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_BODY_IN_OZK, attr);
+    }
+
+    /// Returns true if the function body is lowered to the `ozk` dialect
+    /// (see `wasm-lower-to-ozk`), i.e. the passes adding ops to it should add `ozk` ops.
+    pub fn has_body_in_ozk(&self, ctx: &Context) -> bool {
+        self.get_operation()
+            .deref(ctx)
+            .attributes
+            .contains_key(Self::ATTR_KEY_BODY_IN_OZK)
+    }
 }
 
 impl OneRegionInterface for FuncOp {}
@@ -563,6 +838,150 @@ impl Verify for AddOp {
     }
 }
 
+/// Declares a binary integer op with the type of the operands in the [TypeAttr] attribute
+/// (see [AddOp]).
+macro_rules! int_binary_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal, $attr_key:literal) => {
+        declare_op!($(#[$outer])* $name, $op_name, "wasm");
+
+        impl $name {
+            /// Attribute key
+            pub const ATTR_KEY_OP_TYPE: &str = $attr_key;
+
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock](crate::basic_block::BasicBlock).
+            pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> $name {
+                let ty_attr = TypeAttr::create(ty);
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                op.deref_mut(ctx)
+                    .attributes
+                    .insert(Self::ATTR_KEY_OP_TYPE, ty_attr);
+                $name { op }
+            }
+
+            /// Get the type of the operands and the result of this operation.
+            pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
+                let opref = self.get_operation().deref(ctx);
+                #[allow(clippy::expect_used)]
+                let ty_attr = opref
+                    .attributes
+                    .get(Self::ATTR_KEY_OP_TYPE)
+                    .expect("no type attribute");
+                #[allow(clippy::expect_used)]
+                attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+                    .expect("invalid type attribute")
+                    .get_type()
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", self.get_opid().with_ctx(ctx),)
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                let op = &*self.get_operation().deref(ctx);
+                if op.get_opid() != Self::get_opid_static() {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect OpId".to_string(),
+                    });
+                }
+                if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect number of results or operands".to_string(),
+                    });
+                }
+                if !op
+                    .attributes
+                    .get(Self::ATTR_KEY_OP_TYPE)
+                    .and_then(|attr| attr_cast::<dyn TypedAttrInterface>(&**attr))
+                    .map_or(false, |attr| attr.get_type().deref(ctx).is::<IntegerType>())
+                {
+                    return Err(CompilerError::VerificationError {
+                        msg: format!("{} has no integer type", self.get_opid().with_ctx(ctx)),
+                    });
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes `a - b` (wrapping around)
+    SubOp,
+    "sub",
+    "sub.type"
+);
+
+int_binary_op!(
+    /// Pops two values, pushes their product (wrapping around)
+    MulOp,
+    "mul",
+    "mul.type"
+);
+
+int_binary_op!(
+    /// Pops the shift amount `b` and `a`, pushes `a << (b mod N)` (N is the type width)
+    ShlOp,
+    "shl",
+    "shl.type"
+);
+
+int_binary_op!(
+    /// Pops the shift amount `b` and `a`, pushes `a >> (b mod N)` (logical shift, N is the type width)
+    ShrUOp,
+    "shr_u",
+    "shr_u.type"
+);
+
+int_binary_op!(
+    /// Pops the shift amount `b` and `a`, pushes `a >> (b mod N)` (arithmetic shift, N is the type width)
+    ShrSOp,
+    "shr_s",
+    "shr_s.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes the unsigned quotient `a / b` (traps if `b` is zero)
+    DivUOp,
+    "div_u",
+    "div_u.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes the signed quotient `a / b` rounded toward zero
+    /// (traps if `b` is zero or on the overflow)
+    DivSOp,
+    "div_s",
+    "div_s.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes the unsigned remainder of `a / b` (traps if `b` is zero)
+    RemUOp,
+    "rem_u",
+    "rem_u.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes the signed remainder of `a / b` with the sign of `a`
+    /// (traps if `b` is zero)
+    RemSOp,
+    "rem_s",
+    "rem_s.type"
+);
+
+int_binary_op!(
+    /// Pops a value, pushes the number of its bits set to 1 (the op is unary, but has
+    /// the same type attribute as the binary ones)
+    PopcntOp,
+    "popcnt",
+    "popcnt.type"
+);
+
 declare_op!(
     /// Call a function by it's index in the module
     ///
@@ -1310,63 +1729,182 @@ pub enum MemAccessOpValueType {
     I64,
 }
 
+impl MemAccessOpValueType {
+    /// Convert an i32 or i64 type to a [MemAccessOpValueType].
+    pub fn from_type(ctx: &Context, ty: Ptr<TypeObj>) -> Option<MemAccessOpValueType> {
+        match ty.deref(ctx).downcast_ref::<IntegerType>()?.get_width() {
+            32 => Some(MemAccessOpValueType::I32),
+            64 => Some(MemAccessOpValueType::I64),
+            _ => None,
+        }
+    }
+
+    /// The size of the value in bytes.
+    pub fn size(&self) -> u32 {
+        match self {
+            MemAccessOpValueType::I32 => 4,
+            MemAccessOpValueType::I64 => 8,
+        }
+    }
+
+    /// The natural alignment (the exponent of 2) of the value.
+    pub fn natural_align(&self) -> u32 {
+        self.size().trailing_zeros()
+    }
+
+    fn get_type(&self, ctx: &mut Context) -> Ptr<TypeObj> {
+        match self {
+            MemAccessOpValueType::I32 => i32_type(ctx),
+            MemAccessOpValueType::I64 => i64_type(ctx),
+        }
+    }
+}
+
+/// Get the value of the u32 attribute.
+fn get_u32_attr(op: &Operation, key: &str) -> Option<u32> {
+    let attr = op.attributes.get(key)?.downcast_ref::<IntegerAttr>()?;
+    ApInt::from(attr.clone()).try_to_u32().ok()
+}
+
+/// Get the value type from the type attribute.
+fn get_value_type_attr(ctx: &Context, op: &Operation, key: &str) -> Option<MemAccessOpValueType> {
+    let ty = op
+        .attributes
+        .get(key)?
+        .downcast_ref::<TypeAttr>()?
+        .get_type();
+    MemAccessOpValueType::from_type(ctx, ty)
+}
+
+/// Check the access size (bytes) and the memarg of a [StoreOp] or [LoadOp].
+fn verify_mem_access(
+    ty: MemAccessOpValueType,
+    size: u32,
+    memarg: MemArg,
+) -> Result<(), CompilerError> {
+    if !size.is_power_of_two() || size > ty.size() {
+        return Err(CompilerError::VerificationError {
+            msg: format!("invalid access size {size} for {ty}"),
+        });
+    }
+    if memarg.align > size.trailing_zeros() {
+        return Err(CompilerError::VerificationError {
+            msg: format!(
+                "alignment {} is larger than the access size {size}",
+                1u64 << memarg.align
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Format the access size, the sign extension and the memarg if they are not the default
+/// (the whole value, no offset and the natural alignment).
+fn fmt_mem_access(
+    f: &mut core::fmt::Formatter<'_>,
+    size: u32,
+    signed: Option<bool>,
+    memarg: MemArg,
+) -> core::fmt::Result {
+    if let Some(signed) = signed {
+        write!(f, " {}_{}", size * 8, if signed { "s" } else { "u" })?;
+    }
+    if memarg.offset != 0 {
+        write!(f, " offset={}", memarg.offset)?;
+    }
+    if memarg.align != size.trailing_zeros() {
+        write!(f, " align={}", 1u64 << memarg.align)?;
+    }
+    Ok(())
+}
+
 declare_op!(
     /// Pops the i32 or i64 value and i32 addresss from stack and save the value at the address.
     ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_VALUE_TYPE](Self::ATTR_KEY_VALUE_TYPE) | [TypeAttr] |
+    /// | [ATTR_KEY_SIZE](Self::ATTR_KEY_SIZE) | [IntegerAttr] |
+    /// | [ATTR_KEY_OFFSET](Self::ATTR_KEY_OFFSET) | [IntegerAttr] |
+    /// | [ATTR_KEY_ALIGN](Self::ATTR_KEY_ALIGN) | [IntegerAttr] |
     StoreOp,
     "store",
     "wasm"
 );
 
 impl StoreOp {
-    const ATTR_KEY_VALUE_TYPE: &str = "store.value.type";
-
-    /// Create a new [StoreOp].
+    /// Attribute key for the value type
+    pub const ATTR_KEY_VALUE_TYPE: &str = "store.value.type";
+    /// Attribute key for the number of the stored bytes (the low bytes of the value)
+    pub const ATTR_KEY_SIZE: &str = "store.size";
+    /// Attribute key for the memarg offset
+    pub const ATTR_KEY_OFFSET: &str = "store.offset";
+    /// Attribute key for the memarg alignment
+    pub const ATTR_KEY_ALIGN: &str = "store.align";
+
+    /// Create a new [StoreOp] storing the whole value with no offset and the natural alignment.
     pub fn new_unlinked(ctx: &mut Context, ty: MemAccessOpValueType) -> StoreOp {
-        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        Self::new_sized_unlinked(ctx, ty, ty.size(), MemArg::natural(ty.size()))
+    }
 
-        let value_type_attr = match ty {
-            MemAccessOpValueType::I32 => i32_type(ctx),
-            MemAccessOpValueType::I64 => i64_type(ctx),
-        };
-        op.deref_mut(ctx)
-            .attributes
-            .insert(Self::ATTR_KEY_VALUE_TYPE, TypeAttr::create(value_type_attr));
+    /// Create a new [StoreOp] storing the `size` low bytes of the value (e.g. `i32.store8`).
+    pub fn new_sized_unlinked(
+        ctx: &mut Context,
+        ty: MemAccessOpValueType,
+        size: u32,
+        memarg: MemArg,
+    ) -> StoreOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        let value_type_attr = TypeAttr::create(ty.get_type(ctx));
+        let size_attr = u32_attr(ctx, size);
+        let offset_attr = u32_attr(ctx, memarg.offset);
+        let align_attr = u32_attr(ctx, memarg.align);
+        let attributes = &mut op.deref_mut(ctx).attributes;
+        attributes.insert(Self::ATTR_KEY_VALUE_TYPE, value_type_attr);
+        attributes.insert(Self::ATTR_KEY_SIZE, size_attr);
+        attributes.insert(Self::ATTR_KEY_OFFSET, offset_attr);
+        attributes.insert(Self::ATTR_KEY_ALIGN, align_attr);
         StoreOp { op }
     }
 
     /// Get the type of the value.
     pub fn get_value_type(&self, ctx: &Context) -> MemAccessOpValueType {
         let op = self.get_operation().deref(ctx);
-        let value = op
-            .attributes
-            .get(Self::ATTR_KEY_VALUE_TYPE)
-            .expect("no attribute found");
-        let ty = value
-            .downcast_ref::<TypeAttr>()
-            .expect("Expected TypeAttr")
-            .get_type()
-            .deref(ctx);
-        let int_ty = ty
-            .downcast_ref::<IntegerType>()
-            .expect("Expected IntegerType");
-        assert!(int_ty.get_signedness() == Signedness::Signed);
-        match int_ty.get_width() {
-            32 => MemAccessOpValueType::I32,
-            64 => MemAccessOpValueType::I64,
-            _ => panic!("Unexpected bitwidth"),
+        get_value_type_attr(ctx, &op, Self::ATTR_KEY_VALUE_TYPE).expect("invalid value type")
+    }
+
+    /// Get the number of the stored bytes.
+    pub fn get_size(&self, ctx: &Context) -> u32 {
+        let op = self.get_operation().deref(ctx);
+        get_u32_attr(&op, Self::ATTR_KEY_SIZE).expect("no size attribute")
+    }
+
+    /// Get the offset and the alignment hint.
+    pub fn get_memarg(&self, ctx: &Context) -> MemArg {
+        let op = self.get_operation().deref(ctx);
+        MemArg {
+            offset: get_u32_attr(&op, Self::ATTR_KEY_OFFSET).expect("no offset attribute"),
+            align: get_u32_attr(&op, Self::ATTR_KEY_ALIGN).expect("no align attribute"),
         }
     }
+
+    /// Returns true if the whole value is stored at the address operand (no offset).
+    pub fn is_plain(&self, ctx: &Context) -> bool {
+        self.get_size(ctx) == self.get_value_type(ctx).size() && self.get_memarg(ctx).offset == 0
+    }
 }
 
 impl DisplayWithContext for StoreOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(
-            f,
-            "{} {}",
-            self.get_opid().with_ctx(ctx),
-            self.get_value_type(ctx)
-        )
+        let ty = self.get_value_type(ctx);
+        let size = self.get_size(ctx);
+        write!(f, "{} {}", self.get_opid().with_ctx(ctx), ty)?;
+        if size != ty.size() {
+            write!(f, " {}", size * 8)?;
+        }
+        fmt_mem_access(f, size, None, self.get_memarg(ctx))
     }
 }
 
@@ -1383,67 +1921,118 @@ impl Verify for StoreOp {
                 msg: "Incorrect number of results or operands".to_string(),
             });
         }
-        Ok(())
+        let (Some(ty), Some(size), Some(offset), Some(align)) = (
+            get_value_type_attr(ctx, op, Self::ATTR_KEY_VALUE_TYPE),
+            get_u32_attr(op, Self::ATTR_KEY_SIZE),
+            get_u32_attr(op, Self::ATTR_KEY_OFFSET),
+            get_u32_attr(op, Self::ATTR_KEY_ALIGN),
+        ) else {
+            return Err(CompilerError::VerificationError {
+                msg: "wasm.store has invalid attributes".to_string(),
+            });
+        };
+        verify_mem_access(ty, size, MemArg { offset, align })
     }
 }
 
 declare_op!(
     /// push the i32 or i64 value loaded from i32 addresss poped from the stack
     ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_VALUE_TYPE](Self::ATTR_KEY_VALUE_TYPE) | [TypeAttr] |
+    /// | [ATTR_KEY_SIZE](Self::ATTR_KEY_SIZE) | [IntegerAttr] |
+    /// | [ATTR_KEY_SIGNED](Self::ATTR_KEY_SIGNED) | [IntegerAttr] |
+    /// | [ATTR_KEY_OFFSET](Self::ATTR_KEY_OFFSET) | [IntegerAttr] |
+    /// | [ATTR_KEY_ALIGN](Self::ATTR_KEY_ALIGN) | [IntegerAttr] |
     LoadOp,
     "load",
     "wasm"
 );
 
 impl LoadOp {
-    const ATTR_KEY_VALUE_TYPE: &str = "store.value.type";
-
-    /// Create a new [LoadOp].
+    /// Attribute key for the value type
+    pub const ATTR_KEY_VALUE_TYPE: &str = "store.value.type";
+    /// Attribute key for the number of the loaded bytes
+    pub const ATTR_KEY_SIZE: &str = "load.size";
+    /// Attribute key for the sign extension of the loaded bytes (1 - sign extend, 0 - zero extend)
+    pub const ATTR_KEY_SIGNED: &str = "load.signed";
+    /// Attribute key for the memarg offset
+    pub const ATTR_KEY_OFFSET: &str = "load.offset";
+    /// Attribute key for the memarg alignment
+    pub const ATTR_KEY_ALIGN: &str = "load.align";
+
+    /// Create a new [LoadOp] loading the whole value with no offset and the natural alignment.
     pub fn new_unlinked(ctx: &mut Context, ty: MemAccessOpValueType) -> LoadOp {
-        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        Self::new_sized_unlinked(ctx, ty, ty.size(), false, MemArg::natural(ty.size()))
+    }
 
-        let value_type_attr = match ty {
-            MemAccessOpValueType::I32 => i32_type(ctx),
-            MemAccessOpValueType::I64 => i64_type(ctx),
-        };
-        op.deref_mut(ctx)
-            .attributes
-            .insert(Self::ATTR_KEY_VALUE_TYPE, TypeAttr::create(value_type_attr));
+    /// Create a new [LoadOp] loading `size` bytes extended to the value type
+    /// (e.g. `i32.load8_s`).
+    pub fn new_sized_unlinked(
+        ctx: &mut Context,
+        ty: MemAccessOpValueType,
+        size: u32,
+        signed: bool,
+        memarg: MemArg,
+    ) -> LoadOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        let value_type_attr = TypeAttr::create(ty.get_type(ctx));
+        let size_attr = u32_attr(ctx, size);
+        let signed_attr = u32_attr(ctx, signed as u32);
+        let offset_attr = u32_attr(ctx, memarg.offset);
+        let align_attr = u32_attr(ctx, memarg.align);
+        let attributes = &mut op.deref_mut(ctx).attributes;
+        attributes.insert(Self::ATTR_KEY_VALUE_TYPE, value_type_attr);
+        attributes.insert(Self::ATTR_KEY_SIZE, size_attr);
+        attributes.insert(Self::ATTR_KEY_SIGNED, signed_attr);
+        attributes.insert(Self::ATTR_KEY_OFFSET, offset_attr);
+        attributes.insert(Self::ATTR_KEY_ALIGN, align_attr);
         LoadOp { op }
     }
 
     /// Get the type of the value.
     pub fn get_value_type(&self, ctx: &Context) -> MemAccessOpValueType {
         let op = self.get_operation().deref(ctx);
-        let value = op
-            .attributes
-            .get(Self::ATTR_KEY_VALUE_TYPE)
-            .expect("no attribute found");
-        let ty = value
-            .downcast_ref::<TypeAttr>()
-            .expect("Expected TypeAttr")
-            .get_type()
-            .deref(ctx);
-        let int_ty = ty
-            .downcast_ref::<IntegerType>()
-            .expect("Expected IntegerType");
-        assert!(int_ty.get_signedness() == Signedness::Signed);
-        match int_ty.get_width() {
-            32 => MemAccessOpValueType::I32,
-            64 => MemAccessOpValueType::I64,
-            _ => panic!("Unexpected bitwidth"),
+        get_value_type_attr(ctx, &op, Self::ATTR_KEY_VALUE_TYPE).expect("invalid value type")
+    }
+
+    /// Get the number of the loaded bytes.
+    pub fn get_size(&self, ctx: &Context) -> u32 {
+        let op = self.get_operation().deref(ctx);
+        get_u32_attr(&op, Self::ATTR_KEY_SIZE).expect("no size attribute")
+    }
+
+    /// Returns true if the loaded bytes are sign extended.
+    pub fn is_signed(&self, ctx: &Context) -> bool {
+        let op = self.get_operation().deref(ctx);
+        get_u32_attr(&op, Self::ATTR_KEY_SIGNED).expect("no signed attribute") != 0
+    }
+
+    /// Get the offset and the alignment hint.
+    pub fn get_memarg(&self, ctx: &Context) -> MemArg {
+        let op = self.get_operation().deref(ctx);
+        MemArg {
+            offset: get_u32_attr(&op, Self::ATTR_KEY_OFFSET).expect("no offset attribute"),
+            align: get_u32_attr(&op, Self::ATTR_KEY_ALIGN).expect("no align attribute"),
         }
     }
+
+    /// Returns true if the whole value is loaded from the address operand (no offset).
+    pub fn is_plain(&self, ctx: &Context) -> bool {
+        self.get_size(ctx) == self.get_value_type(ctx).size() && self.get_memarg(ctx).offset == 0
+    }
 }
 
 impl DisplayWithContext for LoadOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(
-            f,
-            "{} {}",
-            self.get_opid().with_ctx(ctx),
-            self.get_value_type(ctx)
-        )
+        let ty = self.get_value_type(ctx);
+        let size = self.get_size(ctx);
+        write!(f, "{} {}", self.get_opid().with_ctx(ctx), ty)?;
+        let signed = (size != ty.size()).then(|| self.is_signed(ctx));
+        fmt_mem_access(f, size, signed, self.get_memarg(ctx))
     }
 }
 
@@ -1460,7 +2049,23 @@ impl Verify for LoadOp {
                 msg: "Incorrect number of results or operands".to_string(),
             });
         }
-        Ok(())
+        let (Some(ty), Some(size), Some(signed), Some(offset), Some(align)) = (
+            get_value_type_attr(ctx, op, Self::ATTR_KEY_VALUE_TYPE),
+            get_u32_attr(op, Self::ATTR_KEY_SIZE),
+            get_u32_attr(op, Self::ATTR_KEY_SIGNED),
+            get_u32_attr(op, Self::ATTR_KEY_OFFSET),
+            get_u32_attr(op, Self::ATTR_KEY_ALIGN),
+        ) else {
+            return Err(CompilerError::VerificationError {
+                msg: "wasm.load has invalid attributes".to_string(),
+            });
+        };
+        if signed != 0 && size == ty.size() {
+            return Err(CompilerError::VerificationError {
+                msg: "wasm.load of the whole value can't be sign extended".to_string(),
+            });
+        }
+        verify_mem_access(ty, size, MemArg { offset, align })
     }
 }
 
@@ -1643,11 +2248,181 @@ impl Verify for I32EqzOp {
     }
 }
 
+int_binary_op!(
+    /// Pops two values, pushes their bitwise AND
+    AndOp,
+    "and",
+    "and.type"
+);
+
+int_binary_op!(
+    /// Pops two values, pushes their bitwise OR
+    OrOp,
+    "or",
+    "or.type"
+);
+
+int_binary_op!(
+    /// Pops two values, pushes their bitwise XOR
+    XorOp,
+    "xor",
+    "xor.type"
+);
+
+int_binary_op!(
+    /// Pops two values, pushes 1 (i32) if they are equal, otherwise 0
+    EqOp,
+    "eq",
+    "eq.type"
+);
+
+int_binary_op!(
+    /// Pops two values, pushes 1 (i32) if they are not equal, otherwise 0
+    NeOp,
+    "ne",
+    "ne.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a < b` (unsigned), otherwise 0
+    LtUOp,
+    "lt_u",
+    "lt_u.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a < b` (signed), otherwise 0
+    LtSOp,
+    "lt_s",
+    "lt_s.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a > b` (unsigned), otherwise 0
+    GtUOp,
+    "gt_u",
+    "gt_u.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a > b` (signed), otherwise 0
+    GtSOp,
+    "gt_s",
+    "gt_s.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a <= b` (unsigned), otherwise 0
+    LeUOp,
+    "le_u",
+    "le_u.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a <= b` (signed), otherwise 0
+    LeSOp,
+    "le_s",
+    "le_s.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a >= b` (unsigned), otherwise 0
+    GeUOp,
+    "ge_u",
+    "ge_u.type"
+);
+
+int_binary_op!(
+    /// Pops `b` and `a`, pushes 1 (i32) if `a >= b` (signed), otherwise 0
+    GeSOp,
+    "ge_s",
+    "ge_s.type"
+);
+
+/// Declares an op without attributes.
+macro_rules! plain_op {
+    ($(#[$outer:meta])* $name:ident, $op_name:literal) => {
+        declare_op!($(#[$outer])* $name, $op_name, "wasm");
+
+        impl $name {
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock](crate::basic_block::BasicBlock).
+            pub fn new_unlinked(ctx: &mut Context) -> $name {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                $name { op }
+            }
+        }
+
+        impl DisplayWithContext for $name {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", self.get_opid().with_ctx(ctx),)
+            }
+        }
+
+        impl Verify for $name {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                let op = &*self.get_operation().deref(ctx);
+                if op.get_opid() != Self::get_opid_static() {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect OpId".to_string(),
+                    });
+                }
+                if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect number of results or operands".to_string(),
+                    });
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+plain_op!(
+    /// Pops the i64 value from the stack and if its zero pushes 1 (i32) otherwise pushes 0.
+    I64EqzOp,
+    "i64.eqz"
+);
+
+plain_op!(
+    /// Pops the i32 value, pushes it zero-extended to i64.
+    I64ExtendI32UOp,
+    "i64.extend_i32_u"
+);
+
+plain_op!(
+    /// Pops the i32 value, pushes it sign-extended to i64.
+    I64ExtendI32SOp,
+    "i64.extend_i32_s"
+);
+
+plain_op!(
+    /// Pops the i64 value, pushes its low 32 bits as i32.
+    I32WrapI64Op,
+    "i32.wrap_i64"
+);
+
+plain_op!(
+    /// Pops the top value from the stack.
+    DropOp,
+    "drop"
+);
+
 pub(crate) fn register(ctx: &mut Context, dialect: &mut Dialect) {
     ModuleOp::register(ctx, dialect);
     ConstantOp::register(ctx, dialect);
     FuncOp::register(ctx, dialect);
     AddOp::register(ctx, dialect);
+    SubOp::register(ctx, dialect);
+    MulOp::register(ctx, dialect);
+    ShlOp::register(ctx, dialect);
+    ShrUOp::register(ctx, dialect);
+    ShrSOp::register(ctx, dialect);
+    DivUOp::register(ctx, dialect);
+    DivSOp::register(ctx, dialect);
+    RemUOp::register(ctx, dialect);
+    RemSOp::register(ctx, dialect);
+    PopcntOp::register(ctx, dialect);
     CallOp::register(ctx, dialect);
     ReturnOp::register(ctx, dialect);
     BlockOp::register(ctx, dialect);
@@ -1662,4 +2437,22 @@ pub(crate) fn register(ctx: &mut Context, dialect: &mut Dialect) {
     BrOp::register(ctx, dialect);
     BrIfOp::register(ctx, dialect);
     I32EqzOp::register(ctx, dialect);
+    AndOp::register(ctx, dialect);
+    OrOp::register(ctx, dialect);
+    XorOp::register(ctx, dialect);
+    EqOp::register(ctx, dialect);
+    NeOp::register(ctx, dialect);
+    LtUOp::register(ctx, dialect);
+    LtSOp::register(ctx, dialect);
+    GtUOp::register(ctx, dialect);
+    GtSOp::register(ctx, dialect);
+    LeUOp::register(ctx, dialect);
+    LeSOp::register(ctx, dialect);
+    GeUOp::register(ctx, dialect);
+    GeSOp::register(ctx, dialect);
+    I64EqzOp::register(ctx, dialect);
+    I64ExtendI32UOp::register(ctx, dialect);
+    I64ExtendI32SOp::register(ctx, dialect);
+    I32WrapI64Op::register(ctx, dialect);
+    DropOp::register(ctx, dialect);
 }
@@ -0,0 +1,822 @@
+//! Typed operand stack verification.
+//!
+//! [StackDepthChange](crate::op_interfaces::StackDepthChange) only tracks how deep the stack is.
+//! Here an abstract stack of value types is propagated through the body of a [FuncOp]
+//! (following `block`, `loop`, `br`, `br_if` and `return` the same way Wasm validation does)
+//! to catch rewrites that leave ops consuming values of the wrong type
+//! or blocks leaving the wrong values on the stack.
+//! The `ozk` ops that Wasm ops are lowered to are checked as well.
+
+use std::collections::HashMap;
+
+use derive_more::Display;
+use ozk_ozk_dialect::attributes::apint_to_i32;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::FieldElemType;
+use pliron::attribute::attr_cast;
+use pliron::common_traits::DisplayWithContext;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::attr_interfaces::TypedAttrInterface;
+use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::dialects::builtin::types::IntegerType;
+use pliron::error::CompilerError;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::r#type::TypeObj;
+use pliron::with_context::AttachContext;
+
+use crate::ops::AddOp;
+use crate::ops::AndOp;
+use crate::ops::BlockOp;
+use crate::ops::BrIfOp;
+use crate::ops::BrOp;
+use crate::ops::CallOp;
+use crate::ops::ConstantOp;
+use crate::ops::DivSOp;
+use crate::ops::DivUOp;
+use crate::ops::DropOp;
+use crate::ops::EqOp;
+use crate::ops::FuncOp;
+use crate::ops::GeSOp;
+use crate::ops::GeUOp;
+use crate::ops::GlobalGetOp;
+use crate::ops::GlobalSetOp;
+use crate::ops::GtSOp;
+use crate::ops::GtUOp;
+use crate::ops::I32EqzOp;
+use crate::ops::I32WrapI64Op;
+use crate::ops::I64EqzOp;
+use crate::ops::I64ExtendI32SOp;
+use crate::ops::I64ExtendI32UOp;
+use crate::ops::LeSOp;
+use crate::ops::LeUOp;
+use crate::ops::LoadOp;
+use crate::ops::LocalGetOp;
+use crate::ops::LocalSetOp;
+use crate::ops::LocalTeeOp;
+use crate::ops::LoopOp;
+use crate::ops::LtSOp;
+use crate::ops::LtUOp;
+use crate::ops::MemAccessOpValueType;
+use crate::ops::ModuleOp;
+use crate::ops::MulOp;
+use crate::ops::NeOp;
+use crate::ops::OrOp;
+use crate::ops::PopcntOp;
+use crate::ops::RemSOp;
+use crate::ops::RemUOp;
+use crate::ops::ReturnOp;
+use crate::ops::ShlOp;
+use crate::ops::ShrSOp;
+use crate::ops::ShrUOp;
+use crate::ops::StoreOp;
+use crate::ops::SubOp;
+use crate::ops::XorOp;
+use crate::types::GlobalIndex;
+use crate::types::RelativeDepth;
+
+/// Type of a value on the operand stack.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display)]
+pub enum StackType {
+    #[display(fmt = "i32")]
+    I32,
+    #[display(fmt = "i64")]
+    I64,
+    #[display(fmt = "felt")]
+    Felt,
+}
+
+impl StackType {
+    /// Convert an IR type to a stack type.
+    pub fn from_type(ctx: &Context, ty: Ptr<TypeObj>) -> Option<StackType> {
+        let ty = ty.deref(ctx);
+        if let Some(int_ty) = ty.downcast_ref::<IntegerType>() {
+            match int_ty.get_width() {
+                32 => Some(StackType::I32),
+                64 => Some(StackType::I64),
+                _ => None,
+            }
+        } else if ty.is::<FieldElemType>() {
+            Some(StackType::Felt)
+        } else {
+            None
+        }
+    }
+}
+
+/// Verify that every op in the function body consumes values of the expected types
+/// and that every block (and the function itself) ends with exactly its result types on the stack.
+///
+/// Calls to functions without a body (imports) and ops this analysis does not know about
+/// leave the stack of the enclosing block unknown (as after a `br`): the rest of the block is
+/// checked against an unknown stack and the checking is precise again after the block.
+pub fn verify_stack_types(
+    ctx: &Context,
+    module_op: &ModuleOp,
+    func_op: &FuncOp,
+) -> Result<(), CompilerError> {
+    check_func(ctx, module_op, func_op, false).map(|_| ())
+}
+
+/// Stack effect of an op: the number of the values it pops and the types of the values it
+/// pushes in their place. For a `block`/`loop` it's the effect of the whole op, for an op that
+/// branches it's the values popped up to the start of the enclosing frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackEffect {
+    pub popped: usize,
+    pub pushed: Vec<StackType>,
+}
+
+/// Stack effects of the ops in the function body (including the nested ones).
+/// Unlike [verify_stack_types] the calls to the imported functions are followed using
+/// their signatures. The ops in unreachable code are not included.
+/// Returns `None` if the function has an op with unknown stack effect.
+pub fn stack_effects(
+    ctx: &Context,
+    module_op: &ModuleOp,
+    func_op: &FuncOp,
+) -> Result<Option<HashMap<Ptr<Operation>, StackEffect>>, CompilerError> {
+    let checker = check_func(ctx, module_op, func_op, true)?;
+    Ok(checker.complete.then_some(checker.effects))
+}
+
+/// Types of the values popped by the `drop` ops (Wasm and `ozk`) in the function body.
+/// The drops in unreachable code and the ones popping a value pushed before an op with
+/// unknown stack effect (in the same block) are not included.
+pub fn dropped_value_types(
+    ctx: &Context,
+    module_op: &ModuleOp,
+    func_op: &FuncOp,
+) -> Result<HashMap<Ptr<Operation>, StackType>, CompilerError> {
+    check_func(ctx, module_op, func_op, false).map(|checker| checker.dropped)
+}
+
+fn check_func<'a>(
+    ctx: &'a Context,
+    module_op: &'a ModuleOp,
+    func_op: &FuncOp,
+    resolve_imports: bool,
+) -> Result<TypedStack<'a>, CompilerError> {
+    let func_type = func_op.get_type(ctx);
+    let mut checker = TypedStack {
+        ctx,
+        module_op,
+        func_sym: func_op.get_symbol_name(ctx),
+        locals: Vec::new(),
+        stack: Vec::new(),
+        frames: Vec::new(),
+        dropped: HashMap::new(),
+        resolve_imports,
+        low_water: 0,
+        effects: HashMap::new(),
+        complete: true,
+    };
+    let params = checker.to_stack_types(func_type.get_inputs())?;
+    let results = checker.to_stack_types(func_type.get_results())?;
+    checker.locals = params.clone();
+    checker
+        .locals
+        .extend(checker.to_stack_types(&func_op.get_locals(ctx))?);
+    if func_op.has_args_on_stack(ctx) {
+        // the first argument is on top of the stack
+        checker.stack = params.into_iter().rev().collect();
+    }
+    checker.frames.push(ControlFrame {
+        label_types: results.clone(),
+        end_types: results,
+        height: 0,
+        unreachable: false,
+    });
+    let ops: Vec<Ptr<Operation>> = func_op.op_iter(ctx).collect();
+    checker.check_ops(ops)?;
+    checker.end_frame("end of function")?;
+    Ok(checker)
+}
+
+/// Control flow frame (function body, `block` or `loop`)
+struct ControlFrame {
+    /// Types expected on the stack when branching to this frame
+    label_types: Vec<StackType>,
+    /// Types expected on the stack at the end of this frame
+    end_types: Vec<StackType>,
+    /// Stack height at the start of this frame
+    height: usize,
+    /// Set after an unconditional branch (the rest of the frame is unreachable) or an op with
+    /// unknown stack effect. The values on the stack below are unknown.
+    unreachable: bool,
+}
+
+enum Flow {
+    /// Keep checking the following ops
+    Continue,
+    /// An op with unknown stack effect was found, the stack of the frame is unknown
+    Opaque,
+}
+
+struct TypedStack<'a> {
+    ctx: &'a Context,
+    module_op: &'a ModuleOp,
+    func_sym: String,
+    locals: Vec<StackType>,
+    stack: Vec<StackType>,
+    frames: Vec<ControlFrame>,
+    /// Types of the values popped by the `drop` ops
+    dropped: HashMap<Ptr<Operation>, StackType>,
+    /// Follow the calls to the imported functions (otherwise their stack effect is unknown)
+    resolve_imports: bool,
+    /// The lowest stack height reached while checking the current op
+    low_water: usize,
+    /// Stack effects of the checked ops
+    effects: HashMap<Ptr<Operation>, StackEffect>,
+    /// Cleared when an op with unknown stack effect is found
+    complete: bool,
+}
+
+impl<'a> TypedStack<'a> {
+    fn error(&self, location: &str, msg: String) -> CompilerError {
+        CompilerError::VerificationError {
+            msg: format!("wasm.func @{}: {location}: {msg}", self.func_sym),
+        }
+    }
+
+    fn op_name(&self, op: Ptr<Operation>) -> String {
+        op.deref(self.ctx).get_opid().with_ctx(self.ctx).to_string()
+    }
+
+    fn to_stack_types(&self, types: &[Ptr<TypeObj>]) -> Result<Vec<StackType>, CompilerError> {
+        types
+            .iter()
+            .map(|ty| {
+                StackType::from_type(self.ctx, *ty).ok_or_else(|| {
+                    self.error(
+                        "signature",
+                        format!("unsupported type {}", ty.with_ctx(self.ctx)),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[allow(clippy::expect_used)]
+    fn frame(&self) -> &ControlFrame {
+        self.frames.last().expect("no control frame")
+    }
+
+    fn push(&mut self, ty: StackType) {
+        self.stack.push(ty);
+    }
+
+    fn pop(&mut self, location: &str, expected: StackType) -> Result<StackType, CompilerError> {
+        let frame = self.frame();
+        if self.stack.len() == frame.height {
+            if frame.unreachable {
+                return Ok(expected);
+            }
+            return Err(self.error(
+                location,
+                format!("expected {expected} on the stack, found empty stack"),
+            ));
+        }
+        #[allow(clippy::expect_used)]
+        let actual = self.stack.pop().expect("stack is not empty");
+        self.low_water = self.low_water.min(self.stack.len());
+        if actual != expected {
+            return Err(self.error(
+                location,
+                format!("expected {expected} on the stack, found {actual}"),
+            ));
+        }
+        Ok(actual)
+    }
+
+    /// Pop a value of any type. Returns `None` in unreachable code when the stack is empty.
+    fn pop_any(&mut self, location: &str) -> Result<Option<StackType>, CompilerError> {
+        let frame = self.frame();
+        if self.stack.len() == frame.height {
+            if frame.unreachable {
+                return Ok(None);
+            }
+            return Err(self.error(
+                location,
+                "expected a value on the stack, found empty stack".to_string(),
+            ));
+        }
+        let ty = self.stack.pop();
+        self.low_water = self.low_water.min(self.stack.len());
+        Ok(ty)
+    }
+
+    fn drop_value(&mut self, location: &str, op: Ptr<Operation>) -> Result<(), CompilerError> {
+        if let Some(ty) = self.pop_any(location)? {
+            self.dropped.insert(op, ty);
+        }
+        Ok(())
+    }
+
+    fn pop_all(&mut self, location: &str, expected: &[StackType]) -> Result<(), CompilerError> {
+        for ty in expected.iter().rev() {
+            self.pop(location, *ty)?;
+        }
+        Ok(())
+    }
+
+    fn set_unreachable(&mut self) {
+        #[allow(clippy::expect_used)]
+        let frame = self.frames.last_mut().expect("no control frame");
+        self.stack.truncate(frame.height);
+        frame.unreachable = true;
+        self.low_water = self.low_water.min(self.stack.len());
+    }
+
+    /// Types expected by a branch to the frame at the given relative depth.
+    fn label_types(
+        &self,
+        location: &str,
+        depth: RelativeDepth,
+    ) -> Result<Vec<StackType>, CompilerError> {
+        let depth = u32::from(depth) as usize;
+        if depth >= self.frames.len() {
+            return Err(self.error(location, format!("invalid branch depth {depth}")));
+        }
+        Ok(self.frames[self.frames.len() - 1 - depth]
+            .label_types
+            .clone())
+    }
+
+    /// Check that only the frame's result types are left on the stack and drop the frame.
+    fn end_frame(&mut self, location: &str) -> Result<(), CompilerError> {
+        let end_types = self.frame().end_types.clone();
+        self.pop_all(location, &end_types)?;
+        let frame = self.frame();
+        if self.stack.len() != frame.height {
+            let extra: Vec<String> = self.stack[frame.height..]
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            return Err(self.error(
+                location,
+                format!(
+                    "unexpected values left on the stack: [{}]",
+                    extra.join(", ")
+                ),
+            ));
+        }
+        self.frames.pop();
+        Ok(())
+    }
+
+    fn check_ops(&mut self, ops: Vec<Ptr<Operation>>) -> Result<(), CompilerError> {
+        for op in ops {
+            let unreachable = self.frame().unreachable;
+            let height = self.stack.len();
+            let outer_low_water = std::mem::replace(&mut self.low_water, height);
+            let flow = self.check_op(op)?;
+            let low_water = self.low_water;
+            self.low_water = outer_low_water.min(low_water);
+            if let Flow::Opaque = flow {
+                // re-synchronise at the end of the frame where the types are known again
+                self.complete = false;
+                self.set_unreachable();
+                continue;
+            }
+            if !unreachable {
+                self.effects.insert(
+                    op,
+                    StackEffect {
+                        popped: height - low_water,
+                        pushed: self.stack[low_water..].to_vec(),
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn check_block(
+        &mut self,
+        location: &str,
+        ty: Ptr<TypeObj>,
+        is_loop: bool,
+        ops: Vec<Ptr<Operation>>,
+    ) -> Result<Flow, CompilerError> {
+        let ctx = self.ctx;
+        let Some(block_type) = ty.deref(ctx).downcast_ref::<FunctionType>().cloned() else {
+            return Err(self.error(location, "block type is not a function type".to_string()));
+        };
+        let inputs = self.to_stack_types(block_type.get_inputs())?;
+        let results = self.to_stack_types(block_type.get_results())?;
+        self.pop_all(location, &inputs)?;
+        self.frames.push(ControlFrame {
+            label_types: if is_loop {
+                inputs.clone()
+            } else {
+                results.clone()
+            },
+            end_types: results.clone(),
+            height: self.stack.len(),
+            unreachable: false,
+        });
+        self.stack.extend(inputs);
+        self.check_ops(ops)?;
+        self.end_frame(location)?;
+        self.stack.extend(results);
+        Ok(Flow::Continue)
+    }
+
+    /// Index of the top of the stack if the current frame has a value at the given index
+    /// (`None` in unreachable code).
+    fn addressable_top(
+        &self,
+        location: &str,
+        index: usize,
+    ) -> Result<Option<usize>, CompilerError> {
+        let frame = self.frame();
+        if self.stack.len() <= frame.height + index {
+            if frame.unreachable {
+                return Ok(None);
+            }
+            return Err(self.error(
+                location,
+                format!("expected at least {} values on the stack", index + 1),
+            ));
+        }
+        Ok(Some(self.stack.len() - 1))
+    }
+
+    fn local_type(&self, location: &str, index: u32) -> Result<StackType, CompilerError> {
+        self.locals
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| self.error(location, format!("undefined local {index}")))
+    }
+
+    fn global_type(&self, location: &str, index: GlobalIndex) -> Result<StackType, CompilerError> {
+        let Some(ty) = self.module_op.get_global_type(self.ctx, index) else {
+            return Err(self.error(location, format!("undefined global {index}")));
+        };
+        Ok(self.to_stack_types(&[ty])?[0])
+    }
+
+    fn check_op(&mut self, op: Ptr<Operation>) -> Result<Flow, CompilerError> {
+        let ctx = self.ctx;
+        let location = self.op_name(op);
+        let location = location.as_str();
+        let opop = op.deref(ctx).get_op(ctx);
+        if let Some(const_op) = opop.downcast_ref::<ConstantOp>() {
+            let value = const_op.get_value(ctx);
+            let Some(ty) = attr_cast::<dyn TypedAttrInterface>(&*value)
+                .and_then(|attr| StackType::from_type(ctx, attr.get_type())) else {
+                return Err(self.error(location, "unsupported constant type".to_string()));
+            };
+            self.push(ty);
+        } else if let Some(ty) = binary_int_op_type(ctx, &*opop) {
+            let ty = self.to_stack_types(&[ty])?[0];
+            self.pop(location, ty)?;
+            self.pop(location, ty)?;
+            self.push(ty);
+        } else if let Some(ty) = compare_op_type(ctx, &*opop) {
+            let ty = self.to_stack_types(&[ty])?[0];
+            self.pop(location, ty)?;
+            self.pop(location, ty)?;
+            self.push(StackType::I32);
+        } else if let Some(popcnt_op) = opop.downcast_ref::<PopcntOp>() {
+            let ty = self.to_stack_types(&[popcnt_op.get_type(ctx)])?[0];
+            self.pop(location, ty)?;
+            self.push(ty);
+        } else if opop.downcast_ref::<I32EqzOp>().is_some() {
+            self.pop(location, StackType::I32)?;
+            self.push(StackType::I32);
+        } else if opop.is::<I64EqzOp>() {
+            self.pop(location, StackType::I64)?;
+            self.push(StackType::I32);
+        } else if opop.is::<I64ExtendI32UOp>() || opop.is::<I64ExtendI32SOp>() {
+            self.pop(location, StackType::I32)?;
+            self.push(StackType::I64);
+        } else if opop.is::<I32WrapI64Op>() {
+            self.pop(location, StackType::I64)?;
+            self.push(StackType::I32);
+        } else if opop.is::<DropOp>() {
+            self.drop_value(location, op)?;
+        } else if let Some(call_op) = opop.downcast_ref::<CallOp>() {
+            let callee = self
+                .module_op
+                .get_func_sym(ctx, call_op.get_func_index(ctx))
+                .and_then(|func_sym| self.module_op.get_func(ctx, &func_sym));
+            if let Some(callee) = callee {
+                self.check_call(location, &callee.get_type(ctx))?;
+            } else if let Some(import_type) = self
+                .module_op
+                .get_import_func_type(ctx, call_op.get_func_index(ctx))
+                .filter(|_| self.resolve_imports)
+            {
+                self.check_call(location, &import_type)?;
+            } else {
+                // imported function, signature is unknown
+                return Ok(Flow::Opaque);
+            }
+        } else if let Some(call_op) = opop.downcast_ref::<ozk::CallOp>() {
+            self.check_call(location, &call_op.get_func_type(ctx))?;
+        } else if let Some(swap_op) = opop.downcast_ref::<ozk::SwapOp>() {
+            let index = u32::from(swap_op.get_index(ctx)) as usize;
+            let frame = self.frame();
+            if self.stack.len() <= frame.height + index {
+                if !frame.unreachable {
+                    return Err(self.error(
+                        location,
+                        format!("expected at least {} values on the stack", index + 1),
+                    ));
+                }
+            } else {
+                let top = self.stack.len() - 1;
+                self.stack.swap(top, top - index);
+                self.low_water = self.low_water.min(top - index);
+            }
+        } else if let Some(movup_op) = opop.downcast_ref::<ozk::MovUpOp>() {
+            let index = u32::from(movup_op.get_index(ctx)) as usize;
+            if let Some(top) = self.addressable_top(location, index)? {
+                let ty = self.stack.remove(top - index);
+                self.stack.push(ty);
+                self.low_water = self.low_water.min(top - index);
+            }
+        } else if let Some(movdn_op) = opop.downcast_ref::<ozk::MovDnOp>() {
+            let index = u32::from(movdn_op.get_index(ctx)) as usize;
+            if let Some(top) = self.addressable_top(location, index)? {
+                let ty = self.stack.remove(top);
+                self.stack.insert(top - index, ty);
+                self.low_water = self.low_water.min(top - index);
+            }
+        } else if opop.downcast_ref::<ReturnOp>().is_some() {
+            #[allow(clippy::expect_used)]
+            let results = self
+                .frames
+                .first()
+                .expect("no function frame")
+                .label_types
+                .clone();
+            self.pop_all(location, &results)?;
+            self.set_unreachable();
+        } else if let Some(block_op) = opop.downcast_ref::<BlockOp>() {
+            let ops = block_op.op_iter(ctx).collect();
+            return self.check_block(location, block_op.get_type(ctx), false, ops);
+        } else if let Some(loop_op) = opop.downcast_ref::<LoopOp>() {
+            let ops = loop_op.op_iter(ctx).collect();
+            return self.check_block(location, loop_op.get_type(ctx), true, ops);
+        } else if let Some(br_op) = opop.downcast_ref::<BrOp>() {
+            let label_types = self.label_types(location, br_op.get_relative_depth(ctx))?;
+            self.pop_all(location, &label_types)?;
+            self.set_unreachable();
+        } else if let Some(br_if_op) = opop.downcast_ref::<BrIfOp>() {
+            self.pop(location, StackType::I32)?;
+            let label_types = self.label_types(location, br_if_op.get_relative_depth(ctx))?;
+            self.pop_all(location, &label_types)?;
+            self.stack.extend(label_types);
+        } else if let Some(local_get_op) = opop.downcast_ref::<LocalGetOp>() {
+            let ty = self.local_type(location, local_get_op.get_index(ctx).into())?;
+            self.push(ty);
+        } else if let Some(local_set_op) = opop.downcast_ref::<LocalSetOp>() {
+            let ty = self.local_type(location, local_set_op.get_index(ctx).into())?;
+            self.pop(location, ty)?;
+        } else if let Some(local_tee_op) = opop.downcast_ref::<LocalTeeOp>() {
+            let index_attr = local_tee_op.get_index(ctx);
+            let Some(index_attr) = index_attr.downcast_ref::<IntegerAttr>() else {
+                return Err(self.error(location, "index is not an IntegerAttr".to_string()));
+            };
+            let index = apint_to_i32(index_attr.clone().into()) as u32;
+            let ty = self.local_type(location, index)?;
+            let ty = self.pop(location, ty)?;
+            self.push(ty);
+        } else if let Some(global_get_op) = opop.downcast_ref::<GlobalGetOp>() {
+            let ty = self.global_type(location, global_get_op.get_index(ctx))?;
+            self.push(ty);
+        } else if let Some(global_set_op) = opop.downcast_ref::<GlobalSetOp>() {
+            let ty = self.global_type(location, global_set_op.get_index(ctx))?;
+            self.pop(location, ty)?;
+        } else if let Some(load_op) = opop.downcast_ref::<LoadOp>() {
+            self.pop(location, StackType::I32)?;
+            self.push(mem_access_type(load_op.get_value_type(ctx)));
+        } else if let Some(store_op) = opop.downcast_ref::<StoreOp>() {
+            self.pop(location, mem_access_type(store_op.get_value_type(ctx)))?;
+            self.pop(location, StackType::I32)?;
+        } else {
+            return self.check_ozk_op(location, op);
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Check the ops of the `ozk` dialect that Wasm ops are lowered to.
+    fn check_ozk_op(&mut self, location: &str, op: Ptr<Operation>) -> Result<Flow, CompilerError> {
+        let ctx = self.ctx;
+        let opop = op.deref(ctx).get_op(ctx);
+        if let Some(const_op) = opop.downcast_ref::<ozk::ConstantOp>() {
+            let value = const_op.get_value(ctx);
+            let Some(ty) = attr_cast::<dyn TypedAttrInterface>(&*value)
+                .and_then(|attr| StackType::from_type(ctx, attr.get_type())) else {
+                return Err(self.error(location, "unsupported constant type".to_string()));
+            };
+            self.push(ty);
+        } else if let Some(ty) = ozk_binary_int_op_type(ctx, &*opop) {
+            let ty = self.to_stack_types(&[ty])?[0];
+            self.pop(location, ty)?;
+            self.pop(location, ty)?;
+            self.push(ty);
+        } else if let Some(ty) = ozk_compare_op_type(ctx, &*opop) {
+            let ty = self.to_stack_types(&[ty])?[0];
+            self.pop(location, ty)?;
+            self.pop(location, ty)?;
+            self.push(StackType::I32);
+        } else if opop.is::<ozk::ExtendI32UOp>() || opop.is::<ozk::ExtendI32SOp>() {
+            self.pop(location, StackType::I32)?;
+            self.push(StackType::I64);
+        } else if opop.is::<ozk::WrapI64Op>() {
+            self.pop(location, StackType::I64)?;
+            self.push(StackType::I32);
+        } else if opop.is::<ozk::DropOp>() {
+            self.drop_value(location, op)?;
+        } else if let Some(eqz_op) = opop.downcast_ref::<ozk::EqzOp>() {
+            let ty = self.to_stack_types(&[eqz_op.get_type(ctx)])?[0];
+            self.pop(location, ty)?;
+            self.push(StackType::I32);
+        } else if opop.is::<ozk::FeltAddOp>()
+            || opop.is::<ozk::FeltSubOp>()
+            || opop.is::<ozk::FeltMulOp>()
+        {
+            self.pop(location, StackType::Felt)?;
+            self.pop(location, StackType::Felt)?;
+            self.push(StackType::Felt);
+        } else if let Some(load_op) = opop.downcast_ref::<ozk::LoadOp>() {
+            let ty = self.to_stack_types(&[load_op.get_type(ctx)])?[0];
+            self.pop(location, StackType::I32)?;
+            self.push(ty);
+        } else if let Some(store_op) = opop.downcast_ref::<ozk::StoreOp>() {
+            let ty = self.to_stack_types(&[store_op.get_type(ctx)])?[0];
+            self.pop(location, ty)?;
+            self.pop(location, StackType::I32)?;
+        } else if let Some(local_get_op) = opop.downcast_ref::<ozk::LocalGetOp>() {
+            let ty = self.local_type(location, local_get_op.get_index(ctx))?;
+            self.push(ty);
+        } else if let Some(local_set_op) = opop.downcast_ref::<ozk::LocalSetOp>() {
+            let ty = self.local_type(location, local_set_op.get_index(ctx))?;
+            self.pop(location, ty)?;
+        } else if let Some(local_tee_op) = opop.downcast_ref::<ozk::LocalTeeOp>() {
+            let ty = self.local_type(location, local_tee_op.get_index(ctx))?;
+            let ty = self.pop(location, ty)?;
+            self.push(ty);
+        } else if opop.is::<ozk::ReturnOp>() {
+            #[allow(clippy::expect_used)]
+            let results = self
+                .frames
+                .first()
+                .expect("no function frame")
+                .label_types
+                .clone();
+            self.pop_all(location, &results)?;
+            self.set_unreachable();
+        } else if let Some(block_op) = opop.downcast_ref::<ozk::BlockOp>() {
+            let ops = block_op.op_iter(ctx).collect();
+            return self.check_block(location, block_op.get_type(ctx), false, ops);
+        } else if let Some(loop_op) = opop.downcast_ref::<ozk::LoopOp>() {
+            let ops = loop_op.op_iter(ctx).collect();
+            return self.check_block(location, loop_op.get_type(ctx), true, ops);
+        } else if let Some(br_op) = opop.downcast_ref::<ozk::BrOp>() {
+            let depth = RelativeDepth::from(br_op.get_relative_depth(ctx));
+            let label_types = self.label_types(location, depth)?;
+            self.pop_all(location, &label_types)?;
+            self.set_unreachable();
+        } else if let Some(br_if_op) = opop.downcast_ref::<ozk::BrIfOp>() {
+            self.pop(location, StackType::I32)?;
+            let depth = RelativeDepth::from(br_if_op.get_relative_depth(ctx));
+            let label_types = self.label_types(location, depth)?;
+            self.pop_all(location, &label_types)?;
+            self.stack.extend(label_types);
+        } else {
+            return Ok(Flow::Opaque);
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn check_call(
+        &mut self,
+        location: &str,
+        func_type: &FunctionType,
+    ) -> Result<(), CompilerError> {
+        let inputs = self.to_stack_types(func_type.get_inputs())?;
+        let results = self.to_stack_types(func_type.get_results())?;
+        self.pop_all(location, &inputs)?;
+        self.stack.extend(results);
+        Ok(())
+    }
+}
+
+/// Operand type of the Wasm integer ops that pop two values and push one.
+fn binary_int_op_type(ctx: &Context, op: &dyn Op) -> Option<Ptr<TypeObj>> {
+    if let Some(op) = op.downcast_ref::<AddOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<SubOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<MulOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ShlOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ShrUOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ShrSOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<DivUOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<DivSOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<RemUOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<RemSOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<AndOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<OrOp>() {
+        Some(op.get_type(ctx))
+    } else {
+        op.downcast_ref::<XorOp>().map(|op| op.get_type(ctx))
+    }
+}
+
+/// Operand type of the Wasm comparison ops that pop two values and push an i32.
+fn compare_op_type(ctx: &Context, op: &dyn Op) -> Option<Ptr<TypeObj>> {
+    if let Some(op) = op.downcast_ref::<EqOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<NeOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<LtUOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<LtSOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<GtUOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<GtSOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<LeUOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<LeSOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<GeUOp>() {
+        Some(op.get_type(ctx))
+    } else {
+        op.downcast_ref::<GeSOp>().map(|op| op.get_type(ctx))
+    }
+}
+
+/// Operand type of the `ozk` integer ops that pop two values and push one.
+fn ozk_binary_int_op_type(ctx: &Context, op: &dyn Op) -> Option<Ptr<TypeObj>> {
+    if let Some(op) = op.downcast_ref::<ozk::WrappingAddOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::WrappingSubOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::WrappingMulOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::ShlOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::ShrUOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::ShrSOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::AndOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::OrOp>() {
+        Some(op.get_type(ctx))
+    } else {
+        op.downcast_ref::<ozk::XorOp>().map(|op| op.get_type(ctx))
+    }
+}
+
+/// Operand type of the `ozk` comparison ops that pop two values and push an i32.
+fn ozk_compare_op_type(ctx: &Context, op: &dyn Op) -> Option<Ptr<TypeObj>> {
+    if let Some(op) = op.downcast_ref::<ozk::EqOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::NeOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::LtUOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::LtSOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::GtUOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::GtSOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::LeUOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::LeSOp>() {
+        Some(op.get_type(ctx))
+    } else if let Some(op) = op.downcast_ref::<ozk::GeUOp>() {
+        Some(op.get_type(ctx))
+    } else {
+        op.downcast_ref::<ozk::GeSOp>().map(|op| op.get_type(ctx))
+    }
+}
+
+fn mem_access_type(ty: MemAccessOpValueType) -> StackType {
+    match ty {
+        MemAccessOpValueType::I32 => StackType::I32,
+        MemAccessOpValueType::I64 => StackType::I64,
+    }
+}
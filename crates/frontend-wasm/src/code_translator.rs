@@ -1,7 +1,11 @@
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use ozk_wasm_dialect::ops::MemAccessOpValueType::I32;
+use ozk_wasm_dialect::ops::MemAccessOpValueType::I64;
+use ozk_wasm_dialect::ops::MemArg;
 use pliron::context::Context;
 use wasmparser::{FuncValidator, Operator, WasmModuleResources};
 
-use crate::{func_builder::FuncBuilder, mod_builder::ModuleBuilder, WasmError};
+use crate::{func_builder::FuncBuilder, mod_builder::ModuleBuilder, wasm_unsupported, WasmError};
 
 /// Translates wasm operators into ozk IR instructions.
 #[allow(unused_variables)]
@@ -44,18 +48,111 @@ pub fn translate_operator(
         Operator::I32Const { value } => func_builder.op().i32const(ctx, *value)?,
         Operator::I64Const { value } => func_builder.op().i64const(ctx, *value)?,
         Operator::I32Add => func_builder.op().i32add(ctx)?,
+        Operator::I32Sub => func_builder.op().i32sub(ctx)?,
+        Operator::I32Mul => func_builder.op().i32mul(ctx)?,
+        Operator::I32Shl => func_builder.op().i32shl(ctx)?,
+        Operator::I32ShrU => func_builder.op().i32shru(ctx)?,
+        Operator::I32ShrS => func_builder.op().i32shrs(ctx)?,
+        Operator::I32DivU => func_builder.op().i32divu(ctx)?,
+        Operator::I32DivS => func_builder.op().i32divs(ctx)?,
+        Operator::I32RemU => func_builder.op().i32remu(ctx)?,
+        Operator::I32RemS => func_builder.op().i32rems(ctx)?,
+        Operator::I32Popcnt => func_builder.op().i32popcnt(ctx)?,
         Operator::I32Eqz => func_builder.op().i32eqz(ctx)?,
-        Operator::I32WrapI64 => func_builder.op().i32wrapi64(ctx),
-        Operator::I32GeU => func_builder.op().i32geu(ctx),
-        Operator::I32And => func_builder.op().i32and(ctx),
+        Operator::I32And => func_builder.op().i32and(ctx)?,
+        Operator::I32Or => func_builder.op().i32or(ctx)?,
+        Operator::I32Xor => func_builder.op().i32xor(ctx)?,
+        Operator::I32Eq => func_builder.op().i32eq(ctx)?,
+        Operator::I32Ne => func_builder.op().i32ne(ctx)?,
+        Operator::I32LtU => func_builder.op().i32ltu(ctx)?,
+        Operator::I32LtS => func_builder.op().i32lts(ctx)?,
+        Operator::I32GtU => func_builder.op().i32gtu(ctx)?,
+        Operator::I32GtS => func_builder.op().i32gts(ctx)?,
+        Operator::I32LeU => func_builder.op().i32leu(ctx)?,
+        Operator::I32LeS => func_builder.op().i32les(ctx)?,
+        Operator::I32GeU => func_builder.op().i32geu(ctx)?,
+        Operator::I32GeS => func_builder.op().i32ges(ctx)?,
+        Operator::I32WrapI64 => func_builder.op().i32wrapi64(ctx)?,
         Operator::I64Add => func_builder.op().i64add(ctx)?,
-        Operator::I64Eqz => func_builder.op().i64eqz(ctx),
-        Operator::I64And => func_builder.op().i64and(ctx),
-        Operator::I64GeU => func_builder.op().i64geu(ctx),
-        Operator::I64Ne => func_builder.op().i64ne(ctx),
-        Operator::I64Eq => func_builder.op().i64eq(ctx),
-        Operator::I64ExtendI32U => func_builder.op().i64extendi32u(ctx),
+        Operator::I64Sub => func_builder.op().i64sub(ctx)?,
+        Operator::I64Mul => func_builder.op().i64mul(ctx)?,
+        Operator::I64Shl => func_builder.op().i64shl(ctx)?,
+        Operator::I64ShrU => func_builder.op().i64shru(ctx)?,
+        Operator::I64ShrS => func_builder.op().i64shrs(ctx)?,
+        Operator::I64And => func_builder.op().i64and(ctx)?,
+        Operator::I64Or => func_builder.op().i64or(ctx)?,
+        Operator::I64Xor => func_builder.op().i64xor(ctx)?,
+        Operator::I64Eq => func_builder.op().i64eq(ctx)?,
+        Operator::I64Ne => func_builder.op().i64ne(ctx)?,
+        Operator::I64LtU => func_builder.op().i64ltu(ctx)?,
+        Operator::I64LtS => func_builder.op().i64lts(ctx)?,
+        Operator::I64GtU => func_builder.op().i64gtu(ctx)?,
+        Operator::I64GtS => func_builder.op().i64gts(ctx)?,
+        Operator::I64LeU => func_builder.op().i64leu(ctx)?,
+        Operator::I64LeS => func_builder.op().i64les(ctx)?,
+        Operator::I64GeU => func_builder.op().i64geu(ctx)?,
+        Operator::I64GeS => func_builder.op().i64ges(ctx)?,
+        Operator::I64Eqz => func_builder.op().i64eqz(ctx)?,
+        Operator::I64ExtendI32U => func_builder.op().i64extendi32u(ctx)?,
+        Operator::I64ExtendI32S => func_builder.op().i64extendi32s(ctx)?,
+        Operator::Drop => func_builder.op().drop(ctx)?,
+        Operator::I32Load { memarg } => load(ctx, func_builder, I32, 4, false, memarg)?,
+        Operator::I32Load8U { memarg } => load(ctx, func_builder, I32, 1, false, memarg)?,
+        Operator::I32Load8S { memarg } => load(ctx, func_builder, I32, 1, true, memarg)?,
+        Operator::I32Load16U { memarg } => load(ctx, func_builder, I32, 2, false, memarg)?,
+        Operator::I32Load16S { memarg } => load(ctx, func_builder, I32, 2, true, memarg)?,
+        Operator::I64Load { memarg } => load(ctx, func_builder, I64, 8, false, memarg)?,
+        Operator::I64Load8U { memarg } => load(ctx, func_builder, I64, 1, false, memarg)?,
+        Operator::I64Load8S { memarg } => load(ctx, func_builder, I64, 1, true, memarg)?,
+        Operator::I64Load16U { memarg } => load(ctx, func_builder, I64, 2, false, memarg)?,
+        Operator::I64Load16S { memarg } => load(ctx, func_builder, I64, 2, true, memarg)?,
+        Operator::I64Load32U { memarg } => load(ctx, func_builder, I64, 4, false, memarg)?,
+        Operator::I64Load32S { memarg } => load(ctx, func_builder, I64, 4, true, memarg)?,
+        Operator::I32Store { memarg } => store(ctx, func_builder, I32, 4, memarg)?,
+        Operator::I32Store8 { memarg } => store(ctx, func_builder, I32, 1, memarg)?,
+        Operator::I32Store16 { memarg } => store(ctx, func_builder, I32, 2, memarg)?,
+        Operator::I64Store { memarg } => store(ctx, func_builder, I64, 8, memarg)?,
+        Operator::I64Store8 { memarg } => store(ctx, func_builder, I64, 1, memarg)?,
+        Operator::I64Store16 { memarg } => store(ctx, func_builder, I64, 2, memarg)?,
+        Operator::I64Store32 { memarg } => store(ctx, func_builder, I64, 4, memarg)?,
         _ => todo!("Wasm op not implemented: {:?}", op),
     };
     Ok(())
 }
+
+fn load(
+    ctx: &mut Context,
+    func_builder: &mut FuncBuilder,
+    ty: MemAccessOpValueType,
+    size: u32,
+    signed: bool,
+    memarg: &wasmparser::MemArg,
+) -> Result<(), WasmError> {
+    let memarg = mem_arg(memarg)?;
+    func_builder.op().load(ctx, ty, size, signed, memarg)?;
+    Ok(())
+}
+
+fn store(
+    ctx: &mut Context,
+    func_builder: &mut FuncBuilder,
+    ty: MemAccessOpValueType,
+    size: u32,
+    memarg: &wasmparser::MemArg,
+) -> Result<(), WasmError> {
+    let memarg = mem_arg(memarg)?;
+    func_builder.op().store(ctx, ty, size, memarg)?;
+    Ok(())
+}
+
+fn mem_arg(memarg: &wasmparser::MemArg) -> Result<MemArg, WasmError> {
+    if memarg.memory != 0 {
+        return Err(wasm_unsupported!("multiple memories"));
+    }
+    let offset = u32::try_from(memarg.offset)
+        .map_err(|_| wasm_unsupported!("memory offset {}", memarg.offset))?;
+    Ok(MemArg {
+        offset,
+        align: memarg.align as u32,
+    })
+}
@@ -28,6 +28,9 @@ pub struct ModuleBuilder {
     import_functions: Vec<(ImportFuncLabel, TypeIndex)>,
     func_names: HashMap<FuncIndex, FuncSym>,
     func_types: HashMap<FuncIndex, TypeIndex>,
+    global_types: Vec<Ptr<TypeObj>>,
+    /// The exported functions (export name, function index)
+    export_funcs: Vec<(String, FuncIndex)>,
 }
 
 impl ModuleBuilder {
@@ -54,6 +57,8 @@ impl ModuleBuilder {
             func_names: HashMap::new(),
             func_types: HashMap::new(),
             import_functions: Vec::new(),
+            global_types: Vec::new(),
+            export_funcs: Vec::new(),
         }
     }
 
@@ -83,10 +88,18 @@ impl ModuleBuilder {
         self.func_types.insert(func_idx, type_idx);
     }
 
+    pub fn push_global_type(&mut self, ty: Ptr<TypeObj>) {
+        self.global_types.push(ty);
+    }
+
     pub fn set_start_func(&mut self, func_idx: u32) {
         self.start_func_idx = Some(func_idx.into());
     }
 
+    pub fn push_export_func(&mut self, name: &str, func_idx: u32) {
+        self.export_funcs.push((name.to_string(), func_idx.into()));
+    }
+
     pub fn push_func_builder(&mut self, func_builder: FuncBuilder) {
         self.functions.push(func_builder);
     }
@@ -104,51 +117,80 @@ impl ModuleBuilder {
             let func_type = self.get_func_type((func_idx as u32).into())?;
             func_sigs.push(func_type);
         }
-        if let Some(start_func_idx) = self.start_func_idx {
-            let start_func_name = self
-                .get_func_name(start_func_idx)
-                .ok_or(ModuleBuilderError::FuncNameNotFound(start_func_idx))?;
-            let _import_func_types = self
-                .import_functions
-                .iter()
-                .map(|(label, ty_idx)| self.get_type(*ty_idx).map(|ty| (label.clone(), ty)))
-                .collect::<Result<Vec<(ImportFuncLabel, Ptr<TypeObj>)>, ModuleBuilderError>>()?;
-            let mut funcs = Vec::new();
-            // TODO: since func indices should be shifted by imported funcs count change the storage and make it obvious
-            let imported_funcs_count = self.import_functions.len() as u32;
-            for (func_idx, func_builder) in self.functions.iter_mut().enumerate() {
-                if let Some(func_name) = self
+        if self.start_func_idx.is_none() && self.export_funcs.is_empty() {
+            return Err(ModuleBuilderError::StartFuncUndefined);
+        }
+        // the functions without a name (no name section) are named after their export
+        for (export_name, func_idx) in &self.export_funcs {
+            if !self.func_names.contains_key(func_idx)
+                && !self
                     .func_names
-                    .get(&(func_idx as u32 + imported_funcs_count).into())
-                {
-                    func_builder.set_name(func_name.clone());
-                }
-                func_builder.set_signature(func_sigs[func_idx]);
-            }
-            let mut all_func_syms: Vec<FuncSym> = Vec::new();
-            for (label, _) in self.import_functions.iter() {
-                all_func_syms.push(label.name.clone().into());
+                    .values()
+                    .any(|func_name| func_name.as_ref() == export_name)
+            {
+                self.func_names
+                    .insert(*func_idx, export_name.clone().into());
             }
-            for func_builder in self.functions {
-                let func = func_builder.build(ctx)?;
-                funcs.push(func);
-                all_func_syms.push(func.get_symbol_name(ctx).into());
+        }
+        let start_func_name = self
+            .start_func_idx
+            .map(|start_func_idx| {
+                self.get_func_name(start_func_idx)
+                    .ok_or(ModuleBuilderError::FuncNameNotFound(start_func_idx))
+            })
+            .transpose()?;
+        let exports = self
+            .export_funcs
+            .iter()
+            .map(|(export_name, func_idx)| {
+                self.get_func_name(*func_idx)
+                    .map(|func_name| (export_name.clone(), func_name))
+                    .ok_or(ModuleBuilderError::FuncNameNotFound(*func_idx))
+            })
+            .collect::<Result<Vec<(String, FuncSym)>, ModuleBuilderError>>()?;
+        let import_funcs = self
+            .import_functions
+            .iter()
+            .map(|(label, ty_idx)| self.get_type(*ty_idx).map(|ty| (label.clone(), ty)))
+            .collect::<Result<Vec<(ImportFuncLabel, Ptr<TypeObj>)>, ModuleBuilderError>>()?;
+        let mut funcs = Vec::new();
+        // TODO: since func indices should be shifted by imported funcs count change the storage and make it obvious
+        let imported_funcs_count = self.import_functions.len() as u32;
+        for (func_idx, func_builder) in self.functions.iter_mut().enumerate() {
+            if let Some(func_name) = self
+                .func_names
+                .get(&(func_idx as u32 + imported_funcs_count).into())
+            {
+                func_builder.set_name(func_name.clone());
             }
-
-            let module_op = ModuleOp::new(
-                ctx,
-                "module_name",
-                start_func_name,
-                all_func_syms,
-                funcs,
-                Vec::new(),
-                Vec::new(),
-            );
-            module_op.verify(ctx)?;
-            Ok(module_op)
-        } else {
-            Err(ModuleBuilderError::StartFuncUndefined)
+            func_builder.set_signature(func_sigs[func_idx]);
         }
+        let mut all_func_syms: Vec<FuncSym> = Vec::new();
+        for (label, _) in self.import_functions.iter() {
+            all_func_syms.push(label.name.clone().into());
+        }
+        for func_builder in self.functions {
+            let func = func_builder.build(ctx)?;
+            funcs.push(func);
+            all_func_syms.push(func.get_symbol_name(ctx).into());
+        }
+
+        let module_op = ModuleOp::new(
+            ctx,
+            "module_name",
+            start_func_name,
+            all_func_syms,
+            funcs,
+            import_funcs.iter().map(|(_, ty)| *ty).collect(),
+            import_funcs
+                .into_iter()
+                .map(|(label, _)| label.module)
+                .collect(),
+            self.global_types,
+        );
+        module_op.set_exports(ctx, exports);
+        module_op.verify(ctx)?;
+        Ok(module_op)
     }
 
     pub fn next_func_idx(&self) -> FuncIndex {
@@ -195,7 +237,7 @@ impl ModuleBuilder {
 
 #[derive(Error, Debug)]
 pub enum ModuleBuilderError {
-    #[error("start function is undefined")]
+    #[error("start function is undefined and there are no exported functions")]
     StartFuncUndefined,
     // #[error("cannot find a body for import function `{0:?}`")]
     // ImportFuncBodyNotFound(ImportFunc),
@@ -79,7 +79,7 @@ pub fn parse_module(
 
             Payload::GlobalSection(globals) => {
                 validator.global_section(&globals)?;
-                // todo!()
+                parse_global_section(ctx, globals, &mut mod_builder)?;
             }
 
             Payload::ExportSection(exports) => {
@@ -156,6 +156,7 @@ fn parse_export_section(
                 if export.name == "__main" {
                     mod_builder.set_start_func(export.index);
                 }
+                mod_builder.push_export_func(export.name, export.index);
             }
             _ => {
                 // dbg!(&export);
@@ -180,6 +181,18 @@ fn parse_type_section(
     Ok(())
 }
 
+fn parse_global_section(
+    ctx: &mut Context,
+    globals: wasmparser::GlobalSectionReader,
+    mod_builder: &mut ModuleBuilder,
+) -> Result<(), WasmError> {
+    for entry in globals {
+        let global = entry?;
+        mod_builder.push_global_type(from_val_type(ctx, &global.ty.content_type));
+    }
+    Ok(())
+}
+
 fn parse_code_section_entry(
     ctx: &mut Context,
     mod_builder: &mut ModuleBuilder,
@@ -3,19 +3,51 @@ use ozk_ozk_dialect::attributes::i64_attr;
 use ozk_ozk_dialect::types::i32_type;
 use ozk_ozk_dialect::types::i64_type;
 use ozk_wasm_dialect::ops::AddOp;
+use ozk_wasm_dialect::ops::AndOp;
 use ozk_wasm_dialect::ops::BlockOp;
 use ozk_wasm_dialect::ops::BrIfOp;
 use ozk_wasm_dialect::ops::BrOp;
 use ozk_wasm_dialect::ops::CallOp;
 use ozk_wasm_dialect::ops::ConstantOp;
+use ozk_wasm_dialect::ops::DivSOp;
+use ozk_wasm_dialect::ops::DivUOp;
+use ozk_wasm_dialect::ops::DropOp;
+use ozk_wasm_dialect::ops::EqOp;
+use ozk_wasm_dialect::ops::GeSOp;
+use ozk_wasm_dialect::ops::GeUOp;
 use ozk_wasm_dialect::ops::GlobalGetOp;
 use ozk_wasm_dialect::ops::GlobalSetOp;
+use ozk_wasm_dialect::ops::GtSOp;
+use ozk_wasm_dialect::ops::GtUOp;
 use ozk_wasm_dialect::ops::I32EqzOp;
+use ozk_wasm_dialect::ops::I32WrapI64Op;
+use ozk_wasm_dialect::ops::I64EqzOp;
+use ozk_wasm_dialect::ops::I64ExtendI32SOp;
+use ozk_wasm_dialect::ops::I64ExtendI32UOp;
+use ozk_wasm_dialect::ops::LeSOp;
+use ozk_wasm_dialect::ops::LeUOp;
+use ozk_wasm_dialect::ops::LoadOp;
 use ozk_wasm_dialect::ops::LocalGetOp;
 use ozk_wasm_dialect::ops::LocalSetOp;
 use ozk_wasm_dialect::ops::LocalTeeOp;
 use ozk_wasm_dialect::ops::LoopOp;
+use ozk_wasm_dialect::ops::LtSOp;
+use ozk_wasm_dialect::ops::LtUOp;
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use ozk_wasm_dialect::ops::MemArg;
+use ozk_wasm_dialect::ops::MulOp;
+use ozk_wasm_dialect::ops::NeOp;
+use ozk_wasm_dialect::ops::OrOp;
+use ozk_wasm_dialect::ops::PopcntOp;
+use ozk_wasm_dialect::ops::RemSOp;
+use ozk_wasm_dialect::ops::RemUOp;
 use ozk_wasm_dialect::ops::ReturnOp;
+use ozk_wasm_dialect::ops::ShlOp;
+use ozk_wasm_dialect::ops::ShrSOp;
+use ozk_wasm_dialect::ops::ShrUOp;
+use ozk_wasm_dialect::ops::StoreOp;
+use ozk_wasm_dialect::ops::SubOp;
+use ozk_wasm_dialect::ops::XorOp;
 use ozk_wasm_dialect::types::from_block_type;
 use pliron::context::Context;
 use pliron::op::Op;
@@ -137,21 +169,152 @@ impl<'a> OpBuilder<'a> {
         self.fbuilder.push(ctx, op)
     }
 
+    pub fn i32sub(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = SubOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32mul(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = MulOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32shl(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = ShlOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32shru(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = ShrUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32shrs(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = ShrSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32divu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = DivUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32divs(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = DivSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32remu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = RemUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32rems(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = RemSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32popcnt(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = PopcntOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
     pub fn i32eqz(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
         let op = I32EqzOp::new_unlinked(ctx).get_operation();
         self.fbuilder.push(ctx, op)
     }
 
-    pub fn i32wrapi64(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i32and(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = AndOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i32and(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i32or(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = OrOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i32geu(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i32xor(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = XorOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32eq(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = EqOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32ne(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = NeOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32ltu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = LtUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32lts(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = LtSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32gtu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = GtUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32gts(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = GtSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32leu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = LeUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32les(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = LeSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32geu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = GeUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32ges(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i32_type(ctx);
+        let op = GeSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32wrapi64(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32WrapI64Op::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
     pub fn i64add(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
@@ -160,28 +323,157 @@ impl<'a> OpBuilder<'a> {
         self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64eqz(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64sub(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = SubOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64eq(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64mul(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = MulOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64and(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64shl(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = ShlOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64geu(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64shru(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = ShrUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64ne(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64shrs(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = ShrSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64extendi32u(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64and(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = AndOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64or(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = OrOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64xor(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = XorOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64eq(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = EqOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64ne(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = NeOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64ltu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = LtUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64lts(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = LtSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64gtu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = GtUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64gts(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = GtSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64leu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = LeUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64les(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = LeSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64geu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = GeUOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64ges(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let ty = i64_type(ctx);
+        let op = GeSOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64eqz(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64EqzOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64extendi32u(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64ExtendI32UOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64extendi32s(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64ExtendI32SOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn drop(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = DropOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    /// Load `size` bytes extended to the value type.
+    pub fn load(
+        &mut self,
+        ctx: &mut Context,
+        ty: MemAccessOpValueType,
+        size: u32,
+        signed: bool,
+        memarg: MemArg,
+    ) -> Result<(), FuncBuilderError> {
+        let op = LoadOp::new_sized_unlinked(ctx, ty, size, signed, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    /// Store the `size` low bytes of the value.
+    pub fn store(
+        &mut self,
+        ctx: &mut Context,
+        ty: MemAccessOpValueType,
+        size: u32,
+        memarg: MemArg,
+    ) -> Result<(), FuncBuilderError> {
+        let op = StoreOp::new_sized_unlinked(ctx, ty, size, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
     // pub fn call(&mut self, ctx: &mut Context, func_index: u32) {
@@ -0,0 +1,320 @@
+//! Dead function elimination. Removes the functions that are not reachable via the call graph
+//! from the entry point of a `wasm.module`, `miden.program` or `valida.program`.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use ozk_miden_dialect::ops as miden;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_valida_dialect::ops as valida;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::op_cast;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::rewrite::RewritePatternSet;
+
+#[derive(Default)]
+pub struct DceUnusedFunctionsPass;
+
+impl Pass for DceUnusedFunctionsPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::<DceUnusedFunctions>::default());
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "dce-unused-functions",
+        description: "Remove the functions that are not reachable from the start function (wasm.module, miden.program, valida.program)",
+        constructor: || Box::<DceUnusedFunctionsPass>::default(),
+    }
+}
+
+#[derive(Default)]
+struct DceUnusedFunctions;
+
+impl RewritePattern for DceUnusedFunctions {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        if let Some(module_op) = opop.downcast_ref::<wasm::ModuleOp>() {
+            // the function indices attribute is left intact, so that the `wasm.call` indices
+            // of the remaining functions stay valid
+            // without the start function (a library) the exported functions are the roots
+            let roots = match module_op.get_start_func_sym(ctx) {
+                Some(start_func_sym) => vec![start_func_sym.into()],
+                None => module_op
+                    .get_exports(ctx)
+                    .into_iter()
+                    .map(|(_, func_sym)| func_sym.into())
+                    .collect(),
+            };
+            let funcs_block = module_op.get_body(ctx, 0);
+            let changed =
+                remove_unreachable_funcs(ctx, rewriter, funcs_block, roots, Some(module_op))?;
+            if changed {
+                // drop the exports of the removed functions
+                let exports = module_op
+                    .get_exports(ctx)
+                    .into_iter()
+                    .filter(|(_, func_sym)| module_op.get_func(ctx, func_sym).is_some())
+                    .collect();
+                module_op.set_exports(ctx, exports);
+            }
+            Ok(changed)
+        } else if let Some(program_op) = opop.downcast_ref::<miden::ProgramOp>() {
+            let roots = std::iter::once(program_op.get_main_proc_sym(ctx))
+                .chain(
+                    program_op
+                        .get_exports(ctx)
+                        .into_iter()
+                        .map(|(_, proc_sym)| proc_sym),
+                )
+                .collect();
+            let funcs_block = program_op.get_body(ctx, 0);
+            remove_unreachable_funcs(ctx, rewriter, funcs_block, roots, None)
+        } else if let Some(program_op) = opop.downcast_ref::<valida::ProgramOp>() {
+            // the functions called from the entry block
+            let roots = program_op
+                .get_entry_block(ctx)
+                .deref(ctx)
+                .iter(ctx)
+                .flat_map(|op| callee_syms(ctx, op, None))
+                .collect();
+            let funcs_block = program_op.get_funcs_block(ctx);
+            remove_unreachable_funcs(ctx, rewriter, funcs_block, roots, None)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+/// Erases the symbol ops (functions, procedures) of the block that are not reachable from
+/// the given roots. Returns true if any function was removed.
+/// The `wasm.call` ops are resolved in `module_op`.
+fn remove_unreachable_funcs(
+    ctx: &mut Context,
+    rewriter: &mut dyn PatternRewriter,
+    funcs_block: Ptr<BasicBlock>,
+    roots: Vec<String>,
+    module_op: Option<&wasm::ModuleOp>,
+) -> Result<bool, anyhow::Error> {
+    let mut funcs: HashMap<String, Ptr<Operation>> = HashMap::new();
+    for op in funcs_block.deref(ctx).iter(ctx) {
+        if let Some(sym_op) = op_cast::<dyn SymbolOpInterface>(op.deref(ctx).get_op(ctx).as_ref()) {
+            funcs.insert(sym_op.get_symbol_name(ctx), op);
+        }
+    }
+    let mut reachable: HashSet<String> = HashSet::new();
+    let mut worklist = roots;
+    while let Some(sym) = worklist.pop() {
+        if !reachable.insert(sym.clone()) {
+            continue;
+        }
+        // imported functions have no body
+        if let Some(func_op) = funcs.get(&sym) {
+            worklist.extend(callee_syms(ctx, *func_op, module_op));
+        }
+    }
+    let mut changed = false;
+    for (sym, func_op) in funcs {
+        if !reachable.contains(&sym) {
+            rewriter.erase_op(ctx, func_op)?;
+            changed = true;
+        }
+    }
+    Ok(changed)
+}
+
+/// Returns the symbols of all functions called in the given op (including the nested ops).
+/// The `wasm.call` ops are resolved in `module_op` and skipped without it.
+fn callee_syms(
+    ctx: &Context,
+    op: Ptr<Operation>,
+    module_op: Option<&wasm::ModuleOp>,
+) -> Vec<String> {
+    let mut callees = Vec::new();
+    op.walk(ctx, WalkOrder::PreOrder, &mut |op| {
+        let opop = &op.deref(ctx).get_op(ctx);
+        if let Some(call_op) = opop.downcast_ref::<wasm::CallOp>() {
+            if let Some(func_sym) = module_op
+                .and_then(|module_op| module_op.get_func_sym(ctx, call_op.get_func_index(ctx)))
+            {
+                callees.push(func_sym.into());
+            }
+        } else if let Some(call_op) = opop.downcast_ref::<ozk::CallOp>() {
+            callees.push(call_op.get_func_sym(ctx));
+        } else if let Some(exec_op) = opop.downcast_ref::<miden::ExecOp>() {
+            callees.push(exec_op.get_callee_sym(ctx));
+        } else if let Some(jalsym_op) = opop.downcast_ref::<valida::JalSymOp>() {
+            callees.push(jalsym_op.get_target_sym(ctx));
+        }
+        WalkResult::Advance
+    });
+    callees
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::miden::lowering::call_op_lowering::WasmToMidenCallOpLoweringPass;
+    use crate::miden::lowering::WasmToMidenCFLoweringPass;
+    use crate::ozk::lowering::WasmToOzkLoweringPass;
+    use crate::tests_util::try_run_wasm_passes;
+    use crate::valida::lowering::func_lowering::WasmToValidaFuncLoweringPass;
+    use crate::valida::lowering::module_lowering::WasmToValidaModuleLoweringPass;
+    use crate::wasm::resolve_call_op::WasmCallOpToOzkCallOpPass;
+    use crate::wasm::track_stack_depth::WasmTrackStackDepthPass;
+
+    /// `main` calls `a` (directly and via `c`) and `b`, `unused` calls `b` and `recursive` calls itself
+    const CALL_GRAPH_WAT: &str = r#"
+(module
+    (start $main)
+    (func $unused
+        call $b
+        return)
+    (func $b
+        return)
+    (func $recursive
+        call $recursive
+        return)
+    (func $a
+        call $b
+        return)
+    (func $c
+        call $a
+        return)
+    (func $main
+        call $c
+        call $a
+        return)
+)"#;
+
+    /// Run the passes and return the symbols of the functions left in the module/program.
+    fn remaining_funcs(wat: &str, passes: Vec<Box<dyn Pass>>) -> Vec<String> {
+        let mut ctx = Context::default();
+        let root_op = try_run_wasm_passes(&mut ctx, wat, passes, false).unwrap();
+        let root_opop = &root_op.deref(&ctx).get_op(&ctx);
+        let funcs_block = if let Some(module_op) = root_opop.downcast_ref::<wasm::ModuleOp>() {
+            module_op.get_body(&ctx, 0)
+        } else if let Some(program_op) = root_opop.downcast_ref::<miden::ProgramOp>() {
+            program_op.get_body(&ctx, 0)
+        } else {
+            root_opop
+                .downcast_ref::<valida::ProgramOp>()
+                .unwrap()
+                .get_funcs_block(&ctx)
+        };
+        let mut syms: Vec<String> = funcs_block
+            .deref(&ctx)
+            .iter(&ctx)
+            .filter_map(|op| {
+                op_cast::<dyn SymbolOpInterface>(op.deref(&ctx).get_op(&ctx).as_ref())
+                    .map(|sym_op| sym_op.get_symbol_name(&ctx))
+            })
+            .collect();
+        syms.sort();
+        syms
+    }
+
+    #[test]
+    fn wasm_module() {
+        assert_eq!(
+            remaining_funcs(
+                CALL_GRAPH_WAT,
+                vec![Box::<DceUnusedFunctionsPass>::default()]
+            ),
+            vec!["a", "b", "c", "main"]
+        );
+    }
+
+    #[test]
+    fn wasm_library() {
+        // no start function, the exported functions are the roots
+        let wat = r#"
+(module
+    (func $unused
+        call $b
+        return)
+    (func $b
+        return)
+    (func $a
+        call $b
+        return)
+    (export "a" (func $a))
+)"#;
+        assert_eq!(
+            remaining_funcs(wat, vec![Box::<DceUnusedFunctionsPass>::default()]),
+            vec!["a", "b"]
+        );
+    }
+
+    #[test]
+    fn wasm_module_with_resolved_calls() {
+        assert_eq!(
+            remaining_funcs(
+                CALL_GRAPH_WAT,
+                vec![
+                    Box::<WasmCallOpToOzkCallOpPass>::default(),
+                    Box::<DceUnusedFunctionsPass>::default(),
+                ]
+            ),
+            vec!["a", "b", "c", "main"]
+        );
+    }
+
+    #[test]
+    fn miden_program() {
+        assert_eq!(
+            remaining_funcs(
+                CALL_GRAPH_WAT,
+                vec![
+                    Box::<WasmToOzkLoweringPass>::default(),
+                    Box::<WasmToMidenCallOpLoweringPass>::default(),
+                    Box::<WasmToMidenCFLoweringPass>::default(),
+                    Box::<DceUnusedFunctionsPass>::default(),
+                ]
+            ),
+            vec!["a", "b", "c", "main", "ozk_miden_main_proc"]
+        );
+    }
+
+    #[test]
+    fn valida_program() {
+        assert_eq!(
+            remaining_funcs(
+                CALL_GRAPH_WAT,
+                vec![
+                    Box::<WasmToOzkLoweringPass>::default(),
+                    Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                    Box::<WasmToValidaFuncLoweringPass>::default(),
+                    Box::<WasmToValidaModuleLoweringPass>::default(),
+                    Box::<DceUnusedFunctionsPass>::default(),
+                ]
+            ),
+            vec!["a", "b", "c", "main"]
+        );
+    }
+}
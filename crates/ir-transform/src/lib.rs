@@ -19,10 +19,13 @@
 #![deny(clippy::panic)]
 
 mod and_minus_8;
-mod locals_to_mem;
-mod save_stack_pub_inputs;
 
+pub mod dce;
 pub mod miden;
+pub mod ozk;
+pub mod pass_manager;
+pub mod pass_registry;
+pub mod ssa;
 pub mod triton;
 pub mod valida;
 pub mod wasm;
@@ -1,3 +1,7 @@
 //! Miden specific transformations
 mod convert_blocks;
 pub mod lowering;
+pub mod memory_layout;
+pub mod recursion_to_loop;
+pub mod save_stack_pub_inputs;
+pub mod secret_inputs;
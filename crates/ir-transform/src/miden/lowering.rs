@@ -1,4 +1,5 @@
 use ozk_miden_dialect::MIDEN_DIALECT;
+use ozk_ozk_dialect::OZK_DIALECT;
 use ozk_wasm_dialect::WASM_DIALECT;
 use pliron::context::Context;
 use pliron::context::Ptr;
@@ -14,12 +15,19 @@ pub mod call_op_lowering;
 
 use self::arith_op_lowering::ArithOpLowering;
 use self::constant_op_lowering::ConstantOpLowering;
+use self::local_op_lowering::LocalOpLowering;
+use self::mem_op_lowering::MemOpLowering;
+use self::stack_op_lowering::StackOpLowering;
 
 mod cf_lowering;
+mod loop_analysis;
 pub use cf_lowering::WasmToMidenCFLoweringPass;
 
 pub mod arith_op_lowering;
 pub mod constant_op_lowering;
+pub mod local_op_lowering;
+pub mod mem_op_lowering;
+pub mod stack_op_lowering;
 
 #[derive(Default)]
 pub struct WasmToMidenArithLoweringPass;
@@ -32,12 +40,67 @@ impl Pass for WasmToMidenArithLoweringPass {
         let mut patterns = RewritePatternSet::default();
         patterns.add(Box::<ConstantOpLowering>::default());
         patterns.add(Box::<ArithOpLowering>::default());
+        patterns.add(Box::<StackOpLowering>::default());
         apply_partial_conversion(ctx, op, target, patterns)?;
         Ok(())
     }
 }
 
-/// The pass that ensures there are no Wasm ops left.
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "miden-lower-arith",
+        description: "Lower ozk constants, arithmetic and stack ops to Miden ops",
+        constructor: || Box::<WasmToMidenArithLoweringPass>::default(),
+    }
+}
+
+/// Lowers Wasm memory access (byte-addressed) to Miden memory access (word-addressed).
+#[derive(Default)]
+pub struct WasmToMidenMemLoweringPass;
+
+impl Pass for WasmToMidenMemLoweringPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut target = ConversionTarget::default();
+        target.add_legal_dialect(MIDEN_DIALECT(ctx));
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::<MemOpLowering>::default());
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "miden-lower-mem",
+        description: "Lower ozk linear memory access to Miden word memory access",
+        constructor: || Box::<WasmToMidenMemLoweringPass>::default(),
+    }
+}
+
+/// Lowers Wasm `local.get/set/tee` to Miden procedure locals access.
+#[derive(Default)]
+pub struct WasmToMidenLocalsLoweringPass;
+
+impl Pass for WasmToMidenLocalsLoweringPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut target = ConversionTarget::default();
+        target.add_legal_dialect(MIDEN_DIALECT(ctx));
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::<LocalOpLowering>::default());
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "miden-lower-locals",
+        description: "Lower ozk locals access to Miden procedure locals",
+        constructor: || Box::<WasmToMidenLocalsLoweringPass>::default(),
+    }
+}
+
+/// The pass that ensures there are no Wasm and `ozk` ops left.
 #[derive(Default)]
 pub struct WasmToMidenFinalLoweringPass;
 
@@ -45,6 +108,7 @@ impl Pass for WasmToMidenFinalLoweringPass {
     fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
         let mut target = ConversionTarget::default();
         target.add_illegal_dialect(WASM_DIALECT(ctx));
+        target.add_illegal_dialect(OZK_DIALECT(ctx));
         target.add_legal_dialect(MIDEN_DIALECT(ctx));
         #[allow(clippy::expect_used)]
         target.add_legal_dialect(
@@ -56,3 +120,11 @@ impl Pass for WasmToMidenFinalLoweringPass {
         Ok(())
     }
 }
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "miden-lower-final",
+        description: "Ensure there are no Wasm and ozk ops left after lowering to Miden",
+        constructor: || Box::<WasmToMidenFinalLoweringPass>::default(),
+    }
+}
@@ -1,43 +1,303 @@
 use anyhow::anyhow;
+use miden::attributes::FieldElem;
+use miden::attributes::FieldElemAttr;
+use miden::ops::DupOp;
+use miden::ops::MovUpOp;
+use miden::ops::SwapOp;
+use miden::ops::U32CheckedAndOp;
+use miden::ops::U32CheckedGtOp;
+use miden::ops::U32CheckedGteOp;
+use miden::ops::U32CheckedLtOp;
+use miden::ops::U32CheckedLteOp;
+use miden::ops::U32CheckedOrOp;
+use miden::ops::U32CheckedShlOp;
+use miden::ops::U32CheckedShrOp;
+use miden::ops::U32CheckedXorOp;
+use miden::ops::U32WrappingAddOp;
+use miden::ops::U32WrappingMulOp;
+use miden::ops::U32WrappingSubOp;
+use miden::types::FieldElemType;
 use ozk_miden_dialect as miden;
+use ozk_ozk_dialect as ozk;
 use ozk_ozk_dialect::types::i32_type;
-use ozk_wasm_dialect as wasm;
 use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::op::Op;
 use pliron::operation::Operation;
 use pliron::pattern_match::PatternRewriter;
 use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
+use pliron::with_context::AttachContext;
+
+/// Integer arithmetic with the Wasm semantics (wrapping around, shift amount modulo the type width)
+#[derive(Debug, Clone, Copy)]
+enum IntArith {
+    Add,
+    Sub,
+    Mul,
+    Shl,
+    ShrU,
+    ShrS,
+    And,
+    Or,
+    Xor,
+}
+
+/// Field element arithmetic
+#[derive(Debug, Clone, Copy)]
+enum FeltArith {
+    Add,
+    Sub,
+    Mul,
+}
+
+/// Integer comparison
+#[derive(Debug, Clone, Copy)]
+enum IntCmp {
+    Eq,
+    Ne,
+    LtU,
+    LtS,
+    GtU,
+    GtS,
+    LeU,
+    LeS,
+    GeU,
+    GeS,
+}
+
+enum Arith {
+    Int(IntArith, Ptr<TypeObj>),
+    Cmp(IntCmp, Ptr<TypeObj>),
+    Eqz(Ptr<TypeObj>),
+    Felt(FeltArith),
+}
+
+fn get_arith(ctx: &Context, op: Ptr<Operation>) -> Option<Arith> {
+    let opop = &op.deref(ctx).get_op(ctx);
+    let int_arith = |arith, ty| Some(Arith::Int(arith, ty));
+    let int_cmp = |cmp, ty| Some(Arith::Cmp(cmp, ty));
+    if let Some(op) = opop.downcast_ref::<ozk::ops::WrappingAddOp>() {
+        int_arith(IntArith::Add, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::WrappingSubOp>() {
+        int_arith(IntArith::Sub, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::WrappingMulOp>() {
+        int_arith(IntArith::Mul, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::ShlOp>() {
+        int_arith(IntArith::Shl, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::ShrUOp>() {
+        int_arith(IntArith::ShrU, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::ShrSOp>() {
+        int_arith(IntArith::ShrS, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::AndOp>() {
+        int_arith(IntArith::And, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::OrOp>() {
+        int_arith(IntArith::Or, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::XorOp>() {
+        int_arith(IntArith::Xor, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::EqOp>() {
+        int_cmp(IntCmp::Eq, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::NeOp>() {
+        int_cmp(IntCmp::Ne, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::EqzOp>() {
+        Some(Arith::Eqz(op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::LtUOp>() {
+        int_cmp(IntCmp::LtU, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::LtSOp>() {
+        int_cmp(IntCmp::LtS, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::GtUOp>() {
+        int_cmp(IntCmp::GtU, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::GtSOp>() {
+        int_cmp(IntCmp::GtS, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::LeUOp>() {
+        int_cmp(IntCmp::LeU, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::LeSOp>() {
+        int_cmp(IntCmp::LeS, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::GeUOp>() {
+        int_cmp(IntCmp::GeU, op.get_type(ctx))
+    } else if let Some(op) = opop.downcast_ref::<ozk::ops::GeSOp>() {
+        int_cmp(IntCmp::GeS, op.get_type(ctx))
+    } else if opop.is::<ozk::ops::FeltAddOp>() {
+        Some(Arith::Felt(FeltArith::Add))
+    } else if opop.is::<ozk::ops::FeltSubOp>() {
+        Some(Arith::Felt(FeltArith::Sub))
+    } else if opop.is::<ozk::ops::FeltMulOp>() {
+        Some(Arith::Felt(FeltArith::Mul))
+    } else {
+        None
+    }
+}
 
 #[derive(Default)]
 pub struct ArithOpLowering {}
 
 impl RewritePattern for ArithOpLowering {
     fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
-        Ok(op
-            .deref(ctx)
-            .get_op(ctx)
-            .downcast_ref::<wasm::ops::AddOp>()
-            .is_some())
+        Ok(get_arith(ctx, op).is_some())
     }
 
-    #[allow(clippy::unwrap_used)]
     fn rewrite(
         &self,
         ctx: &mut Context,
         op: Ptr<Operation>,
         rewriter: &mut dyn PatternRewriter,
     ) -> Result<(), anyhow::Error> {
-        let opop = &op.deref(ctx).get_op(ctx);
-        if let Some(add_op) = opop.downcast_ref::<wasm::ops::AddOp>() {
-            let add_op_ty = add_op.get_type(ctx);
-            if add_op_ty == i32_type(ctx) {
-                let miden_op = miden::ops::AddOp::new_unlinked(ctx);
-                rewriter.replace_op_with(ctx, op, miden_op.get_operation())?;
-            } else {
-                return Err(anyhow!("only 32-bit integers are supported"));
+        let miden_ops = match get_arith(ctx, op) {
+            Some(Arith::Int(arith, ty)) => {
+                check_i32(ctx, op, ty)?;
+                lower_i32_arith(ctx, arith)
+            }
+            Some(Arith::Cmp(cmp, ty)) => {
+                check_i32(ctx, op, ty)?;
+                lower_i32_cmp(ctx, cmp)
+            }
+            Some(Arith::Eqz(ty)) => {
+                check_i32(ctx, op, ty)?;
+                vec![
+                    felt_constant(ctx, 0),
+                    miden::ops::EqOp::new_unlinked(ctx).get_operation(),
+                ]
             }
+            Some(Arith::Felt(arith)) => vec![lower_felt_arith(ctx, arith)],
+            None => return Ok(()),
+        };
+        let Some((last_op, ops)) = miden_ops.split_last() else {
+            return Err(anyhow!("empty lowering"));
+        };
+        for miden_op in ops {
+            rewriter.insert_before(ctx, *miden_op)?;
         }
+        rewriter.replace_op_with(ctx, op, *last_op)?;
         Ok(())
     }
 }
+
+fn lower_felt_arith(ctx: &mut Context, arith: FeltArith) -> Ptr<Operation> {
+    match arith {
+        FeltArith::Add => miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+        FeltArith::Sub => miden::ops::SubOp::new_unlinked(ctx).get_operation(),
+        FeltArith::Mul => miden::ops::MulOp::new_unlinked(ctx).get_operation(),
+    }
+}
+
+/// The i64 values are expected to be split into i32 limbs (`ozk-legalize-i64`) beforehand.
+fn check_i32(ctx: &mut Context, op: Ptr<Operation>, ty: Ptr<TypeObj>) -> Result<(), anyhow::Error> {
+    if ty != i32_type(ctx) {
+        return Err(anyhow!(
+            "{}: only 32-bit integers are supported, legalize i64 with ozk-legalize-i64 first",
+            op.deref(ctx).get_opid().with_ctx(ctx)
+        ));
+    }
+    Ok(())
+}
+
+/// Lower i32 comparison to Miden ops. The signed comparisons flip the sign bit of
+/// the operands and compare them as u32.
+fn lower_i32_cmp(ctx: &mut Context, cmp: IntCmp) -> Vec<Ptr<Operation>> {
+    match cmp {
+        IntCmp::Eq => vec![miden::ops::EqOp::new_unlinked(ctx).get_operation()],
+        IntCmp::Ne => vec![miden::ops::NeqOp::new_unlinked(ctx).get_operation()],
+        IntCmp::LtU => vec![U32CheckedLtOp::new_unlinked(ctx).get_operation()],
+        IntCmp::GtU => vec![U32CheckedGtOp::new_unlinked(ctx).get_operation()],
+        IntCmp::LeU => vec![U32CheckedLteOp::new_unlinked(ctx).get_operation()],
+        IntCmp::GeU => vec![U32CheckedGteOp::new_unlinked(ctx).get_operation()],
+        IntCmp::LtS => {
+            let mut ops = flip_sign_bits(ctx);
+            ops.push(U32CheckedLtOp::new_unlinked(ctx).get_operation());
+            ops
+        }
+        IntCmp::GtS => {
+            let mut ops = flip_sign_bits(ctx);
+            ops.push(U32CheckedGtOp::new_unlinked(ctx).get_operation());
+            ops
+        }
+        IntCmp::LeS => {
+            let mut ops = flip_sign_bits(ctx);
+            ops.push(U32CheckedLteOp::new_unlinked(ctx).get_operation());
+            ops
+        }
+        IntCmp::GeS => {
+            let mut ops = flip_sign_bits(ctx);
+            ops.push(U32CheckedGteOp::new_unlinked(ctx).get_operation());
+            ops
+        }
+    }
+}
+
+/// Flip the sign bits of the two top stack items (maps i32 order onto u32 order).
+fn flip_sign_bits(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let sign_bit = 1 << 31;
+    vec![
+        felt_constant(ctx, sign_bit),
+        U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+        SwapOp::new_unlinked(ctx, 1).get_operation(),
+        felt_constant(ctx, sign_bit),
+        U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+        SwapOp::new_unlinked(ctx, 1).get_operation(),
+    ]
+}
+
+/// Lower i32 arithmetic to Miden `u32*` ops. The i32 values are expected to be on the stack
+/// as their u32 bit pattern.
+fn lower_i32_arith(ctx: &mut Context, arith: IntArith) -> Vec<Ptr<Operation>> {
+    match arith {
+        IntArith::Add => vec![U32WrappingAddOp::new_unlinked(ctx).get_operation()],
+        IntArith::Sub => vec![U32WrappingSubOp::new_unlinked(ctx).get_operation()],
+        IntArith::Mul => vec![U32WrappingMulOp::new_unlinked(ctx).get_operation()],
+        IntArith::And => vec![U32CheckedAndOp::new_unlinked(ctx).get_operation()],
+        IntArith::Or => vec![U32CheckedOrOp::new_unlinked(ctx).get_operation()],
+        IntArith::Xor => vec![U32CheckedXorOp::new_unlinked(ctx).get_operation()],
+        IntArith::Shl => {
+            let mut ops = shift_amount_mod_32(ctx);
+            ops.push(U32CheckedShlOp::new_unlinked(ctx).get_operation());
+            ops
+        }
+        IntArith::ShrU => {
+            let mut ops = shift_amount_mod_32(ctx);
+            ops.push(U32CheckedShrOp::new_unlinked(ctx).get_operation());
+            ops
+        }
+        IntArith::ShrS => {
+            // a >>s b == ((a ^ m) >> b) ^ m, where m is all ones if a is negative, otherwise 0
+            // stack: [b, a]
+            let mut ops = shift_amount_mod_32(ctx);
+            ops.extend([
+                // [a, b]
+                SwapOp::new_unlinked(ctx, 1).get_operation(),
+                // [a, a, b]
+                DupOp::new_unlinked(ctx, 0).get_operation(),
+                // [sign, a, b]
+                felt_constant(ctx, 31),
+                U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+                // [m = 0 - sign, a, b]
+                felt_constant(ctx, 0),
+                SwapOp::new_unlinked(ctx, 1).get_operation(),
+                U32WrappingSubOp::new_unlinked(ctx).get_operation(),
+                // [a ^ m, m, b]
+                DupOp::new_unlinked(ctx, 0).get_operation(),
+                MovUpOp::new_unlinked(ctx, 2).get_operation(),
+                U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+                // [(a ^ m) >> b, m]
+                MovUpOp::new_unlinked(ctx, 2).get_operation(),
+                U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+                // [((a ^ m) >> b) ^ m]
+                U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+            ]);
+            ops
+        }
+    }
+}
+
+/// Wasm takes the shift amount (on top of the stack) modulo 32.
+fn shift_amount_mod_32(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    vec![
+        felt_constant(ctx, 31),
+        U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+pub(crate) fn felt_constant(ctx: &mut Context, value: u64) -> Ptr<Operation> {
+    let ty = FieldElemType::get(ctx);
+    let value = FieldElemAttr::create(ty, FieldElem::new(value));
+    miden::ops::ConstantOp::new_unlinked(ctx, value).get_operation()
+}
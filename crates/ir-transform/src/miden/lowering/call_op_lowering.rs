@@ -1,5 +1,5 @@
-use anyhow::anyhow;
 use ozk_miden_dialect::ops as miden;
+use ozk_ozk_dialect::ops as ozk;
 use ozk_wasm_dialect::ops as wasm;
 use pliron::context::Context;
 use pliron::context::Ptr;
@@ -13,6 +13,15 @@ use pliron::pattern_match::PatternRewriter;
 use pliron::pattern_match::RewritePattern;
 use pliron::rewrite::RewritePatternSet;
 
+/// The module name prefix of the Wasm imports that are Miden library procedures.
+/// The import `(import "miden::std::math::u64" "wrapping_add" ...)` is lowered to the
+/// `std::math::u64::wrapping_add` exec. The arguments are passed on the stack as is
+/// (the last Wasm argument is on top of the stack), so the import's type should match
+/// the procedure's stack signature.
+/// In Rust such imports are declared in an `extern "C"` block with
+/// `#[link(wasm_import_module = "miden::std::math::u64")]`.
+pub const MIDEN_LIBRARY_IMPORT_PREFIX: &str = "miden::";
+
 #[derive(Default)]
 pub struct WasmToMidenCallOpLoweringPass;
 
@@ -27,6 +36,14 @@ impl Pass for WasmToMidenCallOpLoweringPass {
     }
 }
 
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "miden-lower-call-op",
+        description: "Lower ozk calls to miden.exec",
+        constructor: || Box::<WasmToMidenCallOpLoweringPass>::default(),
+    }
+}
+
 #[derive(Default)]
 pub struct CallOpLowering;
 
@@ -54,18 +71,25 @@ impl RewritePattern for CallOpLowering {
         let mut call_ops = Vec::new();
         module_op
             .get_operation()
-            .walk(ctx, WalkOrder::PostOrder, &mut |op| {
-                if let Ok(call_op) = op.deref(ctx).get_op(ctx).downcast::<wasm::CallOp>() {
-                    call_ops.push(call_op);
-                }
+            .walk_only::<ozk::CallOp>(ctx, WalkOrder::PostOrder, &mut |op| {
+                call_ops.push(*op);
                 pliron::operation::WalkResult::Advance
             });
         for call_op in call_ops {
-            let func_index = call_op.get_func_index(ctx);
-            let callee_sym = module_op
-                .get_func_sym(ctx, func_index)
-                .ok_or_else(|| anyhow!("no function with index {}", func_index))?;
-            let miden_exec_op = miden::ExecOp::new_unlinked(ctx, callee_sym);
+            let func_sym = call_op.get_func_sym(ctx);
+            let library_module = module_op
+                .get_func_index(ctx, func_sym.clone().into())
+                .and_then(|func_index| module_op.get_import_func_module(ctx, func_index))
+                .and_then(|module| {
+                    module
+                        .strip_prefix(MIDEN_LIBRARY_IMPORT_PREFIX)
+                        .map(str::to_string)
+                });
+            let callee_sym = match library_module {
+                Some(library_module) => format!("{library_module}::{func_sym}"),
+                None => func_sym,
+            };
+            let miden_exec_op = miden::ExecOp::new_unlinked(ctx, callee_sym.into());
             rewriter.replace_op_with(
                 ctx,
                 call_op.get_operation(),
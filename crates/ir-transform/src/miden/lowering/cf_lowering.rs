@@ -1,38 +1,71 @@
-use bounded_vec::NonEmptyVec;
-use derive_more::From;
 use ozk_miden_dialect::ops as miden;
+use ozk_ozk_dialect::ops as ozk;
 use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::MemAddress;
 use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::dialect_conversion::apply_partial_conversion;
 use pliron::dialect_conversion::ConversionTarget;
 use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
 use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::dialects::builtin::types::FunctionType;
 use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
 use pliron::operation::Operation;
 use pliron::pass::Pass;
 use pliron::pattern_match::PatternRewriter;
 use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
 use pliron::rewrite::RewritePatternSet;
 
-#[derive(Default)]
-pub struct WasmToMidenCFLoweringPass;
+use super::arith_op_lowering::felt_constant;
+use super::loop_analysis::counted_loop;
+use crate::miden::memory_layout::MidenMemoryLayout;
+
+/// Max trip count of a loop lowered to `repeat.N` (the assembler unrolls the `repeat` body)
+const MAX_REPEAT_COUNT: u32 = 64;
+
+pub struct WasmToMidenCFLoweringPass {
+    br_depth_address: MemAddress,
+}
+
+impl WasmToMidenCFLoweringPass {
+    pub fn new(br_depth_address: MemAddress) -> Self {
+        Self { br_depth_address }
+    }
+}
+
+impl Default for WasmToMidenCFLoweringPass {
+    fn default() -> Self {
+        Self::new(MidenMemoryLayout::default().br_depth_address)
+    }
+}
 
 impl Pass for WasmToMidenCFLoweringPass {
     fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
         let target = ConversionTarget::default();
         // TODO: set illegal ops
         let mut patterns = RewritePatternSet::default();
-        patterns.add(Box::<ControlFlowLowering>::default());
+        patterns.add(Box::new(ControlFlowLowering {
+            br_depth_address: u32::from(self.br_depth_address) as u64,
+        }));
         apply_partial_conversion(ctx, op, target, patterns)?;
         Ok(())
     }
 }
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "miden-lower-cf",
+        description: "Lower Wasm module and functions to Miden program and procedures",
+        constructor: || Box::<WasmToMidenCFLoweringPass>::default(),
+    }
+}
 /// Converts Wasm module into Miden program
-/// converting Wasm blocks/loops and branching ops into Miden functions
-#[derive(Default)]
-struct ControlFlowLowering;
+/// converting `ozk` blocks/loops and branching ops into Miden `while.true`/`repeat.N`/`if.true`
+struct ControlFlowLowering {
+    br_depth_address: u64,
+}
 
 impl RewritePattern for ControlFlowLowering {
     fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
@@ -67,46 +100,44 @@ impl RewritePattern for ControlFlowLowering {
             funcs.push(func_op);
         }
         let main_proc_op = miden::ProcOp::new_unlinked(ctx, "ozk_miden_main_proc");
-        let start_func_call_op =
-            miden::ExecOp::new_unlinked(ctx, module_op.get_start_func_sym(ctx));
-        start_func_call_op
-            .get_operation()
-            .insert_at_back(main_proc_op.get_entry_block(ctx), ctx);
+        if let Some(start_func_sym) = module_op.get_start_func_sym(ctx) {
+            let start_func_call_op = miden::ExecOp::new_unlinked(ctx, start_func_sym);
+            start_func_call_op
+                .get_operation()
+                .insert_at_back(main_proc_op.get_entry_block(ctx), ctx);
+        }
         let prog_op = miden::ProgramOp::new(ctx, main_proc_op);
+        let exports = module_op
+            .get_exports(ctx)
+            .into_iter()
+            .map(|(export_name, func_sym)| (export_name, String::from(func_sym.as_ref())))
+            .collect();
+        prog_op.set_exports(ctx, exports);
         // TODO: make a new pass for module->prog conversion
         // plus, handle there imports and all other module stuff
         for func_op in funcs {
             let root_proc_op = miden::ProcOp::new_unlinked(ctx, &func_op.get_symbol_name(ctx));
+            // the function parameters are the first locals
+            let num_locals =
+                func_op.get_type(ctx).get_inputs().len() + func_op.get_locals(ctx).len();
+            root_proc_op.set_num_locals(ctx, num_locals as u32);
             let root_proc_bb = root_proc_op.get_entry_block(ctx);
             prog_op.add_proc_op(ctx, root_proc_op);
-            let mut func_ops = Vec::new();
-            for op in func_op.op_iter(ctx) {
-                func_ops.push(op.deref(ctx).get_op(ctx));
+            let func_ops: Vec<Ptr<Operation>> = func_op.op_iter(ctx).collect();
+            // the branches (and the nested returns) to the function body leave
+            // the branch depth set to 1
+            let reset_br_depth = func_ops.iter().any(|op| {
+                !op.deref(ctx).get_op(ctx).is::<ozk::ReturnOp>() && branch_depth(ctx, *op).is_some()
+            });
+            for op in &func_ops {
+                op.unlink(ctx);
             }
-            for op in func_ops {
-                if let Some(block_op) = op.downcast_ref::<wasm::BlockOp>() {
-                    let proc_ops = convert_block_to_proc(ctx, block_op.into())?;
-                    block_op.get_operation().unlink(ctx);
-                    let callee_proc_op = proc_ops.first();
-                    let call_op = miden::ExecOp::new_unlinked(
-                        ctx,
-                        callee_proc_op.get_symbol_name(ctx).into(),
-                    );
-                    call_op.get_operation().insert_at_back(root_proc_bb, ctx);
-                    for proc_op in proc_ops {
-                        prog_op.add_proc_op(ctx, *proc_op);
-                    }
-                }
-                if op.downcast_ref::<wasm::ReturnOp>().is_some() {
-                    // return in the entry block means that the rest of the ops
-                    // are unreachable and can be removed
-                    rewriter.erase_op(ctx, op.get_operation())?;
-                    // TODO: erase the rest of the ops in this block
-                    break;
-                } else {
-                    op.get_operation().unlink(ctx);
-                    op.get_operation().insert_at_back(root_proc_bb, ctx);
-                };
+            let mut proc_ops = self.lower_ops(ctx, func_ops, 0)?;
+            if reset_br_depth {
+                proc_ops.extend(self.set_br_depth(ctx, 0));
+            }
+            for op in proc_ops {
+                op.insert_at_back(root_proc_bb, ctx);
             }
             rewriter.erase_op(ctx, func_op.get_operation())?;
         }
@@ -115,37 +146,250 @@ impl RewritePattern for ControlFlowLowering {
     }
 }
 
-#[derive(From)]
-enum WasmStructuredOp<'a> {
-    Block(&'a wasm::BlockOp),
-    Loop(&'a wasm::LoopOp),
-    // If(Box<wasm::ops::IfOp>),
+/// Structured control flow lowering.
+///
+/// The branch depth memory cell holds the number of the enclosing blocks/loops left to exit
+/// (0 means no branch is taken). `br n` sets it to `n + 1`, a nested `return` exits every
+/// enclosing block/loop and the function body. The ops following a possible branch are executed
+/// only if the branch depth is 0. The end of a block that can be branched to decrements
+/// the branch depth (if it's not 0). A loop that can be branched to becomes a `while.true`
+/// that starts the next iteration if the branch depth is 1 at the end of the body
+/// (the loop itself is the branch target).
+/// A loop with a constant trip count and no branches out of it (see
+/// [counted_loop](super::loop_analysis::counted_loop)) becomes a `repeat.N` without the exit check.
+///
+/// The values left on the stack below the branch operands are not dropped, i.e. the blocks and
+/// loops are expected to be branched out of with only their result values on the stack.
+impl ControlFlowLowering {
+    /// Lower the ops nested in `nesting` blocks/loops.
+    fn lower_ops(
+        &self,
+        ctx: &mut Context,
+        ops: Vec<Ptr<Operation>>,
+        nesting: u32,
+    ) -> Result<Vec<Ptr<Operation>>, anyhow::Error> {
+        let mut lowered_ops = Vec::new();
+        let mut ops_iter = ops.into_iter();
+        while let Some(op) = ops_iter.next() {
+            let opop = &op.deref(ctx).get_op(ctx);
+            let may_branch = if let Some(block_op) = opop.downcast_ref::<ozk::BlockOp>() {
+                check_no_params(ctx, block_op.get_type(ctx))?;
+                let body: Vec<Ptr<Operation>> = block_op.op_iter(ctx).collect();
+                let depth = max_branch_depth(ctx, &body);
+                for op in &body {
+                    op.unlink(ctx);
+                }
+                lowered_ops.extend(self.lower_ops(ctx, body, nesting + 1)?);
+                if depth.is_some() {
+                    // the block is exited
+                    lowered_ops.extend(self.decrement_br_depth(ctx));
+                }
+                escapes(depth)
+            } else if let Some(loop_op) = opop.downcast_ref::<ozk::LoopOp>() {
+                check_no_params(ctx, loop_op.get_type(ctx))?;
+                if let Some(counted_loop) =
+                    counted_loop(ctx, &lowered_ops, loop_op, MAX_REPEAT_COUNT)
+                {
+                    for op in counted_loop.exit_check {
+                        op.unlink(ctx);
+                    }
+                    let body: Vec<Ptr<Operation>> = loop_op.op_iter(ctx).collect();
+                    for op in &body {
+                        op.unlink(ctx);
+                    }
+                    let mut lowered_body = self.lower_ops(ctx, body, nesting + 1)?;
+                    // the new counter value
+                    lowered_body.push(miden::DropOp::new_unlinked(ctx).get_operation());
+                    let repeat_op = miden::RepeatOp::new_unlinked(ctx, counted_loop.trip_count);
+                    let repeat_block = repeat_op.get_block(ctx);
+                    for op in lowered_body {
+                        op.insert_at_back(repeat_block, ctx);
+                    }
+                    lowered_ops.push(repeat_op.get_operation());
+                    continue;
+                }
+                let body: Vec<Ptr<Operation>> = loop_op.op_iter(ctx).collect();
+                let depth = max_branch_depth(ctx, &body);
+                for op in &body {
+                    op.unlink(ctx);
+                }
+                let lowered_body = self.lower_ops(ctx, body, nesting + 1)?;
+                if depth.is_some() {
+                    lowered_ops.push(felt_constant(ctx, 1));
+                    let while_op = miden::WhileOp::new_unlinked(ctx);
+                    let while_block = while_op.get_block(ctx);
+                    for op in lowered_body {
+                        op.insert_at_back(while_block, ctx);
+                    }
+                    for op in self.loop_condition(ctx) {
+                        op.insert_at_back(while_block, ctx);
+                    }
+                    lowered_ops.push(while_op.get_operation());
+                } else {
+                    // nothing starts the next iteration
+                    lowered_ops.extend(lowered_body);
+                }
+                escapes(depth)
+            } else if let Some(br_op) = opop.downcast_ref::<ozk::BrOp>() {
+                let depth = br_op.get_relative_depth(ctx) as u64;
+                lowered_ops.extend(self.set_br_depth(ctx, depth + 1));
+                // the rest is unreachable
+                break;
+            } else if let Some(br_if_op) = opop.downcast_ref::<ozk::BrIfOp>() {
+                let depth = br_if_op.get_relative_depth(ctx) as u64;
+                // any non-zero i32 is true, `if.true` expects 0 or 1
+                lowered_ops.push(felt_constant(ctx, 0));
+                lowered_ops.push(miden::NeqOp::new_unlinked(ctx).get_operation());
+                let if_op = miden::IfOp::new_unlinked(ctx);
+                for op in self.set_br_depth(ctx, depth + 1) {
+                    op.insert_at_back(if_op.get_then_block(ctx), ctx);
+                }
+                lowered_ops.push(if_op.get_operation());
+                true
+            } else if opop.is::<ozk::ReturnOp>() {
+                if nesting > 0 {
+                    // branch to the function body
+                    lowered_ops.extend(self.set_br_depth(ctx, nesting as u64 + 1));
+                }
+                // return in the function body means that the rest of the ops
+                // are unreachable and can be removed
+                break;
+            } else {
+                lowered_ops.push(op);
+                false
+            };
+            if may_branch {
+                let rest: Vec<Ptr<Operation>> = ops_iter.by_ref().collect();
+                let lowered_rest = self.lower_ops(ctx, rest, nesting)?;
+                if !lowered_rest.is_empty() {
+                    lowered_ops.extend(self.if_br_depth_is_zero(ctx, lowered_rest));
+                }
+            }
+        }
+        Ok(lowered_ops)
+    }
+
+    /// Push the branch depth.
+    fn load_br_depth(&self, ctx: &mut Context) -> Vec<Ptr<Operation>> {
+        vec![
+            felt_constant(ctx, self.br_depth_address),
+            miden::MemLoadOp::new_unlinked(ctx).get_operation(),
+        ]
+    }
+
+    /// Pop the value and store it as the branch depth.
+    fn store_br_depth(&self, ctx: &mut Context) -> Vec<Ptr<Operation>> {
+        vec![
+            felt_constant(ctx, self.br_depth_address),
+            miden::MemStoreOp::new_unlinked(ctx).get_operation(),
+        ]
+    }
+
+    fn set_br_depth(&self, ctx: &mut Context, depth: u64) -> Vec<Ptr<Operation>> {
+        let mut ops = vec![felt_constant(ctx, depth)];
+        ops.extend(self.store_br_depth(ctx));
+        ops
+    }
+
+    /// Decrement the branch depth if it's not 0.
+    fn decrement_br_depth(&self, ctx: &mut Context) -> Vec<Ptr<Operation>> {
+        let mut ops = self.load_br_depth(ctx);
+        ops.push(felt_constant(ctx, 0));
+        ops.push(miden::NeqOp::new_unlinked(ctx).get_operation());
+        let if_op = miden::IfOp::new_unlinked(ctx);
+        let mut then_ops = self.load_br_depth(ctx);
+        then_ops.push(felt_constant(ctx, 1));
+        then_ops.push(miden::SubOp::new_unlinked(ctx).get_operation());
+        then_ops.extend(self.store_br_depth(ctx));
+        for op in then_ops {
+            op.insert_at_back(if_op.get_then_block(ctx), ctx);
+        }
+        ops.push(if_op.get_operation());
+        ops
+    }
+
+    /// The end of the loop body, pushes 1 to start the next iteration if the loop is
+    /// the branch target, otherwise exits the loop as a block.
+    fn loop_condition(&self, ctx: &mut Context) -> Vec<Ptr<Operation>> {
+        let mut ops = self.load_br_depth(ctx);
+        ops.push(felt_constant(ctx, 1));
+        ops.push(miden::EqOp::new_unlinked(ctx).get_operation());
+        let if_op = miden::IfOp::new_unlinked(ctx);
+        let mut then_ops = self.set_br_depth(ctx, 0);
+        then_ops.push(felt_constant(ctx, 1));
+        for op in then_ops {
+            op.insert_at_back(if_op.get_then_block(ctx), ctx);
+        }
+        let mut else_ops = self.decrement_br_depth(ctx);
+        else_ops.push(felt_constant(ctx, 0));
+        for op in else_ops {
+            op.insert_at_back(if_op.get_else_block(ctx), ctx);
+        }
+        ops.push(if_op.get_operation());
+        ops
+    }
+
+    /// Wrap the ops in `if.true` executed only if no branch is taken.
+    fn if_br_depth_is_zero(
+        &self,
+        ctx: &mut Context,
+        ops: Vec<Ptr<Operation>>,
+    ) -> Vec<Ptr<Operation>> {
+        let mut guard_ops = self.load_br_depth(ctx);
+        guard_ops.push(felt_constant(ctx, 0));
+        guard_ops.push(miden::EqOp::new_unlinked(ctx).get_operation());
+        let if_op = miden::IfOp::new_unlinked(ctx);
+        for op in ops {
+            op.insert_at_back(if_op.get_then_block(ctx), ctx);
+        }
+        guard_ops.push(if_op.get_operation());
+        guard_ops
+    }
+}
+
+/// The max relative depth of the branches in the ops that leave the ops
+/// (the nested returns leave every enclosing block/loop).
+/// Returns `None` if there are no such branches.
+pub(super) fn max_branch_depth(ctx: &Context, ops: &[Ptr<Operation>]) -> Option<u32> {
+    ops.iter().filter_map(|op| branch_depth(ctx, *op)).max()
+}
+
+/// The max relative depth of the branches in the op (including the op itself)
+/// that leave the op.
+fn branch_depth(ctx: &Context, op: Ptr<Operation>) -> Option<u32> {
+    let opop = &op.deref(ctx).get_op(ctx);
+    if let Some(block_op) = opop.downcast_ref::<ozk::BlockOp>() {
+        let body: Vec<Ptr<Operation>> = block_op.op_iter(ctx).collect();
+        max_branch_depth(ctx, &body).and_then(|depth| depth.checked_sub(1))
+    } else if let Some(loop_op) = opop.downcast_ref::<ozk::LoopOp>() {
+        let body: Vec<Ptr<Operation>> = loop_op.op_iter(ctx).collect();
+        max_branch_depth(ctx, &body).and_then(|depth| depth.checked_sub(1))
+    } else if let Some(br_op) = opop.downcast_ref::<ozk::BrOp>() {
+        Some(br_op.get_relative_depth(ctx))
+    } else if let Some(br_if_op) = opop.downcast_ref::<ozk::BrIfOp>() {
+        Some(br_if_op.get_relative_depth(ctx))
+    } else if opop.is::<ozk::ReturnOp>() {
+        Some(u32::MAX)
+    } else {
+        None
+    }
+}
+
+/// Returns true if the branch with the given max depth (see [max_branch_depth]) in the
+/// block/loop body leaves the enclosing block/loop as well.
+fn escapes(depth: Option<u32>) -> bool {
+    depth.map_or(false, |depth| depth >= 1)
 }
 
-fn convert_block_to_proc(
-    ctx: &mut Context,
-    _struct_op: WasmStructuredOp,
-) -> Result<NonEmptyVec<Box<miden::ProcOp>>, anyhow::Error> {
-    // TODO: check that all locals are converted to mem access
-    // Repeat this process for every block in the extracted functions recursively.
-    // TODO: expose the block label and get it here
-    let func_name = "block label"; // struct_op.get_symbol_name(ctx);
-    let proc_op = miden::ProcOp::new_unlinked(ctx, func_name);
-    let proc_ops = vec![Box::new(proc_op)];
-    // let mut block_ops = Vec::new();
-    // struct_op.op_iter(ctx).for_each(|op| {
-    //     if let Some(block_op) = op
-    //         .deref(ctx)
-    //         .get_op(ctx)
-    //         .downcast_ref::<wasm::ops::BlockOp>()
-    //     {
-    //         block_ops.push(block_op);
-    //     }
-    // });
-    // proc_ops.push(Box::new(proc_op));
-    // TODO: swap the func operation from WebAssembly into procedure operation in Miden.
-    match NonEmptyVec::from_vec(proc_ops) {
-        Ok(ops) => Ok(ops),
-        Err(_) => todo!("error"),
+fn check_no_params(ctx: &Context, ty: Ptr<TypeObj>) -> Result<(), anyhow::Error> {
+    let has_params = ty
+        .deref(ctx)
+        .downcast_ref::<FunctionType>()
+        .map_or(false, |func_ty| !func_ty.get_inputs().is_empty());
+    if has_params {
+        return Err(anyhow::anyhow!(
+            "blocks and loops with parameters are not supported"
+        ));
     }
+    Ok(())
 }
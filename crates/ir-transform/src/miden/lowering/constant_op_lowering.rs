@@ -1,7 +1,7 @@
 use anyhow::anyhow;
 use miden::attributes::FieldElemAttr;
 use ozk_miden_dialect as miden;
-use ozk_wasm_dialect as wasm;
+use ozk_ozk_dialect as ozk;
 use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::dialects::builtin::attributes::IntegerAttr;
@@ -15,11 +15,8 @@ pub struct ConstantOpLowering {}
 
 impl RewritePattern for ConstantOpLowering {
     fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
-        Ok(op
-            .deref(ctx)
-            .get_op(ctx)
-            .downcast_ref::<wasm::ops::ConstantOp>()
-            .is_some())
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.is::<ozk::ops::ConstantOp>())
     }
 
     #[allow(clippy::unwrap_used)]
@@ -30,15 +27,16 @@ impl RewritePattern for ConstantOpLowering {
         rewriter: &mut dyn PatternRewriter,
     ) -> Result<(), anyhow::Error> {
         let opop = &op.deref(ctx).get_op(ctx);
-        if let Some(const_op) = opop.downcast_ref::<wasm::ops::ConstantOp>() {
-            let value = const_op.get_value(ctx);
-            if let Ok(value_attr) = value.downcast::<IntegerAttr>() {
-                let value = FieldElemAttr::from_integer_attr(ctx, *value_attr)?;
-                let const_op = miden::ops::ConstantOp::new_unlinked(ctx, value);
-                rewriter.replace_op_with(ctx, op, const_op.get_operation())?;
-            } else {
-                return Err(anyhow!("only integer constants are supported"));
-            }
+        let Some(const_op) = opop.downcast_ref::<ozk::ops::ConstantOp>() else {
+            return Ok(());
+        };
+        let value = const_op.get_value(ctx);
+        if let Ok(value_attr) = value.downcast::<IntegerAttr>() {
+            let value = FieldElemAttr::from_integer_attr(ctx, *value_attr)?;
+            let const_op = miden::ops::ConstantOp::new_unlinked(ctx, value);
+            rewriter.replace_op_with(ctx, op, const_op.get_operation())?;
+        } else {
+            return Err(anyhow!("only integer constants are supported"));
         }
         Ok(())
     }
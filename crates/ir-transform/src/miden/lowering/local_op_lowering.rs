@@ -0,0 +1,63 @@
+use ozk_miden_dialect::ops as miden;
+use ozk_ozk_dialect::ops as ozk;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+
+enum LocalAccess {
+    Get(u32),
+    Set(u32),
+    Tee(u32),
+}
+
+fn get_local_access(ctx: &Context, op: Ptr<Operation>) -> Option<LocalAccess> {
+    let opop = op.deref(ctx).get_op(ctx);
+    if let Some(local_get_op) = opop.downcast_ref::<ozk::LocalGetOp>() {
+        Some(LocalAccess::Get(local_get_op.get_index(ctx)))
+    } else if let Some(local_set_op) = opop.downcast_ref::<ozk::LocalSetOp>() {
+        Some(LocalAccess::Set(local_set_op.get_index(ctx)))
+    } else if let Some(local_tee_op) = opop.downcast_ref::<ozk::LocalTeeOp>() {
+        Some(LocalAccess::Tee(local_tee_op.get_index(ctx)))
+    } else {
+        None
+    }
+}
+
+/// Lowers `ozk` locals access to the Miden procedure locals.
+/// The local index is used as the procedure local index (the function parameters are the
+/// first locals, see `WasmExplicitFuncArgsPass`).
+#[derive(Default)]
+pub struct LocalOpLowering {}
+
+impl RewritePattern for LocalOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(get_local_access(ctx, op).is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let miden_op = match get_local_access(ctx, op) {
+            Some(LocalAccess::Get(index)) => {
+                miden::LocLoadOp::new_unlinked(ctx, index).get_operation()
+            }
+            Some(LocalAccess::Set(index)) => {
+                miden::LocStoreOp::new_unlinked(ctx, index).get_operation()
+            }
+            Some(LocalAccess::Tee(index)) => {
+                let dup_op = miden::DupOp::new_unlinked(ctx, 0).get_operation();
+                rewriter.insert_before(ctx, dup_op)?;
+                miden::LocStoreOp::new_unlinked(ctx, index).get_operation()
+            }
+            None => return Ok(()),
+        };
+        rewriter.replace_op_with(ctx, op, miden_op)?;
+        Ok(())
+    }
+}
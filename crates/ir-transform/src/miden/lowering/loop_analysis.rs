@@ -0,0 +1,386 @@
+//! `ozk` loop analysis for picking the native Miden loop.
+
+use ozk_miden_dialect::ops as miden;
+use ozk_ozk_dialect::attributes::apint_to_i32;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::i32_type;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::attr_interfaces::TypedAttrInterface;
+use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::r#type::TypeObj;
+
+use super::cf_lowering::max_branch_depth;
+
+/// A loop with the constant trip count:
+/// ```wat
+/// i32.const <init>
+/// local.set $i
+/// ...             ;; $i is not written
+/// loop
+///   ...           ;; no branches out of the loop and calls, $i is not written
+///   local.get $i
+///   i32.const <step>
+///   i32.add
+///   local.tee $i  ;; or `local.set $i` and `local.get $i`
+///   i32.const <end>
+///   i32.ne
+///   br_if 0
+/// end
+/// ```
+pub struct CountedLoop {
+    pub trip_count: u32,
+    /// The exit check ops (`i32.const <end>`, `i32.ne` and `br_if 0`) at the end of the body.
+    /// The body without them leaves the new counter value on the stack.
+    pub exit_check: Vec<Ptr<Operation>>,
+}
+
+/// Returns the counted loop if the loop runs a constant number of iterations (up to
+/// `max_trip_count`). `preceding_ops` are the ops before the loop in the same block.
+pub fn counted_loop(
+    ctx: &mut Context,
+    preceding_ops: &[Ptr<Operation>],
+    loop_op: &ozk::LoopOp,
+    max_trip_count: u32,
+) -> Option<CountedLoop> {
+    let i32_ty = i32_type(ctx);
+    let ctx = &*ctx;
+    let no_params_and_results = loop_op
+        .get_type(ctx)
+        .deref(ctx)
+        .downcast_ref::<FunctionType>()
+        .map_or(false, |ty| {
+            ty.get_inputs().is_empty() && ty.get_results().is_empty()
+        });
+    if !no_params_and_results {
+        return None;
+    }
+    let body: Vec<Ptr<Operation>> = loop_op.op_iter(ctx).collect();
+    let (rest, update) = split_counter_update(ctx, i32_ty, &body)?;
+    if max_branch_depth(ctx, rest).is_some()
+        || rest.iter().any(|op| writes_local(ctx, *op, update.counter))
+        || rest.iter().any(|op| has_call(ctx, *op))
+    {
+        return None;
+    }
+    let init = counter_init(ctx, i32_ty, preceding_ops, update.counter)?;
+    let distance = update.end.wrapping_sub(init) as u32;
+    let step = update.step as u32;
+    if step == 0 || distance == 0 || distance % step != 0 {
+        return None;
+    }
+    let trip_count = distance / step;
+    (trip_count <= max_trip_count).then_some(CountedLoop {
+        trip_count,
+        exit_check: update.exit_check,
+    })
+}
+
+struct CounterUpdate {
+    counter: u32,
+    step: i32,
+    end: i32,
+    exit_check: Vec<Ptr<Operation>>,
+}
+
+/// Split the loop body into the ops before the counter update and the counter update.
+fn split_counter_update<'a>(
+    ctx: &Context,
+    i32_ty: Ptr<TypeObj>,
+    body: &'a [Ptr<Operation>],
+) -> Option<(&'a [Ptr<Operation>], CounterUpdate)> {
+    let [rest @ .., end_op, ne_op, br_if_op] = body else {
+        return None;
+    };
+    let is_br_if_0 = br_if_op
+        .deref(ctx)
+        .get_op(ctx)
+        .downcast_ref::<ozk::BrIfOp>()
+        .map_or(false, |br_if_op| br_if_op.get_relative_depth(ctx) == 0);
+    let is_i32_ne = ne_op
+        .deref(ctx)
+        .get_op(ctx)
+        .downcast_ref::<ozk::NeOp>()
+        .map_or(false, |ne_op| ne_op.get_type(ctx) == i32_ty);
+    if !is_br_if_0 || !is_i32_ne {
+        return None;
+    }
+    let end = i32_constant(ctx, i32_ty, *end_op)?;
+    let (rest, counter) = match rest {
+        [rest @ .., tee_op] if local_tee_index(ctx, *tee_op).is_some() => {
+            (rest, local_tee_index(ctx, *tee_op)?)
+        }
+        [rest @ .., set_op, get_op] => {
+            let counter = local_set_index(ctx, *set_op)?;
+            if local_get_index(ctx, *get_op) != Some(counter) {
+                return None;
+            }
+            (rest, counter)
+        }
+        _ => return None,
+    };
+    let [rest @ .., get_op, step_op, add_op] = rest else {
+        return None;
+    };
+    let is_i32_add = add_op
+        .deref(ctx)
+        .get_op(ctx)
+        .downcast_ref::<ozk::WrappingAddOp>()
+        .map_or(false, |add_op| add_op.get_type(ctx) == i32_ty);
+    if local_get_index(ctx, *get_op) != Some(counter) || !is_i32_add {
+        return None;
+    }
+    let step = i32_constant(ctx, i32_ty, *step_op)?;
+    Some((
+        rest,
+        CounterUpdate {
+            counter,
+            step,
+            end,
+            exit_check: vec![*end_op, *ne_op, *br_if_op],
+        },
+    ))
+}
+
+/// The constant set to the counter before the loop.
+fn counter_init(
+    ctx: &Context,
+    i32_ty: Ptr<TypeObj>,
+    preceding_ops: &[Ptr<Operation>],
+    counter: u32,
+) -> Option<i32> {
+    let mut ops = preceding_ops.iter().rev();
+    while let Some(op) = ops.next() {
+        if local_set_index(ctx, *op) == Some(counter) {
+            return i32_constant(ctx, i32_ty, *ops.next()?);
+        }
+        if writes_local(ctx, *op, counter) {
+            return None;
+        }
+    }
+    None
+}
+
+/// Returns true if the op (or a nested op) writes the local.
+fn writes_local(ctx: &Context, op: Ptr<Operation>, index: u32) -> bool {
+    let mut found = false;
+    op.walk(ctx, WalkOrder::PreOrder, &mut |op| {
+        if local_set_index(ctx, op) == Some(index) || local_tee_index(ctx, op) == Some(index) {
+            found = true;
+            WalkResult::Interrupt
+        } else {
+            WalkResult::Advance
+        }
+    });
+    found
+}
+
+/// Returns true if the op (or a nested op) is a call. The call may be recursive and
+/// the recursion lowering only splits `while.true` and `if.true` bodies.
+fn has_call(ctx: &Context, op: Ptr<Operation>) -> bool {
+    let mut found = false;
+    op.walk_only::<miden::ExecOp>(ctx, WalkOrder::PreOrder, &mut |_| {
+        found = true;
+        WalkResult::Interrupt
+    });
+    found
+}
+
+fn i32_constant(ctx: &Context, i32_ty: Ptr<TypeObj>, op: Ptr<Operation>) -> Option<i32> {
+    let opop = op.deref(ctx).get_op(ctx);
+    let value = opop.downcast_ref::<ozk::ConstantOp>()?.get_value(ctx);
+    let int_attr = value.downcast_ref::<IntegerAttr>()?;
+    (int_attr.get_type() == i32_ty).then(|| apint_to_i32(int_attr.clone().into()))
+}
+
+fn local_get_index(ctx: &Context, op: Ptr<Operation>) -> Option<u32> {
+    let opop = op.deref(ctx).get_op(ctx);
+    opop.downcast_ref::<ozk::LocalGetOp>()
+        .map(|local_get_op| local_get_op.get_index(ctx))
+}
+
+fn local_set_index(ctx: &Context, op: Ptr<Operation>) -> Option<u32> {
+    let opop = op.deref(ctx).get_op(ctx);
+    opop.downcast_ref::<ozk::LocalSetOp>()
+        .map(|local_set_op| local_set_op.get_index(ctx))
+}
+
+fn local_tee_index(ctx: &Context, op: Ptr<Operation>) -> Option<u32> {
+    let opop = op.deref(ctx).get_op(ctx);
+    opop.downcast_ref::<ozk::LocalTeeOp>()
+        .map(|local_tee_op| local_tee_op.get_index(ctx))
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+    use pliron::linked_list::ContainsLinkedList;
+    use pliron::op::Op;
+
+    use ozk_wasm_dialect::ops as wasm;
+
+    use super::*;
+    use crate::ozk::lowering::WasmToOzkLoweringPass;
+    use crate::tests_util::as_wasm_module;
+    use crate::tests_util::run_wasm_passes;
+
+    /// The trip count of the first top-level loop in the first function.
+    fn trip_count(wat: &str) -> Option<u32> {
+        let mut ctx = Context::default();
+        let module_op =
+            run_wasm_passes(&mut ctx, wat, vec![Box::<WasmToOzkLoweringPass>::default()]);
+        let module_op = as_wasm_module(&ctx, module_op);
+        let func_op = module_op
+            .get_body(&ctx, 0)
+            .deref(&ctx)
+            .iter(&ctx)
+            .find_map(|op| op.deref(&ctx).get_op(&ctx).downcast::<wasm::FuncOp>().ok())
+            .unwrap();
+        let func_ops: Vec<Ptr<Operation>> = func_op.op_iter(&ctx).collect();
+        let (loop_pos, loop_op) = func_ops
+            .iter()
+            .enumerate()
+            .find_map(|(pos, op)| {
+                op.deref(&ctx)
+                    .get_op(&ctx)
+                    .downcast::<ozk::LoopOp>()
+                    .ok()
+                    .map(|loop_op| (pos, *loop_op))
+            })
+            .unwrap();
+        counted_loop(&mut ctx, &func_ops[..loop_pos], &loop_op, 64)
+            .map(|counted_loop| counted_loop.trip_count)
+    }
+
+    #[test]
+    fn local_tee_counter() {
+        let wat = r#"
+(module
+  (func $f (local i32 i32)
+    i32.const 0
+    local.set 0
+    loop
+      local.get 1
+      local.get 0
+      i32.add
+      local.set 1
+      local.get 0
+      i32.const 1
+      i32.add
+      local.tee 0
+      i32.const 10
+      i32.ne
+      br_if 0
+    end)
+  (start $f))
+"#;
+        assert_eq!(trip_count(wat), Some(10));
+    }
+
+    #[test]
+    fn local_set_counter_with_step() {
+        let wat = r#"
+(module
+  (func $f (local i32)
+    i32.const 3
+    local.set 0
+    loop
+      local.get 0
+      i32.const 2
+      i32.add
+      local.set 0
+      local.get 0
+      i32.const 11
+      i32.ne
+      br_if 0
+    end)
+  (start $f))
+"#;
+        assert_eq!(trip_count(wat), Some(4));
+    }
+
+    #[test]
+    fn not_counted() {
+        // the trip count is above the limit
+        let too_long = r#"
+(module
+  (func $f (local i32)
+    i32.const 0
+    local.set 0
+    loop
+      local.get 0
+      i32.const 1
+      i32.add
+      local.tee 0
+      i32.const 1000
+      i32.ne
+      br_if 0
+    end)
+  (start $f))
+"#;
+        assert_eq!(trip_count(too_long), None);
+        // the counter is written in the body
+        let counter_written = r#"
+(module
+  (func $f (local i32)
+    i32.const 0
+    local.set 0
+    loop
+      i32.const 5
+      local.set 0
+      local.get 0
+      i32.const 1
+      i32.add
+      local.tee 0
+      i32.const 10
+      i32.ne
+      br_if 0
+    end)
+  (start $f))
+"#;
+        assert_eq!(trip_count(counter_written), None);
+        // the step does not reach the end
+        let overshoot = r#"
+(module
+  (func $f (local i32)
+    i32.const 0
+    local.set 0
+    loop
+      local.get 0
+      i32.const 3
+      i32.add
+      local.tee 0
+      i32.const 10
+      i32.ne
+      br_if 0
+    end)
+  (start $f))
+"#;
+        assert_eq!(trip_count(overshoot), None);
+        // the body branches out of the loop
+        let early_exit = r#"
+(module
+  (func $f (local i32)
+    i32.const 0
+    local.set 0
+    loop
+      i32.const 1
+      br_if 1
+      local.get 0
+      i32.const 1
+      i32.add
+      local.tee 0
+      i32.const 10
+      i32.ne
+      br_if 0
+    end)
+  (start $f))
+"#;
+        assert_eq!(trip_count(early_exit), None);
+    }
+}
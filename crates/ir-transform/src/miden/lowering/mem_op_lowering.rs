@@ -0,0 +1,345 @@
+//! Wasm linear memory emulation on top of the Miden word-addressed memory.
+//!
+//! The Wasm byte address `a` is mapped to the first element of the Miden memory word at
+//! `a >> 2`, which holds 4 bytes as a u32 in little-endian order (the byte `a` is the bits
+//! `(a & 3) * 8..(a & 3) * 8 + 8`). The remaining 3 elements of the word are unused.
+//!
+//! The accesses that could span two Miden words are split. The `align` hint of the access
+//! is trusted to pick the cheaper sequence when the access cannot span two words, i.e.
+//! a misaligned access with the alignment hint above its actual alignment is undefined.
+
+use anyhow::anyhow;
+use miden::ops::AddOp;
+use miden::ops::DupOp;
+use miden::ops::MemLoadOp;
+use miden::ops::MemStoreOp;
+use miden::ops::MovDnOp;
+use miden::ops::MovUpOp;
+use miden::ops::SubOp;
+use miden::ops::SwapOp;
+use miden::ops::U32CheckedAndOp;
+use miden::ops::U32CheckedNotOp;
+use miden::ops::U32CheckedOrOp;
+use miden::ops::U32CheckedShlOp;
+use miden::ops::U32CheckedShrOp;
+use miden::ops::U32CheckedXorOp;
+use miden::ops::U32WrappingAddOp;
+use miden::ops::U32WrappingMulOp;
+use miden::ops::U32WrappingSubOp;
+use ozk_miden_dialect as miden;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::ops::MemArg;
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+
+use super::arith_op_lowering::felt_constant;
+
+/// The alignment (the exponent of 2) that guarantees the 4 byte access does not span
+/// two Miden words.
+const WORD_ALIGN: u32 = 2;
+
+enum MemOp {
+    Load {
+        ty: MemAccessOpValueType,
+        size: u32,
+        signed: bool,
+        memarg: MemArg,
+    },
+    Store {
+        ty: MemAccessOpValueType,
+        size: u32,
+        memarg: MemArg,
+    },
+}
+
+fn get_mem_op(ctx: &Context, op: Ptr<Operation>) -> Option<MemOp> {
+    let opop = op.deref(ctx).get_op(ctx);
+    if let Some(load_op) = opop.downcast_ref::<ozk::LoadOp>() {
+        Some(MemOp::Load {
+            ty: MemAccessOpValueType::from_type(ctx, load_op.get_type(ctx))?,
+            size: load_op.get_size(ctx),
+            signed: load_op.is_signed(ctx),
+            memarg: load_op.get_memarg(ctx),
+        })
+    } else if let Some(store_op) = opop.downcast_ref::<ozk::StoreOp>() {
+        Some(MemOp::Store {
+            ty: MemAccessOpValueType::from_type(ctx, store_op.get_type(ctx))?,
+            size: store_op.get_size(ctx),
+            memarg: store_op.get_memarg(ctx),
+        })
+    } else {
+        None
+    }
+}
+
+/// Lowers `ozk` `load` and `store` ops (including the sub-word and unaligned ones)
+/// to the Miden memory access.
+#[derive(Default)]
+pub struct MemOpLowering {}
+
+impl RewritePattern for MemOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(get_mem_op(ctx, op).is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let miden_ops = match get_mem_op(ctx, op) {
+            Some(MemOp::Load {
+                ty,
+                size,
+                signed,
+                memarg,
+            }) => {
+                // stack: [a]
+                let mut ops = add_offset(ctx, memarg.offset);
+                ops.extend(lower_load(ctx, ty, size, signed, memarg.align)?);
+                ops
+            }
+            Some(MemOp::Store { ty, size, memarg }) => {
+                // stack: [v, a]
+                let mut ops = Vec::new();
+                if memarg.offset != 0 {
+                    ops.push(SwapOp::new_unlinked(ctx, 1).get_operation());
+                    ops.extend(add_offset(ctx, memarg.offset));
+                    ops.push(SwapOp::new_unlinked(ctx, 1).get_operation());
+                }
+                ops.extend(lower_store(ctx, ty, size, memarg.align)?);
+                ops
+            }
+            None => return Ok(()),
+        };
+        let Some((last_op, ops)) = miden_ops.split_last() else {
+            return Err(anyhow!("empty lowering"));
+        };
+        for miden_op in ops {
+            rewriter.insert_before(ctx, *miden_op)?;
+        }
+        rewriter.replace_op_with(ctx, op, *last_op)?;
+        Ok(())
+    }
+}
+
+/// Add the constant offset to the address on top of the stack.
+fn add_offset(ctx: &mut Context, offset: u32) -> Vec<Ptr<Operation>> {
+    if offset == 0 {
+        return Vec::new();
+    }
+    vec![
+        felt_constant(ctx, offset as u64),
+        U32WrappingAddOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// The mask of the lowest `size` bytes.
+fn bytes_mask(size: u32) -> u64 {
+    (1u64 << (size * 8)) - 1
+}
+
+/// Returns true if the access of `size` bytes with the `align` hint cannot span two Miden words.
+fn is_within_word(size: u32, align: u32) -> bool {
+    size == 1 || (size == 2 && align >= 1) || (size == 4 && align >= WORD_ALIGN)
+}
+
+/// Lower the load of `size` bytes. The address is on top of the stack.
+fn lower_load(
+    ctx: &mut Context,
+    ty: MemAccessOpValueType,
+    size: u32,
+    signed: bool,
+    align: u32,
+) -> Result<Vec<Ptr<Operation>>, anyhow::Error> {
+    check_i32(ty)?;
+    let mut ops = load_u32(ctx, size, align);
+    if signed && size < 4 {
+        ops.extend(sign_extend(ctx, size));
+    }
+    Ok(ops)
+}
+
+/// Load `size` (up to 4) bytes as u32. Stack: [a] -> [v]
+fn load_u32(ctx: &mut Context, size: u32, align: u32) -> Vec<Ptr<Operation>> {
+    let mut ops = if size == 4 && align >= WORD_ALIGN {
+        vec![
+            felt_constant(ctx, 2),
+            U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+            MemLoadOp::new_unlinked(ctx).get_operation(),
+        ]
+    } else if is_within_word(size, align) {
+        let mut ops = byte_shift_and_word_address(ctx);
+        ops.extend([
+            // [m, s]
+            MemLoadOp::new_unlinked(ctx).get_operation(),
+            // [m >> s]
+            SwapOp::new_unlinked(ctx, 1).get_operation(),
+            U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+        ]);
+        ops
+    } else {
+        let mut ops = byte_shift_and_word_address(ctx);
+        ops.extend([
+            // [m0 >> s, w, s]
+            DupOp::new_unlinked(ctx, 0).get_operation(),
+            MemLoadOp::new_unlinked(ctx).get_operation(),
+            DupOp::new_unlinked(ctx, 2).get_operation(),
+            U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+            // [m1, m0 >> s, s]
+            SwapOp::new_unlinked(ctx, 1).get_operation(),
+            felt_constant(ctx, 1),
+            AddOp::new_unlinked(ctx).get_operation(),
+            MemLoadOp::new_unlinked(ctx).get_operation(),
+            // [24 - s, m1, m0 >> s]
+            felt_constant(ctx, 24),
+            MovUpOp::new_unlinked(ctx, 3).get_operation(),
+            SubOp::new_unlinked(ctx).get_operation(),
+            // [m1 << (32 - s), m0 >> s] (split in two shifts since the shift by 32 is invalid)
+            U32CheckedShlOp::new_unlinked(ctx).get_operation(),
+            felt_constant(ctx, 8),
+            U32CheckedShlOp::new_unlinked(ctx).get_operation(),
+            U32CheckedOrOp::new_unlinked(ctx).get_operation(),
+        ]);
+        ops
+    };
+    if size < 4 {
+        ops.extend([
+            felt_constant(ctx, bytes_mask(size)),
+            U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+        ]);
+    }
+    ops
+}
+
+/// Stack: [a] -> [w, s], where `w` is the Miden word address and `s` is the bit offset
+/// of the byte `a` in the word.
+fn byte_shift_and_word_address(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    vec![
+        // [s = (a & 3) * 8, a]
+        DupOp::new_unlinked(ctx, 0).get_operation(),
+        felt_constant(ctx, 3),
+        U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+        felt_constant(ctx, 8),
+        U32WrappingMulOp::new_unlinked(ctx).get_operation(),
+        // [w = a >> 2, s]
+        SwapOp::new_unlinked(ctx, 1).get_operation(),
+        felt_constant(ctx, 2),
+        U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Sign-extend the `size` bytes value to i32: (v ^ m) - m, where m is the sign bit.
+fn sign_extend(ctx: &mut Context, size: u32) -> Vec<Ptr<Operation>> {
+    let sign_bit = 1u64 << (size * 8 - 1);
+    vec![
+        felt_constant(ctx, sign_bit),
+        U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+        felt_constant(ctx, sign_bit),
+        U32WrappingSubOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Lower the store of the lowest `size` bytes. Stack: [v, a] -> []
+fn lower_store(
+    ctx: &mut Context,
+    ty: MemAccessOpValueType,
+    size: u32,
+    align: u32,
+) -> Result<Vec<Ptr<Operation>>, anyhow::Error> {
+    check_i32(ty)?;
+    Ok(store_u32(ctx, size, align))
+}
+
+/// The i64 values are expected to be split into i32 limbs (`ozk-legalize-i64`) beforehand.
+fn check_i32(ty: MemAccessOpValueType) -> Result<(), anyhow::Error> {
+    match ty {
+        MemAccessOpValueType::I32 => Ok(()),
+        MemAccessOpValueType::I64 => Err(anyhow!(
+            "only 32-bit memory access is supported, legalize i64 with ozk-legalize-i64 first"
+        )),
+    }
+}
+
+/// Store the lowest `size` (up to 4) bytes of u32. Stack: [v, a] -> []
+fn store_u32(ctx: &mut Context, size: u32, align: u32) -> Vec<Ptr<Operation>> {
+    if size == 4 && align >= WORD_ALIGN {
+        vec![
+            SwapOp::new_unlinked(ctx, 1).get_operation(),
+            felt_constant(ctx, 2),
+            U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+            MemStoreOp::new_unlinked(ctx).get_operation(),
+        ]
+    } else if is_within_word(size, align) {
+        store_within_word(ctx, size)
+    } else {
+        // store byte by byte
+        let mut ops = Vec::new();
+        for i in 0..size {
+            let is_last = i + 1 == size;
+            if !is_last {
+                // [v, a, v, a]
+                ops.extend([
+                    DupOp::new_unlinked(ctx, 1).get_operation(),
+                    DupOp::new_unlinked(ctx, 1).get_operation(),
+                ]);
+            }
+            if i > 0 {
+                // [v >> 8i, a + i, ...]
+                ops.extend([
+                    felt_constant(ctx, (i * 8) as u64),
+                    U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+                    SwapOp::new_unlinked(ctx, 1).get_operation(),
+                ]);
+                ops.extend(add_offset(ctx, i));
+                ops.push(SwapOp::new_unlinked(ctx, 1).get_operation());
+            }
+            ops.extend(store_within_word(ctx, 1));
+        }
+        ops
+    }
+}
+
+/// Store the lowest `size` bytes into a single Miden word, keeping the rest of its bytes.
+/// Stack: [v, a] -> []
+fn store_within_word(ctx: &mut Context, size: u32) -> Vec<Ptr<Operation>> {
+    let mask = bytes_mask(size);
+    vec![
+        // [v & mask, a]
+        felt_constant(ctx, mask),
+        U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+        // [s = (a & 3) * 8, v & mask, a]
+        DupOp::new_unlinked(ctx, 1).get_operation(),
+        felt_constant(ctx, 3),
+        U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+        felt_constant(ctx, 8),
+        U32WrappingMulOp::new_unlinked(ctx).get_operation(),
+        // [(v & mask) << s, a, s]
+        DupOp::new_unlinked(ctx, 0).get_operation(),
+        MovDnOp::new_unlinked(ctx, 3).get_operation(),
+        U32CheckedShlOp::new_unlinked(ctx).get_operation(),
+        // [m, (v & mask) << s, s, w = a >> 2]
+        SwapOp::new_unlinked(ctx, 1).get_operation(),
+        felt_constant(ctx, 2),
+        U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+        DupOp::new_unlinked(ctx, 0).get_operation(),
+        MovDnOp::new_unlinked(ctx, 3).get_operation(),
+        MemLoadOp::new_unlinked(ctx).get_operation(),
+        // [m & !(mask << s), (v & mask) << s, w]
+        felt_constant(ctx, mask),
+        MovUpOp::new_unlinked(ctx, 3).get_operation(),
+        U32CheckedShlOp::new_unlinked(ctx).get_operation(),
+        U32CheckedNotOp::new_unlinked(ctx).get_operation(),
+        U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+        // [w, m']
+        U32CheckedOrOp::new_unlinked(ctx).get_operation(),
+        SwapOp::new_unlinked(ctx, 1).get_operation(),
+        MemStoreOp::new_unlinked(ctx).get_operation(),
+    ]
+}
@@ -0,0 +1,65 @@
+use ozk_miden_dialect::ops as miden;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::ord_n::Ord16;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+
+enum StackOp {
+    Swap(Ord16),
+    MovUp(Ord16),
+    MovDn(Ord16),
+    Drop,
+}
+
+fn get_stack_op(ctx: &Context, op: Ptr<Operation>) -> Option<StackOp> {
+    let opop = op.deref(ctx).get_op(ctx);
+    if let Some(swap_op) = opop.downcast_ref::<ozk::SwapOp>() {
+        Some(StackOp::Swap(swap_op.get_index(ctx)))
+    } else if let Some(movup_op) = opop.downcast_ref::<ozk::MovUpOp>() {
+        Some(StackOp::MovUp(movup_op.get_index(ctx)))
+    } else if let Some(movdn_op) = opop.downcast_ref::<ozk::MovDnOp>() {
+        Some(StackOp::MovDn(movdn_op.get_index(ctx)))
+    } else if opop.is::<ozk::DropOp>() {
+        Some(StackOp::Drop)
+    } else {
+        None
+    }
+}
+
+/// Lowers the OZK stack manipulation ops to their Miden counterparts.
+#[derive(Default)]
+pub struct StackOpLowering {}
+
+impl RewritePattern for StackOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(get_stack_op(ctx, op).is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let index = |ord: Ord16| u32::from(ord) as u8;
+        let miden_op = match get_stack_op(ctx, op) {
+            Some(StackOp::Swap(ord)) => {
+                miden::SwapOp::new_unlinked(ctx, index(ord)).get_operation()
+            }
+            Some(StackOp::MovUp(ord)) => {
+                miden::MovUpOp::new_unlinked(ctx, index(ord)).get_operation()
+            }
+            Some(StackOp::MovDn(ord)) => {
+                miden::MovDnOp::new_unlinked(ctx, index(ord)).get_operation()
+            }
+            Some(StackOp::Drop) => miden::DropOp::new_unlinked(ctx).get_operation(),
+            None => return Ok(()),
+        };
+        rewriter.replace_op_with(ctx, op, miden_op)?;
+        Ok(())
+    }
+}
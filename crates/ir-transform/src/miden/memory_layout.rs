@@ -0,0 +1,57 @@
+//! Miden memory layout shared by the Miden passes and the Miden codegen.
+
+use ozk_wasm_dialect::types::MemAddress;
+
+/// Miden memory layout.
+/// Addresses start from the max and decrease as new values are stored.
+/// Accomodating the space in the end of the available memory.
+///
+/// The Wasm linear memory (byte-addressed) is mapped onto the Miden memory words by
+/// the byte address divided by 4, i.e. it occupies the words below `2^30`. The globals and
+/// the locals frames are accessed as Wasm memory as well, and the rest of the regions are raw
+/// Miden word addresses.
+pub struct MidenMemoryLayout {
+    /// The address of the first public input. Public inputs are saved from the stack on program launch.
+    pub pub_inputs_start_address: i32,
+    /// The address of the first public output. Public outputs are put on the stack when program finishes.
+    pub pub_outputs_start_address: i32,
+    /// The address of the first global variable. Global variables are stored in memory according to their index.
+    pub globals_start_address: MemAddress,
+    /// The top of the frames (Wasm memory, below the globals) of the locals spilled by
+    /// `wasm-locals-to-mem`. Frames grow down.
+    pub locals_frames_start_address: MemAddress,
+    /// The address of the branch depth used by the structured control flow lowering.
+    pub br_depth_address: MemAddress,
+    /// The address of the frame pointer of the recursive procedures.
+    pub frame_pointer_address: MemAddress,
+    /// The top of the frames of the recursive procedures. Frames grow down.
+    pub recursion_frames_start_address: MemAddress,
+}
+
+impl Default for MidenMemoryLayout {
+    fn default() -> Self {
+        let max_public_inputs: u32 = 1024;
+        let max_public_outputs: u32 = 1024;
+        let max_globals: u32 = 1024;
+        let inputs_offset: u32 = 0;
+        let i64_size: u32 = 8;
+        let outputs_offset: u32 = max_public_inputs * i64_size;
+        let globals_offset: u32 = outputs_offset + max_public_outputs * i64_size;
+        let br_depth_offset: u32 = globals_offset + max_globals * i64_size;
+        let frame_pointer_offset: u32 = br_depth_offset + i64_size;
+        let recursion_frames_offset: u32 = frame_pointer_offset + i64_size;
+        // aligned to 8 bytes so the i64 globals do not span the Miden words
+        let globals_start_address = ((i32::MAX - globals_offset as i32) as u32) & !7;
+        Self {
+            pub_inputs_start_address: i32::MAX - inputs_offset as i32,
+            pub_outputs_start_address: i32::MAX - outputs_offset as i32,
+            globals_start_address: globals_start_address.into(),
+            // the globals are stored down from their start address
+            locals_frames_start_address: (globals_start_address - max_globals * i64_size).into(),
+            br_depth_address: ((i32::MAX - br_depth_offset as i32) as u32).into(),
+            frame_pointer_address: ((i32::MAX - frame_pointer_offset as i32) as u32).into(),
+            recursion_frames_start_address: ((i32::MAX - recursion_frames_offset as i32) as u32)
+                .into(),
+        }
+    }
+}
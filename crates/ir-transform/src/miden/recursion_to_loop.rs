@@ -0,0 +1,634 @@
+//! MASM has no recursion (`exec` inlines the procedure), so the procedures of every call graph
+//! cycle (strongly connected component) are merged into a single dispatcher procedure.
+//!
+//! The bodies of the procedures are split at the calls inside the component into state blocks,
+//! and the dispatcher loop runs the block of the state on top of the stack until the exit
+//! state (0). A call allocates the callee frame in memory (with zeroed locals), stores the
+//! return state in its first slot and jumps to the callee entry state. The end of the callee loads the return state
+//! and frees the frame. The procedure locals are kept in the frames (addressed with the frame
+//! pointer stored at the frame pointer address), the operand stack values live across a call
+//! stay on the stack.
+//! Every procedure of the component is left as a stub calling the dispatcher with its entry
+//! state and the exit state as the return state.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+use ozk_miden_dialect::ops as miden;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::op_interfaces::get_callees_syms;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::rewrite::RewritePatternSet;
+
+use super::lowering::arith_op_lowering::felt_constant;
+use super::memory_layout::MidenMemoryLayout;
+
+/// The state that exits the dispatcher loop
+const EXIT_STATE: u64 = 0;
+
+pub struct MidenRecursionToLoopPass {
+    frame_pointer_address: u32,
+    frames_start_address: u32,
+}
+
+impl MidenRecursionToLoopPass {
+    /// `frames_start_address` is the (exclusive) top of the frames region.
+    pub fn new(frame_pointer_address: u32, frames_start_address: u32) -> Self {
+        Self {
+            frame_pointer_address,
+            frames_start_address,
+        }
+    }
+}
+
+impl Pass for MidenRecursionToLoopPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::new(RecursionToLoop {
+            frame_pointer_address: self.frame_pointer_address as u64,
+            frames_start_address: self.frames_start_address as u64,
+        }));
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "miden-recursion-to-loop",
+        description: "Merge the recursive Miden procedures into a dispatcher loop with the frames in memory",
+        constructor: || {
+            let layout = MidenMemoryLayout::default();
+            Box::new(MidenRecursionToLoopPass::new(
+                u32::from(layout.frame_pointer_address),
+                u32::from(layout.recursion_frames_start_address),
+            ))
+        },
+    }
+}
+
+struct RecursionToLoop {
+    frame_pointer_address: u64,
+    frames_start_address: u64,
+}
+
+impl RewritePattern for RecursionToLoop {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(prog_op) = opop.downcast_ref::<miden::ProgramOp>().cloned() else {
+            return Ok(false);
+        };
+        let procs: Vec<miden::ProcOp> = prog_op
+            .get_body(ctx, 0)
+            .deref(ctx)
+            .iter(ctx)
+            .filter_map(|op| {
+                op.deref(ctx)
+                    .get_op(ctx)
+                    .downcast_ref::<miden::ProcOp>()
+                    .cloned()
+            })
+            .collect();
+        let sccs = recursive_sccs(ctx, &procs);
+        if sccs.is_empty() {
+            return Ok(false);
+        }
+        let main_proc_sym = prog_op.get_main_proc_sym(ctx);
+        for scc in sccs {
+            if scc.contains(&main_proc_sym) {
+                return Err(anyhow!("recursive main procedure {main_proc_sym}"));
+            }
+            let scc_procs: Vec<miden::ProcOp> = procs
+                .iter()
+                .filter(|proc_op| scc.contains(&proc_op.get_symbol_name(ctx)))
+                .cloned()
+                .collect();
+            let dispatcher = self.convert_scc(ctx, rewriter, &scc, scc_procs)?;
+            prog_op.add_proc_op(ctx, dispatcher);
+        }
+        let main_proc = procs
+            .iter()
+            .find(|proc_op| proc_op.get_symbol_name(ctx) == main_proc_sym)
+            .ok_or_else(|| anyhow!("main proc is not found"))?;
+        let init_ops = vec![
+            felt_constant(ctx, self.frames_start_address),
+            felt_constant(ctx, self.frame_pointer_address),
+            miden::MemStoreOp::new_unlinked(ctx).get_operation(),
+        ];
+        for op in init_ops.into_iter().rev() {
+            op.insert_at_front(main_proc.get_entry_block(ctx), ctx);
+        }
+        Ok(true)
+    }
+}
+
+/// Procedure entry in the dispatcher
+struct ProcInfo {
+    entry_state: u64,
+    /// The return state slot and the locals
+    frame_size: u64,
+}
+
+impl RecursionToLoop {
+    /// Move the bodies of the procedures into a new dispatcher procedure and replace them
+    /// with the stubs calling the dispatcher.
+    fn convert_scc(
+        &self,
+        ctx: &mut Context,
+        rewriter: &mut dyn PatternRewriter,
+        scc: &HashSet<String>,
+        mut scc_procs: Vec<miden::ProcOp>,
+    ) -> Result<miden::ProcOp, anyhow::Error> {
+        scc_procs.sort_by_key(|proc_op| proc_op.get_symbol_name(ctx));
+        let dispatcher_name = format!(
+            "ozk_dispatch_{}",
+            scc_procs
+                .first()
+                .map(|proc_op| proc_op.get_symbol_name(ctx))
+                .unwrap_or_default()
+        );
+        let mut flattener = Flattener {
+            scc,
+            blocks: Vec::new(),
+        };
+        let mut infos = HashMap::new();
+        for proc_op in &scc_procs {
+            self.locals_to_frame(ctx, rewriter, proc_op)?;
+            let frame_size = proc_op.get_num_locals(ctx) as u64 + 1;
+            let entry = flattener.new_block(frame_size);
+            let ops: Vec<Ptr<Operation>> = proc_op.op_iter(ctx).collect();
+            let exit = flattener.flatten(ctx, rewriter, ops, entry)?;
+            flattener.terminate(exit, Terminator::Return)?;
+            infos.insert(
+                proc_op.get_symbol_name(ctx),
+                ProcInfo {
+                    entry_state: state(entry),
+                    frame_size,
+                },
+            );
+        }
+        // the stubs
+        for proc_op in &scc_procs {
+            let info = infos
+                .get(&proc_op.get_symbol_name(ctx))
+                .ok_or_else(|| anyhow!("no dispatcher entry"))?;
+            let mut ops = self.call_ops(ctx, info, EXIT_STATE);
+            ops.push(
+                miden::ExecOp::new_unlinked(ctx, dispatcher_name.clone().into()).get_operation(),
+            );
+            proc_op.set_num_locals(ctx, 0);
+            for op in ops {
+                op.insert_at_back(proc_op.get_entry_block(ctx), ctx);
+            }
+        }
+        // Stack: [state, ...]
+        let loop_op = miden::WhileOp::new_unlinked(ctx);
+        let loop_block = loop_op.get_block(ctx);
+        for (index, block) in flattener.blocks.into_iter().enumerate() {
+            let mut check_ops = vec![
+                miden::DupOp::new_unlinked(ctx, 0).get_operation(),
+                felt_constant(ctx, state(index)),
+                miden::EqOp::new_unlinked(ctx).get_operation(),
+            ];
+            let if_op = miden::IfOp::new_unlinked(ctx);
+            let then_block = if_op.get_then_block(ctx);
+            let mut then_ops = vec![miden::DropOp::new_unlinked(ctx).get_operation()];
+            then_ops.extend(block.ops);
+            then_ops.extend(self.terminator_ops(
+                ctx,
+                &infos,
+                block.frame_size,
+                block.terminator,
+            )?);
+            for op in then_ops {
+                op.insert_at_back(then_block, ctx);
+            }
+            check_ops.push(if_op.get_operation());
+            for op in check_ops {
+                op.insert_at_back(loop_block, ctx);
+            }
+        }
+        // continue until the exit state
+        for op in [
+            miden::DupOp::new_unlinked(ctx, 0).get_operation(),
+            felt_constant(ctx, EXIT_STATE),
+            miden::NeqOp::new_unlinked(ctx).get_operation(),
+        ] {
+            op.insert_at_back(loop_block, ctx);
+        }
+        let dispatcher = miden::ProcOp::new_unlinked(ctx, &dispatcher_name);
+        for op in [
+            felt_constant(ctx, 1),
+            loop_op.get_operation(),
+            // the exit state
+            miden::DropOp::new_unlinked(ctx).get_operation(),
+        ] {
+            op.insert_at_back(dispatcher.get_entry_block(ctx), ctx);
+        }
+        Ok(dispatcher)
+    }
+
+    /// Replace the procedure locals access with the frame slots access.
+    fn locals_to_frame(
+        &self,
+        ctx: &mut Context,
+        rewriter: &mut dyn PatternRewriter,
+        proc_op: &miden::ProcOp,
+    ) -> Result<(), anyhow::Error> {
+        let mut local_ops = Vec::new();
+        proc_op
+            .get_operation()
+            .walk(ctx, WalkOrder::PreOrder, &mut |op| {
+                let opop = op.deref(ctx).get_op(ctx);
+                if opop.is::<miden::LocLoadOp>()
+                    || opop.is::<miden::LocStoreOp>()
+                    || opop.is::<miden::LocStoreWOp>()
+                    || opop.is::<miden::LocAddrOp>()
+                {
+                    local_ops.push(op);
+                }
+                WalkResult::Advance
+            });
+        for op in local_ops {
+            let opop = op.deref(ctx).get_op(ctx);
+            let (index, access_op) = if let Some(load_op) = opop.downcast_ref::<miden::LocLoadOp>()
+            {
+                let index = load_op.get_index(ctx);
+                (
+                    index,
+                    Some(miden::MemLoadOp::new_unlinked(ctx).get_operation()),
+                )
+            } else if let Some(store_op) = opop.downcast_ref::<miden::LocStoreOp>() {
+                let index = store_op.get_index(ctx);
+                (
+                    index,
+                    Some(miden::MemStoreOp::new_unlinked(ctx).get_operation()),
+                )
+            } else if let Some(addr_op) = opop.downcast_ref::<miden::LocAddrOp>() {
+                (addr_op.get_index(ctx), None)
+            } else {
+                return Err(anyhow!(
+                    "word access to the locals of the recursive procedure {} is not supported",
+                    proc_op.get_symbol_name(ctx)
+                ));
+            };
+            // the first slot holds the return state
+            let mut ops = self.frame_slot_address(ctx, index as u64 + 1);
+            ops.extend(access_op);
+            for new_op in ops {
+                new_op.insert_before(ctx, op);
+            }
+            rewriter.erase_op(ctx, op)?;
+        }
+        Ok(())
+    }
+
+    /// Push the address of the slot of the current frame.
+    fn frame_slot_address(&self, ctx: &mut Context, slot: u64) -> Vec<Ptr<Operation>> {
+        let mut ops = self.load_frame_pointer(ctx);
+        if slot > 0 {
+            ops.push(felt_constant(ctx, slot));
+            ops.push(miden::AddOp::new_unlinked(ctx).get_operation());
+        }
+        ops
+    }
+
+    fn load_frame_pointer(&self, ctx: &mut Context) -> Vec<Ptr<Operation>> {
+        vec![
+            felt_constant(ctx, self.frame_pointer_address),
+            miden::MemLoadOp::new_unlinked(ctx).get_operation(),
+        ]
+    }
+
+    /// Move the frame pointer by `size` (down if `alloc` is set).
+    fn move_frame_pointer(&self, ctx: &mut Context, size: u64, alloc: bool) -> Vec<Ptr<Operation>> {
+        let mut ops = self.load_frame_pointer(ctx);
+        ops.push(felt_constant(ctx, size));
+        ops.push(if alloc {
+            miden::SubOp::new_unlinked(ctx).get_operation()
+        } else {
+            miden::AddOp::new_unlinked(ctx).get_operation()
+        });
+        ops.push(felt_constant(ctx, self.frame_pointer_address));
+        ops.push(miden::MemStoreOp::new_unlinked(ctx).get_operation());
+        ops
+    }
+
+    /// Allocate the callee frame, store the return state in it and push the callee entry state.
+    /// The frame memory is reused, so the locals are zeroed.
+    fn call_ops(
+        &self,
+        ctx: &mut Context,
+        callee: &ProcInfo,
+        return_state: u64,
+    ) -> Vec<Ptr<Operation>> {
+        let mut ops = self.move_frame_pointer(ctx, callee.frame_size, true);
+        for slot in 0..callee.frame_size {
+            let value = if slot == 0 { return_state } else { 0 };
+            ops.push(felt_constant(ctx, value));
+            ops.extend(self.frame_slot_address(ctx, slot));
+            ops.push(miden::MemStoreOp::new_unlinked(ctx).get_operation());
+        }
+        ops.push(felt_constant(ctx, callee.entry_state));
+        ops
+    }
+
+    /// Push the next state.
+    fn terminator_ops(
+        &self,
+        ctx: &mut Context,
+        infos: &HashMap<String, ProcInfo>,
+        frame_size: u64,
+        terminator: Option<Terminator>,
+    ) -> Result<Vec<Ptr<Operation>>, anyhow::Error> {
+        match terminator {
+            Some(Terminator::Jump(next)) => Ok(vec![felt_constant(ctx, state(next))]),
+            Some(Terminator::Branch {
+                then_block,
+                else_block,
+            }) => {
+                // Stack: [condition, ...]
+                let if_op = miden::IfOp::new_unlinked(ctx);
+                felt_constant(ctx, state(then_block))
+                    .insert_at_back(if_op.get_then_block(ctx), ctx);
+                felt_constant(ctx, state(else_block))
+                    .insert_at_back(if_op.get_else_block(ctx), ctx);
+                Ok(vec![if_op.get_operation()])
+            }
+            Some(Terminator::Call {
+                callee,
+                return_block,
+            }) => {
+                let info = infos
+                    .get(&callee)
+                    .ok_or_else(|| anyhow!("no dispatcher entry for {callee}"))?;
+                Ok(self.call_ops(ctx, info, state(return_block)))
+            }
+            Some(Terminator::Return) => {
+                // load the return state and free the frame
+                let mut ops = self.frame_slot_address(ctx, 0);
+                ops.push(miden::MemLoadOp::new_unlinked(ctx).get_operation());
+                ops.extend(self.move_frame_pointer(ctx, frame_size, false));
+                Ok(ops)
+            }
+            None => Err(anyhow!("unterminated state block")),
+        }
+    }
+}
+
+/// The state of the block with the given index
+fn state(block_index: usize) -> u64 {
+    block_index as u64 + 1
+}
+
+enum Terminator {
+    Jump(usize),
+    /// Pops the condition
+    Branch {
+        then_block: usize,
+        else_block: usize,
+    },
+    Call {
+        callee: String,
+        return_block: usize,
+    },
+    Return,
+}
+
+/// Straight-line ops run in a dispatcher iteration
+struct StateBlock {
+    ops: Vec<Ptr<Operation>>,
+    terminator: Option<Terminator>,
+    /// Frame size of the procedure the block belongs to
+    frame_size: u64,
+}
+
+/// Splits the procedure bodies at the calls to the procedures of the component.
+struct Flattener<'a> {
+    scc: &'a HashSet<String>,
+    blocks: Vec<StateBlock>,
+}
+
+impl Flattener<'_> {
+    fn new_block(&mut self, frame_size: u64) -> usize {
+        self.blocks.push(StateBlock {
+            ops: Vec::new(),
+            terminator: None,
+            frame_size,
+        });
+        self.blocks.len() - 1
+    }
+
+    fn terminate(&mut self, block: usize, terminator: Terminator) -> Result<(), anyhow::Error> {
+        let block = self
+            .blocks
+            .get_mut(block)
+            .ok_or_else(|| anyhow!("no state block {block}"))?;
+        block.terminator = Some(terminator);
+        Ok(())
+    }
+
+    /// Move the ops into the state blocks starting with `current`, returns the last block.
+    fn flatten(
+        &mut self,
+        ctx: &mut Context,
+        rewriter: &mut dyn PatternRewriter,
+        ops: Vec<Ptr<Operation>>,
+        mut current: usize,
+    ) -> Result<usize, anyhow::Error> {
+        for op in ops {
+            let frame_size = self
+                .blocks
+                .get(current)
+                .map(|block| block.frame_size)
+                .ok_or_else(|| anyhow!("no state block {current}"))?;
+            if !self.calls_scc(ctx, op) {
+                op.unlink(ctx);
+                if let Some(block) = self.blocks.get_mut(current) {
+                    block.ops.push(op);
+                }
+                continue;
+            }
+            let opop = op.deref(ctx).get_op(ctx);
+            if let Some(exec_op) = opop.downcast_ref::<miden::ExecOp>() {
+                let callee = exec_op.get_callee_sym(ctx);
+                let return_block = self.new_block(frame_size);
+                self.terminate(
+                    current,
+                    Terminator::Call {
+                        callee,
+                        return_block,
+                    },
+                )?;
+                rewriter.erase_op(ctx, op)?;
+                current = return_block;
+            } else if let Some(if_op) = opop.downcast_ref::<miden::IfOp>() {
+                let then_ops: Vec<Ptr<Operation>> = if_op.then_op_iter(ctx).collect();
+                let else_ops: Vec<Ptr<Operation>> = if_op.else_op_iter(ctx).collect();
+                let then_block = self.new_block(frame_size);
+                let else_block = self.new_block(frame_size);
+                self.terminate(
+                    current,
+                    Terminator::Branch {
+                        then_block,
+                        else_block,
+                    },
+                )?;
+                let then_exit = self.flatten(ctx, rewriter, then_ops, then_block)?;
+                let else_exit = self.flatten(ctx, rewriter, else_ops, else_block)?;
+                let join_block = self.new_block(frame_size);
+                self.terminate(then_exit, Terminator::Jump(join_block))?;
+                self.terminate(else_exit, Terminator::Jump(join_block))?;
+                rewriter.erase_op(ctx, op)?;
+                current = join_block;
+            } else if let Some(while_op) = opop.downcast_ref::<miden::WhileOp>() {
+                let body_ops: Vec<Ptr<Operation>> = while_op.op_iter(ctx).collect();
+                // Stack: [condition, ...]
+                let header_block = self.new_block(frame_size);
+                let body_block = self.new_block(frame_size);
+                let exit_block = self.new_block(frame_size);
+                self.terminate(current, Terminator::Jump(header_block))?;
+                self.terminate(
+                    header_block,
+                    Terminator::Branch {
+                        then_block: body_block,
+                        else_block: exit_block,
+                    },
+                )?;
+                let body_exit = self.flatten(ctx, rewriter, body_ops, body_block)?;
+                self.terminate(body_exit, Terminator::Jump(header_block))?;
+                rewriter.erase_op(ctx, op)?;
+                current = exit_block;
+            } else {
+                return Err(anyhow!("unexpected op with a nested call"));
+            }
+        }
+        Ok(current)
+    }
+
+    /// Returns true if the op is (or contains) a call to a procedure of the component.
+    fn calls_scc(&self, ctx: &Context, op: Ptr<Operation>) -> bool {
+        let mut found = false;
+        op.walk_only::<miden::ExecOp>(ctx, WalkOrder::PreOrder, &mut |exec_op| {
+            if self.scc.contains(&exec_op.get_callee_sym(ctx)) {
+                found = true;
+                WalkResult::Interrupt
+            } else {
+                WalkResult::Advance
+            }
+        });
+        found
+    }
+}
+
+/// The strongly connected components of the call graph with a cycle (Tarjan's algorithm).
+fn recursive_sccs(ctx: &Context, procs: &[miden::ProcOp]) -> Vec<HashSet<String>> {
+    let names: Vec<String> = procs
+        .iter()
+        .map(|proc_op| proc_op.get_symbol_name(ctx))
+        .collect();
+    let index_of: HashMap<&str, usize> = names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (name.as_str(), index))
+        .collect();
+    let callees: Vec<Vec<usize>> = procs
+        .iter()
+        .map(|proc_op| {
+            get_callees_syms(ctx, proc_op.get_operation())
+                .iter()
+                .filter_map(|callee| index_of.get(callee.as_str()).copied())
+                .collect()
+        })
+        .collect();
+    let mut tarjan = Tarjan {
+        callees: &callees,
+        next_index: 0,
+        indices: vec![None; names.len()],
+        low_links: vec![0; names.len()],
+        stack: Vec::new(),
+        on_stack: vec![false; names.len()],
+        sccs: Vec::new(),
+    };
+    for node in 0..names.len() {
+        if tarjan.indices.get(node).copied().flatten().is_none() {
+            tarjan.visit(node);
+        }
+    }
+    tarjan
+        .sccs
+        .into_iter()
+        .filter(|scc| match scc.as_slice() {
+            [node] => callees.get(*node).map_or(false, |c| c.contains(node)),
+            _ => true,
+        })
+        .map(|scc| {
+            scc.into_iter()
+                .filter_map(|node| names.get(node).cloned())
+                .collect()
+        })
+        .collect()
+}
+
+struct Tarjan<'a> {
+    callees: &'a [Vec<usize>],
+    next_index: usize,
+    indices: Vec<Option<usize>>,
+    low_links: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    sccs: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: usize) {
+        self.indices[node] = Some(self.next_index);
+        self.low_links[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+        let callees = self.callees;
+        for &callee in &callees[node] {
+            match self.indices[callee] {
+                None => {
+                    self.visit(callee);
+                    self.low_links[node] = self.low_links[node].min(self.low_links[callee]);
+                }
+                Some(callee_index) if self.on_stack[callee] => {
+                    self.low_links[node] = self.low_links[node].min(callee_index);
+                }
+                Some(_) => (),
+            }
+        }
+        if Some(self.low_links[node]) == self.indices[node] {
+            let mut scc = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                scc.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.sccs.push(scc);
+        }
+    }
+}
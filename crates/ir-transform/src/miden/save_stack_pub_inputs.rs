@@ -0,0 +1,329 @@
+//! In Miden VM public inputs are stored on the stack. This pass saves the public inputs from the stack
+//! and stores them in the memory, so that `ozk_stdlib_pub_input` calls can read them one by one.
+//! The values passed to `ozk_stdlib_pub_output` calls are stored in the memory as well and
+//! put on the stack when the program finishes.
+//!
+//! The memory cell at the start address of each region holds the address of the next value.
+//! The values are stored below it, the first one at the start address - [VALUE_SIZE].
+//!
+//! The values are stored as field elements, while the i64 values on the stack are split into
+//! the low and high u32 limbs (the high one on top, see `ozk-legalize-i64`).
+
+use ozk_miden_dialect::ops as miden;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::rewrite::RewritePatternSet;
+
+use super::lowering::arith_op_lowering::felt_constant;
+
+pub const PUB_INPUT_FUNC_NAME: &str = "ozk_stdlib_pub_input";
+pub const PUB_OUTPUT_FUNC_NAME: &str = "ozk_stdlib_pub_output";
+pub const SAVE_PUB_INPUTS_PROC_NAME: &str = "save_pub_inputs";
+pub const INIT_PUB_OUTPUTS_PROC_NAME: &str = "init_pub_outputs";
+pub const LOAD_PUB_OUTPUTS_ON_STACK_PROC_NAME: &str = "load_pub_outputs_on_stack";
+
+/// Memory size of a public input/output (i64)
+pub const VALUE_SIZE: u64 = 8;
+
+/// The number of the stack items saved as public inputs
+/// (the stack inputs that are directly accessible on the program start).
+const MAX_STACK_PUB_INPUTS: u64 = 16;
+
+pub struct MidenSaveStackPubInputsPass {
+    pub_inputs_start_address: i32,
+    pub_outputs_start_address: i32,
+}
+
+impl MidenSaveStackPubInputsPass {
+    pub fn new(pub_inputs_start_address: i32, pub_outputs_start_address: i32) -> Self {
+        Self {
+            pub_inputs_start_address,
+            pub_outputs_start_address,
+        }
+    }
+}
+
+impl Pass for MidenSaveStackPubInputsPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::new(SaveStackPubInputs {
+            pub_inputs_start_address: self.pub_inputs_start_address as u32 as u64,
+            pub_outputs_start_address: self.pub_outputs_start_address as u32 as u64,
+        }));
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "miden-save-stack-pub-inputs",
+        description: "Save the Miden public inputs from the stack to memory and put the public outputs on the stack at exit",
+        constructor: || Box::new(MidenSaveStackPubInputsPass::new(i32::MAX, i32::MAX - 1024 * 8)),
+    }
+}
+
+struct SaveStackPubInputs {
+    pub_inputs_start_address: u64,
+    pub_outputs_start_address: u64,
+}
+
+impl RewritePattern for SaveStackPubInputs {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        _rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(prog_op) = opop.downcast_ref::<miden::ProgramOp>() else {
+            return Ok(false);
+        };
+        let uses_pub_inputs = has_undefined_callee(ctx, prog_op, PUB_INPUT_FUNC_NAME);
+        let uses_pub_outputs = has_undefined_callee(ctx, prog_op, PUB_OUTPUT_FUNC_NAME);
+        if !uses_pub_inputs && !uses_pub_outputs {
+            return Ok(false);
+        }
+        let Some(main_proc) = find_proc(ctx, prog_op, &prog_op.get_main_proc_sym(ctx)) else {
+            return Err(anyhow::anyhow!("main proc is not found"));
+        };
+        let main_proc_block = main_proc.get_entry_block(ctx);
+        if uses_pub_outputs {
+            let init_proc = self.init_pub_outputs_proc(ctx);
+            prog_op.add_proc_op(ctx, init_proc);
+            let store_proc = self.store_pub_output_proc(ctx);
+            prog_op.add_proc_op(ctx, store_proc);
+            let load_proc = self.load_pub_outputs_on_stack_proc(ctx);
+            prog_op.add_proc_op(ctx, load_proc);
+            exec_op(ctx, INIT_PUB_OUTPUTS_PROC_NAME).insert_at_front(main_proc_block, ctx);
+            exec_op(ctx, LOAD_PUB_OUTPUTS_ON_STACK_PROC_NAME).insert_at_back(main_proc_block, ctx);
+        }
+        if uses_pub_inputs {
+            let save_proc = self.save_pub_inputs_proc(ctx);
+            prog_op.add_proc_op(ctx, save_proc);
+            let read_proc = self.read_pub_input_proc(ctx);
+            prog_op.add_proc_op(ctx, read_proc);
+            // the stack inputs should be saved before anything is pushed on the stack
+            exec_op(ctx, SAVE_PUB_INPUTS_PROC_NAME).insert_at_front(main_proc_block, ctx);
+        }
+        Ok(true)
+    }
+}
+
+impl SaveStackPubInputs {
+    /// Store the top stack items in the public inputs region (the top item first)
+    /// and set the next input address to the first one.
+    fn save_pub_inputs_proc(&self, ctx: &mut Context) -> miden::ProcOp {
+        let mut ops = Vec::new();
+        for index in 1..=MAX_STACK_PUB_INPUTS {
+            // Stack: [pub input, ...]
+            ops.push(felt_constant(
+                ctx,
+                self.pub_inputs_start_address - index * VALUE_SIZE,
+            ));
+            // Stack: [address, pub input, ...]
+            ops.push(miden::MemStoreOp::new_unlinked(ctx).get_operation());
+        }
+        ops.extend(set_next_address(
+            ctx,
+            self.pub_inputs_start_address,
+            self.pub_inputs_start_address - VALUE_SIZE,
+        ));
+        build_proc(ctx, SAVE_PUB_INPUTS_PROC_NAME, ops)
+    }
+
+    /// Load the next public input on the stack.
+    fn read_pub_input_proc(&self, ctx: &mut Context) -> miden::ProcOp {
+        let mut ops = load_next_address(ctx, self.pub_inputs_start_address);
+        ops.extend([
+            // Stack: [address, address]
+            miden::DupOp::new_unlinked(ctx, 0).get_operation(),
+            // Stack: [pub input, address]
+            miden::MemLoadOp::new_unlinked(ctx).get_operation(),
+            // Stack: [address, pub input]
+            miden::SwapOp::new_unlinked(ctx, 1).get_operation(),
+        ]);
+        ops.extend(decrement_address(ctx));
+        ops.extend(store_next_address(ctx, self.pub_inputs_start_address));
+        // Stack: [hi, lo]
+        ops.push(miden::U32SplitOp::new_unlinked(ctx).get_operation());
+        build_proc(ctx, PUB_INPUT_FUNC_NAME, ops)
+    }
+
+    /// Set the next output address to the first one.
+    fn init_pub_outputs_proc(&self, ctx: &mut Context) -> miden::ProcOp {
+        let ops = set_next_address(
+            ctx,
+            self.pub_outputs_start_address,
+            self.pub_outputs_start_address - VALUE_SIZE,
+        );
+        build_proc(ctx, INIT_PUB_OUTPUTS_PROC_NAME, ops)
+    }
+
+    /// Store the value on top of the stack as the next public output.
+    fn store_pub_output_proc(&self, ctx: &mut Context) -> miden::ProcOp {
+        // Stack: [hi, lo]
+        let mut ops = join_limbs(ctx);
+        // Stack: [pub output]
+        ops.extend(load_next_address(ctx, self.pub_outputs_start_address));
+        ops.extend([
+            // Stack: [address, address, pub output]
+            miden::DupOp::new_unlinked(ctx, 0).get_operation(),
+            // Stack: [pub output, address, address]
+            miden::MovUpOp::new_unlinked(ctx, 2).get_operation(),
+            // Stack: [address, pub output, address]
+            miden::SwapOp::new_unlinked(ctx, 1).get_operation(),
+            // Stack: [address]
+            miden::MemStoreOp::new_unlinked(ctx).get_operation(),
+        ]);
+        ops.extend(decrement_address(ctx));
+        ops.extend(store_next_address(ctx, self.pub_outputs_start_address));
+        build_proc(ctx, PUB_OUTPUT_FUNC_NAME, ops)
+    }
+
+    /// Push the stored public outputs on the stack, the last one ends up on top.
+    fn load_pub_outputs_on_stack_proc(&self, ctx: &mut Context) -> miden::ProcOp {
+        // Stack: [next address]
+        let mut ops = load_next_address(ctx, self.pub_outputs_start_address);
+        // Stack: [address, next address]
+        ops.push(felt_constant(
+            ctx,
+            self.pub_outputs_start_address - VALUE_SIZE,
+        ));
+        ops.extend(address_neq_next_address(ctx));
+        let while_op = miden::WhileOp::new_unlinked(ctx);
+        let mut body_ops = vec![
+            // Stack: [address, address, next address, outputs ...]
+            miden::DupOp::new_unlinked(ctx, 0).get_operation(),
+            // Stack: [pub output, address, next address, outputs ...]
+            miden::MemLoadOp::new_unlinked(ctx).get_operation(),
+            // Stack: [address, next address, pub output, outputs ...]
+            miden::MovUpOp::new_unlinked(ctx, 2).get_operation(),
+            miden::MovUpOp::new_unlinked(ctx, 2).get_operation(),
+        ];
+        body_ops.extend(decrement_address(ctx));
+        body_ops.extend(address_neq_next_address(ctx));
+        for op in body_ops {
+            op.insert_at_back(while_op.get_block(ctx), ctx);
+        }
+        ops.extend([
+            while_op.get_operation(),
+            // Stack: [outputs ...]
+            miden::DropOp::new_unlinked(ctx).get_operation(),
+            miden::DropOp::new_unlinked(ctx).get_operation(),
+        ]);
+        build_proc(ctx, LOAD_PUB_OUTPUTS_ON_STACK_PROC_NAME, ops)
+    }
+}
+
+/// Returns true if the program calls the proc that is not defined in it.
+pub(super) fn has_undefined_callee(
+    ctx: &Context,
+    prog_op: &miden::ProgramOp,
+    callee_sym: &str,
+) -> bool {
+    let mut called = false;
+    prog_op
+        .get_operation()
+        .walk_only::<miden::ExecOp>(ctx, WalkOrder::PreOrder, &mut |exec_op| {
+            if exec_op.get_callee_sym(ctx) == callee_sym {
+                called = true;
+                WalkResult::Interrupt
+            } else {
+                WalkResult::Advance
+            }
+        });
+    called && find_proc(ctx, prog_op, callee_sym).is_none()
+}
+
+fn find_proc(ctx: &Context, prog_op: &miden::ProgramOp, sym: &str) -> Option<miden::ProcOp> {
+    prog_op
+        .get_body(ctx, 0)
+        .deref(ctx)
+        .iter(ctx)
+        .filter_map(|op| {
+            op.deref(ctx)
+                .get_op(ctx)
+                .downcast_ref::<miden::ProcOp>()
+                .cloned()
+        })
+        .find(|proc_op| proc_op.get_symbol_name(ctx) == sym)
+}
+
+pub(super) fn build_proc(ctx: &mut Context, name: &str, ops: Vec<Ptr<Operation>>) -> miden::ProcOp {
+    let proc_op = miden::ProcOp::new_unlinked(ctx, name);
+    let block = proc_op.get_entry_block(ctx);
+    for op in ops {
+        op.insert_at_back(block, ctx);
+    }
+    proc_op
+}
+
+fn exec_op(ctx: &mut Context, callee_sym: &str) -> Ptr<Operation> {
+    miden::ExecOp::new_unlinked(ctx, callee_sym.into()).get_operation()
+}
+
+/// Push the next value address stored at the region start address.
+fn load_next_address(ctx: &mut Context, start_address: u64) -> Vec<Ptr<Operation>> {
+    vec![
+        felt_constant(ctx, start_address),
+        miden::MemLoadOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Pop the next value address and store it at the region start address.
+fn store_next_address(ctx: &mut Context, start_address: u64) -> Vec<Ptr<Operation>> {
+    vec![
+        felt_constant(ctx, start_address),
+        miden::MemStoreOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+fn set_next_address(
+    ctx: &mut Context,
+    start_address: u64,
+    next_address: u64,
+) -> Vec<Ptr<Operation>> {
+    let mut ops = vec![felt_constant(ctx, next_address)];
+    ops.extend(store_next_address(ctx, start_address));
+    ops
+}
+
+/// Join the u32 limbs of i64 into a field element. Stack: [hi, lo] -> [hi * 2^32 + lo]
+fn join_limbs(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    vec![
+        felt_constant(ctx, 1 << 32),
+        miden::MulOp::new_unlinked(ctx).get_operation(),
+        miden::AddOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Move the address on top of the stack to the next value.
+fn decrement_address(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    vec![
+        felt_constant(ctx, VALUE_SIZE),
+        miden::SubOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Push `address != next address` keeping both on the stack.
+fn address_neq_next_address(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    vec![
+        miden::DupOp::new_unlinked(ctx, 1).get_operation(),
+        miden::DupOp::new_unlinked(ctx, 1).get_operation(),
+        miden::NeqOp::new_unlinked(ctx).get_operation(),
+    ]
+}
@@ -0,0 +1,199 @@
+//! In Miden VM secret inputs are provided via the advice stack. This pass replaces the
+//! `ozk_stdlib_secret_input` calls with `adv_push.1 u32split` and adds the proc for the
+//! `ozk_stdlib_secret_input_slice` calls that reads the secret inputs into the Wasm memory.
+//!
+//! The secret inputs are u64 values read in the order they are put on the advice stack.
+
+use ozk_miden_dialect::ops as miden;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::rewrite::RewritePatternSet;
+
+use super::lowering::arith_op_lowering::felt_constant;
+use super::save_stack_pub_inputs::build_proc;
+use super::save_stack_pub_inputs::has_undefined_callee;
+
+pub const SECRET_INPUT_FUNC_NAME: &str = "ozk_stdlib_secret_input";
+pub const SECRET_INPUT_SLICE_FUNC_NAME: &str = "ozk_stdlib_secret_input_slice";
+
+/// The number of the secret inputs read from the advice stack with a single `adv_loadw`
+const WORD_SIZE: u64 = 4;
+
+/// The number of the Miden memory words a u64 value occupies in the Wasm memory
+/// (see [super::lowering::mem_op_lowering])
+const VALUE_WORDS: u64 = 2;
+
+#[derive(Default)]
+pub struct MidenSecretInputsPass;
+
+impl Pass for MidenSecretInputsPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::<SecretInputs>::default());
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "miden-secret-inputs",
+        description: "Read the Miden secret inputs from the advice stack",
+        constructor: || Box::<MidenSecretInputsPass>::default(),
+    }
+}
+
+#[derive(Default)]
+struct SecretInputs;
+
+impl RewritePattern for SecretInputs {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(prog_op) = opop.downcast_ref::<miden::ProgramOp>() else {
+            return Ok(false);
+        };
+        let uses_secret_input = has_undefined_callee(ctx, prog_op, SECRET_INPUT_FUNC_NAME);
+        let uses_secret_input_slice =
+            has_undefined_callee(ctx, prog_op, SECRET_INPUT_SLICE_FUNC_NAME);
+        if !uses_secret_input && !uses_secret_input_slice {
+            return Ok(false);
+        }
+        if uses_secret_input {
+            let mut exec_ops = Vec::new();
+            prog_op.get_operation().walk_only::<miden::ExecOp>(
+                ctx,
+                WalkOrder::PreOrder,
+                &mut |exec_op| {
+                    if exec_op.get_callee_sym(ctx) == SECRET_INPUT_FUNC_NAME {
+                        exec_ops.push(exec_op.get_operation());
+                    }
+                    WalkResult::Advance
+                },
+            );
+            for exec_op in exec_ops {
+                let adv_push_op = miden::AdvPushOp::new_unlinked(ctx, 1).get_operation();
+                rewriter.set_insertion_point(exec_op);
+                rewriter.insert_before(ctx, adv_push_op)?;
+                // the i64 value is split into the u32 limbs, the high one on top
+                let u32_split_op = miden::U32SplitOp::new_unlinked(ctx).get_operation();
+                rewriter.replace_op_with(ctx, exec_op, u32_split_op)?;
+            }
+        }
+        if uses_secret_input_slice {
+            let slice_proc = secret_input_slice_proc(ctx);
+            prog_op.add_proc_op(ctx, slice_proc);
+        }
+        Ok(true)
+    }
+}
+
+/// Read `len` secret inputs into the u64 slice at the Wasm memory address `ptr`
+/// (4 byte aligned). The secret inputs are read by words with `adv_loadw` while there are
+/// at least 4 of them left, and the rest one by one with `adv_push.1`.
+/// The values are stored in the Wasm memory layout of u64 (see
+/// [super::lowering::mem_op_lowering]), so they cannot be piped into the memory words
+/// with `adv_pipe` directly.
+fn secret_input_slice_proc(ctx: &mut Context) -> miden::ProcOp {
+    // Stack: [len, ptr]
+    let mut ops = vec![
+        miden::SwapOp::new_unlinked(ctx, 1).get_operation(),
+        felt_constant(ctx, 2),
+        miden::U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+        // Stack: [len, word address]
+        miden::SwapOp::new_unlinked(ctx, 1).get_operation(),
+    ];
+    ops.extend(len_gte(ctx, WORD_SIZE));
+    let words_loop = miden::WhileOp::new_unlinked(ctx);
+    let mut words_loop_ops = vec![
+        miden::PadWOp::new_unlinked(ctx).get_operation(),
+        // Stack: [v3, v2, v1, v0, len, word address]
+        miden::AdvLoadWOp::new_unlinked(ctx).get_operation(),
+        // Stack: [v0, v3, v2, v1, len, word address]
+        miden::MovUpOp::new_unlinked(ctx, 3).get_operation(),
+    ];
+    words_loop_ops.extend(store_value(ctx, 0, 5));
+    // Stack: [v1, v3, v2, len, word address]
+    words_loop_ops.push(miden::MovUpOp::new_unlinked(ctx, 2).get_operation());
+    words_loop_ops.extend(store_value(ctx, 1, 4));
+    // Stack: [v2, v3, len, word address]
+    words_loop_ops.push(miden::SwapOp::new_unlinked(ctx, 1).get_operation());
+    words_loop_ops.extend(store_value(ctx, 2, 3));
+    words_loop_ops.extend(store_value(ctx, 3, 2));
+    words_loop_ops.extend(advance(ctx, WORD_SIZE));
+    words_loop_ops.extend(len_gte(ctx, WORD_SIZE));
+    for op in words_loop_ops {
+        op.insert_at_back(words_loop.get_block(ctx), ctx);
+    }
+    ops.push(words_loop.get_operation());
+    ops.extend(len_gte(ctx, 1));
+    let values_loop = miden::WhileOp::new_unlinked(ctx);
+    // Stack: [v, len, word address]
+    let mut values_loop_ops = vec![miden::AdvPushOp::new_unlinked(ctx, 1).get_operation()];
+    values_loop_ops.extend(store_value(ctx, 0, 2));
+    values_loop_ops.extend(advance(ctx, 1));
+    values_loop_ops.extend(len_gte(ctx, 1));
+    for op in values_loop_ops {
+        op.insert_at_back(values_loop.get_block(ctx), ctx);
+    }
+    ops.extend([
+        values_loop.get_operation(),
+        miden::DropOp::new_unlinked(ctx).get_operation(),
+        miden::DropOp::new_unlinked(ctx).get_operation(),
+    ]);
+    build_proc(ctx, SECRET_INPUT_SLICE_FUNC_NAME, ops)
+}
+
+/// Store the value on top of the stack as the `index`-th u64 from the word address
+/// at the given depth (the low 32 bits in the first word, the high 32 bits in the next one).
+fn store_value(ctx: &mut Context, index: u64, address_depth: u8) -> Vec<Ptr<Operation>> {
+    vec![
+        // Stack: [hi, lo, ...]
+        miden::U32SplitOp::new_unlinked(ctx).get_operation(),
+        miden::DupOp::new_unlinked(ctx, address_depth + 1).get_operation(),
+        felt_constant(ctx, index * VALUE_WORDS + 1),
+        miden::AddOp::new_unlinked(ctx).get_operation(),
+        // Stack: [lo, ...]
+        miden::MemStoreOp::new_unlinked(ctx).get_operation(),
+        miden::DupOp::new_unlinked(ctx, address_depth).get_operation(),
+        felt_constant(ctx, index * VALUE_WORDS),
+        miden::AddOp::new_unlinked(ctx).get_operation(),
+        miden::MemStoreOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Decrement `len` and move the word address past the given number of the stored values.
+fn advance(ctx: &mut Context, count: u64) -> Vec<Ptr<Operation>> {
+    vec![
+        // Stack: [len, word address]
+        felt_constant(ctx, count),
+        miden::SubOp::new_unlinked(ctx).get_operation(),
+        miden::SwapOp::new_unlinked(ctx, 1).get_operation(),
+        felt_constant(ctx, count * VALUE_WORDS),
+        miden::AddOp::new_unlinked(ctx).get_operation(),
+        miden::SwapOp::new_unlinked(ctx, 1).get_operation(),
+    ]
+}
+
+/// Push `len >= count` keeping `len` on the stack.
+fn len_gte(ctx: &mut Context, count: u64) -> Vec<Ptr<Operation>> {
+    vec![
+        miden::DupOp::new_unlinked(ctx, 0).get_operation(),
+        felt_constant(ctx, count),
+        miden::U32CheckedGteOp::new_unlinked(ctx).get_operation(),
+    ]
+}
@@ -0,0 +1,3 @@
+//! Target-independent transformations of the mid-level `ozk` dialect
+pub mod legalize_i64;
+pub mod lowering;
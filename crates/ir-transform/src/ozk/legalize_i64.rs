@@ -0,0 +1,1151 @@
+//! i64 legalization for the targets with 32-bit words (Valida, Miden's `u32` ops).
+//!
+//! Every i64 value is split into two i32 limbs: the low limb is pushed first and the high limb
+//! ends up on top of the stack. i64 parameters, results, locals, block and callee types become
+//! two i32s, and every `ozk` op on i64 is expanded into a sequence of i32 ops
+//! (carry-propagating add/sub, full 64-bit mul, limb-wise comparisons and branch-free shifts).
+//! The expansions keep their operands in scratch i32 locals appended to the function locals.
+//!
+//! Expects the function bodies lowered to `ozk` (`wasm-lower-to-ozk`) with the arguments
+//! in the locals (i.e. before `wasm-explicit-func-args`).
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::attributes::apint_to_i64;
+use ozk_ozk_dialect::attributes::i32_attr;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::ops::MemArg;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::typed_stack::dropped_value_types;
+use ozk_wasm_dialect::typed_stack::StackType;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::attr_interfaces::TypedAttrInterface;
+use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::dialects::builtin::types::IntegerType;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
+use pliron::rewrite::RewritePatternSet;
+use pliron::with_context::AttachContext;
+
+#[derive(Default)]
+pub struct LegalizeI64Pass;
+
+impl Pass for LegalizeI64Pass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::<LegalizeI64>::default());
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "ozk-legalize-i64",
+        description: "Split i64 values into i32 hi/lo limbs for the targets with 32-bit words",
+        constructor: || Box::<LegalizeI64Pass>::default(),
+    }
+}
+
+#[derive(Default)]
+pub struct LegalizeI64;
+
+impl RewritePattern for LegalizeI64 {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        _rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(module_op) = opop.downcast_ref::<wasm::ModuleOp>() else {
+            return Ok(false);
+        };
+        let func_ops: Vec<wasm::FuncOp> = module_op
+            .get_body(ctx, 0)
+            .deref(ctx)
+            .iter(ctx)
+            .filter_map(|op| {
+                op.deref(ctx)
+                    .get_op(ctx)
+                    .downcast_ref::<wasm::FuncOp>()
+                    .cloned()
+            })
+            .collect();
+        let mut changed = false;
+        for func_op in func_ops {
+            changed |= legalize_func(ctx, module_op, &func_op)?;
+        }
+        Ok(changed)
+    }
+}
+
+/// Split the i64 values in the function. Returns true if the function had any.
+fn legalize_func(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    func_op: &wasm::FuncOp,
+) -> Result<bool, anyhow::Error> {
+    let func_sym = func_op.get_symbol_name(ctx);
+    let dropped = dropped_value_types(ctx, module_op, func_op)
+        .map_err(|err| anyhow!("wasm.func @{func_sym}: {err:?}"))?;
+    let func_type = func_op.get_type(ctx);
+    let params = func_type.get_inputs().clone();
+    if func_op.has_args_on_stack(ctx) && params.iter().any(|ty| is_i64(ctx, *ty)) {
+        return Err(anyhow!(
+            "wasm.func @{func_sym}: i64 arguments on the stack are not supported, \
+             legalize before making the arguments explicit"
+        ));
+    }
+    let locals = func_op.get_locals(ctx);
+    let mut local_map = Vec::new();
+    let mut next_index = 0;
+    for ty in params.iter().chain(locals.iter()) {
+        let is_i64 = is_i64(ctx, *ty);
+        local_map.push(LocalSlot {
+            index: next_index,
+            is_i64,
+        });
+        next_index += if is_i64 { 2 } else { 1 };
+    }
+    let entry_block = func_op.get_entry_block(ctx);
+    let mut legalizer = I64Legalizer {
+        func_sym,
+        changed: local_map.iter().any(|slot| slot.is_i64)
+            || func_type.get_results().iter().any(|ty| is_i64(ctx, *ty)),
+        local_map,
+        scratch_base: next_index,
+        num_scratch: 0,
+        dropped,
+        block: entry_block,
+    };
+    let ops: Vec<Ptr<Operation>> = func_op.op_iter(ctx).collect();
+    legalizer.legalize_ops(ctx, ops, entry_block)?;
+    if !legalizer.changed {
+        return Ok(false);
+    }
+    let new_func_type = legalize_func_type(ctx, &func_type);
+    func_op.set_type(ctx, new_func_type);
+    let mut new_locals = legalize_types(ctx, &locals);
+    let i32_ty = i32_type(ctx);
+    new_locals.extend(std::iter::repeat(i32_ty).take(legalizer.num_scratch as usize));
+    func_op.set_locals(ctx, new_locals);
+    Ok(true)
+}
+
+fn is_i64(ctx: &Context, ty: Ptr<TypeObj>) -> bool {
+    ty.deref(ctx)
+        .downcast_ref::<IntegerType>()
+        .map_or(false, |int_ty| int_ty.get_width() == 64)
+}
+
+/// Replace every i64 with the low and high i32 limbs.
+fn legalize_types(ctx: &mut Context, types: &[Ptr<TypeObj>]) -> Vec<Ptr<TypeObj>> {
+    let i32_ty = i32_type(ctx);
+    types
+        .iter()
+        .flat_map(|ty| {
+            if is_i64(ctx, *ty) {
+                vec![i32_ty, i32_ty]
+            } else {
+                vec![*ty]
+            }
+        })
+        .collect()
+}
+
+fn legalize_func_type(ctx: &mut Context, func_type: &FunctionType) -> Ptr<TypeObj> {
+    let inputs = legalize_types(ctx, func_type.get_inputs());
+    let results = legalize_types(ctx, func_type.get_results());
+    FunctionType::get(ctx, inputs, results)
+}
+
+fn has_i64(ctx: &Context, func_type: &FunctionType) -> bool {
+    func_type
+        .get_inputs()
+        .iter()
+        .chain(func_type.get_results().iter())
+        .any(|ty| is_i64(ctx, *ty))
+}
+
+/// Local variable after the legalization
+#[derive(Debug, Clone, Copy)]
+struct LocalSlot {
+    /// Index of the local (of the low limb for i64)
+    index: u32,
+    is_i64: bool,
+}
+
+/// `ozk` integer ops that pop two operands of the op type
+#[derive(Debug, Clone, Copy)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    And,
+    Or,
+    Xor,
+    Shl,
+    ShrU,
+    ShrS,
+    Eq,
+    Ne,
+    LtU,
+    LtS,
+    GtU,
+    GtS,
+    LeU,
+    LeS,
+    GeU,
+    GeS,
+}
+
+fn get_bin_op(ctx: &Context, op: &dyn Op) -> Option<(BinOp, Ptr<TypeObj>)> {
+    if let Some(op) = op.downcast_ref::<ozk::WrappingAddOp>() {
+        Some((BinOp::Add, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::WrappingSubOp>() {
+        Some((BinOp::Sub, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::WrappingMulOp>() {
+        Some((BinOp::Mul, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::AndOp>() {
+        Some((BinOp::And, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::OrOp>() {
+        Some((BinOp::Or, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::XorOp>() {
+        Some((BinOp::Xor, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::ShlOp>() {
+        Some((BinOp::Shl, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::ShrUOp>() {
+        Some((BinOp::ShrU, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::ShrSOp>() {
+        Some((BinOp::ShrS, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::EqOp>() {
+        Some((BinOp::Eq, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::NeOp>() {
+        Some((BinOp::Ne, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::LtUOp>() {
+        Some((BinOp::LtU, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::LtSOp>() {
+        Some((BinOp::LtS, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::GtUOp>() {
+        Some((BinOp::GtU, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::GtSOp>() {
+        Some((BinOp::GtS, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::LeUOp>() {
+        Some((BinOp::LeU, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::LeSOp>() {
+        Some((BinOp::LeS, op.get_type(ctx)))
+    } else if let Some(op) = op.downcast_ref::<ozk::GeUOp>() {
+        Some((BinOp::GeU, op.get_type(ctx)))
+    } else {
+        op.downcast_ref::<ozk::GeSOp>()
+            .map(|op| (BinOp::GeS, op.get_type(ctx)))
+    }
+}
+
+/// Build the i32 version of the op.
+fn i32_bin_op(ctx: &mut Context, bin_op: BinOp) -> Ptr<Operation> {
+    let ty = i32_type(ctx);
+    match bin_op {
+        BinOp::Add => ozk::WrappingAddOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::Sub => ozk::WrappingSubOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::Mul => ozk::WrappingMulOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::And => ozk::AndOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::Or => ozk::OrOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::Xor => ozk::XorOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::Shl => ozk::ShlOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::ShrU => ozk::ShrUOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::ShrS => ozk::ShrSOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::Eq => ozk::EqOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::Ne => ozk::NeOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::LtU => ozk::LtUOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::LtS => ozk::LtSOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::GtU => ozk::GtUOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::GtS => ozk::GtSOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::LeU => ozk::LeUOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::LeS => ozk::LeSOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::GeU => ozk::GeUOp::new_unlinked(ctx, ty).get_operation(),
+        BinOp::GeS => ozk::GeSOp::new_unlinked(ctx, ty).get_operation(),
+    }
+}
+
+// Scratch locals of the expansions (relative to the first scratch local).
+// The operands are popped into them, `b` being the top one.
+const A_LO: u32 = 0;
+const A_HI: u32 = 1;
+const B_LO: u32 = 2;
+const B_HI: u32 = 3;
+
+/// Scratch locals for the intermediate values
+const T0: u32 = 4;
+const T1: u32 = 5;
+const T2: u32 = 6;
+const T3: u32 = 7;
+const T4: u32 = 8;
+const T5: u32 = 9;
+
+struct I64Legalizer {
+    func_sym: String,
+    /// Set if any i64 value was split
+    changed: bool,
+    /// New slot of every original local (parameters first)
+    local_map: Vec<LocalSlot>,
+    /// Index of the first scratch local
+    scratch_base: u32,
+    /// Number of scratch locals used by the expansions
+    num_scratch: u32,
+    /// Types of the values popped by the `drop` ops
+    dropped: HashMap<Ptr<Operation>, StackType>,
+    /// Block the legalized ops are appended to
+    block: Ptr<BasicBlock>,
+}
+
+impl I64Legalizer {
+    fn error(&self, ctx: &Context, op: Ptr<Operation>, msg: &str) -> anyhow::Error {
+        anyhow!(
+            "wasm.func @{}: {}: {msg}",
+            self.func_sym,
+            op.deref(ctx).get_opid().with_ctx(ctx)
+        )
+    }
+
+    fn append(&self, ctx: &mut Context, op: Ptr<Operation>) {
+        op.insert_at_back(self.block, ctx);
+    }
+
+    /// Move the ops to the end of the given block, expanding the ones on i64.
+    fn legalize_ops(
+        &mut self,
+        ctx: &mut Context,
+        ops: Vec<Ptr<Operation>>,
+        block: Ptr<BasicBlock>,
+    ) -> Result<(), anyhow::Error> {
+        let parent_block = self.block;
+        self.block = block;
+        for op in &ops {
+            op.unlink(ctx);
+        }
+        for op in ops {
+            self.legalize_op(ctx, op)?;
+        }
+        self.block = parent_block;
+        Ok(())
+    }
+
+    fn legalize_op(&mut self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        if let Some(const_op) = opop.downcast_ref::<ozk::ConstantOp>() {
+            let value = const_op.get_value(ctx);
+            let i64_value = value
+                .downcast_ref::<IntegerAttr>()
+                .filter(|int_attr| is_i64(ctx, int_attr.get_type()))
+                .map(|int_attr| apint_to_i64(int_attr.clone().into()));
+            if let Some(value) = i64_value {
+                self.changed = true;
+                self.i32_const(ctx, value as i32);
+                self.i32_const(ctx, (value >> 32) as i32);
+            } else {
+                self.append(ctx, op);
+            }
+        } else if let Some((bin_op, ty)) = get_bin_op(ctx, &**opop) {
+            if is_i64(ctx, ty) {
+                self.changed = true;
+                self.expand_bin_op(ctx, bin_op);
+            } else {
+                self.append(ctx, op);
+            }
+        } else if let Some(eqz_op) = opop.downcast_ref::<ozk::EqzOp>() {
+            if is_i64(ctx, eqz_op.get_type(ctx)) {
+                self.changed = true;
+                self.emit_bin_op(ctx, BinOp::Or);
+                self.emit_eqz(ctx);
+            } else {
+                self.append(ctx, op);
+            }
+        } else if opop.is::<ozk::ExtendI32UOp>() {
+            self.changed = true;
+            self.i32_const(ctx, 0);
+        } else if opop.is::<ozk::ExtendI32SOp>() {
+            self.changed = true;
+            self.tee_scratch(ctx, T0);
+            self.get_scratch(ctx, T0);
+            self.i32_const(ctx, 31);
+            self.emit_bin_op(ctx, BinOp::ShrS);
+        } else if opop.is::<ozk::WrapI64Op>() {
+            self.changed = true;
+            self.emit_drop(ctx);
+        } else if opop.is::<ozk::DropOp>() {
+            match self.dropped.get(&op) {
+                Some(StackType::I64) => {
+                    self.changed = true;
+                    self.emit_drop(ctx);
+                    self.emit_drop(ctx);
+                }
+                Some(StackType::I32 | StackType::Felt) => self.append(ctx, op),
+                None => return Err(self.error(ctx, op, "unknown type of the dropped value")),
+            }
+        } else if let Some(load_op) = opop.downcast_ref::<ozk::LoadOp>() {
+            if is_i64(ctx, load_op.get_type(ctx)) {
+                self.changed = true;
+                let size = load_op.get_size(ctx);
+                let memarg = load_op.get_memarg(ctx);
+                if size == 8 {
+                    // [addr] -> [lo, hi], the low limb is at the lower address
+                    let hi_memarg = self.hi_limb_memarg(ctx, op, memarg)?;
+                    self.tee_scratch(ctx, T0);
+                    self.emit_i32_load(ctx, 4, false, lo_limb_memarg(memarg));
+                    self.get_scratch(ctx, T0);
+                    self.emit_i32_load(ctx, 4, false, hi_memarg);
+                } else {
+                    // [addr] -> [lo, hi], the high limb is the extension of the low one
+                    let signed = load_op.is_signed(ctx);
+                    self.emit_i32_load(ctx, size, signed && size < 4, memarg);
+                    if signed {
+                        self.tee_scratch(ctx, T0);
+                        self.get_scratch(ctx, T0);
+                        self.i32_const(ctx, 31);
+                        self.emit_bin_op(ctx, BinOp::ShrS);
+                    } else {
+                        self.i32_const(ctx, 0);
+                    }
+                }
+            } else {
+                self.append(ctx, op);
+            }
+        } else if let Some(store_op) = opop.downcast_ref::<ozk::StoreOp>() {
+            if is_i64(ctx, store_op.get_type(ctx)) {
+                self.changed = true;
+                let size = store_op.get_size(ctx);
+                let memarg = store_op.get_memarg(ctx);
+                if size == 8 {
+                    // [addr, lo, hi] -> []
+                    let hi_memarg = self.hi_limb_memarg(ctx, op, memarg)?;
+                    self.set_scratch(ctx, T1);
+                    self.set_scratch(ctx, T0);
+                    self.tee_scratch(ctx, T2);
+                    self.get_scratch(ctx, T0);
+                    self.emit_i32_store(ctx, 4, lo_limb_memarg(memarg));
+                    self.get_scratch(ctx, T2);
+                    self.get_scratch(ctx, T1);
+                    self.emit_i32_store(ctx, 4, hi_memarg);
+                } else {
+                    // [addr, lo, hi] -> [], only the low bytes are stored
+                    self.emit_drop(ctx);
+                    self.emit_i32_store(ctx, size, memarg);
+                }
+            } else {
+                self.append(ctx, op);
+            }
+        } else if let Some(local_get_op) = opop.downcast_ref::<ozk::LocalGetOp>() {
+            let slot = self.local_slot(ctx, op, local_get_op.get_index(ctx))?;
+            self.local_get(ctx, slot.index);
+            if slot.is_i64 {
+                self.local_get(ctx, slot.index + 1);
+            }
+        } else if let Some(local_set_op) = opop.downcast_ref::<ozk::LocalSetOp>() {
+            let slot = self.local_slot(ctx, op, local_set_op.get_index(ctx))?;
+            if slot.is_i64 {
+                self.local_set(ctx, slot.index + 1);
+            }
+            self.local_set(ctx, slot.index);
+        } else if let Some(local_tee_op) = opop.downcast_ref::<ozk::LocalTeeOp>() {
+            let slot = self.local_slot(ctx, op, local_tee_op.get_index(ctx))?;
+            if slot.is_i64 {
+                self.local_set(ctx, slot.index + 1);
+                self.local_tee(ctx, slot.index);
+                self.local_get(ctx, slot.index + 1);
+            } else {
+                self.local_tee(ctx, slot.index);
+            }
+        } else if let Some(call_op) = opop.downcast_ref::<ozk::CallOp>() {
+            let func_type = call_op.get_func_type(ctx);
+            if has_i64(ctx, &func_type) {
+                self.changed = true;
+                let func_sym = call_op.get_func_sym(ctx);
+                let new_func_type = legalize_func_type(ctx, &func_type);
+                #[allow(clippy::expect_used)]
+                let new_func_type = new_func_type
+                    .deref(ctx)
+                    .downcast_ref::<FunctionType>()
+                    .expect("function type")
+                    .clone();
+                let new_call_op = ozk::CallOp::new_unlinked(ctx, func_sym.into(), new_func_type);
+                self.append(ctx, new_call_op.get_operation());
+            } else {
+                self.append(ctx, op);
+            }
+        } else if let Some(block_op) = opop.downcast_ref::<ozk::BlockOp>() {
+            let ty = block_op.get_type(ctx);
+            let ops: Vec<Ptr<Operation>> = block_op.op_iter(ctx).collect();
+            let Some(block_type) = self.legalize_block_type(ctx, ty) else {
+                self.append(ctx, op);
+                return self.legalize_ops(ctx, ops, block_op.get_block(ctx));
+            };
+            let new_block_op = ozk::BlockOp::new_unlinked(ctx, block_type);
+            self.append(ctx, new_block_op.get_operation());
+            self.legalize_ops(ctx, ops, new_block_op.get_block(ctx))?;
+        } else if let Some(loop_op) = opop.downcast_ref::<ozk::LoopOp>() {
+            let ty = loop_op.get_type(ctx);
+            let ops: Vec<Ptr<Operation>> = loop_op.op_iter(ctx).collect();
+            let Some(loop_type) = self.legalize_block_type(ctx, ty) else {
+                self.append(ctx, op);
+                return self.legalize_ops(ctx, ops, loop_op.get_block(ctx));
+            };
+            let new_loop_op = ozk::LoopOp::new_unlinked(ctx, loop_type);
+            self.append(ctx, new_loop_op.get_operation());
+            self.legalize_ops(ctx, ops, new_loop_op.get_block(ctx))?;
+        } else {
+            self.append(ctx, op);
+        }
+        Ok(())
+    }
+
+    /// Returns the legalized block type if the given one has i64 inputs or results.
+    fn legalize_block_type(&mut self, ctx: &mut Context, ty: Ptr<TypeObj>) -> Option<Ptr<TypeObj>> {
+        let block_type = ty.deref(ctx).downcast_ref::<FunctionType>().cloned()?;
+        if !has_i64(ctx, &block_type) {
+            return None;
+        }
+        self.changed = true;
+        Some(legalize_func_type(ctx, &block_type))
+    }
+
+    fn local_slot(
+        &self,
+        ctx: &Context,
+        op: Ptr<Operation>,
+        index: u32,
+    ) -> Result<LocalSlot, anyhow::Error> {
+        self.local_map
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| self.error(ctx, op, &format!("undefined local {index}")))
+    }
+
+    /// Expand the op on two i64 operands (`[a_lo, a_hi, b_lo, b_hi]` on the stack).
+    fn expand_bin_op(&mut self, ctx: &mut Context, bin_op: BinOp) {
+        self.set_scratch(ctx, B_HI);
+        self.set_scratch(ctx, B_LO);
+        self.set_scratch(ctx, A_HI);
+        self.set_scratch(ctx, A_LO);
+        match bin_op {
+            BinOp::Add => self.expand_add(ctx),
+            BinOp::Sub => self.expand_sub(ctx),
+            BinOp::Mul => self.expand_mul(ctx),
+            BinOp::And | BinOp::Or | BinOp::Xor => {
+                self.limb_op(ctx, A_LO, B_LO, bin_op);
+                self.limb_op(ctx, A_HI, B_HI, bin_op);
+            }
+            BinOp::Shl => self.expand_shl(ctx),
+            BinOp::ShrU => self.expand_shr(ctx, false),
+            BinOp::ShrS => self.expand_shr(ctx, true),
+            BinOp::Eq => {
+                self.limb_op(ctx, A_LO, B_LO, BinOp::Eq);
+                self.limb_op(ctx, A_HI, B_HI, BinOp::Eq);
+                self.emit_bin_op(ctx, BinOp::And);
+            }
+            BinOp::Ne => {
+                self.limb_op(ctx, A_LO, B_LO, BinOp::Ne);
+                self.limb_op(ctx, A_HI, B_HI, BinOp::Ne);
+                self.emit_bin_op(ctx, BinOp::Or);
+            }
+            BinOp::LtU | BinOp::LtS | BinOp::GtU | BinOp::GtS => {
+                self.expand_ordering(ctx, bin_op, to_unsigned(bin_op))
+            }
+            BinOp::LeU => self.expand_ordering(ctx, BinOp::LtU, BinOp::LeU),
+            BinOp::LeS => self.expand_ordering(ctx, BinOp::LtS, BinOp::LeU),
+            BinOp::GeU => self.expand_ordering(ctx, BinOp::GtU, BinOp::GeU),
+            BinOp::GeS => self.expand_ordering(ctx, BinOp::GtS, BinOp::GeU),
+        }
+    }
+
+    fn expand_add(&mut self, ctx: &mut Context) {
+        // lo = a_lo + b_lo, the carry is set if lo wrapped around (lo < a_lo)
+        self.limb_op(ctx, A_LO, B_LO, BinOp::Add);
+        self.tee_scratch(ctx, T0);
+        self.get_scratch(ctx, T0);
+        self.get_scratch(ctx, A_LO);
+        self.emit_bin_op(ctx, BinOp::LtU);
+        // hi = carry + a_hi + b_hi
+        self.get_scratch(ctx, A_HI);
+        self.emit_bin_op(ctx, BinOp::Add);
+        self.get_scratch(ctx, B_HI);
+        self.emit_bin_op(ctx, BinOp::Add);
+    }
+
+    fn expand_sub(&mut self, ctx: &mut Context) {
+        // lo = a_lo - b_lo
+        self.limb_op(ctx, A_LO, B_LO, BinOp::Sub);
+        // hi = a_hi - b_hi - borrow, the borrow is set if a_lo < b_lo
+        self.limb_op(ctx, A_HI, B_HI, BinOp::Sub);
+        self.limb_op(ctx, A_LO, B_LO, BinOp::LtU);
+        self.emit_bin_op(ctx, BinOp::Sub);
+    }
+
+    fn expand_mul(&mut self, ctx: &mut Context) {
+        // a_lo and b_lo split into the 16-bit halves, so that the products fit in 32 bits
+        // T0 = a_lo & 0xFFFF, T1 = a_lo >> 16, T2 = b_lo & 0xFFFF, T3 = b_lo >> 16
+        for (src, low_half, high_half) in [(A_LO, T0, T1), (B_LO, T2, T3)] {
+            self.get_scratch(ctx, src);
+            self.i32_const(ctx, 0xFFFF);
+            self.emit_bin_op(ctx, BinOp::And);
+            self.set_scratch(ctx, low_half);
+            self.get_scratch(ctx, src);
+            self.i32_const(ctx, 16);
+            self.emit_bin_op(ctx, BinOp::ShrU);
+            self.set_scratch(ctx, high_half);
+        }
+        // the cross products
+        self.limb_op(ctx, T0, T3, BinOp::Mul);
+        self.set_scratch(ctx, T4);
+        self.limb_op(ctx, T1, T2, BinOp::Mul);
+        self.set_scratch(ctx, T5);
+        // lo = a_lo * b_lo
+        self.limb_op(ctx, A_LO, B_LO, BinOp::Mul);
+        // the high 32 bits of a_lo * b_lo (unsigned):
+        // T1 * T3 + (T4 >> 16) + (T5 >> 16)
+        //   + (((T0 * T2) >> 16) + (T4 & 0xFFFF) + (T5 & 0xFFFF)) >> 16
+        self.limb_op(ctx, T1, T3, BinOp::Mul);
+        for cross in [T4, T5] {
+            self.get_scratch(ctx, cross);
+            self.i32_const(ctx, 16);
+            self.emit_bin_op(ctx, BinOp::ShrU);
+            self.emit_bin_op(ctx, BinOp::Add);
+        }
+        self.limb_op(ctx, T0, T2, BinOp::Mul);
+        self.i32_const(ctx, 16);
+        self.emit_bin_op(ctx, BinOp::ShrU);
+        for cross in [T4, T5] {
+            self.get_scratch(ctx, cross);
+            self.i32_const(ctx, 0xFFFF);
+            self.emit_bin_op(ctx, BinOp::And);
+            self.emit_bin_op(ctx, BinOp::Add);
+        }
+        self.i32_const(ctx, 16);
+        self.emit_bin_op(ctx, BinOp::ShrU);
+        self.emit_bin_op(ctx, BinOp::Add);
+        // hi = mulhi(a_lo, b_lo) + a_lo * b_hi + a_hi * b_lo
+        self.limb_op(ctx, A_LO, B_HI, BinOp::Mul);
+        self.emit_bin_op(ctx, BinOp::Add);
+        self.limb_op(ctx, A_HI, B_LO, BinOp::Mul);
+        self.emit_bin_op(ctx, BinOp::Add);
+    }
+
+    /// `hi_cmp(a_hi, b_hi) | ((a_hi == b_hi) & lo_cmp(a_lo, b_lo))`,
+    /// where `hi_cmp` is the strict comparison and `lo_cmp` is unsigned.
+    fn expand_ordering(&mut self, ctx: &mut Context, hi_cmp: BinOp, lo_cmp: BinOp) {
+        self.limb_op(ctx, A_HI, B_HI, hi_cmp);
+        self.limb_op(ctx, A_HI, B_HI, BinOp::Eq);
+        self.limb_op(ctx, A_LO, B_LO, lo_cmp);
+        self.emit_bin_op(ctx, BinOp::And);
+        self.emit_bin_op(ctx, BinOp::Or);
+    }
+
+    /// Store the shift amount modulo 32 in T0, the mask that is all ones if the shift amount
+    /// modulo 64 is at least 32 in T1 and its complement in T2.
+    fn shift_amount(&mut self, ctx: &mut Context) {
+        self.get_scratch(ctx, B_LO);
+        self.i32_const(ctx, 31);
+        self.emit_bin_op(ctx, BinOp::And);
+        self.set_scratch(ctx, T0);
+        // T3 = (b_lo >> 5) & 1
+        self.get_scratch(ctx, B_LO);
+        self.i32_const(ctx, 5);
+        self.emit_bin_op(ctx, BinOp::ShrU);
+        self.i32_const(ctx, 1);
+        self.emit_bin_op(ctx, BinOp::And);
+        self.set_scratch(ctx, T3);
+        // T1 = 0 - T3
+        self.i32_const(ctx, 0);
+        self.get_scratch(ctx, T3);
+        self.emit_bin_op(ctx, BinOp::Sub);
+        self.set_scratch(ctx, T1);
+        // T2 = T3 - 1
+        self.get_scratch(ctx, T3);
+        self.i32_const(ctx, 1);
+        self.emit_bin_op(ctx, BinOp::Sub);
+        self.set_scratch(ctx, T2);
+    }
+
+    fn expand_shl(&mut self, ctx: &mut Context) {
+        self.shift_amount(ctx);
+        // lo = (a_lo << s) & !big
+        self.limb_op(ctx, A_LO, T0, BinOp::Shl);
+        self.get_scratch(ctx, T2);
+        self.emit_bin_op(ctx, BinOp::And);
+        // hi = (((a_hi << s) | ((a_lo >> 1) >> (31 - s))) & !big) | ((a_lo << s) & big)
+        self.limb_op(ctx, A_HI, T0, BinOp::Shl);
+        self.get_scratch(ctx, A_LO);
+        self.i32_const(ctx, 1);
+        self.emit_bin_op(ctx, BinOp::ShrU);
+        self.i32_const(ctx, 31);
+        self.get_scratch(ctx, T0);
+        self.emit_bin_op(ctx, BinOp::Sub);
+        self.emit_bin_op(ctx, BinOp::ShrU);
+        self.emit_bin_op(ctx, BinOp::Or);
+        self.get_scratch(ctx, T2);
+        self.emit_bin_op(ctx, BinOp::And);
+        self.limb_op(ctx, A_LO, T0, BinOp::Shl);
+        self.get_scratch(ctx, T1);
+        self.emit_bin_op(ctx, BinOp::And);
+        self.emit_bin_op(ctx, BinOp::Or);
+    }
+
+    fn expand_shr(&mut self, ctx: &mut Context, signed: bool) {
+        let shr_hi = if signed { BinOp::ShrS } else { BinOp::ShrU };
+        self.shift_amount(ctx);
+        // lo = (((a_lo >> s) | ((a_hi << 1) << (31 - s))) & !big) | ((a_hi >> s) & big)
+        self.limb_op(ctx, A_LO, T0, BinOp::ShrU);
+        self.get_scratch(ctx, A_HI);
+        self.i32_const(ctx, 1);
+        self.emit_bin_op(ctx, BinOp::Shl);
+        self.i32_const(ctx, 31);
+        self.get_scratch(ctx, T0);
+        self.emit_bin_op(ctx, BinOp::Sub);
+        self.emit_bin_op(ctx, BinOp::Shl);
+        self.emit_bin_op(ctx, BinOp::Or);
+        self.get_scratch(ctx, T2);
+        self.emit_bin_op(ctx, BinOp::And);
+        self.limb_op(ctx, A_HI, T0, shr_hi);
+        self.get_scratch(ctx, T1);
+        self.emit_bin_op(ctx, BinOp::And);
+        self.emit_bin_op(ctx, BinOp::Or);
+        // hi = ((a_hi >> s) & !big) | (sign & big), the sign is 0 for the unsigned shift
+        self.limb_op(ctx, A_HI, T0, shr_hi);
+        self.get_scratch(ctx, T2);
+        self.emit_bin_op(ctx, BinOp::And);
+        if signed {
+            self.get_scratch(ctx, A_HI);
+            self.i32_const(ctx, 31);
+            self.emit_bin_op(ctx, BinOp::ShrS);
+            self.get_scratch(ctx, T1);
+            self.emit_bin_op(ctx, BinOp::And);
+            self.emit_bin_op(ctx, BinOp::Or);
+        }
+    }
+
+    /// Push `lhs <op> rhs` of the scratch locals.
+    fn limb_op(&mut self, ctx: &mut Context, lhs: u32, rhs: u32, bin_op: BinOp) {
+        self.get_scratch(ctx, lhs);
+        self.get_scratch(ctx, rhs);
+        self.emit_bin_op(ctx, bin_op);
+    }
+
+    fn emit_bin_op(&self, ctx: &mut Context, bin_op: BinOp) {
+        let op = i32_bin_op(ctx, bin_op);
+        self.append(ctx, op);
+    }
+
+    fn emit_eqz(&self, ctx: &mut Context) {
+        let ty = i32_type(ctx);
+        let op = ozk::EqzOp::new_unlinked(ctx, ty).get_operation();
+        self.append(ctx, op);
+    }
+
+    fn emit_drop(&self, ctx: &mut Context) {
+        let op = ozk::DropOp::new_unlinked(ctx).get_operation();
+        self.append(ctx, op);
+    }
+
+    fn emit_i32_load(&self, ctx: &mut Context, size: u32, signed: bool, memarg: MemArg) {
+        let ty = i32_type(ctx);
+        let op = ozk::LoadOp::new_sized_unlinked(ctx, ty, size, signed, memarg).get_operation();
+        self.append(ctx, op);
+    }
+
+    fn emit_i32_store(&self, ctx: &mut Context, size: u32, memarg: MemArg) {
+        let ty = i32_type(ctx);
+        let op = ozk::StoreOp::new_sized_unlinked(ctx, ty, size, memarg).get_operation();
+        self.append(ctx, op);
+    }
+
+    /// The memarg of the high limb access of a whole i64 (4 bytes after the low limb).
+    fn hi_limb_memarg(
+        &self,
+        ctx: &Context,
+        op: Ptr<Operation>,
+        memarg: MemArg,
+    ) -> Result<MemArg, anyhow::Error> {
+        let offset = memarg
+            .offset
+            .checked_add(4)
+            .ok_or_else(|| self.error(ctx, op, "the high limb offset overflows"))?;
+        Ok(MemArg {
+            offset,
+            ..lo_limb_memarg(memarg)
+        })
+    }
+
+    fn i32_const(&self, ctx: &mut Context, value: i32) {
+        let value = i32_attr(ctx, value);
+        let op = ozk::ConstantOp::new_unlinked(ctx, value).get_operation();
+        self.append(ctx, op);
+    }
+
+    fn local_get(&self, ctx: &mut Context, index: u32) {
+        let op = ozk::LocalGetOp::new_unlinked(ctx, index).get_operation();
+        self.append(ctx, op);
+    }
+
+    fn local_set(&self, ctx: &mut Context, index: u32) {
+        let op = ozk::LocalSetOp::new_unlinked(ctx, index).get_operation();
+        self.append(ctx, op);
+    }
+
+    fn local_tee(&self, ctx: &mut Context, index: u32) {
+        let op = ozk::LocalTeeOp::new_unlinked(ctx, index).get_operation();
+        self.append(ctx, op);
+    }
+
+    fn scratch(&mut self, scratch: u32) -> u32 {
+        self.num_scratch = self.num_scratch.max(scratch + 1);
+        self.scratch_base + scratch
+    }
+
+    fn get_scratch(&mut self, ctx: &mut Context, scratch: u32) {
+        let index = self.scratch(scratch);
+        self.local_get(ctx, index);
+    }
+
+    fn set_scratch(&mut self, ctx: &mut Context, scratch: u32) {
+        let index = self.scratch(scratch);
+        self.local_set(ctx, index);
+    }
+
+    fn tee_scratch(&mut self, ctx: &mut Context, scratch: u32) {
+        let index = self.scratch(scratch);
+        self.local_tee(ctx, index);
+    }
+}
+
+/// The memarg of the low limb access of a whole i64, the limbs are at most 4-byte aligned.
+fn lo_limb_memarg(memarg: MemArg) -> MemArg {
+    MemArg {
+        offset: memarg.offset,
+        align: memarg.align.min(2),
+    }
+}
+
+/// The unsigned version of the (strict) comparison.
+fn to_unsigned(bin_op: BinOp) -> BinOp {
+    match bin_op {
+        BinOp::LtS => BinOp::LtU,
+        BinOp::GtS => BinOp::GtU,
+        BinOp::LeS => BinOp::LeU,
+        BinOp::GeS => BinOp::GeU,
+        BinOp::Add
+        | BinOp::Sub
+        | BinOp::Mul
+        | BinOp::And
+        | BinOp::Or
+        | BinOp::Xor
+        | BinOp::Shl
+        | BinOp::ShrU
+        | BinOp::ShrS
+        | BinOp::Eq
+        | BinOp::Ne
+        | BinOp::LtU
+        | BinOp::GtU
+        | BinOp::LeU
+        | BinOp::GeU => bin_op,
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::panic)]
+#[cfg(test)]
+mod tests {
+    use ozk_ozk_dialect::attributes::apint_to_i32;
+
+    use super::*;
+    use crate::ozk::lowering::WasmToOzkLoweringPass;
+    use crate::tests_util::as_wasm_module;
+    use crate::tests_util::run_wasm_passes;
+
+    const OPERANDS: [i64; 10] = [
+        0,
+        1,
+        -1,
+        0xFFFF_FFFF,
+        0x1_0000_0000,
+        0x8000_0000,
+        i64::MAX,
+        i64::MIN,
+        0x1234_5678_9ABC_DEF0,
+        -0x1234_5678_9ABC_DEF0,
+    ];
+
+    const SHIFT_AMOUNTS: [i64; 9] = [0, 1, 31, 32, 33, 63, 64, 65, -1];
+
+    /// Lower the function `f` of the module to `ozk` and legalize it.
+    fn legalize(ctx: &mut Context, func: &str) -> wasm::FuncOp {
+        let wat = format!(
+            r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        return)
+    {func}
+)"#
+        );
+        // checks that the legalized functions only have i32 values on the stack
+        let module_op = run_wasm_passes(
+            ctx,
+            &wat,
+            vec![
+                Box::<WasmToOzkLoweringPass>::default(),
+                Box::<LegalizeI64Pass>::default(),
+            ],
+        );
+        as_wasm_module(ctx, module_op)
+            .get_func(ctx, &"f".to_string().into())
+            .unwrap()
+    }
+
+    fn eval_i32(bin_op: BinOp, a: u32, b: u32) -> u32 {
+        let (sa, sb) = (a as i32, b as i32);
+        match bin_op {
+            BinOp::Add => a.wrapping_add(b),
+            BinOp::Sub => a.wrapping_sub(b),
+            BinOp::Mul => a.wrapping_mul(b),
+            BinOp::And => a & b,
+            BinOp::Or => a | b,
+            BinOp::Xor => a ^ b,
+            BinOp::Shl => a.wrapping_shl(b),
+            BinOp::ShrU => a.wrapping_shr(b),
+            BinOp::ShrS => sa.wrapping_shr(b) as u32,
+            BinOp::Eq => (a == b) as u32,
+            BinOp::Ne => (a != b) as u32,
+            BinOp::LtU => (a < b) as u32,
+            BinOp::LtS => (sa < sb) as u32,
+            BinOp::GtU => (a > b) as u32,
+            BinOp::GtS => (sa > sb) as u32,
+            BinOp::LeU => (a <= b) as u32,
+            BinOp::LeS => (sa <= sb) as u32,
+            BinOp::GeU => (a >= b) as u32,
+            BinOp::GeS => (sa >= sb) as u32,
+        }
+    }
+
+    /// Run the legalized function on an interpreter that only knows i32 ops.
+    fn run(ctx: &Context, func_op: &wasm::FuncOp, args: &[u32]) -> Vec<u32> {
+        let mut locals = args.to_vec();
+        locals.resize(args.len() + func_op.get_locals(ctx).len(), 0);
+        let mut stack: Vec<u32> = Vec::new();
+        let mut memory = vec![0u8; 64];
+        for op in func_op.op_iter(ctx) {
+            let opop = &op.deref(ctx).get_op(ctx);
+            if let Some(const_op) = opop.downcast_ref::<ozk::ConstantOp>() {
+                let value = const_op.get_value(ctx);
+                let int_attr = value.downcast_ref::<IntegerAttr>().unwrap();
+                stack.push(apint_to_i32(int_attr.clone().into()) as u32);
+            } else if let Some((bin_op, ty)) = get_bin_op(ctx, &**opop) {
+                assert!(!is_i64(ctx, ty), "i64 op left after legalization");
+                let b = stack.pop().unwrap();
+                let a = stack.pop().unwrap();
+                stack.push(eval_i32(bin_op, a, b));
+            } else if opop.is::<ozk::EqzOp>() {
+                let a = stack.pop().unwrap();
+                stack.push((a == 0) as u32);
+            } else if opop.is::<ozk::DropOp>() {
+                stack.pop().unwrap();
+            } else if let Some(load_op) = opop.downcast_ref::<ozk::LoadOp>() {
+                assert!(
+                    !is_i64(ctx, load_op.get_type(ctx)),
+                    "i64 load left after legalization"
+                );
+                let size = load_op.get_size(ctx) as usize;
+                let addr = (stack.pop().unwrap() + load_op.get_memarg(ctx).offset) as usize;
+                let mut bytes = [0u8; 4];
+                bytes[..size].copy_from_slice(&memory[addr..addr + size]);
+                let value = u32::from_le_bytes(bytes);
+                let shift = 32 - size as u32 * 8;
+                stack.push(if load_op.is_signed(ctx) {
+                    (((value << shift) as i32) >> shift) as u32
+                } else {
+                    value
+                });
+            } else if let Some(store_op) = opop.downcast_ref::<ozk::StoreOp>() {
+                assert!(
+                    !is_i64(ctx, store_op.get_type(ctx)),
+                    "i64 store left after legalization"
+                );
+                let size = store_op.get_size(ctx) as usize;
+                let value = stack.pop().unwrap();
+                let addr = (stack.pop().unwrap() + store_op.get_memarg(ctx).offset) as usize;
+                memory[addr..addr + size].copy_from_slice(&value.to_le_bytes()[..size]);
+            } else if let Some(local_get_op) = opop.downcast_ref::<ozk::LocalGetOp>() {
+                stack.push(locals[local_get_op.get_index(ctx) as usize]);
+            } else if let Some(local_set_op) = opop.downcast_ref::<ozk::LocalSetOp>() {
+                locals[local_set_op.get_index(ctx) as usize] = stack.pop().unwrap();
+            } else if let Some(local_tee_op) = opop.downcast_ref::<ozk::LocalTeeOp>() {
+                locals[local_tee_op.get_index(ctx) as usize] = *stack.last().unwrap();
+            } else if opop.is::<ozk::ReturnOp>() {
+                break;
+            } else {
+                panic!("unexpected op {}", op.with_ctx(ctx));
+            }
+        }
+        stack
+    }
+
+    fn limbs(value: i64) -> [u32; 2] {
+        [value as u32, (value >> 32) as u32]
+    }
+
+    /// Check the legalized `a <op> b` against the native i64 evaluation.
+    fn check_bin_op(op: &str, rhs_values: &[i64], expected: impl Fn(i64, i64) -> i64) {
+        let is_cmp = !matches!(
+            op,
+            "add" | "sub" | "mul" | "and" | "or" | "xor" | "shl" | "shr_u" | "shr_s"
+        );
+        let result_type = if is_cmp { "i32" } else { "i64" };
+        let mut ctx = Context::default();
+        let func_op = legalize(
+            &mut ctx,
+            &format!(
+                "(func $f (param i64 i64) (result {result_type})
+                    local.get 0
+                    local.get 1
+                    i64.{op})"
+            ),
+        );
+        for a in OPERANDS {
+            for b in rhs_values {
+                let [a_lo, a_hi] = limbs(a);
+                let [b_lo, b_hi] = limbs(*b);
+                let actual = run(&ctx, &func_op, &[a_lo, a_hi, b_lo, b_hi]);
+                let expected = expected(a, *b);
+                let expected = if is_cmp {
+                    vec![expected as u32]
+                } else {
+                    limbs(expected).to_vec()
+                };
+                assert_eq!(actual, expected, "i64.{op} {a:#x} {b:#x}");
+            }
+        }
+    }
+
+    #[test]
+    fn arith() {
+        check_bin_op("add", &OPERANDS, |a, b| a.wrapping_add(b));
+        check_bin_op("sub", &OPERANDS, |a, b| a.wrapping_sub(b));
+        check_bin_op("mul", &OPERANDS, |a, b| a.wrapping_mul(b));
+    }
+
+    #[test]
+    fn bitwise() {
+        check_bin_op("and", &OPERANDS, |a, b| a & b);
+        check_bin_op("or", &OPERANDS, |a, b| a | b);
+        check_bin_op("xor", &OPERANDS, |a, b| a ^ b);
+    }
+
+    #[test]
+    fn shifts() {
+        check_bin_op("shl", &SHIFT_AMOUNTS, |a, b| a.wrapping_shl(b as u32));
+        check_bin_op("shr_u", &SHIFT_AMOUNTS, |a, b| {
+            (a as u64).wrapping_shr(b as u32) as i64
+        });
+        check_bin_op("shr_s", &SHIFT_AMOUNTS, |a, b| a.wrapping_shr(b as u32));
+    }
+
+    #[test]
+    fn comparisons() {
+        check_bin_op("eq", &OPERANDS, |a, b| (a == b) as i64);
+        check_bin_op("ne", &OPERANDS, |a, b| (a != b) as i64);
+        check_bin_op("lt_u", &OPERANDS, |a, b| ((a as u64) < (b as u64)) as i64);
+        check_bin_op("lt_s", &OPERANDS, |a, b| (a < b) as i64);
+        check_bin_op("gt_u", &OPERANDS, |a, b| ((a as u64) > (b as u64)) as i64);
+        check_bin_op("gt_s", &OPERANDS, |a, b| (a > b) as i64);
+        check_bin_op("le_u", &OPERANDS, |a, b| ((a as u64) <= (b as u64)) as i64);
+        check_bin_op("le_s", &OPERANDS, |a, b| (a <= b) as i64);
+        check_bin_op("ge_u", &OPERANDS, |a, b| ((a as u64) >= (b as u64)) as i64);
+        check_bin_op("ge_s", &OPERANDS, |a, b| (a >= b) as i64);
+    }
+
+    #[test]
+    fn locals_constants_and_conversions() {
+        let mut ctx = Context::default();
+        let func_op = legalize(
+            &mut ctx,
+            r#"(func $f (param i32 i64) (result i64 i32)
+                (local i64)
+                local.get 1
+                i64.const 0x100000001
+                i64.mul
+                local.tee 2
+                drop
+                local.get 2
+                local.get 0
+                i64.extend_i32_s
+                i64.add
+                local.get 0
+                i64.extend_i32_u
+                i64.eqz
+                local.get 2
+                i32.wrap_i64
+                i32.add)"#,
+        );
+        for x in [0, 1, -1, i32::MIN] {
+            for y in OPERANDS {
+                let [y_lo, y_hi] = limbs(y);
+                let actual = run(&ctx, &func_op, &[x as u32, y_lo, y_hi]);
+                let local = y.wrapping_mul(0x1_0000_0001);
+                let mut expected = limbs(local.wrapping_add(x as i64)).to_vec();
+                expected.push(((x == 0) as u32).wrapping_add(local as u32));
+                assert_eq!(actual, expected, "{x} {y:#x}");
+            }
+        }
+    }
+
+    #[test]
+    fn memory_access() {
+        let mut ctx = Context::default();
+        let func_op = legalize(
+            &mut ctx,
+            r#"(func $f (param i32 i64) (result i64 i64 i64 i64)
+                local.get 0
+                local.get 1
+                i64.store offset=8
+                local.get 0
+                local.get 1
+                i64.store16 offset=4
+                local.get 0
+                i64.load offset=8
+                local.get 0
+                i64.load8_s offset=8
+                local.get 0
+                i64.load32_u offset=12
+                local.get 0
+                i64.load16_s offset=4)"#,
+        );
+        for x in [0, 4, 16] {
+            for y in OPERANDS {
+                let [y_lo, y_hi] = limbs(y);
+                let actual = run(&ctx, &func_op, &[x, y_lo, y_hi]);
+                let expected: Vec<u32> =
+                    [y, y as i8 as i64, (y >> 32) & 0xFFFF_FFFF, y as i16 as i64]
+                        .into_iter()
+                        .flat_map(limbs)
+                        .collect();
+                assert_eq!(actual, expected, "{x} {y:#x}");
+            }
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! Shared lowering of Wasm function bodies to the mid-level `ozk` dialect.
+//!
+//! The `wasm.module` and `wasm.func` ops are kept as containers, every op in the function
+//! bodies is replaced with its `ozk` counterpart. Each VM then only lowers `ozk` ops.
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::attributes::apint_to_u32;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::i64_type;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
+use pliron::rewrite::RewritePatternSet;
+use pliron::with_context::AttachContext;
+
+#[derive(Default)]
+pub struct WasmToOzkLoweringPass;
+
+impl Pass for WasmToOzkLoweringPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::<WasmOpsToOzkOps>::default());
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "wasm-lower-to-ozk",
+        description: "Lower Wasm ops in function bodies to the target-independent ozk dialect",
+        constructor: || Box::<WasmToOzkLoweringPass>::default(),
+    }
+}
+
+/// Converts all the ops in the function bodies of a Wasm module to `ozk` ops.
+/// Globals are expected to be converted to memory access (`wasm-globals-to-mem`) beforehand.
+#[derive(Default)]
+pub struct WasmOpsToOzkOps;
+
+impl RewritePattern for WasmOpsToOzkOps {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(module_op) = opop.downcast_ref::<wasm::ModuleOp>() else {
+            return Ok(false);
+        };
+        // post order, so that the ops in blocks and loops are converted before they are moved
+        let mut ops = Vec::new();
+        module_op
+            .get_operation()
+            .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+                ops.push(op);
+                WalkResult::Advance
+            });
+        let mut changed = false;
+        for op in ops {
+            let opop = &op.deref(ctx).get_op(ctx);
+            if let Some(func_op) = opop.downcast_ref::<wasm::FuncOp>() {
+                func_op.set_body_in_ozk(ctx);
+            } else if let Some(ozk_op) = lower_op(ctx, module_op, op)? {
+                rewriter.replace_op_with(ctx, op, ozk_op)?;
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+/// Build the `ozk` op for the given Wasm op.
+/// Returns `None` for the ops that are not lowered (module, functions and non-Wasm ops).
+fn lower_op(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    op: Ptr<Operation>,
+) -> Result<Option<Ptr<Operation>>, anyhow::Error> {
+    let opop = &op.deref(ctx).get_op(ctx);
+    let ozk_op = if let Some(const_op) = opop.downcast_ref::<wasm::ConstantOp>() {
+        let value = const_op.get_value(ctx);
+        if !value.is::<IntegerAttr>() {
+            return Err(anyhow!(
+                "only integer constants are supported, got {}",
+                value.with_ctx(ctx)
+            ));
+        }
+        ozk::ConstantOp::new_unlinked(ctx, value).get_operation()
+    } else if let Some(add_op) = opop.downcast_ref::<wasm::AddOp>() {
+        let ty = add_op.get_type(ctx);
+        ozk::WrappingAddOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(sub_op) = opop.downcast_ref::<wasm::SubOp>() {
+        let ty = sub_op.get_type(ctx);
+        ozk::WrappingSubOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(mul_op) = opop.downcast_ref::<wasm::MulOp>() {
+        let ty = mul_op.get_type(ctx);
+        ozk::WrappingMulOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(shl_op) = opop.downcast_ref::<wasm::ShlOp>() {
+        let ty = shl_op.get_type(ctx);
+        ozk::ShlOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(shr_u_op) = opop.downcast_ref::<wasm::ShrUOp>() {
+        let ty = shr_u_op.get_type(ctx);
+        ozk::ShrUOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(shr_s_op) = opop.downcast_ref::<wasm::ShrSOp>() {
+        let ty = shr_s_op.get_type(ctx);
+        ozk::ShrSOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(and_op) = opop.downcast_ref::<wasm::AndOp>() {
+        let ty = and_op.get_type(ctx);
+        ozk::AndOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(or_op) = opop.downcast_ref::<wasm::OrOp>() {
+        let ty = or_op.get_type(ctx);
+        ozk::OrOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(xor_op) = opop.downcast_ref::<wasm::XorOp>() {
+        let ty = xor_op.get_type(ctx);
+        ozk::XorOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(eq_op) = opop.downcast_ref::<wasm::EqOp>() {
+        let ty = eq_op.get_type(ctx);
+        ozk::EqOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(ne_op) = opop.downcast_ref::<wasm::NeOp>() {
+        let ty = ne_op.get_type(ctx);
+        ozk::NeOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(lt_u_op) = opop.downcast_ref::<wasm::LtUOp>() {
+        let ty = lt_u_op.get_type(ctx);
+        ozk::LtUOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(lt_s_op) = opop.downcast_ref::<wasm::LtSOp>() {
+        let ty = lt_s_op.get_type(ctx);
+        ozk::LtSOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(gt_u_op) = opop.downcast_ref::<wasm::GtUOp>() {
+        let ty = gt_u_op.get_type(ctx);
+        ozk::GtUOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(gt_s_op) = opop.downcast_ref::<wasm::GtSOp>() {
+        let ty = gt_s_op.get_type(ctx);
+        ozk::GtSOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(le_u_op) = opop.downcast_ref::<wasm::LeUOp>() {
+        let ty = le_u_op.get_type(ctx);
+        ozk::LeUOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(le_s_op) = opop.downcast_ref::<wasm::LeSOp>() {
+        let ty = le_s_op.get_type(ctx);
+        ozk::LeSOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(ge_u_op) = opop.downcast_ref::<wasm::GeUOp>() {
+        let ty = ge_u_op.get_type(ctx);
+        ozk::GeUOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(ge_s_op) = opop.downcast_ref::<wasm::GeSOp>() {
+        let ty = ge_s_op.get_type(ctx);
+        ozk::GeSOp::new_unlinked(ctx, ty).get_operation()
+    } else if opop.is::<wasm::I64EqzOp>() {
+        let ty = i64_type(ctx);
+        ozk::EqzOp::new_unlinked(ctx, ty).get_operation()
+    } else if opop.is::<wasm::I64ExtendI32UOp>() {
+        ozk::ExtendI32UOp::new_unlinked(ctx).get_operation()
+    } else if opop.is::<wasm::I64ExtendI32SOp>() {
+        ozk::ExtendI32SOp::new_unlinked(ctx).get_operation()
+    } else if opop.is::<wasm::I32WrapI64Op>() {
+        ozk::WrapI64Op::new_unlinked(ctx).get_operation()
+    } else if opop.is::<wasm::DropOp>() {
+        ozk::DropOp::new_unlinked(ctx).get_operation()
+    } else if opop.is::<wasm::I32EqzOp>() {
+        let ty = i32_type(ctx);
+        ozk::EqzOp::new_unlinked(ctx, ty).get_operation()
+    } else if let Some(load_op) = opop.downcast_ref::<wasm::LoadOp>() {
+        let ty = mem_access_type(ctx, load_op.get_value_type(ctx));
+        let size = load_op.get_size(ctx);
+        let signed = load_op.is_signed(ctx);
+        let memarg = load_op.get_memarg(ctx);
+        ozk::LoadOp::new_sized_unlinked(ctx, ty, size, signed, memarg).get_operation()
+    } else if let Some(store_op) = opop.downcast_ref::<wasm::StoreOp>() {
+        let ty = mem_access_type(ctx, store_op.get_value_type(ctx));
+        let size = store_op.get_size(ctx);
+        let memarg = store_op.get_memarg(ctx);
+        ozk::StoreOp::new_sized_unlinked(ctx, ty, size, memarg).get_operation()
+    } else if let Some(local_get_op) = opop.downcast_ref::<wasm::LocalGetOp>() {
+        let index = local_get_op.get_index(ctx).into();
+        ozk::LocalGetOp::new_unlinked(ctx, index).get_operation()
+    } else if let Some(local_set_op) = opop.downcast_ref::<wasm::LocalSetOp>() {
+        let index = local_set_op.get_index(ctx).into();
+        ozk::LocalSetOp::new_unlinked(ctx, index).get_operation()
+    } else if let Some(local_tee_op) = opop.downcast_ref::<wasm::LocalTeeOp>() {
+        let index_attr = local_tee_op.get_index(ctx);
+        let Some(index_attr) = index_attr.downcast_ref::<IntegerAttr>() else {
+            return Err(anyhow!("wasm.local.tee index is not an IntegerAttr"));
+        };
+        let index = apint_to_u32(index_attr.clone().into());
+        ozk::LocalTeeOp::new_unlinked(ctx, index).get_operation()
+    } else if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+        let ozk_block_op = ozk::BlockOp::new_unlinked(ctx, block_op.get_type(ctx));
+        let ops: Vec<Ptr<Operation>> = block_op.op_iter(ctx).collect();
+        move_ops(ctx, ops, ozk_block_op.get_block(ctx));
+        ozk_block_op.get_operation()
+    } else if let Some(loop_op) = opop.downcast_ref::<wasm::LoopOp>() {
+        let ozk_loop_op = ozk::LoopOp::new_unlinked(ctx, loop_op.get_type(ctx));
+        let ops: Vec<Ptr<Operation>> = loop_op.op_iter(ctx).collect();
+        move_ops(ctx, ops, ozk_loop_op.get_block(ctx));
+        ozk_loop_op.get_operation()
+    } else if let Some(br_op) = opop.downcast_ref::<wasm::BrOp>() {
+        let depth = br_op.get_relative_depth(ctx).into();
+        ozk::BrOp::new_unlinked(ctx, depth).get_operation()
+    } else if let Some(br_if_op) = opop.downcast_ref::<wasm::BrIfOp>() {
+        let depth = br_if_op.get_relative_depth(ctx).into();
+        ozk::BrIfOp::new_unlinked(ctx, depth).get_operation()
+    } else if opop.is::<wasm::ReturnOp>() {
+        ozk::ReturnOp::new_unlinked(ctx).get_operation()
+    } else if let Some(call_op) = opop.downcast_ref::<wasm::CallOp>() {
+        let func_index = call_op.get_func_index(ctx);
+        let func_sym = module_op
+            .get_func_sym(ctx, func_index)
+            .ok_or_else(|| anyhow!("no function with index {}", func_index))?;
+        // the imported functions have no body, the callee is resolved by the target
+        let func_type = match module_op.get_func(ctx, &func_sym) {
+            Some(func_op) => func_op.get_type(ctx),
+            None => module_op
+                .get_import_func_type(ctx, func_index)
+                .ok_or_else(|| anyhow!("no type for the function {}", func_sym))?,
+        };
+        ozk::CallOp::new_unlinked(ctx, func_sym, func_type).get_operation()
+    } else if opop.is::<wasm::GlobalGetOp>() || opop.is::<wasm::GlobalSetOp>() {
+        return Err(anyhow!(
+            "{} is not supported, convert globals to memory access first",
+            op.deref(ctx).get_opid().with_ctx(ctx)
+        ));
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(ozk_op))
+}
+
+fn mem_access_type(ctx: &mut Context, ty: MemAccessOpValueType) -> Ptr<TypeObj> {
+    match ty {
+        MemAccessOpValueType::I32 => i32_type(ctx),
+        MemAccessOpValueType::I64 => i64_type(ctx),
+    }
+}
+
+/// Move the (already lowered) ops to the end of the block.
+fn move_ops(ctx: &mut Context, ops: Vec<Ptr<Operation>>, block: Ptr<BasicBlock>) {
+    for op in ops {
+        op.unlink(ctx);
+        op.insert_at_back(block, ctx);
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests_util::count_ops;
+    use crate::tests_util::run_wasm_passes;
+
+    #[test]
+    fn lower_func_bodies() {
+        let wat = r#"
+(module
+    (start $main)
+    (func $inc (param i32) (result i32)
+        local.get 0
+        i32.const 1
+        i32.add)
+    (func $main
+        (local i32 i64)
+        block
+            loop
+                local.get 0
+                call $inc
+                local.tee 0
+                i32.eqz
+                br_if 1
+                br 0
+            end
+        end
+        i64.const 8
+        i64.const 1
+        i64.add
+        local.set 1
+        return)
+)"#;
+        let mut ctx = Context::default();
+        // checks the operand stack types of the lowered functions
+        let module_op =
+            run_wasm_passes(&mut ctx, wat, vec![Box::<WasmToOzkLoweringPass>::default()]);
+        assert_eq!(count_ops::<wasm::FuncOp>(&ctx, module_op), 2);
+        assert_eq!(count_ops::<wasm::BlockOp>(&ctx, module_op), 0);
+        assert_eq!(count_ops::<wasm::LoopOp>(&ctx, module_op), 0);
+        assert_eq!(count_ops::<wasm::CallOp>(&ctx, module_op), 0);
+        assert_eq!(count_ops::<wasm::AddOp>(&ctx, module_op), 0);
+        assert_eq!(count_ops::<ozk::BlockOp>(&ctx, module_op), 1);
+        assert_eq!(count_ops::<ozk::LoopOp>(&ctx, module_op), 1);
+        assert_eq!(count_ops::<ozk::CallOp>(&ctx, module_op), 1);
+        assert_eq!(count_ops::<ozk::WrappingAddOp>(&ctx, module_op), 2);
+        assert_eq!(count_ops::<ozk::LocalTeeOp>(&ctx, module_op), 1);
+        assert_eq!(count_ops::<ozk::EqzOp>(&ctx, module_op), 1);
+        assert_eq!(count_ops::<ozk::BrIfOp>(&ctx, module_op), 1);
+        assert_eq!(count_ops::<ozk::BrOp>(&ctx, module_op), 1);
+    }
+}
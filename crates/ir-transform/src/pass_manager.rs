@@ -0,0 +1,315 @@
+//! Pass manager that can verify the IR after each pass.
+
+use pliron::common_traits::Verify;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::operation::Operation;
+use pliron::pass::Pass;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PassManagerError {
+    #[error("IR verification failed before running any pass: {msg}")]
+    InvalidInput { msg: String },
+    #[error("IR verification failed after pass {pass}: {msg}")]
+    InvalidAfterPass { pass: String, msg: String },
+}
+
+/// Runs passes in the order they were added.
+/// When verification is enabled, the IR is verified before the first pass and after every pass,
+/// and the first pass that left the IR in an invalid state is reported.
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Box<dyn Pass>>,
+    verify_after_each_pass: bool,
+}
+
+impl PassManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a pass to the end of the pipeline.
+    pub fn add_pass(&mut self, pass: Box<dyn Pass>) {
+        self.passes.push(pass);
+    }
+
+    /// Enable or disable running the verifier after every pass.
+    pub fn set_verify_after_each_pass(&mut self, enabled: bool) {
+        self.verify_after_each_pass = enabled;
+    }
+
+    /// Returns true if the verifier is run after every pass.
+    pub fn is_verify_after_each_pass(&self) -> bool {
+        self.verify_after_each_pass
+    }
+
+    /// Run all passes on the given operation.
+    /// An error returned by a pass is annotated with the pass name.
+    pub fn run(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        if self.verify_after_each_pass {
+            verify_op(ctx, op).map_err(|msg| PassManagerError::InvalidInput { msg })?;
+        }
+        for pass in &self.passes {
+            pass.run_on_operation(ctx, op)
+                .map_err(|err| err.context(format!("pass {} failed", pass.name())))?;
+            if self.verify_after_each_pass {
+                verify_op(ctx, op).map_err(|msg| PassManagerError::InvalidAfterPass {
+                    pass: pass.name().to_string(),
+                    msg,
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn verify_op(ctx: &Context, op: Ptr<Operation>) -> Result<(), String> {
+    op.deref(ctx)
+        .get_op(ctx)
+        .verify(ctx)
+        .map_err(|err| format!("{err:?}"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::panic)]
+mod tests {
+    use expect_test::expect;
+    use ozk_ozk_dialect::attributes::i64_attr;
+    use ozk_wasm_dialect as wasm;
+    use ozk_wasm_dialect::types::MemAddress;
+    use pliron::op::Op;
+    use pliron::operation::WalkOrder;
+    use pliron::operation::WalkResult;
+
+    use super::*;
+    use crate::tests_util::try_run_wasm_passes;
+    use crate::wasm::explicit_func_args_pass::WasmExplicitFuncArgsPass;
+    use crate::wasm::globals_to_mem::WasmGlobalsToMemPass;
+    use crate::wasm::resolve_call_op::WasmCallOpToOzkCallOpPass;
+
+    /// Breaks the IR by appending a call to an undefined function to the start function.
+    struct CallUndefinedFuncPass;
+
+    impl Pass for CallUndefinedFuncPass {
+        fn run_on_operation(
+            &self,
+            ctx: &mut Context,
+            op: Ptr<Operation>,
+        ) -> Result<(), anyhow::Error> {
+            let mut module_ops = Vec::new();
+            op.walk_only::<wasm::ops::ModuleOp>(ctx, WalkOrder::PreOrder, &mut |module_op| {
+                module_ops.push(*module_op);
+                WalkResult::Advance
+            });
+            for module_op in module_ops {
+                let start_func = module_op
+                    .get_func(ctx, &module_op.get_start_func_sym(ctx).unwrap())
+                    .unwrap();
+                let call_op = wasm::ops::CallOp::new_unlinked(ctx, 42.into());
+                call_op
+                    .get_operation()
+                    .insert_at_front(start_func.get_entry_block(ctx), ctx);
+            }
+            Ok(())
+        }
+    }
+
+    /// Makes the first `wasm.const` in the module push an i64 value.
+    struct ConstToI64Pass;
+
+    impl Pass for ConstToI64Pass {
+        fn run_on_operation(
+            &self,
+            ctx: &mut Context,
+            op: Ptr<Operation>,
+        ) -> Result<(), anyhow::Error> {
+            let mut const_ops = Vec::new();
+            op.walk_only::<wasm::ops::ConstantOp>(ctx, WalkOrder::PreOrder, &mut |const_op| {
+                const_ops.push(*const_op);
+                WalkResult::Advance
+            });
+            let const_op = const_ops.first().unwrap();
+            let value = i64_attr(ctx, 1);
+            const_op
+                .get_operation()
+                .deref_mut(ctx)
+                .attributes
+                .insert(wasm::ops::ConstantOp::ATTR_KEY_VALUE, value);
+            Ok(())
+        }
+    }
+
+    /// Leaves an extra i32 value on the stack at the end of the first `wasm.block`.
+    struct PushInBlockPass;
+
+    impl Pass for PushInBlockPass {
+        fn run_on_operation(
+            &self,
+            ctx: &mut Context,
+            op: Ptr<Operation>,
+        ) -> Result<(), anyhow::Error> {
+            let mut block_ops = Vec::new();
+            op.walk_only::<wasm::ops::BlockOp>(ctx, WalkOrder::PreOrder, &mut |block_op| {
+                block_ops.push(*block_op);
+                WalkResult::Advance
+            });
+            let block_op = block_ops.first().unwrap();
+            let const_op = wasm::ops::ConstantOp::new_i32_unlinked(ctx, 1);
+            const_op
+                .get_operation()
+                .insert_at_back(block_op.get_block(ctx), ctx);
+            Ok(())
+        }
+    }
+
+    fn run_passes(
+        wat: &str,
+        passes: Vec<Box<dyn Pass>>,
+        verify: bool,
+    ) -> Result<(), anyhow::Error> {
+        let mut ctx = Context::default();
+        try_run_wasm_passes(&mut ctx, wat, passes, verify).map(|_| ())
+    }
+
+    fn verification_error_after_pass(err: anyhow::Error) -> (String, String) {
+        let err = err.downcast::<PassManagerError>().unwrap();
+        let PassManagerError::InvalidAfterPass { pass, msg } = err else {
+            panic!("unexpected error: {err}");
+        };
+        (pass, msg)
+    }
+
+    fn run_pipeline(verify: bool) -> Result<(), anyhow::Error> {
+        run_passes(
+            r#"
+(module
+    (start $main)
+    (func $main
+        return)
+)"#,
+            vec![
+                Box::<WasmExplicitFuncArgsPass>::default(),
+                Box::new(CallUndefinedFuncPass),
+            ],
+            verify,
+        )
+    }
+
+    #[test]
+    fn reports_pass_that_broke_ir() {
+        assert!(run_pipeline(false).is_ok());
+        let (pass, msg) = verification_error_after_pass(run_pipeline(true).unwrap_err());
+        assert!(pass.contains("CallUndefinedFuncPass"), "{pass}");
+        expect![[r#"VerificationError { msg: "unresolved callee in wasm.module: wasm.call 42" }"#]]
+            .assert_eq(&msg);
+    }
+
+    #[test]
+    fn typed_stack_accepts_control_flow() {
+        let wat = r#"
+(module
+    (start $main)
+    (global $g (mut i32) (i32.const 0))
+    (func $add (param i32 i64) (result i64)
+        local.get 1
+        local.get 1
+        i64.add)
+    (func $main
+        (local i32 i64)
+        block (result i32)
+            i32.const 1
+            local.get 0
+            i32.eqz
+            br_if 0
+        end
+        local.set 0
+        loop
+            global.get $g
+            i32.const 1
+            i32.add
+            global.set $g
+            local.get 0
+            br_if 0
+        end
+        i32.const 2
+        i64.const 3
+        call $add
+        local.set 1
+        return)
+)"#;
+        run_passes(
+            wat,
+            vec![
+                Box::<WasmExplicitFuncArgsPass>::default(),
+                Box::new(WasmGlobalsToMemPass::new(MemAddress::from(i32::MAX as u32))),
+                Box::<WasmCallOpToOzkCallOpPass>::default(),
+            ],
+            true,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn typed_stack_reports_op_type_mismatch() {
+        let wat = r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 1
+        i32.const 2
+        i32.add
+        local.set 0)
+)"#;
+        assert!(run_passes(wat, vec![Box::new(ConstToI64Pass)], false).is_ok());
+        let err = run_passes(wat, vec![Box::new(ConstToI64Pass)], true).unwrap_err();
+        let (_, msg) = verification_error_after_pass(err);
+        expect![[r#"VerificationError { msg: "wasm.func @main: wasm.add: expected i32 on the stack, found i64" }"#]]
+            .assert_eq(&msg);
+    }
+
+    #[test]
+    fn typed_stack_checks_ops_after_block_with_unknown_op() {
+        // the stack effect of the imported function call is unknown to the verifier
+        let wat = r#"
+(module
+    (import "env" "f" (func $f (param i32)))
+    (start $main)
+    (func $main
+        (local i32)
+        block
+            local.get 0
+            call $f
+        end
+        i32.const 1
+        i32.const 2
+        i32.add
+        local.set 0)
+)"#;
+        assert!(run_passes(wat, vec![Box::new(ConstToI64Pass)], false).is_ok());
+        let err = run_passes(wat, vec![Box::new(ConstToI64Pass)], true).unwrap_err();
+        let (_, msg) = verification_error_after_pass(err);
+        expect![[r#"VerificationError { msg: "wasm.func @main: wasm.add: expected i32 on the stack, found i64" }"#]]
+            .assert_eq(&msg);
+    }
+
+    #[test]
+    fn typed_stack_reports_block_results_mismatch() {
+        let wat = r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        block
+            i32.const 1
+            local.set 0
+        end)
+)"#;
+        let err = run_passes(wat, vec![Box::new(PushInBlockPass)], true).unwrap_err();
+        let (_, msg) = verification_error_after_pass(err);
+        expect![[r#"VerificationError { msg: "wasm.func @main: wasm.block: unexpected values left on the stack: [i32]" }"#]]
+            .assert_eq(&msg);
+    }
+}
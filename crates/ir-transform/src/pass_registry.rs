@@ -0,0 +1,95 @@
+//! Name-to-pass registry.
+//!
+//! Passes register themselves with [`inventory::submit!`] next to their definition,
+//! so that tools (like `ozk-opt`) can build a pipeline from a list of pass names,
+//! e.g. `wasm-track-stack-depth,valida-track-pc`.
+
+use pliron::pass::Pass;
+use thiserror::Error;
+
+/// A registered pass that can be looked up by its name.
+pub struct PassRegistration {
+    /// Unique name of the pass used in pipeline descriptions
+    pub name: &'static str,
+    /// One line description of the pass
+    pub description: &'static str,
+    /// Creates a new instance of the pass
+    pub constructor: fn() -> Box<dyn Pass>,
+}
+
+inventory::collect!(PassRegistration);
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PassRegistryError {
+    #[error("unknown pass: {0}")]
+    UnknownPass(String),
+    #[error("empty pass name in pipeline: {0:?}")]
+    EmptyPassName(String),
+}
+
+/// All registered passes sorted by name.
+pub fn registered_passes() -> Vec<&'static PassRegistration> {
+    let mut passes: Vec<&'static PassRegistration> =
+        inventory::iter::<PassRegistration>.into_iter().collect();
+    passes.sort_by_key(|reg| reg.name);
+    passes
+}
+
+/// Find a registered pass by its name.
+pub fn lookup_pass(name: &str) -> Option<&'static PassRegistration> {
+    inventory::iter::<PassRegistration>
+        .into_iter()
+        .find(|reg| reg.name == name)
+}
+
+/// Create the passes for a comma-separated list of pass names (in order).
+pub fn parse_pass_pipeline(pipeline: &str) -> Result<Vec<Box<dyn Pass>>, PassRegistryError> {
+    if pipeline.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    pipeline
+        .split(',')
+        .map(str::trim)
+        .map(|name| {
+            if name.is_empty() {
+                return Err(PassRegistryError::EmptyPassName(pipeline.to_string()));
+            }
+            lookup_pass(name)
+                .map(|reg| (reg.constructor)())
+                .ok_or_else(|| PassRegistryError::UnknownPass(name.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_pass_names_are_unique() {
+        let names: Vec<&str> = registered_passes().iter().map(|reg| reg.name).collect();
+        let mut deduped = names.clone();
+        deduped.dedup();
+        assert_eq!(names, deduped);
+        assert!(names.contains(&"wasm-track-stack-depth"));
+        assert!(names.contains(&"valida-track-pc"));
+    }
+
+    #[test]
+    fn parse_pipeline() {
+        let passes = parse_pass_pipeline("wasm-track-stack-depth, valida-track-pc").unwrap();
+        assert_eq!(passes.len(), 2);
+        assert!(parse_pass_pipeline("").unwrap().is_empty());
+        assert_eq!(
+            parse_pass_pipeline("wasm-track-stack-depth,no-such-pass").err(),
+            Some(PassRegistryError::UnknownPass("no-such-pass".to_string()))
+        );
+        assert_eq!(
+            parse_pass_pipeline("wasm-track-stack-depth,,valida-track-pc").err(),
+            Some(PassRegistryError::EmptyPassName(
+                "wasm-track-stack-depth,,valida-track-pc".to_string()
+            ))
+        );
+    }
+}
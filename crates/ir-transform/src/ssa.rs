@@ -0,0 +1,4 @@
+//! Conversions between the stack-based Wasm dialect and the value-based SSA dialect
+
+pub mod ssa_to_wasm;
+pub mod wasm_to_ssa;
@@ -0,0 +1,713 @@
+//! Stack scheduling: convert `ssa.func` back into a stack-based `wasm.func`.
+//!
+//! Every op is emitted as an expression tree: an operand stays on the stack (instead of
+//! going through a local) when it is used only once and its producer immediately precedes
+//! the consumer (or the consumer's other inlined operands) in the same block.
+//! All other values are kept in locals, except for the constants that are never used
+//! (e.g. the zero initializers of the locals), which are dropped.
+//! `ssa.block`/`ssa.loop` arguments and results are passed through locals as well,
+//! and `ssa.br_if` becomes a `block` that is skipped with `br_if` when the condition is zero.
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::types::i64_type;
+use ozk_ssa_dialect::ops as ssa;
+use ozk_ssa_dialect::ops::block_arguments;
+use ozk_ssa_dialect::ops::op_operands;
+use ozk_ssa_dialect::ops::op_results;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use ozk_wasm_dialect::types::GlobalIndex;
+use ozk_wasm_dialect::types::RelativeDepth;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
+use pliron::r#type::Typed;
+use pliron::rewrite::RewritePatternSet;
+use pliron::value::Value;
+use pliron::with_context::AttachContext;
+
+#[derive(Default)]
+pub struct SsaToWasmPass;
+
+impl Pass for SsaToWasmPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::<SsaToWasm>::default());
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "ssa-to-wasm",
+        description: "Schedule ssa.func bodies back onto the operand stack as wasm.func",
+        constructor: || Box::<SsaToWasmPass>::default(),
+    }
+}
+
+#[derive(Default)]
+pub struct SsaToWasm;
+
+impl RewritePattern for SsaToWasm {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(module_op) = opop.downcast_ref::<wasm::ModuleOp>() else {
+            return Ok(false);
+        };
+        let func_ops: Vec<ssa::FuncOp> = module_op
+            .get_body(ctx, 0)
+            .deref(ctx)
+            .iter(ctx)
+            .filter_map(|op| {
+                op.deref(ctx)
+                    .get_op(ctx)
+                    .downcast_ref::<ssa::FuncOp>()
+                    .cloned()
+            })
+            .collect();
+        if func_ops.is_empty() {
+            return Ok(false);
+        }
+        for func_op in func_ops {
+            let wasm_func_op = schedule_func(ctx, module_op, &func_op)?;
+            rewriter.replace_op_with(ctx, func_op.get_operation(), wasm_func_op.get_operation())?;
+        }
+        Ok(true)
+    }
+}
+
+fn schedule_func(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    func_op: &ssa::FuncOp,
+) -> Result<wasm::FuncOp, anyhow::Error> {
+    let mut uses: HashMap<Value, usize> = HashMap::new();
+    func_op
+        .get_operation()
+        .walk(ctx, WalkOrder::PreOrder, &mut |op| {
+            for value in op_operands(ctx, op) {
+                *uses.entry(value).or_default() += 1;
+            }
+            WalkResult::Advance
+        });
+    let ssa_entry_block = func_op.get_entry_block(ctx);
+    let params = block_arguments(ctx, ssa_entry_block);
+    let entry_block = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+    let mut scheduler = StackScheduler {
+        module_op: *module_op,
+        func_sym: func_op.get_symbol_name(ctx),
+        uses,
+        locals: params
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| (*value, idx as u32))
+            .collect(),
+        num_params: params.len() as u32,
+        local_types: Vec::new(),
+        block: entry_block,
+        frames: vec![Frame {
+            branch_slots: None,
+            yield_slots: Vec::new(),
+        }],
+    };
+    scheduler.schedule_block(ctx, ssa_entry_block)?;
+    Ok(wasm::FuncOp::new_unlinked_with_block(
+        ctx,
+        func_op.get_symbol_name(ctx).into(),
+        func_op.get_type_attr(ctx),
+        entry_block,
+        scheduler.local_types,
+    ))
+}
+
+/// Control flow frame (function body, `block` or `loop`)
+struct Frame {
+    /// Values whose locals receive the arguments of a branch to this frame.
+    /// `None` for the function body, where the branch arguments are returned on the stack.
+    branch_slots: Option<Vec<Value>>,
+    /// Values whose locals receive the operands of the `ssa.yield` ending this frame
+    yield_slots: Vec<Value>,
+}
+
+/// Operand of an expression tree
+enum TreeOperand {
+    /// Computed in place, right before the consumer
+    Tree(Tree),
+    /// Read from the local of the value
+    Local(Value),
+}
+
+/// An op with its operands
+struct Tree {
+    op: Ptr<Operation>,
+    operands: Vec<TreeOperand>,
+}
+
+struct StackScheduler {
+    module_op: wasm::ModuleOp,
+    func_sym: String,
+    /// Number of uses of every value in the function
+    uses: HashMap<Value, usize>,
+    /// Local index of the values that are kept in locals
+    locals: HashMap<Value, u32>,
+    num_params: u32,
+    /// Types of the locals allocated so far (besides the parameters)
+    local_types: Vec<Ptr<TypeObj>>,
+    /// Block the wasm ops are appended to
+    block: Ptr<BasicBlock>,
+    frames: Vec<Frame>,
+}
+
+impl StackScheduler {
+    fn error(&self, ctx: &Context, op: Ptr<Operation>, msg: String) -> anyhow::Error {
+        anyhow!(
+            "ssa.func @{}: {}: {msg}",
+            self.func_sym,
+            op.deref(ctx).get_opid().with_ctx(ctx)
+        )
+    }
+
+    fn append(&self, ctx: &mut Context, op: Ptr<Operation>) {
+        op.insert_at_back(self.block, ctx);
+    }
+
+    /// Local index of the value, allocated on the first request.
+    fn local_of(&mut self, ctx: &Context, value: Value) -> u32 {
+        if let Some(index) = self.locals.get(&value) {
+            return *index;
+        }
+        let index = self.num_params + self.local_types.len() as u32;
+        self.local_types.push(value.get_type(ctx));
+        self.locals.insert(value, index);
+        index
+    }
+
+    fn emit_local_get(&mut self, ctx: &mut Context, value: Value) {
+        let index = self.local_of(ctx, value);
+        let op = wasm::LocalGetOp::new_unlinked(ctx, index).get_operation();
+        self.append(ctx, op);
+    }
+
+    fn emit_local_set(&mut self, ctx: &mut Context, value: Value) {
+        let index = self.local_of(ctx, value);
+        let op = wasm::LocalSetOp::new_unlinked(ctx, index).get_operation();
+        self.append(ctx, op);
+    }
+
+    /// Pop the values from the stack into the locals of the `slots` (the last one is on top).
+    fn emit_set_slots(&mut self, ctx: &mut Context, slots: &[Value]) {
+        for slot in slots.iter().rev() {
+            self.emit_local_set(ctx, *slot);
+        }
+    }
+
+    fn is_single_use(&self, value: &Value) -> bool {
+        self.uses.get(value).copied() == Some(1)
+    }
+
+    fn is_unused_constant(&self, ctx: &Context, op: Ptr<Operation>) -> bool {
+        op.deref(ctx).get_op(ctx).is::<ssa::ConstantOp>()
+            && op_results(ctx, op)
+                .iter()
+                .all(|result| !self.uses.contains_key(result))
+    }
+
+    fn schedule_block(
+        &mut self,
+        ctx: &mut Context,
+        block: Ptr<BasicBlock>,
+    ) -> Result<(), anyhow::Error> {
+        let ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+        // trees whose single result is not consumed yet, in the program order
+        let mut pending: Vec<(Tree, Value)> = Vec::new();
+        for op in ops {
+            if self.is_unused_constant(ctx, op) {
+                continue;
+            }
+            let operands = op_operands(ctx, op);
+            let mut tree_operands: Vec<TreeOperand> = operands
+                .iter()
+                .map(|value| TreeOperand::Local(*value))
+                .collect();
+            if can_consume_trees(ctx, op) {
+                // only the trees right before the op can be computed in place,
+                // starting from the last operand
+                for (idx, value) in operands.iter().enumerate().rev() {
+                    match pending.last() {
+                        Some((_, result)) if result == value && self.is_single_use(value) => {
+                            #[allow(clippy::expect_used)]
+                            let (tree, _) = pending.pop().expect("pending tree");
+                            tree_operands[idx] = TreeOperand::Tree(tree);
+                        }
+                        _ => break,
+                    }
+                }
+            }
+            let tree = Tree {
+                op,
+                operands: tree_operands,
+            };
+            let results = op_results(ctx, op);
+            if can_consume_trees(ctx, op) && results.len() == 1 && self.is_single_use(&results[0]) {
+                // emitted when consumed (or flushed) after the trees before it
+                pending.push((tree, results[0]));
+            } else {
+                // the trees before the op keep the program order
+                for (tree, result) in pending.drain(..) {
+                    self.emit_tree(ctx, tree)?;
+                    self.emit_local_set(ctx, result);
+                }
+                let leaves_results_on_stack = can_consume_trees(ctx, op);
+                self.emit_tree(ctx, tree)?;
+                if leaves_results_on_stack {
+                    self.emit_set_slots(ctx, &results);
+                }
+            }
+        }
+        for (tree, result) in pending {
+            self.emit_tree(ctx, tree)?;
+            self.emit_local_set(ctx, result);
+        }
+        Ok(())
+    }
+
+    /// Emit the operands of the tree and then its op.
+    fn emit_tree(&mut self, ctx: &mut Context, tree: Tree) -> Result<(), anyhow::Error> {
+        let opop = tree.op.deref(ctx).get_op(ctx);
+        if let Some(br_if_op) = opop.downcast_ref::<ssa::BrIfOp>() {
+            return self.emit_br_if(ctx, br_if_op);
+        }
+        for operand in tree.operands {
+            match operand {
+                TreeOperand::Tree(tree) => self.emit_tree(ctx, tree)?,
+                TreeOperand::Local(value) => self.emit_local_get(ctx, value),
+            }
+        }
+        self.emit_op(ctx, tree.op)
+    }
+
+    /// Emit the op, its operands are on the stack.
+    fn emit_op(&mut self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        let new_op = if let Some(const_op) = opop.downcast_ref::<ssa::ConstantOp>() {
+            let value = const_op.get_value(ctx);
+            wasm::ConstantOp::new_unlinked(ctx, value).get_operation()
+        } else if let Some(new_op) = wasm_int_op(ctx, &*opop, op_operands(ctx, op)) {
+            new_op
+        } else if opop.is::<ssa::DropOp>() {
+            wasm::DropOp::new_unlinked(ctx).get_operation()
+        } else if let Some(call_op) = opop.downcast_ref::<ssa::CallOp>() {
+            let func_sym = call_op.get_func_sym(ctx);
+            let Some(func_index) = self.module_op.get_func_index(ctx, func_sym.clone()) else {
+                return Err(self.error(
+                    ctx,
+                    op,
+                    format!("undefined function {}", func_sym.as_ref()),
+                ));
+            };
+            wasm::CallOp::new_unlinked(ctx, func_index).get_operation()
+        } else if let Some(load_op) = opop.downcast_ref::<ssa::LoadOp>() {
+            let ty = self.mem_access_type(ctx, op, op_results(ctx, op)[0])?;
+            let size = load_op.get_size(ctx);
+            let signed = load_op.is_signed(ctx);
+            let memarg = load_op.get_memarg(ctx);
+            wasm::LoadOp::new_sized_unlinked(ctx, ty, size, signed, memarg).get_operation()
+        } else if let Some(store_op) = opop.downcast_ref::<ssa::StoreOp>() {
+            let ty = self.mem_access_type(ctx, op, op_operands(ctx, op)[1])?;
+            let size = store_op.get_size(ctx);
+            let memarg = store_op.get_memarg(ctx);
+            wasm::StoreOp::new_sized_unlinked(ctx, ty, size, memarg).get_operation()
+        } else if let Some(global_get_op) = opop.downcast_ref::<ssa::GlobalGetOp>() {
+            let index = global_get_op.get_index(ctx);
+            wasm::GlobalGetOp::new_unlinked(ctx, index).get_operation()
+        } else if let Some(global_set_op) = opop.downcast_ref::<ssa::GlobalSetOp>() {
+            let index = GlobalIndex::from(global_set_op.get_index(ctx));
+            wasm::GlobalSetOp::new_unlinked(ctx, index).get_operation()
+        } else if opop.downcast_ref::<ssa::ReturnOp>().is_some() {
+            wasm::ReturnOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<ssa::YieldOp>().is_some() {
+            #[allow(clippy::expect_used)]
+            let slots = self
+                .frames
+                .last()
+                .expect("no control frame")
+                .yield_slots
+                .clone();
+            self.emit_set_slots(ctx, &slots);
+            return Ok(());
+        } else if let Some(br_op) = opop.downcast_ref::<ssa::BrOp>() {
+            let depth = br_op.get_relative_depth(ctx);
+            let slots = self.branch_slots(ctx, op, depth)?;
+            self.emit_set_slots(ctx, &slots);
+            wasm::BrOp::new_unlinked(ctx, RelativeDepth::from(depth)).get_operation()
+        } else if let Some(block_op) = opop.downcast_ref::<ssa::BlockOp>() {
+            let entry_block = block_op.get_entry_block(ctx);
+            let results = op_results(ctx, op);
+            return self.emit_structured(ctx, false, entry_block, results.clone(), results);
+        } else if let Some(loop_op) = opop.downcast_ref::<ssa::LoopOp>() {
+            let entry_block = loop_op.get_entry_block(ctx);
+            let args = block_arguments(ctx, entry_block);
+            let results = op_results(ctx, op);
+            return self.emit_structured(ctx, true, entry_block, args, results);
+        } else {
+            return Err(self.error(ctx, op, "unsupported op".to_string()));
+        };
+        self.append(ctx, new_op);
+        Ok(())
+    }
+
+    fn mem_access_type(
+        &self,
+        ctx: &Context,
+        op: Ptr<Operation>,
+        value: Value,
+    ) -> Result<MemAccessOpValueType, anyhow::Error> {
+        let ty = value.get_type(ctx);
+        MemAccessOpValueType::from_type(ctx, ty).ok_or_else(|| {
+            self.error(
+                ctx,
+                op,
+                format!("unsupported memory access type {}", ty.with_ctx(ctx)),
+            )
+        })
+    }
+
+    /// Slots of the branch target at the given relative depth
+    /// (empty for the function body, its results stay on the stack).
+    fn branch_slots(
+        &self,
+        ctx: &Context,
+        op: Ptr<Operation>,
+        depth: u32,
+    ) -> Result<Vec<Value>, anyhow::Error> {
+        let depth = depth as usize;
+        if depth >= self.frames.len() {
+            return Err(self.error(ctx, op, format!("invalid branch depth {depth}")));
+        }
+        Ok(self.frames[self.frames.len() - 1 - depth]
+            .branch_slots
+            .clone()
+            .unwrap_or_default())
+    }
+
+    /// Emit `ssa.block`/`ssa.loop` as an empty-typed `block`/`loop`.
+    /// The operands (on the stack) are moved into the locals of the entry block arguments.
+    fn emit_structured(
+        &mut self,
+        ctx: &mut Context,
+        is_loop: bool,
+        entry_block: Ptr<BasicBlock>,
+        branch_slots: Vec<Value>,
+        yield_slots: Vec<Value>,
+    ) -> Result<(), anyhow::Error> {
+        let args = block_arguments(ctx, entry_block);
+        self.emit_set_slots(ctx, &args);
+        let empty_type = FunctionType::get(ctx, Vec::new(), Vec::new());
+        let (new_op, body) = if is_loop {
+            let loop_op = wasm::LoopOp::new_unlinked(ctx, empty_type);
+            (loop_op.get_operation(), loop_op.get_block(ctx))
+        } else {
+            let block_op = wasm::BlockOp::new_unlinked(ctx, empty_type);
+            (block_op.get_operation(), block_op.get_block(ctx))
+        };
+        self.append(ctx, new_op);
+        let outer_block = self.block;
+        self.block = body;
+        self.frames.push(Frame {
+            branch_slots: Some(branch_slots),
+            yield_slots,
+        });
+        self.schedule_block(ctx, entry_block)?;
+        self.frames.pop();
+        self.block = outer_block;
+        Ok(())
+    }
+
+    /// Emit `ssa.br_if` as
+    /// `block { local.get cond; i32.eqz; br_if 0; <args>; <set target slots>; br (depth + 1) }`.
+    fn emit_br_if(
+        &mut self,
+        ctx: &mut Context,
+        br_if_op: &ssa::BrIfOp,
+    ) -> Result<(), anyhow::Error> {
+        let op = br_if_op.get_operation();
+        let depth = br_if_op.get_relative_depth(ctx);
+        let slots = self.branch_slots(ctx, op, depth)?;
+        let empty_type = FunctionType::get(ctx, Vec::new(), Vec::new());
+        let block_op = wasm::BlockOp::new_unlinked(ctx, empty_type);
+        self.append(ctx, block_op.get_operation());
+        let outer_block = self.block;
+        self.block = block_op.get_block(ctx);
+        let condition = br_if_op.get_condition(ctx);
+        self.emit_local_get(ctx, condition);
+        let eqz_op = wasm::I32EqzOp::new_unlinked(ctx).get_operation();
+        self.append(ctx, eqz_op);
+        let skip_op = wasm::BrIfOp::new_unlinked(ctx, RelativeDepth::from(0)).get_operation();
+        self.append(ctx, skip_op);
+        for arg in br_if_op.get_args(ctx) {
+            self.emit_local_get(ctx, arg);
+        }
+        self.emit_set_slots(ctx, &slots);
+        let br_op = wasm::BrOp::new_unlinked(ctx, RelativeDepth::from(depth + 1)).get_operation();
+        self.append(ctx, br_op);
+        self.block = outer_block;
+        Ok(())
+    }
+}
+
+/// The wasm op of an `ssa` integer op (arithmetic, comparison or conversion) given its operands.
+fn wasm_int_op(ctx: &mut Context, op: &dyn Op, operands: Vec<Value>) -> Option<Ptr<Operation>> {
+    let ty = operands.first()?.get_type(ctx);
+    let new_op = if op.is::<ssa::AddOp>() {
+        wasm::AddOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::SubOp>() {
+        wasm::SubOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::MulOp>() {
+        wasm::MulOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::AndOp>() {
+        wasm::AndOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::OrOp>() {
+        wasm::OrOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::XorOp>() {
+        wasm::XorOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::ShlOp>() {
+        wasm::ShlOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::ShrUOp>() {
+        wasm::ShrUOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::ShrSOp>() {
+        wasm::ShrSOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::EqOp>() {
+        wasm::EqOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::NeOp>() {
+        wasm::NeOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::LtUOp>() {
+        wasm::LtUOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::LtSOp>() {
+        wasm::LtSOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::GtUOp>() {
+        wasm::GtUOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::GtSOp>() {
+        wasm::GtSOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::LeUOp>() {
+        wasm::LeUOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::LeSOp>() {
+        wasm::LeSOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::GeUOp>() {
+        wasm::GeUOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::GeSOp>() {
+        wasm::GeSOp::new_unlinked(ctx, ty).get_operation()
+    } else if op.is::<ssa::EqzOp>() {
+        if ty == i64_type(ctx) {
+            wasm::I64EqzOp::new_unlinked(ctx).get_operation()
+        } else {
+            wasm::I32EqzOp::new_unlinked(ctx).get_operation()
+        }
+    } else if op.is::<ssa::ExtendI32UOp>() {
+        wasm::I64ExtendI32UOp::new_unlinked(ctx).get_operation()
+    } else if op.is::<ssa::ExtendI32SOp>() {
+        wasm::I64ExtendI32SOp::new_unlinked(ctx).get_operation()
+    } else if op.is::<ssa::WrapI64Op>() {
+        wasm::I32WrapI64Op::new_unlinked(ctx).get_operation()
+    } else {
+        return None;
+    };
+    Some(new_op)
+}
+
+/// Returns true if the op takes its operands from the stack (possibly computed in place)
+/// and leaves its results on the stack.
+fn can_consume_trees(ctx: &Context, op: Ptr<Operation>) -> bool {
+    let opop = op.deref(ctx).get_op(ctx);
+    !(opop.is::<ssa::BlockOp>() || opop.is::<ssa::LoopOp>() || opop.is::<ssa::BrIfOp>())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::ssa::wasm_to_ssa::WasmToSsaPass;
+    use crate::tests_util::as_wasm_module;
+    use crate::tests_util::count_ops;
+    use crate::tests_util::func_op_names;
+    use crate::tests_util::render_func;
+    use crate::tests_util::run_wasm_passes;
+
+    const CONTROL_FLOW_WAT: &str = r#"
+(module
+    (start $main)
+    (memory 1)
+    (global $g (mut i32) (i32.const 0))
+    (func $add (param i32 i64) (result i64)
+        local.get 1
+        local.get 1
+        i64.add)
+    (func $main
+        (local i32 i64)
+        block (result i32)
+            i32.const 1
+            local.get 0
+            i32.eqz
+            br_if 0
+            local.set 0
+            i32.const 3
+        end
+        local.set 0
+        loop
+            global.get $g
+            i32.const 1
+            i32.add
+            local.tee 0
+            global.set $g
+            local.get 0
+            br_if 0
+        end
+        i32.const 8
+        i32.const 2
+        i64.const 3
+        call $add
+        i64.store
+        return)
+)"#;
+
+    #[test]
+    fn wasm_to_ssa_converts_all_funcs() {
+        let mut ctx = Context::default();
+        let module_op = run_wasm_passes(
+            &mut ctx,
+            CONTROL_FLOW_WAT,
+            vec![Box::<WasmToSsaPass>::default()],
+        );
+        assert_eq!(count_ops::<wasm::FuncOp>(&ctx, module_op), 0);
+        assert_eq!(count_ops::<ssa::FuncOp>(&ctx, module_op), 2);
+        // no stack ops are left
+        assert_eq!(count_ops::<wasm::LocalGetOp>(&ctx, module_op), 0);
+        assert_eq!(count_ops::<wasm::LocalSetOp>(&ctx, module_op), 0);
+        assert_eq!(count_ops::<ssa::BlockOp>(&ctx, module_op), 1);
+        assert_eq!(count_ops::<ssa::LoopOp>(&ctx, module_op), 1);
+        assert_eq!(count_ops::<ssa::BrIfOp>(&ctx, module_op), 2);
+    }
+
+    #[test]
+    fn ssa_round_trip_verifies() {
+        let mut ctx = Context::default();
+        let module_op = run_wasm_passes(
+            &mut ctx,
+            CONTROL_FLOW_WAT,
+            vec![
+                Box::<WasmToSsaPass>::default(),
+                Box::<SsaToWasmPass>::default(),
+            ],
+        );
+        assert_eq!(count_ops::<ssa::FuncOp>(&ctx, module_op), 0);
+        assert_eq!(count_ops::<wasm::FuncOp>(&ctx, module_op), 2);
+    }
+
+    #[test]
+    fn single_use_values_stay_on_the_stack() {
+        let wat = r#"
+(module
+    (start $main)
+    (func $main (param i32 i32) (result i32)
+        (local i32)
+        local.get 0
+        local.get 1
+        i32.add
+        local.set 2
+        local.get 2
+        i32.const 1
+        i32.add)
+)"#;
+        let mut ctx = Context::default();
+        let module_op = run_wasm_passes(
+            &mut ctx,
+            wat,
+            vec![
+                Box::<WasmToSsaPass>::default(),
+                Box::<SsaToWasmPass>::default(),
+            ],
+        );
+        // the declared local is replaced with the stack and its unused zero initializer
+        // is dropped
+        assert_eq!(
+            func_op_names(&ctx, &as_wasm_module(&ctx, module_op), "main"),
+            vec![
+                "wasm.local.get",
+                "wasm.local.get",
+                "wasm.add",
+                "wasm.const",
+                "wasm.add",
+                "wasm.return",
+            ]
+        );
+    }
+
+    #[test]
+    fn int_ops_and_sized_memory_access_round_trip() {
+        let wat = r#"
+(module
+    (start $main)
+    (memory 1)
+    (func $main (param i32 i64) (result i32)
+        local.get 0
+        local.get 1
+        i64.const 1
+        i64.sub
+        i64.store8 offset=4
+        local.get 0
+        i32.load16_s offset=2
+        local.get 0
+        i64.extend_i32_s
+        i32.wrap_i64
+        i32.lt_s
+        local.get 1
+        i64.eqz
+        drop)
+)"#;
+        let mut ctx = Context::default();
+        let module_op = run_wasm_passes(
+            &mut ctx,
+            wat,
+            vec![
+                Box::<WasmToSsaPass>::default(),
+                Box::<SsaToWasmPass>::default(),
+            ],
+        );
+        let ops = render_func(&ctx, &as_wasm_module(&ctx, module_op), "main");
+        assert!(ops.contains(&"wasm.store i64 8 offset=4".to_string()));
+        assert!(ops.contains(&"wasm.load i32 16_s offset=2".to_string()));
+        let names = func_op_names(&ctx, &as_wasm_module(&ctx, module_op), "main");
+        for name in [
+            "wasm.sub",
+            "wasm.i64.extend_i32_s",
+            "wasm.i32.wrap_i64",
+            "wasm.lt_s",
+            "wasm.i64.eqz",
+            "wasm.drop",
+        ] {
+            assert!(names.iter().any(|op| op == name), "{name} in {names:?}");
+        }
+    }
+}
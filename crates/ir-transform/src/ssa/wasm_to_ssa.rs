@@ -0,0 +1,591 @@
+//! Stack-to-SSA conversion.
+//!
+//! The operand stack and the locals of a `wasm.func` are simulated with SSA values
+//! while walking its body, so every wasm op becomes an `ssa` op taking its operands explicitly.
+//! Locals are threaded through the structured control flow: `ssa.block`/`ssa.loop` take
+//! the current local values (after the block inputs) as operands and return them
+//! (after the block results), and every branch passes them to its target.
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::attributes::apint_to_i32;
+use ozk_ozk_dialect::attributes::i32_attr;
+use ozk_ozk_dialect::attributes::i64_attr;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::i64_type;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_ssa_dialect::ops as ssa;
+use ozk_ssa_dialect::ops::block_arguments;
+use ozk_ssa_dialect::ops::op_results;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use ozk_wasm_dialect::typed_stack::StackType;
+use ozk_wasm_dialect::types::RelativeDepth;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
+use pliron::r#type::Typed;
+use pliron::rewrite::RewritePatternSet;
+use pliron::value::Value;
+use pliron::with_context::AttachContext;
+
+#[derive(Default)]
+pub struct WasmToSsaPass;
+
+impl Pass for WasmToSsaPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::<WasmToSsa>::default());
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "wasm-to-ssa",
+        description: "Convert wasm.func bodies into ssa.func (operands as SSA values)",
+        constructor: || Box::<WasmToSsaPass>::default(),
+    }
+}
+
+#[derive(Default)]
+pub struct WasmToSsa;
+
+impl RewritePattern for WasmToSsa {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(module_op) = opop.downcast_ref::<wasm::ModuleOp>() else {
+            return Ok(false);
+        };
+        let func_ops: Vec<wasm::FuncOp> = module_op
+            .get_body(ctx, 0)
+            .deref(ctx)
+            .iter(ctx)
+            .filter_map(|op| {
+                op.deref(ctx)
+                    .get_op(ctx)
+                    .downcast_ref::<wasm::FuncOp>()
+                    .cloned()
+            })
+            .collect();
+        if func_ops.is_empty() {
+            return Ok(false);
+        }
+        // collect the signatures up front since the callees are replaced one by one
+        let func_types: HashMap<String, Ptr<TypeObj>> = func_ops
+            .iter()
+            .map(|func_op| (func_op.get_symbol_name(ctx), func_op.get_type_attr(ctx)))
+            .collect();
+        for func_op in func_ops {
+            let ssa_func_op = convert_func(ctx, module_op, &func_types, &func_op)?;
+            rewriter.replace_op_with(ctx, func_op.get_operation(), ssa_func_op.get_operation())?;
+        }
+        Ok(true)
+    }
+}
+
+fn convert_func(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    func_types: &HashMap<String, Ptr<TypeObj>>,
+    func_op: &wasm::FuncOp,
+) -> Result<ssa::FuncOp, anyhow::Error> {
+    let func_sym = func_op.get_symbol_name(ctx);
+    let ssa_func_op =
+        ssa::FuncOp::new_unlinked(ctx, func_sym.clone().into(), func_op.get_type_attr(ctx));
+    let entry_block = ssa_func_op.get_entry_block(ctx);
+    let params = block_arguments(ctx, entry_block);
+    let num_results = func_op.get_type(ctx).get_results().len();
+    let mut converter = SsaConverter {
+        module_op: *module_op,
+        func_types,
+        func_sym,
+        block: entry_block,
+        stack: Vec::new(),
+        locals: params.clone(),
+        frames: vec![Frame {
+            kind: FrameKind::Func,
+            num_label_values: num_results,
+            height: 0,
+            unreachable: false,
+        }],
+    };
+    for ty in func_op.get_locals(ctx) {
+        let zero = converter.zero(ctx, ty)?;
+        converter.locals.push(zero);
+    }
+    if func_op.has_args_on_stack(ctx) {
+        // the first argument is on top of the stack
+        converter.stack = params.into_iter().rev().collect();
+    }
+    let ops: Vec<Ptr<Operation>> = func_op.op_iter(ctx).collect();
+    converter.convert_ops(ctx, ops)?;
+    if !converter.frame().unreachable {
+        let results = converter.pop_n("end of function", num_results)?;
+        converter.check_frame_stack_is_empty("end of function")?;
+        let return_op = ssa::ReturnOp::new_unlinked(ctx, results);
+        converter.append(ctx, return_op.get_operation());
+    }
+    Ok(ssa_func_op)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum FrameKind {
+    Func,
+    Block,
+    Loop,
+}
+
+/// Control flow frame (function body, `block` or `loop`)
+struct Frame {
+    kind: FrameKind,
+    /// Number of values (besides the locals) passed by a branch to this frame
+    num_label_values: usize,
+    /// Stack height at the start of this frame
+    height: usize,
+    /// Set after an unconditional branch, the rest of the frame is skipped
+    unreachable: bool,
+}
+
+struct SsaConverter<'a> {
+    module_op: wasm::ModuleOp,
+    func_types: &'a HashMap<String, Ptr<TypeObj>>,
+    func_sym: String,
+    /// Block the converted ops are appended to
+    block: Ptr<BasicBlock>,
+    stack: Vec<Value>,
+    locals: Vec<Value>,
+    frames: Vec<Frame>,
+}
+
+impl<'a> SsaConverter<'a> {
+    fn error(&self, location: &str, msg: String) -> anyhow::Error {
+        anyhow!("wasm.func @{}: {location}: {msg}", self.func_sym)
+    }
+
+    #[allow(clippy::expect_used)]
+    fn frame(&self) -> &Frame {
+        self.frames.last().expect("no control frame")
+    }
+
+    fn append(&self, ctx: &mut Context, op: Ptr<Operation>) {
+        op.insert_at_back(self.block, ctx);
+    }
+
+    /// Append the op and push its results on the stack.
+    fn append_and_push(&mut self, ctx: &mut Context, op: Ptr<Operation>) {
+        self.append(ctx, op);
+        self.stack.extend(op_results(ctx, op));
+    }
+
+    fn pop_n(&mut self, location: &str, n: usize) -> Result<Vec<Value>, anyhow::Error> {
+        if self.stack.len() < self.frame().height + n {
+            return Err(self.error(
+                location,
+                format!("expected {n} value(s) on the stack, found empty stack"),
+            ));
+        }
+        Ok(self.stack.split_off(self.stack.len() - n))
+    }
+
+    fn pop(&mut self, location: &str) -> Result<Value, anyhow::Error> {
+        Ok(self.pop_n(location, 1)?[0])
+    }
+
+    fn peek_n(&self, location: &str, n: usize) -> Result<Vec<Value>, anyhow::Error> {
+        if self.stack.len() < self.frame().height + n {
+            return Err(self.error(
+                location,
+                format!("expected {n} value(s) on the stack, found empty stack"),
+            ));
+        }
+        Ok(self.stack[self.stack.len() - n..].to_vec())
+    }
+
+    fn check_frame_stack_is_empty(&self, location: &str) -> Result<(), anyhow::Error> {
+        let extra = self.stack.len() - self.frame().height;
+        if extra != 0 {
+            return Err(self.error(
+                location,
+                format!("{extra} unexpected value(s) left on the stack"),
+            ));
+        }
+        Ok(())
+    }
+
+    fn set_unreachable(&mut self) {
+        #[allow(clippy::expect_used)]
+        let frame = self.frames.last_mut().expect("no control frame");
+        self.stack.truncate(frame.height);
+        frame.unreachable = true;
+    }
+
+    /// Values passed by a branch to the frame at the given relative depth.
+    fn branch_args(
+        &mut self,
+        location: &str,
+        depth: RelativeDepth,
+        pop: bool,
+    ) -> Result<Vec<Value>, anyhow::Error> {
+        let depth = u32::from(depth) as usize;
+        if depth >= self.frames.len() {
+            return Err(self.error(location, format!("invalid branch depth {depth}")));
+        }
+        let target = &self.frames[self.frames.len() - 1 - depth];
+        let (kind, num_label_values) = (target.kind, target.num_label_values);
+        let mut args = if pop {
+            self.pop_n(location, num_label_values)?
+        } else {
+            self.peek_n(location, num_label_values)?
+        };
+        if kind != FrameKind::Func {
+            args.extend(self.locals.iter().copied());
+        }
+        Ok(args)
+    }
+
+    fn local(&self, location: &str, index: u32) -> Result<Value, anyhow::Error> {
+        self.locals
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| self.error(location, format!("undefined local {index}")))
+    }
+
+    fn set_local(&mut self, location: &str, index: u32, value: Value) -> Result<(), anyhow::Error> {
+        let Some(local) = self.locals.get_mut(index as usize) else {
+            return Err(self.error(location, format!("undefined local {index}")));
+        };
+        *local = value;
+        Ok(())
+    }
+
+    /// Zero constant of the given type (initial value of the declared locals)
+    fn zero(&mut self, ctx: &mut Context, ty: Ptr<TypeObj>) -> Result<Value, anyhow::Error> {
+        let value = match StackType::from_type(ctx, ty) {
+            Some(StackType::I32) => i32_attr(ctx, 0),
+            Some(StackType::I64) => i64_attr(ctx, 0),
+            Some(StackType::Felt) | None => {
+                return Err(self.error(
+                    "locals",
+                    format!("unsupported local type {}", ty.with_ctx(ctx)),
+                ))
+            }
+        };
+        let const_op = ssa::ConstantOp::new_unlinked(ctx, value);
+        self.append(ctx, const_op.get_operation());
+        Ok(const_op.get_result(ctx))
+    }
+
+    fn convert_ops(
+        &mut self,
+        ctx: &mut Context,
+        ops: Vec<Ptr<Operation>>,
+    ) -> Result<(), anyhow::Error> {
+        for op in ops {
+            self.convert_op(ctx, op)?;
+            if self.frame().unreachable {
+                // the rest of the frame is dead code
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn convert_op(&mut self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let location = op.deref(ctx).get_opid().with_ctx(ctx).to_string();
+        let location = location.as_str();
+        let opop = op.deref(ctx).get_op(ctx);
+        if let Some(const_op) = opop.downcast_ref::<wasm::ConstantOp>() {
+            let value = const_op.get_value(ctx);
+            let new_op = ssa::ConstantOp::new_unlinked(ctx, value).get_operation();
+            self.append_and_push(ctx, new_op);
+        } else if let Some(build) = binary_op_builder(&*opop) {
+            let rhs = self.pop(location)?;
+            let lhs = self.pop(location)?;
+            let new_op = build(ctx, lhs, rhs);
+            self.append_and_push(ctx, new_op);
+        } else if let Some(build) = unary_op_builder(&*opop) {
+            let value = self.pop(location)?;
+            let new_op = build(ctx, value);
+            self.append_and_push(ctx, new_op);
+        } else if opop.is::<wasm::DropOp>() {
+            let value = self.pop(location)?;
+            let new_op = ssa::DropOp::new_unlinked(ctx, value).get_operation();
+            self.append(ctx, new_op);
+        } else if let Some(call_op) = opop.downcast_ref::<wasm::CallOp>() {
+            let func_index = call_op.get_func_index(ctx);
+            let Some(func_sym) = self.module_op.get_func_sym(ctx, func_index) else {
+                return Err(self.error(location, format!("undefined function {func_index}")));
+            };
+            self.convert_call(ctx, location, func_sym)?;
+        } else if let Some(call_op) = opop.downcast_ref::<ozk::CallOp>() {
+            let func_sym: FuncSym = call_op.get_func_sym(ctx).into();
+            self.convert_call(ctx, location, func_sym)?;
+        } else if let Some(swap_op) = opop.downcast_ref::<ozk::SwapOp>() {
+            let index = usize::from(swap_op.get_index(ctx));
+            if self.stack.len() <= self.frame().height + index {
+                return Err(self.error(
+                    location,
+                    format!("expected at least {} values on the stack", index + 1),
+                ));
+            }
+            let top = self.stack.len() - 1;
+            self.stack.swap(top, top - index);
+        } else if opop.downcast_ref::<wasm::ReturnOp>().is_some() {
+            #[allow(clippy::expect_used)]
+            let num_results = self
+                .frames
+                .first()
+                .expect("no function frame")
+                .num_label_values;
+            let results = self.pop_n(location, num_results)?;
+            let new_op = ssa::ReturnOp::new_unlinked(ctx, results).get_operation();
+            self.append(ctx, new_op);
+            self.set_unreachable();
+        } else if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+            let ops = block_op.op_iter(ctx).collect();
+            self.convert_structured(ctx, location, block_op.get_type(ctx), FrameKind::Block, ops)?;
+        } else if let Some(loop_op) = opop.downcast_ref::<wasm::LoopOp>() {
+            let ops = loop_op.op_iter(ctx).collect();
+            self.convert_structured(ctx, location, loop_op.get_type(ctx), FrameKind::Loop, ops)?;
+        } else if let Some(br_op) = opop.downcast_ref::<wasm::BrOp>() {
+            let depth = br_op.get_relative_depth(ctx);
+            let args = self.branch_args(location, depth, true)?;
+            let new_op = ssa::BrOp::new_unlinked(ctx, depth.into(), args).get_operation();
+            self.append(ctx, new_op);
+            self.set_unreachable();
+        } else if let Some(br_if_op) = opop.downcast_ref::<wasm::BrIfOp>() {
+            let depth = br_if_op.get_relative_depth(ctx);
+            let condition = self.pop(location)?;
+            let args = self.branch_args(location, depth, false)?;
+            let new_op =
+                ssa::BrIfOp::new_unlinked(ctx, depth.into(), condition, args).get_operation();
+            self.append(ctx, new_op);
+        } else if let Some(local_get_op) = opop.downcast_ref::<wasm::LocalGetOp>() {
+            let value = self.local(location, local_get_op.get_index(ctx).into())?;
+            self.stack.push(value);
+        } else if let Some(local_set_op) = opop.downcast_ref::<wasm::LocalSetOp>() {
+            let value = self.pop(location)?;
+            self.set_local(location, local_set_op.get_index(ctx).into(), value)?;
+        } else if let Some(local_tee_op) = opop.downcast_ref::<wasm::LocalTeeOp>() {
+            let index_attr = local_tee_op.get_index(ctx);
+            let Some(index_attr) = index_attr.downcast_ref::<IntegerAttr>() else {
+                return Err(self.error(location, "index is not an IntegerAttr".to_string()));
+            };
+            let index = apint_to_i32(index_attr.clone().into()) as u32;
+            let value = self.peek_n(location, 1)?[0];
+            self.set_local(location, index, value)?;
+        } else if let Some(global_get_op) = opop.downcast_ref::<wasm::GlobalGetOp>() {
+            let index = global_get_op.get_index(ctx);
+            let Some(ty) = self.module_op.get_global_type(ctx, index) else {
+                return Err(self.error(location, format!("undefined global {index}")));
+            };
+            let new_op = ssa::GlobalGetOp::new_unlinked(ctx, index.into(), ty).get_operation();
+            self.append_and_push(ctx, new_op);
+        } else if let Some(global_set_op) = opop.downcast_ref::<wasm::GlobalSetOp>() {
+            let index = global_set_op.get_index(ctx);
+            let value = self.pop(location)?;
+            let new_op = ssa::GlobalSetOp::new_unlinked(ctx, index.into(), value).get_operation();
+            self.append(ctx, new_op);
+        } else if let Some(load_op) = opop.downcast_ref::<wasm::LoadOp>() {
+            let ty = mem_access_type(ctx, load_op.get_value_type(ctx));
+            let size = load_op.get_size(ctx);
+            let signed = load_op.is_signed(ctx);
+            let memarg = load_op.get_memarg(ctx);
+            let address = self.pop(location)?;
+            let new_op = ssa::LoadOp::new_sized_unlinked(ctx, address, ty, size, signed, memarg)
+                .get_operation();
+            self.append_and_push(ctx, new_op);
+        } else if let Some(store_op) = opop.downcast_ref::<wasm::StoreOp>() {
+            let size = store_op.get_size(ctx);
+            let memarg = store_op.get_memarg(ctx);
+            let value = self.pop(location)?;
+            let address = self.pop(location)?;
+            let new_op =
+                ssa::StoreOp::new_sized_unlinked(ctx, address, value, size, memarg).get_operation();
+            self.append(ctx, new_op);
+        } else {
+            return Err(self.error(location, "unsupported op".to_string()));
+        }
+        Ok(())
+    }
+
+    fn convert_call(
+        &mut self,
+        ctx: &mut Context,
+        location: &str,
+        func_sym: FuncSym,
+    ) -> Result<(), anyhow::Error> {
+        let Some(func_type) = self.func_types.get(func_sym.as_ref()).copied() else {
+            return Err(self.error(
+                location,
+                format!("calls to imported function {} are not supported", func_sym.as_ref()),
+            ));
+        };
+        let num_args = func_type
+            .deref(ctx)
+            .downcast_ref::<FunctionType>()
+            .map_or(0, |func_type| func_type.get_inputs().len());
+        let args = self.pop_n(location, num_args)?;
+        let new_op = ssa::CallOp::new_unlinked(ctx, func_sym, func_type, args).get_operation();
+        self.append_and_push(ctx, new_op);
+        Ok(())
+    }
+
+    /// Convert `block`/`loop` into `ssa.block`/`ssa.loop` that takes the block inputs
+    /// followed by the locals and returns the block results followed by the locals.
+    fn convert_structured(
+        &mut self,
+        ctx: &mut Context,
+        location: &str,
+        ty: Ptr<TypeObj>,
+        kind: FrameKind,
+        ops: Vec<Ptr<Operation>>,
+    ) -> Result<(), anyhow::Error> {
+        let Some(block_type) = ty.deref(ctx).downcast_ref::<FunctionType>().cloned() else {
+            return Err(self.error(location, "block type is not a function type".to_string()));
+        };
+        let num_inputs = block_type.get_inputs().len();
+        let num_results = block_type.get_results().len();
+        let mut operands = self.pop_n(location, num_inputs)?;
+        operands.extend(self.locals.iter().copied());
+        let mut result_types = block_type.get_results().clone();
+        result_types.extend(self.locals.iter().map(|value| value.get_type(ctx)));
+        let (op, entry_block) = if kind == FrameKind::Loop {
+            let loop_op = ssa::LoopOp::new_unlinked(ctx, operands, result_types);
+            (loop_op.get_operation(), loop_op.get_entry_block(ctx))
+        } else {
+            let block_op = ssa::BlockOp::new_unlinked(ctx, operands, result_types);
+            (block_op.get_operation(), block_op.get_entry_block(ctx))
+        };
+        self.append(ctx, op);
+
+        let outer_block = self.block;
+        self.block = entry_block;
+        let mut args = block_arguments(ctx, entry_block);
+        self.locals = args.split_off(num_inputs);
+        self.frames.push(Frame {
+            kind,
+            num_label_values: if kind == FrameKind::Loop {
+                num_inputs
+            } else {
+                num_results
+            },
+            height: self.stack.len(),
+            unreachable: false,
+        });
+        self.stack.extend(args);
+        self.convert_ops(ctx, ops)?;
+        if !self.frame().unreachable {
+            let mut results = self.pop_n(location, num_results)?;
+            self.check_frame_stack_is_empty(location)?;
+            results.extend(self.locals.iter().copied());
+            let yield_op = ssa::YieldOp::new_unlinked(ctx, results);
+            self.append(ctx, yield_op.get_operation());
+        }
+        #[allow(clippy::expect_used)]
+        let frame = self.frames.pop().expect("no control frame");
+        self.stack.truncate(frame.height);
+        self.block = outer_block;
+
+        let mut results = op_results(ctx, op);
+        self.locals = results.split_off(num_results);
+        self.stack.extend(results);
+        Ok(())
+    }
+}
+
+/// Builds the `ssa` op of a wasm op that pops two values and pushes one
+type BinaryOpBuilder = fn(&mut Context, Value, Value) -> Ptr<Operation>;
+
+/// Builds the `ssa` op of a wasm op that pops one value and pushes one
+type UnaryOpBuilder = fn(&mut Context, Value) -> Ptr<Operation>;
+
+fn binary_op_builder(op: &dyn Op) -> Option<BinaryOpBuilder> {
+    let build: BinaryOpBuilder = if op.is::<wasm::AddOp>() {
+        |ctx, lhs, rhs| ssa::AddOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::SubOp>() {
+        |ctx, lhs, rhs| ssa::SubOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::MulOp>() {
+        |ctx, lhs, rhs| ssa::MulOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::AndOp>() {
+        |ctx, lhs, rhs| ssa::AndOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::OrOp>() {
+        |ctx, lhs, rhs| ssa::OrOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::XorOp>() {
+        |ctx, lhs, rhs| ssa::XorOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::ShlOp>() {
+        |ctx, lhs, rhs| ssa::ShlOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::ShrUOp>() {
+        |ctx, lhs, rhs| ssa::ShrUOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::ShrSOp>() {
+        |ctx, lhs, rhs| ssa::ShrSOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::EqOp>() {
+        |ctx, lhs, rhs| ssa::EqOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::NeOp>() {
+        |ctx, lhs, rhs| ssa::NeOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::LtUOp>() {
+        |ctx, lhs, rhs| ssa::LtUOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::LtSOp>() {
+        |ctx, lhs, rhs| ssa::LtSOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::GtUOp>() {
+        |ctx, lhs, rhs| ssa::GtUOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::GtSOp>() {
+        |ctx, lhs, rhs| ssa::GtSOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::LeUOp>() {
+        |ctx, lhs, rhs| ssa::LeUOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::LeSOp>() {
+        |ctx, lhs, rhs| ssa::LeSOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::GeUOp>() {
+        |ctx, lhs, rhs| ssa::GeUOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else if op.is::<wasm::GeSOp>() {
+        |ctx, lhs, rhs| ssa::GeSOp::new_unlinked(ctx, lhs, rhs).get_operation()
+    } else {
+        return None;
+    };
+    Some(build)
+}
+
+fn unary_op_builder(op: &dyn Op) -> Option<UnaryOpBuilder> {
+    let build: UnaryOpBuilder = if op.is::<wasm::I32EqzOp>() || op.is::<wasm::I64EqzOp>() {
+        |ctx, value| ssa::EqzOp::new_unlinked(ctx, value).get_operation()
+    } else if op.is::<wasm::I64ExtendI32UOp>() {
+        |ctx, value| ssa::ExtendI32UOp::new_unlinked(ctx, value).get_operation()
+    } else if op.is::<wasm::I64ExtendI32SOp>() {
+        |ctx, value| ssa::ExtendI32SOp::new_unlinked(ctx, value).get_operation()
+    } else if op.is::<wasm::I32WrapI64Op>() {
+        |ctx, value| ssa::WrapI64Op::new_unlinked(ctx, value).get_operation()
+    } else {
+        return None;
+    };
+    Some(build)
+}
+
+fn mem_access_type(ctx: &mut Context, ty: MemAccessOpValueType) -> Ptr<TypeObj> {
+    match ty {
+        MemAccessOpValueType::I32 => i32_type(ctx),
+        MemAccessOpValueType::I64 => i64_type(ctx),
+    }
+}
@@ -11,18 +11,88 @@ use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
 use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
 use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
 use pliron::pass::Pass;
 use pliron::with_context::AttachContext;
 
-pub fn check_wasm_pass<T: Pass>(pass: &T, wat: &str, expected: expect_test::Expect) {
+use crate::pass_manager::PassManager;
+
+/// Parse the Wat module, registering the dialects the passes can lower it to.
+pub fn parse_wat(ctx: &mut Context, wat: &str) -> wasm::ops::ModuleOp {
     let source = wat::parse_str(wat).unwrap();
-    let mut ctx = Context::default();
     let frontend_config = WasmFrontendConfig::default();
-    ozk_wasm_dialect::register(&mut ctx);
-    ozk_ozk_dialect::register(&mut ctx);
-    frontend_config.register(&mut ctx);
-    let wasm_module_op =
-        ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
+    frontend_config.register(ctx);
+    ozk_ozk_dialect::register(ctx);
+    ozk_miden_dialect::register(ctx);
+    ozk_valida_dialect::register(ctx);
+    ozk_ssa_dialect::register(ctx);
+    ozk_frontend_wasm::parse_module(ctx, &source, &frontend_config).unwrap()
+}
+
+/// Parse the Wat module and run the passes on it, verifying the IR after each pass.
+/// Returns the op the module was lowered to.
+pub fn run_wasm_passes(ctx: &mut Context, wat: &str, passes: Vec<Box<dyn Pass>>) -> Ptr<Operation> {
+    try_run_wasm_passes(ctx, wat, passes, true).unwrap()
+}
+
+/// Same as [run_wasm_passes], but returns the error of the pass manager and only verifies
+/// the IR after each pass if `verify` is set.
+pub fn try_run_wasm_passes(
+    ctx: &mut Context,
+    wat: &str,
+    passes: Vec<Box<dyn Pass>>,
+    verify: bool,
+) -> Result<Ptr<Operation>, anyhow::Error> {
+    let wasm_module_op = parse_wat(ctx, wat);
+    let wrapper_module = wrap_in_builtin_module(ctx, wasm_module_op);
+    let mut pass_manager = PassManager::new();
+    pass_manager.set_verify_after_each_pass(verify);
+    for pass in passes {
+        pass_manager.add_pass(pass);
+    }
+    pass_manager.run(ctx, wrapper_module.get_operation())?;
+    Ok(unwrap_from_builtin_module(ctx, wrapper_module))
+}
+
+pub fn as_wasm_module(ctx: &Context, op: Ptr<Operation>) -> wasm::ops::ModuleOp {
+    *op.deref(ctx)
+        .get_op(ctx)
+        .downcast::<wasm::ops::ModuleOp>()
+        .unwrap_or_else(|_| panic!("expected wasm.module"))
+}
+
+/// Number of `T` ops nested in `op` (including `op` itself).
+pub fn count_ops<T: Op>(ctx: &Context, op: Ptr<Operation>) -> usize {
+    let mut count = 0;
+    op.walk_only::<T>(ctx, WalkOrder::PreOrder, &mut |_| {
+        count += 1;
+        WalkResult::Advance
+    });
+    count
+}
+
+/// Render the ops in the body of the function (without the nested ops).
+pub fn render_func(ctx: &Context, module_op: &wasm::ops::ModuleOp, name: &str) -> Vec<String> {
+    let func_op = module_op.get_func(ctx, &name.to_string().into()).unwrap();
+    func_op
+        .op_iter(ctx)
+        .map(|op| op.deref(ctx).with_ctx(ctx).to_string())
+        .collect()
+}
+
+/// Names of the ops in the body of the function (without the nested ops).
+pub fn func_op_names(ctx: &Context, module_op: &wasm::ops::ModuleOp, name: &str) -> Vec<String> {
+    let func_op = module_op.get_func(ctx, &name.to_string().into()).unwrap();
+    func_op
+        .op_iter(ctx)
+        .map(|op| op.deref(ctx).get_opid().with_ctx(ctx).to_string())
+        .collect()
+}
+
+pub fn check_wasm_pass<T: Pass>(pass: &T, wat: &str, expected: expect_test::Expect) {
+    let mut ctx = Context::default();
+    let wasm_module_op = parse_wat(&mut ctx, wat);
     pass.run_on_operation(&mut ctx, wasm_module_op.get_operation())
         .unwrap();
     expected.assert_eq(wasm_module_op.with_ctx(&ctx).to_string().as_str());
@@ -33,15 +103,8 @@ pub fn check_wasm_valida_passes(
     wat: &str,
     expected: expect_test::Expect,
 ) {
-    let source = wat::parse_str(wat).unwrap();
     let mut ctx = Context::default();
-    let frontend_config = WasmFrontendConfig::default();
-    ozk_wasm_dialect::register(&mut ctx);
-    ozk_ozk_dialect::register(&mut ctx);
-    ozk_valida_dialect::register(&mut ctx);
-    frontend_config.register(&mut ctx);
-    let wasm_module_op =
-        ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
+    let wasm_module_op = parse_wat(&mut ctx, wat);
     let wrapper_module = wrap_in_builtin_module(&mut ctx, wasm_module_op);
     for pass in passes {
         eprintln!(" before {} pass:", pass.name());
@@ -53,7 +116,8 @@ pub fn check_wasm_valida_passes(
     expected.assert_eq(unwrapped_op.with_ctx(&ctx).to_string().as_str());
 }
 
-fn wrap_in_builtin_module(
+/// Wrap the module in a `builtin.module`, passes cannot replace the root op.
+pub fn wrap_in_builtin_module(
     ctx: &mut Context,
     wasm_module: wasm::ops::ModuleOp,
 ) -> builtin::ops::ModuleOp {
@@ -64,7 +128,7 @@ fn wrap_in_builtin_module(
     wrapper_module
 }
 
-fn unwrap_from_builtin_module(
+pub fn unwrap_from_builtin_module(
     ctx: &mut Context,
     builtin_module: builtin::ops::ModuleOp,
 ) -> Ptr<Operation> {
@@ -1,3 +1,4 @@
+use ozk_ozk_dialect::OZK_DIALECT;
 use ozk_valida_dialect::VALIDA_DIALECT;
 use ozk_wasm_dialect::WASM_DIALECT;
 use pliron::context::Context;
@@ -15,7 +16,7 @@ pub mod func_lowering;
 pub mod module_lowering;
 pub mod resolve_target_sym_to_pc;
 
-/// The pass that ensures there are no Wasm ops left.
+/// The pass that ensures there are no Wasm and `ozk` ops left.
 #[derive(Default)]
 pub struct WasmToValidaFinalLoweringPass;
 
@@ -23,6 +24,7 @@ impl Pass for WasmToValidaFinalLoweringPass {
     fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
         let mut target = ConversionTarget::default();
         target.add_illegal_dialect(WASM_DIALECT(ctx));
+        target.add_illegal_dialect(OZK_DIALECT(ctx));
         target.add_legal_dialect(VALIDA_DIALECT(ctx));
         #[allow(clippy::expect_used)]
         target.add_legal_dialect(
@@ -34,3 +36,11 @@ impl Pass for WasmToValidaFinalLoweringPass {
         Ok(())
     }
 }
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "valida-lower-final",
+        description: "Ensure there are no Wasm and ozk ops left after lowering to Valida",
+        constructor: || Box::<WasmToValidaFinalLoweringPass>::default(),
+    }
+}
@@ -2,8 +2,9 @@
 #![allow(dead_code)]
 
 use anyhow::anyhow;
+use ozk_ozk_dialect as ozk;
+use ozk_ozk_dialect::types::i32_type;
 use ozk_valida_dialect as valida;
-use ozk_wasm_dialect as wasm;
 use ozk_wasm_dialect::op_interfaces::TrackedStackDepth;
 use pliron::context::Context;
 use pliron::context::Ptr;
@@ -36,6 +37,14 @@ impl Pass for WasmToValidaArithLoweringPass {
     }
 }
 
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "valida-lower-arith",
+        description: "Lower ozk constants and arithmetic ops to Valida ops",
+        constructor: || Box::<WasmToValidaArithLoweringPass>::default(),
+    }
+}
+
 #[derive(Default)]
 pub struct ConstantOpLowering {}
 
@@ -44,7 +53,7 @@ impl RewritePattern for ConstantOpLowering {
         Ok(op
             .deref(ctx)
             .get_op(ctx)
-            .downcast_ref::<wasm::ops::ConstantOp>()
+            .downcast_ref::<ozk::ops::ConstantOp>()
             .is_some())
     }
 
@@ -56,7 +65,7 @@ impl RewritePattern for ConstantOpLowering {
         rewriter: &mut dyn PatternRewriter,
     ) -> Result<(), anyhow::Error> {
         let opop = &op.deref(ctx).get_op(ctx);
-        if let Some(const_op) = opop.downcast_ref::<wasm::ops::ConstantOp>() {
+        if let Some(const_op) = opop.downcast_ref::<ozk::ops::ConstantOp>() {
             let value = const_op.get_value(ctx);
             if let Ok(value_attr) = value.downcast::<IntegerAttr>() {
                 // TODO: Note that because a full 32-bit value does not fit within one field element, we assume that values have been decomposed into 4 8-byte elements
@@ -88,7 +97,7 @@ impl RewritePattern for ArithOpLowering {
         Ok(op
             .deref(ctx)
             .get_op(ctx)
-            .downcast_ref::<wasm::ops::AddOp>()
+            .downcast_ref::<ozk::ops::WrappingAddOp>()
             .is_some())
     }
 
@@ -100,8 +109,11 @@ impl RewritePattern for ArithOpLowering {
         rewriter: &mut dyn PatternRewriter,
     ) -> Result<(), anyhow::Error> {
         let opop = &op.deref(ctx).get_op(ctx);
-        if let Some(wasm_add_op) = opop.downcast_ref::<wasm::ops::AddOp>() {
-            let wasm_stack_depth_before_op = wasm_add_op.get_stack_depth(ctx);
+        if let Some(add_op) = opop.downcast_ref::<ozk::ops::WrappingAddOp>() {
+            if add_op.get_type(ctx) != i32_type(ctx) {
+                return Err(anyhow!("only 32-bit integers are supported"));
+            }
+            let wasm_stack_depth_before_op = add_op.get_stack_depth(ctx);
             // add wasm pops 2 values and pushes 1,
             // so the result ends up on the first argument stack slot
             let result_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1());
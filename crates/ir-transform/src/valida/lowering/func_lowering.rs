@@ -1,4 +1,7 @@
 use anyhow::Ok;
+use ozk::ops::LocalGetOp;
+use ozk::ops::LocalSetOp;
+use ozk::ops::ReturnOp;
 use ozk_ozk_dialect as ozk;
 use ozk_valida_dialect as valida;
 use ozk_wasm_dialect as wasm;
@@ -17,9 +20,6 @@ use pliron::pattern_match::RewritePattern;
 use pliron::rewrite::RewritePatternSet;
 use valida::types::Operands;
 use wasm::op_interfaces::TrackedStackDepth;
-use wasm::ops::LocalGetOp;
-use wasm::ops::LocalSetOp;
-use wasm::ops::ReturnOp;
 
 use crate::valida::fp_from_wasm_stack;
 
@@ -37,6 +37,14 @@ impl Pass for WasmToValidaFuncLoweringPass {
     }
 }
 
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "valida-lower-func",
+        description: "Lower Wasm functions, ozk locals and returns to Valida",
+        constructor: || Box::<WasmToValidaFuncLoweringPass>::default(),
+    }
+}
+
 #[derive(Default)]
 pub struct FuncOpLowering {}
 
@@ -164,7 +172,7 @@ fn convert_func_arg_and_locals(
         });
     let fp_func_first_arg: i32 = 12;
     for local_get_op in local_get_ops {
-        let zero_based_index: i32 = u32::from(local_get_op.get_index(ctx)) as i32;
+        let zero_based_index: i32 = local_get_op.get_index(ctx) as i32;
         let wasm_stack_depth_before_op = local_get_op.get_stack_depth(ctx);
         let to_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.next()).into();
         let from_fp: i32 =
@@ -187,7 +195,7 @@ fn convert_func_arg_and_locals(
             WalkResult::Advance
         });
     for local_set_op in local_set_ops {
-        let zero_based_index: i32 = u32::from(local_set_op.get_index(ctx)) as i32;
+        let zero_based_index: i32 = local_set_op.get_index(ctx) as i32;
         let wasm_stack_depth_before_op = local_set_op.get_stack_depth(ctx);
         let from_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op).into();
         let to_fp: i32 = -(zero_based_index + 1) * 4;
@@ -201,6 +209,7 @@ fn convert_func_arg_and_locals(
 mod tests {
     use expect_test::expect;
 
+    use crate::ozk::lowering::WasmToOzkLoweringPass;
     use crate::tests_util::check_wasm_valida_passes;
     use crate::valida::lowering::arith_op_lowering::WasmToValidaArithLoweringPass;
     use crate::wasm::track_stack_depth::WasmTrackStackDepthPass;
@@ -211,6 +220,7 @@ mod tests {
     fn func_op_lowering() {
         check_wasm_valida_passes(
             vec![
+                Box::<WasmToOzkLoweringPass>::default(),
                 Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
                 Box::<WasmToValidaFuncLoweringPass>::default(),
             ],
@@ -236,16 +246,17 @@ mod tests {
                       entry():
                         valida.sw 0 -4(fp) 12(fp) 0 0
                         valida.sw 0 -8(fp) 16(fp) 0 0
-                        wasm.add
+                        ozk.wrapping_add si32
                         valida.sw 0 16(fp) -4(fp) 0 0
                         valida.jalv -4(fp) 0(fp) 4(fp) 0 0
                     }
                     valida.func @main {
                       entry():
-                        wasm.const 0x3: si32
-                        wasm.const 0x4: si32
-                        wasm.call 0
-                        valida.sw 0 8(fp) -8(fp) 0 0
+                        ozk.constant 0x3: si32
+                        ozk.constant 0x4: si32
+                        valida.imm32 -16(fp) 0 0 0 20
+                        valida.jalsym -20(fp) add -20 0 0
+                        valida.sw 0 8(fp) -4(fp) 0 0
                         valida.jalv -4(fp) 0(fp) 4(fp) 0 0
                     }
                 }"#]],
@@ -256,6 +267,7 @@ mod tests {
     fn smoke_local_var_access() {
         check_wasm_valida_passes(
             vec![
+                Box::<WasmToOzkLoweringPass>::default(),
                 Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
                 Box::<WasmToValidaArithLoweringPass>::default(),
                 Box::<WasmToValidaFuncLoweringPass>::default(),
@@ -1,3 +1,4 @@
+use anyhow::anyhow;
 use ozk_valida_dialect as valida;
 use ozk_wasm_dialect as wasm;
 use pliron::basic_block::BasicBlock;
@@ -29,6 +30,14 @@ impl Pass for WasmToValidaModuleLoweringPass {
     }
 }
 
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "valida-lower-module",
+        description: "Lower Wasm module to Valida program",
+        constructor: || Box::<WasmToValidaModuleLoweringPass>::default(),
+    }
+}
+
 #[derive(Default)]
 pub struct ModuleLowering {}
 
@@ -52,6 +61,9 @@ impl RewritePattern for ModuleLowering {
         let Some(wasm_module_op) = opop.downcast_ref::<wasm::ops::ModuleOp>() else {
             panic!("expected ModuleOp");
         };
+        let main_func_sym = wasm_module_op
+            .get_start_func_sym(ctx)
+            .ok_or_else(|| anyhow!("the module has no start function"))?;
         let mut func_ops = Vec::new();
         for func_op in wasm_module_op.get_body(ctx, 0).deref(ctx).iter(ctx) {
             func_ops.push(func_op);
@@ -60,7 +72,6 @@ impl RewritePattern for ModuleLowering {
         for op in &func_ops {
             op.unlink(ctx);
         }
-        let main_func_sym = wasm_module_op.get_start_func_sym(ctx);
         let entry_block = build_prog_entry_block(ctx, main_func_sym.into());
         let prog_op = valida::ops::ProgramOp::new(ctx, entry_block, func_ops);
         rewriter.replace_op_with(ctx, wasm_module_op.get_operation(), prog_op.get_operation())?;
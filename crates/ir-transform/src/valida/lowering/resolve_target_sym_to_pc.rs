@@ -35,6 +35,14 @@ impl Pass for ValidaResolveTargetSymToPcPass {
     }
 }
 
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "valida-resolve-target-sym",
+        description: "Replace valida.jalsym with valida.jal to the callee program counter",
+        constructor: || Box::<ValidaResolveTargetSymToPcPass>::default(),
+    }
+}
+
 #[derive(Default)]
 struct ValidaResolveTargetSymToPc;
 
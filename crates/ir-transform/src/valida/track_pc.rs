@@ -32,6 +32,14 @@ impl Pass for ValidaTrackProgramCounterPass {
     }
 }
 
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "valida-track-pc",
+        description: "Annotate Valida ops with their program counter",
+        constructor: || Box::<ValidaTrackProgramCounterPass>::default(),
+    }
+}
+
 #[derive(Default)]
 pub struct ValidaTrackProgramCounter;
 
@@ -79,11 +87,11 @@ mod tests {
 
     use expect_test::expect;
 
+    use crate::ozk::lowering::WasmToOzkLoweringPass;
     use crate::tests_util::check_wasm_valida_passes;
     use crate::valida::lowering::arith_op_lowering::WasmToValidaArithLoweringPass;
     use crate::valida::lowering::func_lowering::WasmToValidaFuncLoweringPass;
     use crate::valida::lowering::module_lowering::WasmToValidaModuleLoweringPass;
-    use crate::wasm::resolve_call_op::WasmCallOpToOzkCallOpPass;
     use crate::wasm::track_stack_depth::WasmTrackStackDepthPass;
 
     use super::*;
@@ -92,7 +100,7 @@ mod tests {
     fn smoke_track_pc() {
         check_wasm_valida_passes(
             vec![
-                Box::<WasmCallOpToOzkCallOpPass>::default(),
+                Box::<WasmToOzkLoweringPass>::default(),
                 Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
                 Box::<WasmToValidaArithLoweringPass>::default(),
                 Box::<WasmToValidaFuncLoweringPass>::default(),
@@ -1,6 +1,11 @@
 //! Wasm conversions
 
+pub mod canonicalize;
 pub mod explicit_func_args_pass;
 pub mod globals_to_mem;
+pub mod inline;
+pub mod locals_to_mem;
+pub mod pseudo_ops;
+pub mod spill_stack;
 pub mod resolve_call_op;
 pub mod track_stack_depth;
@@ -0,0 +1,948 @@
+//! Canonicalization of the Wasm function bodies with peephole rewrites of short op sequences:
+//! constant folding, identity elimination, strength reduction and
+//! `local.set`/`local.get` pairs.
+//!
+//! Every [PeepholeRule] is applied to every position of the blocks of `wasm.func`, `wasm.block`
+//! and `wasm.loop` until none of them matches.
+
+use std::rc::Rc;
+
+use ozk_ozk_dialect::attributes::apint_to_i32;
+use ozk_ozk_dialect::attributes::apint_to_i64;
+use ozk_ozk_dialect::attributes::i32_attr;
+use ozk_ozk_dialect::attributes::i64_attr;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::attr_interfaces::TypedAttrInterface;
+use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::dialects::builtin::types::IntegerType;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
+use pliron::rewrite::RewritePatternSet;
+
+/// A rewrite of a short sequence of ops
+pub trait PeepholeRule {
+    /// Match the ops at the start of `ops` and build their replacement.
+    /// Returns the number of the matched ops and the ops to put in their place.
+    fn apply(
+        &self,
+        ctx: &mut Context,
+        ops: &[Ptr<Operation>],
+    ) -> Option<(usize, Vec<Ptr<Operation>>)>;
+}
+
+/// The longest sequence of ops any of the rules matches
+const MAX_WINDOW: usize = 4;
+
+pub struct WasmCanonicalizePass {
+    rules: Rc<Vec<Box<dyn PeepholeRule>>>,
+}
+
+impl WasmCanonicalizePass {
+    /// Canonicalize with the given rules only.
+    pub fn new(rules: Vec<Box<dyn PeepholeRule>>) -> Self {
+        Self {
+            rules: Rc::new(rules),
+        }
+    }
+}
+
+impl Default for WasmCanonicalizePass {
+    fn default() -> Self {
+        Self::new(default_rules())
+    }
+}
+
+/// All the rules of this module
+pub fn default_rules() -> Vec<Box<dyn PeepholeRule>> {
+    vec![
+        Box::new(ConstantFolding),
+        Box::new(IdentityElimination),
+        Box::new(StrengthReduction),
+        Box::new(LocalPairs),
+    ]
+}
+
+impl Pass for WasmCanonicalizePass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::new(WasmPeephole {
+            rules: self.rules.clone(),
+        }));
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "wasm-canonicalize",
+        description: "Fold constants, drop identity ops, reduce strength and merge local.set/local.get pairs",
+        constructor: || Box::<WasmCanonicalizePass>::default(),
+    }
+}
+
+/// Applies the rules to the block of a `wasm.func`, `wasm.block` or `wasm.loop`.
+struct WasmPeephole {
+    rules: Rc<Vec<Box<dyn PeepholeRule>>>,
+}
+
+impl RewritePattern for WasmPeephole {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let block = if let Some(func_op) = opop.downcast_ref::<wasm::FuncOp>() {
+            func_op.get_entry_block(ctx)
+        } else if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+            block_op.get_block(ctx)
+        } else if let Some(loop_op) = opop.downcast_ref::<wasm::LoopOp>() {
+            loop_op.get_block(ctx)
+        } else {
+            return Ok(false);
+        };
+        rewrite_block(ctx, rewriter, block, &self.rules)
+    }
+}
+
+/// Apply the rules until none matches. Returns true if the block was changed.
+fn rewrite_block(
+    ctx: &mut Context,
+    rewriter: &mut dyn PatternRewriter,
+    block: Ptr<BasicBlock>,
+    rules: &[Box<dyn PeepholeRule>],
+) -> Result<bool, anyhow::Error> {
+    let mut ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+    let mut changed = false;
+    let mut pos = 0;
+    while pos < ops.len() {
+        match rules.iter().find_map(|rule| rule.apply(ctx, &ops[pos..])) {
+            Some((matched, replacement)) => {
+                rewriter.set_insertion_point(ops[pos]);
+                for new_op in &replacement {
+                    rewriter.insert_before(ctx, *new_op)?;
+                }
+                for old_op in ops.splice(pos..pos + matched, replacement) {
+                    rewriter.erase_op(ctx, old_op)?;
+                }
+                changed = true;
+                // the replacement might complete a sequence that starts before it
+                pos = pos.saturating_sub(MAX_WINDOW - 1);
+            }
+            None => pos += 1,
+        }
+    }
+    Ok(changed)
+}
+
+/// Integer ops that pop two operands of the op type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    And,
+    Or,
+    Xor,
+    Shl,
+    ShrU,
+    ShrS,
+    Eq,
+    Ne,
+    LtU,
+    LtS,
+    GtU,
+    GtS,
+    LeU,
+    LeS,
+    GeU,
+    GeS,
+}
+
+fn get_bin_op(ctx: &Context, op: Ptr<Operation>) -> Option<(BinOp, Ptr<TypeObj>)> {
+    let opop = &op.deref(ctx).get_op(ctx);
+    if let Some(op) = opop.downcast_ref::<wasm::AddOp>() {
+        Some((BinOp::Add, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::SubOp>() {
+        Some((BinOp::Sub, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::MulOp>() {
+        Some((BinOp::Mul, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::AndOp>() {
+        Some((BinOp::And, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::OrOp>() {
+        Some((BinOp::Or, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::XorOp>() {
+        Some((BinOp::Xor, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::ShlOp>() {
+        Some((BinOp::Shl, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::ShrUOp>() {
+        Some((BinOp::ShrU, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::ShrSOp>() {
+        Some((BinOp::ShrS, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::EqOp>() {
+        Some((BinOp::Eq, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::NeOp>() {
+        Some((BinOp::Ne, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::LtUOp>() {
+        Some((BinOp::LtU, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::LtSOp>() {
+        Some((BinOp::LtS, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::GtUOp>() {
+        Some((BinOp::GtU, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::GtSOp>() {
+        Some((BinOp::GtS, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::LeUOp>() {
+        Some((BinOp::LeU, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::LeSOp>() {
+        Some((BinOp::LeS, op.get_type(ctx)))
+    } else if let Some(op) = opop.downcast_ref::<wasm::GeUOp>() {
+        Some((BinOp::GeU, op.get_type(ctx)))
+    } else {
+        opop.downcast_ref::<wasm::GeSOp>()
+            .map(|op| (BinOp::GeS, op.get_type(ctx)))
+    }
+}
+
+/// Value of an integer `wasm.const`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IntConst {
+    I32(i32),
+    I64(i64),
+}
+
+impl IntConst {
+    fn is_zero(self) -> bool {
+        matches!(self, IntConst::I32(0) | IntConst::I64(0))
+    }
+
+    fn is_one(self) -> bool {
+        matches!(self, IntConst::I32(1) | IntConst::I64(1))
+    }
+
+    fn is_all_ones(self) -> bool {
+        matches!(self, IntConst::I32(-1) | IntConst::I64(-1))
+    }
+
+    /// True if the shift by this amount does not change the value (the amount is
+    /// taken modulo the type width).
+    fn is_no_op_shift(self) -> bool {
+        match self {
+            IntConst::I32(amount) => amount & 31 == 0,
+            IntConst::I64(amount) => amount & 63 == 0,
+        }
+    }
+
+    /// Returns `k` if the value is `2^k` (as an unsigned integer) and `k > 0`.
+    fn power_of_two_exp(self) -> Option<u32> {
+        let (is_power_of_two, exp) = match self {
+            IntConst::I32(value) => ((value as u32).is_power_of_two(), value.trailing_zeros()),
+            IntConst::I64(value) => ((value as u64).is_power_of_two(), value.trailing_zeros()),
+        };
+        (is_power_of_two && exp > 0).then_some(exp)
+    }
+
+    fn with_value_of_same_type(self, value: i64) -> IntConst {
+        match self {
+            IntConst::I32(_) => IntConst::I32(value as i32),
+            IntConst::I64(_) => IntConst::I64(value),
+        }
+    }
+}
+
+fn get_int_const(ctx: &Context, op: Ptr<Operation>) -> Option<IntConst> {
+    let opop = &op.deref(ctx).get_op(ctx);
+    let value = opop.downcast_ref::<wasm::ConstantOp>()?.get_value(ctx);
+    let int_attr = value.downcast_ref::<IntegerAttr>()?;
+    match int_width(ctx, int_attr.get_type())? {
+        32 => Some(IntConst::I32(apint_to_i32(int_attr.clone().into()))),
+        64 => Some(IntConst::I64(apint_to_i64(int_attr.clone().into()))),
+        _ => None,
+    }
+}
+
+fn int_width(ctx: &Context, ty: Ptr<TypeObj>) -> Option<usize> {
+    ty.deref(ctx)
+        .downcast_ref::<IntegerType>()
+        .map(|int_ty| int_ty.get_width())
+}
+
+/// True if the constant has the type of the op operands.
+fn has_type(ctx: &Context, value: IntConst, ty: Ptr<TypeObj>) -> bool {
+    match value {
+        IntConst::I32(_) => int_width(ctx, ty) == Some(32),
+        IntConst::I64(_) => int_width(ctx, ty) == Some(64),
+    }
+}
+
+fn new_const(ctx: &mut Context, value: IntConst) -> Ptr<Operation> {
+    let attr = match value {
+        IntConst::I32(value) => i32_attr(ctx, value),
+        IntConst::I64(value) => i64_attr(ctx, value),
+    };
+    wasm::ConstantOp::new_unlinked(ctx, attr).get_operation()
+}
+
+fn fold_i32(bin_op: BinOp, a: i32, b: i32) -> i32 {
+    let (ua, ub) = (a as u32, b as u32);
+    match bin_op {
+        BinOp::Add => a.wrapping_add(b),
+        BinOp::Sub => a.wrapping_sub(b),
+        BinOp::Mul => a.wrapping_mul(b),
+        BinOp::And => a & b,
+        BinOp::Or => a | b,
+        BinOp::Xor => a ^ b,
+        BinOp::Shl => a.wrapping_shl(ub),
+        BinOp::ShrU => ua.wrapping_shr(ub) as i32,
+        BinOp::ShrS => a.wrapping_shr(ub),
+        BinOp::Eq => (a == b) as i32,
+        BinOp::Ne => (a != b) as i32,
+        BinOp::LtU => (ua < ub) as i32,
+        BinOp::LtS => (a < b) as i32,
+        BinOp::GtU => (ua > ub) as i32,
+        BinOp::GtS => (a > b) as i32,
+        BinOp::LeU => (ua <= ub) as i32,
+        BinOp::LeS => (a <= b) as i32,
+        BinOp::GeU => (ua >= ub) as i32,
+        BinOp::GeS => (a >= b) as i32,
+    }
+}
+
+fn fold_i64(bin_op: BinOp, a: i64, b: i64) -> IntConst {
+    let (ua, ub) = (a as u64, b as u64);
+    let cmp = |result: bool| IntConst::I32(result as i32);
+    match bin_op {
+        BinOp::Add => IntConst::I64(a.wrapping_add(b)),
+        BinOp::Sub => IntConst::I64(a.wrapping_sub(b)),
+        BinOp::Mul => IntConst::I64(a.wrapping_mul(b)),
+        BinOp::And => IntConst::I64(a & b),
+        BinOp::Or => IntConst::I64(a | b),
+        BinOp::Xor => IntConst::I64(a ^ b),
+        BinOp::Shl => IntConst::I64(a.wrapping_shl(ub as u32)),
+        BinOp::ShrU => IntConst::I64(ua.wrapping_shr(ub as u32) as i64),
+        BinOp::ShrS => IntConst::I64(a.wrapping_shr(ub as u32)),
+        BinOp::Eq => cmp(a == b),
+        BinOp::Ne => cmp(a != b),
+        BinOp::LtU => cmp(ua < ub),
+        BinOp::LtS => cmp(a < b),
+        BinOp::GtU => cmp(ua > ub),
+        BinOp::GtS => cmp(a > b),
+        BinOp::LeU => cmp(ua <= ub),
+        BinOp::LeS => cmp(a <= b),
+        BinOp::GeU => cmp(ua >= ub),
+        BinOp::GeS => cmp(a >= b),
+    }
+}
+
+/// Evaluate the op on constants (of the same type).
+fn fold(bin_op: BinOp, a: IntConst, b: IntConst) -> Option<IntConst> {
+    match (a, b) {
+        (IntConst::I32(a), IntConst::I32(b)) => Some(IntConst::I32(fold_i32(bin_op, a, b))),
+        (IntConst::I64(a), IntConst::I64(b)) => Some(fold_i64(bin_op, a, b)),
+        (IntConst::I32(_), IntConst::I64(_)) | (IntConst::I64(_), IntConst::I32(_)) => None,
+    }
+}
+
+/// Evaluate the ops that pop a single constant.
+fn fold_unary(ctx: &Context, op: Ptr<Operation>, value: IntConst) -> Option<IntConst> {
+    let opop = &op.deref(ctx).get_op(ctx);
+    match value {
+        IntConst::I32(value) => {
+            if opop.is::<wasm::I32EqzOp>() {
+                Some(IntConst::I32((value == 0) as i32))
+            } else if opop.is::<wasm::I64ExtendI32UOp>() {
+                Some(IntConst::I64(value as u32 as i64))
+            } else if opop.is::<wasm::I64ExtendI32SOp>() {
+                Some(IntConst::I64(value as i64))
+            } else {
+                None
+            }
+        }
+        IntConst::I64(value) => {
+            if opop.is::<wasm::I64EqzOp>() {
+                Some(IntConst::I32((value == 0) as i32))
+            } else if opop.is::<wasm::I32WrapI64Op>() {
+                Some(IntConst::I32(value as i32))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Evaluates the ops on constants:
+/// `c1, c2, op` -> `c1 op c2`, `c, unary_op` -> `unary_op(c)`,
+/// `c1, add, c2, add` -> `c1 + c2, add` and drops the dropped constants.
+pub struct ConstantFolding;
+
+impl PeepholeRule for ConstantFolding {
+    fn apply(
+        &self,
+        ctx: &mut Context,
+        ops: &[Ptr<Operation>],
+    ) -> Option<(usize, Vec<Ptr<Operation>>)> {
+        let lhs = get_int_const(ctx, *ops.first()?)?;
+        let next_op = *ops.get(1)?;
+        if next_op.deref(ctx).get_op(ctx).is::<wasm::DropOp>() {
+            return Some((2, vec![]));
+        }
+        if let Some(value) = fold_unary(ctx, next_op, lhs) {
+            return Some((2, vec![new_const(ctx, value)]));
+        }
+        if let Some((BinOp::Add, ty)) = get_bin_op(ctx, next_op) {
+            // (x + c1) + c2 = x + (c1 + c2)
+            let rhs = ops.get(2).and_then(|op| get_int_const(ctx, *op))?;
+            let (BinOp::Add, next_ty) = get_bin_op(ctx, *ops.get(3)?)? else {
+                return None;
+            };
+            if !has_type(ctx, lhs, ty) || ty != next_ty {
+                return None;
+            }
+            let sum = fold(BinOp::Add, lhs, rhs)?;
+            let add_op = wasm::AddOp::new_unlinked(ctx, ty).get_operation();
+            return Some((4, vec![new_const(ctx, sum), add_op]));
+        }
+        let rhs = get_int_const(ctx, next_op)?;
+        let (bin_op, ty) = get_bin_op(ctx, *ops.get(2)?)?;
+        if !has_type(ctx, lhs, ty) {
+            return None;
+        }
+        let result = fold(bin_op, lhs, rhs)?;
+        Some((3, vec![new_const(ctx, result)]))
+    }
+}
+
+/// Removes the ops that leave the other operand unchanged:
+/// `x + 0`, `x - 0`, `x | 0`, `x ^ 0`, `x * 1`, `x & -1` and the shifts by 0.
+pub struct IdentityElimination;
+
+impl PeepholeRule for IdentityElimination {
+    fn apply(
+        &self,
+        ctx: &mut Context,
+        ops: &[Ptr<Operation>],
+    ) -> Option<(usize, Vec<Ptr<Operation>>)> {
+        let rhs = get_int_const(ctx, *ops.first()?)?;
+        let (bin_op, ty) = get_bin_op(ctx, *ops.get(1)?)?;
+        if !has_type(ctx, rhs, ty) {
+            return None;
+        }
+        let is_identity = match bin_op {
+            BinOp::Add | BinOp::Sub | BinOp::Or | BinOp::Xor => rhs.is_zero(),
+            BinOp::Mul => rhs.is_one(),
+            BinOp::And => rhs.is_all_ones(),
+            BinOp::Shl | BinOp::ShrU | BinOp::ShrS => rhs.is_no_op_shift(),
+            BinOp::Eq
+            | BinOp::Ne
+            | BinOp::LtU
+            | BinOp::LtS
+            | BinOp::GtU
+            | BinOp::GtS
+            | BinOp::LeU
+            | BinOp::LeS
+            | BinOp::GeU
+            | BinOp::GeS => false,
+        };
+        is_identity.then(|| (2, vec![]))
+    }
+}
+
+/// Replaces the ops with the cheaper ones:
+/// `x * 2^k` -> `x << k`, `x * 0` and `x & 0` -> `drop, 0`, `x == 0` -> `eqz`.
+pub struct StrengthReduction;
+
+impl PeepholeRule for StrengthReduction {
+    fn apply(
+        &self,
+        ctx: &mut Context,
+        ops: &[Ptr<Operation>],
+    ) -> Option<(usize, Vec<Ptr<Operation>>)> {
+        let rhs = get_int_const(ctx, *ops.first()?)?;
+        let (bin_op, ty) = get_bin_op(ctx, *ops.get(1)?)?;
+        if !has_type(ctx, rhs, ty) {
+            return None;
+        }
+        match bin_op {
+            BinOp::Mul | BinOp::And if rhs.is_zero() => {
+                let drop_op = wasm::DropOp::new_unlinked(ctx).get_operation();
+                Some((2, vec![drop_op, new_const(ctx, rhs)]))
+            }
+            BinOp::Mul => {
+                let exp = rhs.power_of_two_exp()?;
+                let amount = new_const(ctx, rhs.with_value_of_same_type(exp as i64));
+                let shl_op = wasm::ShlOp::new_unlinked(ctx, ty).get_operation();
+                Some((2, vec![amount, shl_op]))
+            }
+            BinOp::Eq if rhs.is_zero() => {
+                let eqz_op = match rhs {
+                    IntConst::I32(_) => wasm::I32EqzOp::new_unlinked(ctx).get_operation(),
+                    IntConst::I64(_) => wasm::I64EqzOp::new_unlinked(ctx).get_operation(),
+                };
+                Some((2, vec![eqz_op]))
+            }
+            BinOp::Add
+            | BinOp::Sub
+            | BinOp::And
+            | BinOp::Or
+            | BinOp::Xor
+            | BinOp::Shl
+            | BinOp::ShrU
+            | BinOp::ShrS
+            | BinOp::Eq
+            | BinOp::Ne
+            | BinOp::LtU
+            | BinOp::LtS
+            | BinOp::GtU
+            | BinOp::GtS
+            | BinOp::LeU
+            | BinOp::LeS
+            | BinOp::GeU
+            | BinOp::GeS => None,
+        }
+    }
+}
+
+/// Merges the `local.*` ops on the same local:
+/// `local.set n, local.get n` -> `local.tee n`, `local.get n, local.set n` -> nothing and
+/// `local.tee n, local.set n` -> `local.set n`.
+pub struct LocalPairs;
+
+/// Local op with its index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LocalAccess {
+    Get(u32),
+    Set(u32),
+    Tee(u32),
+}
+
+fn get_local_access(ctx: &Context, op: Ptr<Operation>) -> Option<LocalAccess> {
+    let opop = &op.deref(ctx).get_op(ctx);
+    if let Some(local_get_op) = opop.downcast_ref::<wasm::LocalGetOp>() {
+        Some(LocalAccess::Get(local_get_op.get_index(ctx).into()))
+    } else if let Some(local_set_op) = opop.downcast_ref::<wasm::LocalSetOp>() {
+        Some(LocalAccess::Set(local_set_op.get_index(ctx).into()))
+    } else if let Some(local_tee_op) = opop.downcast_ref::<wasm::LocalTeeOp>() {
+        let index_attr = local_tee_op.get_index(ctx);
+        let index_attr = index_attr.downcast_ref::<IntegerAttr>()?;
+        Some(LocalAccess::Tee(
+            apint_to_i32(index_attr.clone().into()) as u32
+        ))
+    } else {
+        None
+    }
+}
+
+impl PeepholeRule for LocalPairs {
+    fn apply(
+        &self,
+        ctx: &mut Context,
+        ops: &[Ptr<Operation>],
+    ) -> Option<(usize, Vec<Ptr<Operation>>)> {
+        let first = get_local_access(ctx, *ops.first()?)?;
+        let second = get_local_access(ctx, *ops.get(1)?)?;
+        match (first, second) {
+            (LocalAccess::Set(set_index), LocalAccess::Get(get_index))
+                if set_index == get_index =>
+            {
+                let tee_op = wasm::LocalTeeOp::new_unlinked(ctx, set_index).get_operation();
+                Some((2, vec![tee_op]))
+            }
+            (LocalAccess::Get(get_index), LocalAccess::Set(set_index))
+                if set_index == get_index =>
+            {
+                Some((2, vec![]))
+            }
+            (LocalAccess::Tee(tee_index), LocalAccess::Set(set_index))
+                if set_index == tee_index =>
+            {
+                let set_op = wasm::LocalSetOp::new_unlinked(ctx, set_index).get_operation();
+                Some((2, vec![set_op]))
+            }
+            (
+                LocalAccess::Get(_) | LocalAccess::Set(_) | LocalAccess::Tee(_),
+                LocalAccess::Get(_) | LocalAccess::Set(_) | LocalAccess::Tee(_),
+            ) => None,
+        }
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests_util::as_wasm_module;
+    use crate::tests_util::run_wasm_passes;
+
+    /// Canonicalize the `main` function with the given rules and render its ops.
+    fn canonicalize(func: &str, rules: Vec<Box<dyn PeepholeRule>>) -> Vec<String> {
+        let wat = format!(
+            r#"
+(module
+    (start $main)
+    {func}
+)"#
+        );
+        let mut ctx = Context::default();
+        // checks that the rewritten functions are still well typed
+        let module_op = run_wasm_passes(
+            &mut ctx,
+            &wat,
+            vec![Box::new(WasmCanonicalizePass::new(rules))],
+        );
+        let func_op = as_wasm_module(&ctx, module_op)
+            .get_func(&ctx, &"main".to_string().into())
+            .unwrap();
+        func_op
+            .op_iter(&ctx)
+            .map(|op| render_op(&ctx, op))
+            .collect()
+    }
+
+    fn render_op(ctx: &Context, op: Ptr<Operation>) -> String {
+        if let Some(value) = get_int_const(ctx, op) {
+            match value {
+                IntConst::I32(value) => format!("i32.const {value}"),
+                IntConst::I64(value) => format!("i64.const {value}"),
+            }
+        } else if let Some(access) = get_local_access(ctx, op) {
+            match access {
+                LocalAccess::Get(index) => format!("local.get {index}"),
+                LocalAccess::Set(index) => format!("local.set {index}"),
+                LocalAccess::Tee(index) => format!("local.tee {index}"),
+            }
+        } else {
+            op.deref(ctx).get_opid().with_ctx(ctx).to_string()
+        }
+    }
+
+    #[test]
+    fn constant_folding_binary_ops() {
+        let ops = canonicalize(
+            "(func $main (result i32)
+                i32.const 2
+                i32.const 3
+                i32.mul
+                i32.const 4
+                i32.add
+                i32.const 1
+                i32.shl)",
+            vec![Box::new(ConstantFolding)],
+        );
+        assert_eq!(ops, vec!["i32.const 20", "wasm.return"]);
+    }
+
+    #[test]
+    fn constant_folding_wraps_around() {
+        let ops = canonicalize(
+            "(func $main (result i32)
+                i32.const 0x7fffffff
+                i32.const 1
+                i32.add
+                i32.const -8
+                i32.const 1
+                i32.shr_u
+                i32.xor)",
+            vec![Box::new(ConstantFolding)],
+        );
+        // (i32::MAX + 1) ^ (-8 >>u 1) = 0x8000_0000 ^ 0x7fff_fffc
+        assert_eq!(ops, vec!["i32.const -4", "wasm.return"]);
+    }
+
+    #[test]
+    fn constant_folding_i64_comparison() {
+        let ops = canonicalize(
+            "(func $main (result i32)
+                i64.const -1
+                i64.const 1
+                i64.lt_u)",
+            vec![Box::new(ConstantFolding)],
+        );
+        assert_eq!(ops, vec!["i32.const 0", "wasm.return"]);
+    }
+
+    #[test]
+    fn constant_folding_unary_ops() {
+        let ops = canonicalize(
+            "(func $main (result i32)
+                i32.const -1
+                i64.extend_i32_u
+                i64.eqz
+                i32.const 0
+                i32.eqz
+                i32.add)",
+            vec![Box::new(ConstantFolding)],
+        );
+        assert_eq!(ops, vec!["i32.const 1", "wasm.return"]);
+    }
+
+    #[test]
+    fn constant_folding_dropped_constant() {
+        let ops = canonicalize(
+            "(func $main (param i32) (result i32)
+                local.get 0
+                i32.const 7
+                drop)",
+            vec![Box::new(ConstantFolding)],
+        );
+        assert_eq!(ops, vec!["local.get 0", "wasm.return"]);
+    }
+
+    #[test]
+    fn constant_folding_reassociates_adds() {
+        let ops = canonicalize(
+            "(func $main (param i32) (result i32)
+                local.get 0
+                i32.const 1
+                i32.add
+                i32.const 2
+                i32.add
+                i32.const 3
+                i32.add)",
+            vec![Box::new(ConstantFolding)],
+        );
+        assert_eq!(
+            ops,
+            vec!["local.get 0", "i32.const 6", "wasm.add", "wasm.return"]
+        );
+    }
+
+    #[test]
+    fn identity_elimination() {
+        let ops = canonicalize(
+            "(func $main (param i32 i64) (result i32 i64)
+                local.get 0
+                i32.const 0
+                i32.add
+                i32.const -1
+                i32.and
+                i32.const 1
+                i32.mul
+                i32.const 32
+                i32.shl
+                i32.const 0
+                i32.sub
+                local.get 1
+                i64.const 0
+                i64.or
+                i64.const 0
+                i64.xor
+                i64.const 64
+                i64.shr_s)",
+            vec![Box::new(IdentityElimination)],
+        );
+        assert_eq!(ops, vec!["local.get 0", "local.get 1", "wasm.return"]);
+    }
+
+    #[test]
+    fn identity_elimination_keeps_other_ops() {
+        let ops = canonicalize(
+            "(func $main (param i32) (result i32)
+                i32.const 0
+                local.get 0
+                i32.sub
+                i32.const 1
+                i32.shl
+                i32.const 0
+                i32.eq)",
+            vec![Box::new(IdentityElimination)],
+        );
+        assert_eq!(
+            ops,
+            vec![
+                "i32.const 0",
+                "local.get 0",
+                "wasm.sub",
+                "i32.const 1",
+                "wasm.shl",
+                "i32.const 0",
+                "wasm.eq",
+                "wasm.return"
+            ]
+        );
+    }
+
+    #[test]
+    fn strength_reduction_mul_by_power_of_two() {
+        let ops = canonicalize(
+            "(func $main (param i32 i64) (result i32 i64)
+                local.get 0
+                i32.const 8
+                i32.mul
+                local.get 1
+                i64.const 0x100000000
+                i64.mul)",
+            vec![Box::new(StrengthReduction)],
+        );
+        assert_eq!(
+            ops,
+            vec![
+                "local.get 0",
+                "i32.const 3",
+                "wasm.shl",
+                "local.get 1",
+                "i64.const 32",
+                "wasm.shl",
+                "wasm.return"
+            ]
+        );
+    }
+
+    #[test]
+    fn strength_reduction_mul_and_by_zero() {
+        let ops = canonicalize(
+            "(func $main (param i32 i64) (result i32 i64)
+                local.get 0
+                i32.const 0
+                i32.mul
+                local.get 1
+                i64.const 0
+                i64.and)",
+            vec![Box::new(StrengthReduction)],
+        );
+        assert_eq!(
+            ops,
+            vec![
+                "local.get 0",
+                "wasm.drop",
+                "i32.const 0",
+                "local.get 1",
+                "wasm.drop",
+                "i64.const 0",
+                "wasm.return"
+            ]
+        );
+    }
+
+    #[test]
+    fn strength_reduction_eq_zero() {
+        let ops = canonicalize(
+            "(func $main (param i32 i64) (result i32 i32)
+                local.get 0
+                i32.const 0
+                i32.eq
+                local.get 1
+                i64.const 0
+                i64.eq)",
+            vec![Box::new(StrengthReduction)],
+        );
+        assert_eq!(
+            ops,
+            vec![
+                "local.get 0",
+                "wasm.i32.eqz",
+                "local.get 1",
+                "wasm.i64.eqz",
+                "wasm.return"
+            ]
+        );
+    }
+
+    #[test]
+    fn strength_reduction_keeps_mul_by_non_power_of_two() {
+        let ops = canonicalize(
+            "(func $main (param i32) (result i32)
+                local.get 0
+                i32.const 6
+                i32.mul)",
+            vec![Box::new(StrengthReduction)],
+        );
+        assert_eq!(
+            ops,
+            vec!["local.get 0", "i32.const 6", "wasm.mul", "wasm.return"]
+        );
+    }
+
+    #[test]
+    fn local_pairs() {
+        let ops = canonicalize(
+            "(func $main (param i32) (result i32)
+                (local i32)
+                local.get 0
+                local.set 1
+                local.get 1
+                local.get 0
+                local.set 0
+                local.tee 1
+                local.set 1
+                local.get 1)",
+            vec![Box::new(LocalPairs)],
+        );
+        assert_eq!(
+            ops,
+            vec!["local.get 0", "local.tee 1", "local.tee 1", "wasm.return"]
+        );
+    }
+
+    #[test]
+    fn local_tee_set_pair_followed_by_other_op() {
+        let ops = canonicalize(
+            "(func $main (param i32) (result i32)
+                (local i32)
+                local.get 0
+                local.tee 1
+                local.set 1
+                i32.const 7)",
+            vec![Box::new(LocalPairs)],
+        );
+        assert_eq!(
+            ops,
+            vec!["local.get 0", "local.set 1", "i32.const 7", "wasm.return"]
+        );
+    }
+
+    #[test]
+    fn local_pairs_on_different_locals() {
+        let ops = canonicalize(
+            "(func $main (param i32) (result i32)
+                (local i32)
+                local.get 0
+                local.set 1
+                local.get 0)",
+            vec![Box::new(LocalPairs)],
+        );
+        assert_eq!(
+            ops,
+            vec!["local.get 0", "local.set 1", "local.get 0", "wasm.return"]
+        );
+    }
+
+    #[test]
+    fn all_rules() {
+        let ops = canonicalize(
+            "(func $main (param i32) (result i32)
+                (local i32)
+                local.get 0
+                i32.const 2
+                i32.const 2
+                i32.mul
+                i32.mul
+                i32.const 0
+                i32.add
+                local.set 1
+                local.get 1)",
+            default_rules(),
+        );
+        assert_eq!(
+            ops,
+            vec![
+                "local.get 0",
+                "i32.const 2",
+                "wasm.shl",
+                "local.tee 1",
+                "wasm.return"
+            ]
+        );
+    }
+}
@@ -1,5 +1,5 @@
+use ozk_ozk_dialect::ops as ozk;
 use ozk_wasm_dialect::ops as wasm;
-use ozk_wasm_dialect::ops::LocalSetOp;
 use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::dialect_conversion::apply_partial_conversion;
@@ -26,6 +26,14 @@ impl Pass for WasmExplicitFuncArgsPass {
     }
 }
 
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "wasm-explicit-func-args",
+        description: "Put function arguments on the stack and pop them into locals at function entry",
+        constructor: || Box::<WasmExplicitFuncArgsPass>::default(),
+    }
+}
+
 #[derive(Default)]
 pub struct WasmExplicitFuncArgs;
 
@@ -35,7 +43,7 @@ impl RewritePattern for WasmExplicitFuncArgs {
             .deref(ctx)
             .get_op(ctx)
             .downcast_ref::<wasm::FuncOp>()
-            .is_some())
+            .map_or(false, |func_op| !func_op.has_args_on_stack(ctx)))
     }
 
     #[allow(clippy::panic)]
@@ -52,10 +60,17 @@ impl RewritePattern for WasmExplicitFuncArgs {
             panic!("unexpected op {}", op.deref(ctx).with_ctx(ctx));
         };
         let func_type = func_op.get_type(ctx);
-        for (idx, _) in func_type.get_inputs().iter().enumerate().rev() {
-            let local_set_op = LocalSetOp::new_unlinked(ctx, idx as u32).get_operation();
+        let body_in_ozk = func_op.has_body_in_ozk(ctx);
+        // the last argument is on top of the stack, so it's popped first
+        for (idx, _) in func_type.get_inputs().iter().enumerate() {
+            let local_set_op = if body_in_ozk {
+                ozk::LocalSetOp::new_unlinked(ctx, idx as u32).get_operation()
+            } else {
+                wasm::LocalSetOp::new_unlinked(ctx, idx as u32).get_operation()
+            };
             local_set_op.insert_at_front(func_op.get_entry_block(ctx), ctx);
         }
+        func_op.set_args_on_stack(ctx);
         Ok(())
     }
 }
@@ -1,6 +1,7 @@
 use ozk_ozk_dialect::ops as ozk;
 use ozk_ozk_dialect::ord_n::Ord16;
 use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::GlobalIndex;
 use ozk_wasm_dialect::types::MemAddress;
 use pliron::context::Context;
 use pliron::context::Ptr;
@@ -8,6 +9,8 @@ use pliron::dialect_conversion::apply_partial_conversion;
 use pliron::dialect_conversion::ConversionTarget;
 use pliron::op::Op;
 use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
 use pliron::pass::Pass;
 use pliron::pattern_match::PatternRewriter;
 use pliron::pattern_match::RewritePattern;
@@ -28,23 +31,80 @@ impl Pass for WasmGlobalsToMemPass {
     fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
         let target = ConversionTarget::default();
         // TODO: set illegal ops
+        let global_types = global_value_types(ctx, op)?;
         let mut patterns = RewritePatternSet::default();
-        patterns.add(Box::new(WasmGlobalSetToMem::new(self.start_addr)));
-        patterns.add(Box::new(WasmGlobalGetToMem::new(self.start_addr)));
+        patterns.add(Box::new(WasmGlobalSetToMem::new(
+            self.start_addr,
+            global_types.clone(),
+        )));
+        patterns.add(Box::new(WasmGlobalGetToMem::new(
+            self.start_addr,
+            global_types,
+        )));
         apply_partial_conversion(ctx, op, target, patterns)?;
         Ok(())
     }
 }
 
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "wasm-globals-to-mem",
+        description: "Store Wasm globals in memory (placed below the top of the i32 address space)",
+        constructor: || Box::new(WasmGlobalsToMemPass::new(MemAddress::from(i32::MAX as u32))),
+    }
+}
+
 const MAX_GLOBAL_VAR_SIZE_BYTES: u32 = 8; // i64
 
+/// Types of the globals (indexed by the global index) of the Wasm module in `op`.
+fn global_value_types(
+    ctx: &Context,
+    op: Ptr<Operation>,
+) -> Result<Vec<wasm::MemAccessOpValueType>, anyhow::Error> {
+    let mut module_ops = Vec::new();
+    op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PreOrder, &mut |module_op| {
+        module_ops.push(*module_op);
+        WalkResult::Advance
+    });
+    let mut global_types = Vec::new();
+    if let Some(module_op) = module_ops.first() {
+        let mut global_index = 0;
+        while let Some(ty) = module_op.get_global_type(ctx, global_index.into()) {
+            let value_type = wasm::MemAccessOpValueType::from_type(ctx, ty).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "unsupported type {} of global {global_index}",
+                    ty.with_ctx(ctx)
+                )
+            })?;
+            global_types.push(value_type);
+            global_index += 1;
+        }
+    }
+    Ok(global_types)
+}
+
+/// Type of the global with the given index.
+fn global_value_type(
+    global_types: &[wasm::MemAccessOpValueType],
+    global_index: GlobalIndex,
+) -> Result<wasm::MemAccessOpValueType, anyhow::Error> {
+    global_types
+        .get(u32::from(global_index) as usize)
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("undefined global {global_index}"))
+}
+
 pub struct WasmGlobalSetToMem {
     start_addr: MemAddress,
+    global_types: Vec<wasm::MemAccessOpValueType>,
 }
 
 impl WasmGlobalSetToMem {
-    pub fn new(start_addr: MemAddress) -> Self {
-        Self { start_addr }
+    pub fn new(start_addr: MemAddress, global_types: Vec<wasm::MemAccessOpValueType>) -> Self {
+        Self {
+            start_addr,
+            global_types,
+        }
     }
 }
 
@@ -73,26 +133,27 @@ impl RewritePattern for WasmGlobalSetToMem {
         let offset: u32 = u32::from(global_set_op.get_index(ctx)) * MAX_GLOBAL_VAR_SIZE_BYTES;
         let address = u32::from(self.start_addr) - offset;
         let constant_op = wasm::ConstantOp::new_i32_unlinked(ctx, address as i32);
-        let i64store_op = wasm::StoreOp::new_unlinked(ctx, wasm::MemAccessOpValueType::I64);
+        let value_type = global_value_type(&self.global_types, global_set_op.get_index(ctx))?;
+        let store_op = wasm::StoreOp::new_unlinked(ctx, value_type);
         rewriter.insert_before(ctx, constant_op.get_operation())?;
         let swap_op = ozk::SwapOp::new_unlinked(ctx, Ord16::ST1);
         rewriter.insert_before(ctx, swap_op.get_operation())?;
-        rewriter.replace_op_with(
-            ctx,
-            global_set_op.get_operation(),
-            i64store_op.get_operation(),
-        )?;
+        rewriter.replace_op_with(ctx, global_set_op.get_operation(), store_op.get_operation())?;
         Ok(())
     }
 }
 
 pub struct WasmGlobalGetToMem {
     start_addr: MemAddress,
+    global_types: Vec<wasm::MemAccessOpValueType>,
 }
 
 impl WasmGlobalGetToMem {
-    pub fn new(start_addr: MemAddress) -> Self {
-        Self { start_addr }
+    pub fn new(start_addr: MemAddress, global_types: Vec<wasm::MemAccessOpValueType>) -> Self {
+        Self {
+            start_addr,
+            global_types,
+        }
     }
 }
 
@@ -121,13 +182,10 @@ impl RewritePattern for WasmGlobalGetToMem {
         let offset: u32 = u32::from(global_get_op.get_index(ctx)) * MAX_GLOBAL_VAR_SIZE_BYTES;
         let address = u32::from(self.start_addr) - offset;
         let constant_op = wasm::ConstantOp::new_i32_unlinked(ctx, address as i32);
-        let i64load_op = wasm::LoadOp::new_unlinked(ctx, wasm::MemAccessOpValueType::I64);
+        let value_type = global_value_type(&self.global_types, global_get_op.get_index(ctx))?;
+        let load_op = wasm::LoadOp::new_unlinked(ctx, value_type);
         rewriter.insert_before(ctx, constant_op.get_operation())?;
-        rewriter.replace_op_with(
-            ctx,
-            global_get_op.get_operation(),
-            i64load_op.get_operation(),
-        )?;
+        rewriter.replace_op_with(ctx, global_get_op.get_operation(), load_op.get_operation())?;
         Ok(())
     }
 }
@@ -170,6 +228,43 @@ mod tests {
                         wasm.const 0x9: si32
                         wasm.const 0x1000: si32
                         ozk.swap 1
+                        wasm.store I32
+                        wasm.const 0x1000: si32
+                        wasm.load I32
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn globals_get_set_i64() {
+        let pass = WasmGlobalsToMemPass {
+            start_addr: 0x1000.into(),
+        };
+        check_wasm_pass(
+            &pass,
+            r#"
+(module
+    (type (;2;) (func))
+    (global $MyGlobalVal (mut i64) i64.const 42)
+    (export "main" (func $main))
+    (start $main)
+    (func $main
+        i64.const 9
+        global.set $MyGlobalVal
+        global.get $MyGlobalVal
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.const 0x9: si64
+                        wasm.const 0x1000: si32
+                        ozk.swap 1
                         wasm.store I64
                         wasm.const 0x1000: si32
                         wasm.load I64
@@ -0,0 +1,682 @@
+//! Inlining of the small leaf functions (functions without calls) at their call sites.
+//!
+//! The callee arguments are popped into the new caller locals appended after the caller locals,
+//! followed by the (zero-initialized) callee locals, and the callee body is copied with the
+//! local indices shifted accordingly. If the callee only returns at the end of its body,
+//! the body is put in place of the call as is. Otherwise it's wrapped in a `wasm.block` and
+//! every `return` becomes a `br` to the end of that block.
+//!
+//! Expects the arguments in the locals (i.e. runs before `wasm-explicit-func-args`).
+
+use ozk_ozk_dialect::attributes::apint_to_i32;
+use ozk_ozk_dialect::attributes::i32_attr;
+use ozk_ozk_dialect::attributes::i64_attr;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::op_interfaces::StackDepthChange;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::attribute::AttrObj;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::dialects::builtin::types::IntegerType;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::op_cast;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
+use pliron::rewrite::RewritePatternSet;
+
+/// Decides which callees are worth inlining. The targets set the threshold according to
+/// their call overhead.
+#[derive(Debug, Clone, Copy)]
+pub struct InlineCostModel {
+    /// Inline the callees with at most this many ops (including the nested ones)
+    pub threshold: usize,
+}
+
+impl InlineCostModel {
+    /// `exec` is a plain procedure call, inline only the tiny functions where the
+    /// argument/result shuffling costs more than the body.
+    pub fn miden() -> Self {
+        Self { threshold: 8 }
+    }
+
+    /// Every call sets up a new frame with `jal` and returns with `jalv`, moving the arguments
+    /// and results through the memory.
+    pub fn valida() -> Self {
+        Self { threshold: 16 }
+    }
+
+    /// Returns true if the callee with the given body size should be inlined.
+    pub fn should_inline(&self, callee_size: usize) -> bool {
+        callee_size <= self.threshold
+    }
+}
+
+impl Default for InlineCostModel {
+    fn default() -> Self {
+        Self::miden()
+    }
+}
+
+#[derive(Default)]
+pub struct WasmInlinePass {
+    cost_model: InlineCostModel,
+}
+
+impl WasmInlinePass {
+    pub fn new(cost_model: InlineCostModel) -> Self {
+        Self { cost_model }
+    }
+}
+
+impl Pass for WasmInlinePass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::new(WasmInline {
+            cost_model: self.cost_model,
+        }));
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "wasm-inline",
+        description: "Inline the small leaf functions at their call sites",
+        constructor: || Box::<WasmInlinePass>::default(),
+    }
+}
+
+struct WasmInline {
+    cost_model: InlineCostModel,
+}
+
+impl RewritePattern for WasmInline {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(module_op) = opop.downcast_ref::<wasm::ModuleOp>() else {
+            return Ok(false);
+        };
+        let mut changed = false;
+        // every inlining removes a call, so this terminates
+        loop {
+            let func_ops: Vec<wasm::FuncOp> = module_op
+                .get_body(ctx, 0)
+                .deref(ctx)
+                .iter(ctx)
+                .filter_map(|op| {
+                    op.deref(ctx)
+                        .get_op(ctx)
+                        .downcast_ref::<wasm::FuncOp>()
+                        .cloned()
+                })
+                .collect();
+            let mut inlined = false;
+            for caller in func_ops {
+                if caller.has_args_on_stack(ctx) {
+                    continue;
+                }
+                let mut inliner = Inliner {
+                    module_op,
+                    caller,
+                    cost_model: self.cost_model,
+                    new_locals: Vec::new(),
+                };
+                if inliner.inline_calls(ctx, rewriter, caller.get_entry_block(ctx))? {
+                    let mut locals = caller.get_locals(ctx);
+                    locals.extend(inliner.new_locals);
+                    caller.set_locals(ctx, locals);
+                    inlined = true;
+                }
+            }
+            if !inlined {
+                break;
+            }
+            changed = true;
+        }
+        Ok(changed)
+    }
+}
+
+struct Inliner<'a> {
+    module_op: &'a wasm::ModuleOp,
+    caller: wasm::FuncOp,
+    cost_model: InlineCostModel,
+    /// Locals added to the caller for the inlined callees
+    new_locals: Vec<Ptr<TypeObj>>,
+}
+
+impl Inliner<'_> {
+    /// Inline the calls in the block (and the nested blocks). Returns true if any call was inlined.
+    fn inline_calls(
+        &mut self,
+        ctx: &mut Context,
+        rewriter: &mut dyn PatternRewriter,
+        block: Ptr<BasicBlock>,
+    ) -> Result<bool, anyhow::Error> {
+        let ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+        let mut changed = false;
+        for op in ops {
+            let opop = &op.deref(ctx).get_op(ctx);
+            if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+                changed |= self.inline_calls(ctx, rewriter, block_op.get_block(ctx))?;
+            } else if let Some(loop_op) = opop.downcast_ref::<wasm::LoopOp>() {
+                changed |= self.inline_calls(ctx, rewriter, loop_op.get_block(ctx))?;
+            } else if let Some(callee) = self.get_inlinable_callee(ctx, op) {
+                if let Some(inlined_ops) = self.inline_call(ctx, &callee) {
+                    rewriter.set_insertion_point(op);
+                    for inlined_op in inlined_ops {
+                        rewriter.insert_before(ctx, inlined_op)?;
+                    }
+                    rewriter.erase_op(ctx, op)?;
+                    changed = true;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Returns the callee if the op is a call of a function worth inlining.
+    fn get_inlinable_callee(&self, ctx: &Context, op: Ptr<Operation>) -> Option<wasm::FuncOp> {
+        let callee_sym = get_callee_sym(ctx, self.module_op, op)?;
+        // imported functions are not in the module
+        let callee = self.module_op.get_func(ctx, &callee_sym)?;
+        if callee.get_operation() == self.caller.get_operation()
+            || callee.has_args_on_stack(ctx)
+            || has_calls(ctx, self.module_op, &callee)
+        {
+            return None;
+        }
+        let size = callee.op_iter(ctx).map(|op| count_ops(ctx, op)).sum();
+        self.cost_model.should_inline(size).then_some(callee)
+    }
+
+    /// Build the ops to put in place of the call. Returns None if the callee has
+    /// ops that can't be inlined.
+    fn inline_call(
+        &mut self,
+        ctx: &mut Context,
+        callee: &wasm::FuncOp,
+    ) -> Option<Vec<Ptr<Operation>>> {
+        let callee_type = callee.get_type(ctx);
+        let params = callee_type.get_inputs().clone();
+        let results = callee_type.get_results().clone();
+        let callee_locals = callee.get_locals(ctx);
+        let local_base = (self.caller.get_type(ctx).get_inputs().len()
+            + self.caller.get_locals(ctx).len()
+            + self.new_locals.len()) as u32;
+        let body: Vec<Ptr<Operation>> = callee.op_iter(ctx).collect();
+        let mut ops = Vec::new();
+        // the last argument is on top of the stack
+        for index in (0..params.len() as u32).rev() {
+            ops.push(wasm::LocalSetOp::new_unlinked(ctx, local_base + index).get_operation());
+        }
+        for (index, ty) in callee_locals.iter().enumerate() {
+            let zero = zero_attr(ctx, *ty)?;
+            ops.push(wasm::ConstantOp::new_unlinked(ctx, zero).get_operation());
+            let index = local_base + (params.len() + index) as u32;
+            ops.push(wasm::LocalSetOp::new_unlinked(ctx, index).get_operation());
+        }
+        if let Some(body) = straight_line_body(ctx, &body, results.len()) {
+            for op in body {
+                ops.push(clone_op(ctx, op, local_base, 0)?);
+            }
+        } else {
+            let block_type = FunctionType::get(ctx, vec![], results);
+            let block_op = wasm::BlockOp::new_unlinked(ctx, block_type);
+            let block = block_op.get_block(ctx);
+            for op in body {
+                clone_op(ctx, op, local_base, 0)?.insert_at_back(block, ctx);
+            }
+            ops.push(block_op.get_operation());
+        }
+        self.new_locals.extend(params);
+        self.new_locals.extend(callee_locals);
+        Some(ops)
+    }
+}
+
+fn get_callee_sym(
+    ctx: &Context,
+    module_op: &wasm::ModuleOp,
+    op: Ptr<Operation>,
+) -> Option<FuncSym> {
+    let opop = &op.deref(ctx).get_op(ctx);
+    if let Some(call_op) = opop.downcast_ref::<wasm::CallOp>() {
+        module_op.get_func_sym(ctx, call_op.get_func_index(ctx))
+    } else {
+        opop.downcast_ref::<ozk::CallOp>()
+            .map(|call_op| call_op.get_func_sym(ctx).into())
+    }
+}
+
+fn has_calls(ctx: &Context, module_op: &wasm::ModuleOp, func_op: &wasm::FuncOp) -> bool {
+    let mut has_calls = false;
+    func_op
+        .get_operation()
+        .walk(ctx, WalkOrder::PreOrder, &mut |op| {
+            if get_callee_sym(ctx, module_op, op).is_some()
+                || op.deref(ctx).get_op(ctx).is::<wasm::CallOp>()
+            {
+                has_calls = true;
+                WalkResult::Interrupt
+            } else {
+                WalkResult::Advance
+            }
+        });
+    has_calls
+}
+
+/// Number of ops including the nested ones
+fn count_ops(ctx: &Context, op: Ptr<Operation>) -> usize {
+    let mut count = 0;
+    op.walk(ctx, WalkOrder::PreOrder, &mut |_| {
+        count += 1;
+        WalkResult::Advance
+    });
+    count
+}
+
+/// Returns the body without the trailing `return` if it's the only exit of the function
+/// and leaves exactly the results on the stack, i.e. the body can be put in place of
+/// the call without a block.
+fn straight_line_body(
+    ctx: &Context,
+    body: &[Ptr<Operation>],
+    num_results: usize,
+) -> Option<Vec<Ptr<Operation>>> {
+    let body = match body.split_last() {
+        Some((last_op, rest)) if last_op.deref(ctx).get_op(ctx).is::<wasm::ReturnOp>() => rest,
+        Some(_) | None => body,
+    };
+    let mut stack_depth: i32 = 0;
+    for op in body {
+        // nested blocks, branches and returns are not straight line code
+        let opop = &op.deref(ctx).get_op(ctx);
+        if opop.is::<wasm::ReturnOp>() {
+            return None;
+        }
+        stack_depth += op_cast::<dyn StackDepthChange>(opop.as_ref())?.get_stack_depth_change(ctx);
+    }
+    (stack_depth == num_results as i32).then(|| body.to_vec())
+}
+
+fn zero_attr(ctx: &mut Context, ty: Ptr<TypeObj>) -> Option<AttrObj> {
+    let width = ty
+        .deref(ctx)
+        .downcast_ref::<IntegerType>()
+        .map(|int_ty| int_ty.get_width())?;
+    match width {
+        32 => Some(i32_attr(ctx, 0)),
+        64 => Some(i64_attr(ctx, 0)),
+        _ => None,
+    }
+}
+
+macro_rules! clone_typed_op {
+    ($ctx:expr, $opop:expr, $($op:ty),*) => {
+        $(
+            if let Some(op) = $opop.downcast_ref::<$op>() {
+                let ty = op.get_type($ctx);
+                return Some(<$op>::new_unlinked($ctx, ty).get_operation());
+            }
+        )*
+    };
+}
+
+macro_rules! clone_plain_op {
+    ($ctx:expr, $opop:expr, $($op:ty),*) => {
+        $(
+            if $opop.is::<$op>() {
+                return Some(<$op>::new_unlinked($ctx).get_operation());
+            }
+        )*
+    };
+}
+
+/// Copy the callee op with the local indices shifted by `local_base` and `return` turned
+/// into the branch to the end of the inlined body. `depth` is the number of the blocks
+/// the op is nested in within the callee body.
+/// Returns None for the ops that can't be inlined.
+fn clone_op(
+    ctx: &mut Context,
+    op: Ptr<Operation>,
+    local_base: u32,
+    depth: u32,
+) -> Option<Ptr<Operation>> {
+    let opop = &op.deref(ctx).get_op(ctx);
+    clone_typed_op!(
+        ctx,
+        opop,
+        wasm::AddOp,
+        wasm::SubOp,
+        wasm::MulOp,
+        wasm::AndOp,
+        wasm::OrOp,
+        wasm::XorOp,
+        wasm::ShlOp,
+        wasm::ShrUOp,
+        wasm::ShrSOp,
+        wasm::DivUOp,
+        wasm::DivSOp,
+        wasm::RemUOp,
+        wasm::RemSOp,
+        wasm::PopcntOp,
+        wasm::EqOp,
+        wasm::NeOp,
+        wasm::LtUOp,
+        wasm::LtSOp,
+        wasm::GtUOp,
+        wasm::GtSOp,
+        wasm::LeUOp,
+        wasm::LeSOp,
+        wasm::GeUOp,
+        wasm::GeSOp
+    );
+    clone_plain_op!(
+        ctx,
+        opop,
+        wasm::I32EqzOp,
+        wasm::I64EqzOp,
+        wasm::I64ExtendI32UOp,
+        wasm::I64ExtendI32SOp,
+        wasm::I32WrapI64Op,
+        wasm::DropOp
+    );
+    if let Some(const_op) = opop.downcast_ref::<wasm::ConstantOp>() {
+        let value = const_op.get_value(ctx);
+        Some(wasm::ConstantOp::new_unlinked(ctx, value).get_operation())
+    } else if let Some(local_get_op) = opop.downcast_ref::<wasm::LocalGetOp>() {
+        let index = local_base + u32::from(local_get_op.get_index(ctx));
+        Some(wasm::LocalGetOp::new_unlinked(ctx, index).get_operation())
+    } else if let Some(local_set_op) = opop.downcast_ref::<wasm::LocalSetOp>() {
+        let index = local_base + u32::from(local_set_op.get_index(ctx));
+        Some(wasm::LocalSetOp::new_unlinked(ctx, index).get_operation())
+    } else if let Some(local_tee_op) = opop.downcast_ref::<wasm::LocalTeeOp>() {
+        let index_attr = local_tee_op.get_index(ctx);
+        let index_attr = index_attr.downcast_ref::<IntegerAttr>()?;
+        let index = local_base + apint_to_i32(index_attr.clone().into()) as u32;
+        Some(wasm::LocalTeeOp::new_unlinked(ctx, index).get_operation())
+    } else if let Some(global_get_op) = opop.downcast_ref::<wasm::GlobalGetOp>() {
+        let index = global_get_op.get_index(ctx).into();
+        Some(wasm::GlobalGetOp::new_unlinked(ctx, index).get_operation())
+    } else if let Some(global_set_op) = opop.downcast_ref::<wasm::GlobalSetOp>() {
+        let index = global_set_op.get_index(ctx);
+        Some(wasm::GlobalSetOp::new_unlinked(ctx, index).get_operation())
+    } else if let Some(load_op) = opop.downcast_ref::<wasm::LoadOp>() {
+        let ty = load_op.get_value_type(ctx);
+        let size = load_op.get_size(ctx);
+        let signed = load_op.is_signed(ctx);
+        let memarg = load_op.get_memarg(ctx);
+        Some(wasm::LoadOp::new_sized_unlinked(ctx, ty, size, signed, memarg).get_operation())
+    } else if let Some(store_op) = opop.downcast_ref::<wasm::StoreOp>() {
+        let ty = store_op.get_value_type(ctx);
+        let size = store_op.get_size(ctx);
+        let memarg = store_op.get_memarg(ctx);
+        Some(wasm::StoreOp::new_sized_unlinked(ctx, ty, size, memarg).get_operation())
+    } else if let Some(br_op) = opop.downcast_ref::<wasm::BrOp>() {
+        // the branches to the function body now target the inlined block
+        let relative_depth = br_op.get_relative_depth(ctx);
+        Some(wasm::BrOp::new_unlinked(ctx, relative_depth).get_operation())
+    } else if let Some(br_if_op) = opop.downcast_ref::<wasm::BrIfOp>() {
+        let relative_depth = br_if_op.get_relative_depth(ctx);
+        Some(wasm::BrIfOp::new_unlinked(ctx, relative_depth).get_operation())
+    } else if opop.is::<wasm::ReturnOp>() {
+        Some(wasm::BrOp::new_unlinked(ctx, depth.into()).get_operation())
+    } else if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+        let ty = block_op.get_type(ctx);
+        let new_block_op = wasm::BlockOp::new_unlinked(ctx, ty);
+        clone_ops(
+            ctx,
+            block_op.get_block(ctx),
+            new_block_op.get_block(ctx),
+            local_base,
+            depth + 1,
+        )?;
+        Some(new_block_op.get_operation())
+    } else if let Some(loop_op) = opop.downcast_ref::<wasm::LoopOp>() {
+        let ty = loop_op.get_type(ctx);
+        let new_loop_op = wasm::LoopOp::new_unlinked(ctx, ty);
+        clone_ops(
+            ctx,
+            loop_op.get_block(ctx),
+            new_loop_op.get_block(ctx),
+            local_base,
+            depth + 1,
+        )?;
+        Some(new_loop_op.get_operation())
+    } else {
+        None
+    }
+}
+
+fn clone_ops(
+    ctx: &mut Context,
+    from: Ptr<BasicBlock>,
+    to: Ptr<BasicBlock>,
+    local_base: u32,
+    depth: u32,
+) -> Option<()> {
+    let ops: Vec<Ptr<Operation>> = from.deref(ctx).iter(ctx).collect();
+    for op in ops {
+        clone_op(ctx, op, local_base, depth)?.insert_at_back(to, ctx);
+    }
+    Some(())
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use pliron::with_context::AttachContext;
+
+    use super::*;
+    use crate::tests_util::as_wasm_module;
+    use crate::tests_util::run_wasm_passes;
+
+    /// Inline with the given threshold and render the ops of `main` (nested ops are indented).
+    /// Returns the ops and the number of `main` locals.
+    fn inline(funcs: &str, threshold: usize) -> (Vec<String>, usize) {
+        let wat = format!(
+            r#"
+(module
+    (start $main)
+    {funcs}
+)"#
+        );
+        let mut ctx = Context::default();
+        // checks the stack types of the callers with the inlined bodies
+        let module_op = run_wasm_passes(
+            &mut ctx,
+            &wat,
+            vec![Box::new(WasmInlinePass::new(InlineCostModel { threshold }))],
+        );
+        let main_func = as_wasm_module(&ctx, module_op)
+            .get_func(&ctx, &"main".to_string().into())
+            .unwrap();
+        let mut ops = Vec::new();
+        render_block(&ctx, main_func.get_entry_block(&ctx), "", &mut ops);
+        (ops, main_func.get_locals(&ctx).len())
+    }
+
+    fn render_block(ctx: &Context, block: Ptr<BasicBlock>, indent: &str, out: &mut Vec<String>) {
+        for op in block.deref(ctx).iter(ctx) {
+            let opop = &op.deref(ctx).get_op(ctx);
+            if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+                out.push(format!("{indent}wasm.block"));
+                render_block(ctx, block_op.get_block(ctx), &format!("{indent}  "), out);
+            } else {
+                out.push(format!("{indent}{}", op.deref(ctx).with_ctx(ctx)));
+            }
+        }
+    }
+
+    #[test]
+    fn straight_line_callee() {
+        let (ops, num_locals) = inline(
+            "(func $add (param i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.add)
+            (func $main (param i32) (result i32)
+                (local i32)
+                local.get 0
+                i32.const 5
+                call $add)",
+            16,
+        );
+        assert_eq!(
+            ops,
+            vec![
+                "wasm.local.get 0",
+                "wasm.const 0x5: si32",
+                "wasm.local.set 0x3: ui32",
+                "wasm.local.set 0x2: ui32",
+                "wasm.local.get 2",
+                "wasm.local.get 3",
+                "wasm.add",
+                "wasm.return",
+            ]
+        );
+        // the callee parameters are appended to the caller locals
+        assert_eq!(num_locals, 3);
+    }
+
+    #[test]
+    fn callee_locals_are_zeroed() {
+        let (ops, num_locals) = inline(
+            "(func $counter (result i64)
+                (local i64)
+                local.get 0
+                i64.const 1
+                i64.add
+                local.tee 0)
+            (func $main (result i64)
+                call $counter)",
+            16,
+        );
+        assert!(!ops.iter().any(|op| op.starts_with("wasm.call")), "{ops:?}");
+        assert_eq!(ops[0], "wasm.const 0x0: si64");
+        assert_eq!(ops[1], "wasm.local.set 0x0: ui32");
+        assert_eq!(num_locals, 1);
+    }
+
+    #[test]
+    fn returns_become_branches_to_the_inlined_block() {
+        let (ops, _) = inline(
+            "(func $f (param i32) (result i32)
+                block
+                    local.get 0
+                    br_if 0
+                    i32.const 7
+                    return
+                end
+                local.get 0)
+            (func $main (param i32) (result i32)
+                local.get 0
+                call $f)",
+            16,
+        );
+        assert_eq!(
+            ops,
+            vec![
+                "wasm.local.get 0",
+                "wasm.local.set 0x1: ui32",
+                "wasm.block",
+                "  wasm.block",
+                "    wasm.local.get 1",
+                "    wasm.br_if 0",
+                "    wasm.const 0x7: si32",
+                "    wasm.br 1",
+                "  wasm.local.get 1",
+                "  wasm.br 0",
+                "wasm.return",
+            ]
+        );
+    }
+
+    #[test]
+    fn callee_above_threshold_is_not_inlined() {
+        let (ops, num_locals) = inline(
+            "(func $add (param i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.add)
+            (func $main (result i32)
+                i32.const 1
+                i32.const 2
+                call $add)",
+            3,
+        );
+        assert_eq!(
+            ops,
+            vec![
+                "wasm.const 0x1: si32",
+                "wasm.const 0x2: si32",
+                "wasm.call 0",
+                "wasm.return",
+            ]
+        );
+        assert_eq!(num_locals, 0);
+    }
+
+    #[test]
+    fn callers_become_leaves_after_inlining() {
+        let (ops, _) = inline(
+            "(func $one (result i32)
+                i32.const 1)
+            (func $two (result i32)
+                call $one
+                call $one
+                i32.add)
+            (func $main (result i32)
+                call $two)",
+            16,
+        );
+        assert_eq!(
+            ops,
+            vec![
+                "wasm.const 0x1: si32",
+                "wasm.const 0x1: si32",
+                "wasm.add",
+                "wasm.return",
+            ]
+        );
+    }
+
+    #[test]
+    fn recursive_callee_is_not_inlined() {
+        let (ops, _) = inline(
+            "(func $rec (param i32) (result i32)
+                local.get 0
+                call $rec)
+            (func $main (result i32)
+                i32.const 1
+                call $rec)",
+            16,
+        );
+        assert_eq!(
+            ops,
+            vec!["wasm.const 0x1: si32", "wasm.call 0", "wasm.return"]
+        );
+    }
+}
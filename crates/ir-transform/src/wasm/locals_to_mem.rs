@@ -0,0 +1,545 @@
+//! Spill the function locals (including the parameters) to a frame in memory.
+//!
+//! The frames are allocated in a stack that grows down from the start address. The address
+//! of the current frame (base pointer) is kept in a new global, set to the start address
+//! at the entry of the start function. Every function with locals moves the base pointer down
+//! by its frame size on entry and back on every return, so the recursive calls get their own frames.
+//! A local `i` is stored at the base pointer + `i` * [LOCAL_SLOT_SIZE_BYTES].
+//!
+//! Expects the arguments on the stack (i.e. runs after `wasm-explicit-func-args`) and produces
+//! `global.get/set` ops for the base pointer (i.e. runs before `wasm-globals-to-mem`).
+//!
+//! Library-only for now: no target pipeline runs it. The Miden pipeline lowers the locals
+//! natively and runs `wasm-explicit-func-args` after the ozk lowering, when the globals are
+//! already in memory. The registry constructor places the frames below the globals of
+//! [MidenMemoryLayout].
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::attributes::apint_to_i32;
+use ozk_ozk_dialect::attributes::i32_attr;
+use ozk_ozk_dialect::attributes::i64_attr;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::ord_n::Ord16;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::GlobalIndex;
+use ozk_wasm_dialect::types::MemAddress;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::rewrite::RewritePatternSet;
+
+use crate::miden::memory_layout::MidenMemoryLayout;
+
+/// Size of the memory slot of a local (fits i64)
+pub const LOCAL_SLOT_SIZE_BYTES: u32 = 8;
+
+pub struct WasmLocalsToMemPass {
+    start_addr: MemAddress,
+}
+
+impl WasmLocalsToMemPass {
+    /// `start_addr` is the (exclusive) top of the frames region.
+    pub fn new(start_addr: MemAddress) -> Self {
+        Self { start_addr }
+    }
+}
+
+impl Pass for WasmLocalsToMemPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::new(WasmLocalsToMem {
+            start_addr: self.start_addr,
+        }));
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "wasm-locals-to-mem",
+        description: "Store Wasm locals in the memory frames addressed by a base pointer global (placed below the globals)",
+        constructor: || {
+            let layout = MidenMemoryLayout::default();
+            Box::new(WasmLocalsToMemPass::new(layout.locals_frames_start_address))
+        },
+    }
+}
+
+struct WasmLocalsToMem {
+    start_addr: MemAddress,
+}
+
+impl RewritePattern for WasmLocalsToMem {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        _rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(module_op) = opop.downcast_ref::<wasm::ModuleOp>() else {
+            return Ok(false);
+        };
+        let func_ops: Vec<wasm::FuncOp> = module_op
+            .get_body(ctx, 0)
+            .deref(ctx)
+            .iter(ctx)
+            .filter_map(|op| {
+                op.deref(ctx)
+                    .get_op(ctx)
+                    .downcast_ref::<wasm::FuncOp>()
+                    .cloned()
+            })
+            .collect();
+        if !func_ops.iter().any(|func_op| uses_locals(ctx, func_op)) {
+            return Ok(false);
+        }
+        let start_func = module_op
+            .get_start_func_sym(ctx)
+            .and_then(|start_func_sym| module_op.get_func(ctx, &start_func_sym))
+            .ok_or_else(|| anyhow!("start function is not found to initialize the base pointer"))?;
+        let i32_ty = i32_type(ctx);
+        let base_ptr = module_op.append_global(ctx, i32_ty);
+        for func_op in &func_ops {
+            let frame = Frame::new(ctx, func_op, base_ptr)?;
+            let entry_block = func_op.get_entry_block(ctx);
+            let mut prologue = Vec::new();
+            if func_op.get_operation() == start_func.get_operation() {
+                prologue.push(
+                    wasm::ConstantOp::new_i32_unlinked(ctx, u32::from(self.start_addr) as i32)
+                        .get_operation(),
+                );
+                prologue.push(wasm::GlobalSetOp::new_unlinked(ctx, base_ptr).get_operation());
+            }
+            prologue.extend(frame.prologue(ctx)?);
+            frame.rewrite_block(ctx, entry_block, prologue)?;
+            func_op.set_locals(ctx, Vec::new());
+        }
+        Ok(true)
+    }
+}
+
+/// Returns true if the function has locals to spill (the parameters are accessed
+/// via the local ops).
+fn uses_locals(ctx: &Context, func_op: &wasm::FuncOp) -> bool {
+    if !func_op.get_locals(ctx).is_empty() {
+        return true;
+    }
+    let mut found = false;
+    func_op
+        .get_operation()
+        .walk(ctx, WalkOrder::PreOrder, &mut |op| {
+            let opop = &op.deref(ctx).get_op(ctx);
+            if opop.is::<wasm::LocalGetOp>()
+                || opop.is::<wasm::LocalSetOp>()
+                || opop.is::<wasm::LocalTeeOp>()
+            {
+                found = true;
+                WalkResult::Interrupt
+            } else {
+                WalkResult::Advance
+            }
+        });
+    found
+}
+
+/// Memory frame of a function
+struct Frame {
+    base_ptr: GlobalIndex,
+    /// Types of the parameters followed by the locals (indexed by the local index)
+    local_types: Vec<wasm::MemAccessOpValueType>,
+    /// Number of the parameters (initialized from the stack on entry)
+    num_params: usize,
+}
+
+impl Frame {
+    fn new(
+        ctx: &Context,
+        func_op: &wasm::FuncOp,
+        base_ptr: GlobalIndex,
+    ) -> Result<Frame, anyhow::Error> {
+        let params = func_op.get_type(ctx).get_inputs().clone();
+        if !params.is_empty() && !func_op.has_args_on_stack(ctx) {
+            return Err(anyhow!(
+                "function {} expects the arguments in the locals, run wasm-explicit-func-args first",
+                func_op.get_symbol_name(ctx)
+            ));
+        }
+        let num_params = params.len();
+        let local_types = params
+            .into_iter()
+            .chain(func_op.get_locals(ctx))
+            .enumerate()
+            .map(|(index, ty)| {
+                wasm::MemAccessOpValueType::from_type(ctx, ty)
+                    .ok_or_else(|| anyhow!("unsupported type of local {index}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Frame {
+            base_ptr,
+            local_types,
+            num_params,
+        })
+    }
+
+    fn size(&self) -> i32 {
+        (self.local_types.len() as u32 * LOCAL_SLOT_SIZE_BYTES) as i32
+    }
+
+    /// Allocate the frame and zero the locals (the frame memory is reused by the calls).
+    fn prologue(&self, ctx: &mut Context) -> Result<Vec<Ptr<Operation>>, anyhow::Error> {
+        if self.local_types.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut ops = self.move_base_ptr(ctx, -self.size());
+        for index in self.num_params..self.local_types.len() {
+            let zero_attr = match self.local_types[index] {
+                wasm::MemAccessOpValueType::I32 => i32_attr(ctx, 0),
+                wasm::MemAccessOpValueType::I64 => i64_attr(ctx, 0),
+            };
+            ops.push(wasm::ConstantOp::new_unlinked(ctx, zero_attr).get_operation());
+            ops.extend(self.store_local(ctx, index as u32)?);
+        }
+        Ok(ops)
+    }
+
+    /// Free the frame.
+    fn epilogue(&self, ctx: &mut Context) -> Vec<Ptr<Operation>> {
+        if self.local_types.is_empty() {
+            return Vec::new();
+        }
+        self.move_base_ptr(ctx, self.size())
+    }
+
+    fn move_base_ptr(&self, ctx: &mut Context, delta: i32) -> Vec<Ptr<Operation>> {
+        let i32_ty = i32_type(ctx);
+        vec![
+            wasm::GlobalGetOp::new_unlinked(ctx, self.base_ptr.into()).get_operation(),
+            wasm::ConstantOp::new_i32_unlinked(ctx, delta).get_operation(),
+            wasm::AddOp::new_unlinked(ctx, i32_ty).get_operation(),
+            wasm::GlobalSetOp::new_unlinked(ctx, self.base_ptr).get_operation(),
+        ]
+    }
+
+    /// Push the address of the local.
+    fn local_address(&self, ctx: &mut Context, index: u32) -> Vec<Ptr<Operation>> {
+        let i32_ty = i32_type(ctx);
+        vec![
+            wasm::GlobalGetOp::new_unlinked(ctx, self.base_ptr.into()).get_operation(),
+            wasm::ConstantOp::new_i32_unlinked(ctx, (index * LOCAL_SLOT_SIZE_BYTES) as i32)
+                .get_operation(),
+            wasm::AddOp::new_unlinked(ctx, i32_ty).get_operation(),
+        ]
+    }
+
+    fn local_type(&self, index: u32) -> Result<wasm::MemAccessOpValueType, anyhow::Error> {
+        self.local_types
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| anyhow!("undefined local {index}"))
+    }
+
+    /// Push the value of the local.
+    fn load_local(
+        &self,
+        ctx: &mut Context,
+        index: u32,
+    ) -> Result<Vec<Ptr<Operation>>, anyhow::Error> {
+        let value_type = self.local_type(index)?;
+        let mut ops = self.local_address(ctx, index);
+        ops.push(wasm::LoadOp::new_unlinked(ctx, value_type).get_operation());
+        Ok(ops)
+    }
+
+    /// Pop the value on top of the stack into the local.
+    fn store_local(
+        &self,
+        ctx: &mut Context,
+        index: u32,
+    ) -> Result<Vec<Ptr<Operation>>, anyhow::Error> {
+        let value_type = self.local_type(index)?;
+        let mut ops = self.local_address(ctx, index);
+        ops.push(ozk::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation());
+        ops.push(wasm::StoreOp::new_unlinked(ctx, value_type).get_operation());
+        Ok(ops)
+    }
+
+    /// Replace the local ops in the block (and the nested blocks) with the memory access
+    /// and free the frame before every return. `prologue` is put at the start of the block.
+    fn rewrite_block(
+        &self,
+        ctx: &mut Context,
+        block: Ptr<BasicBlock>,
+        prologue: Vec<Ptr<Operation>>,
+    ) -> Result<(), anyhow::Error> {
+        let old_ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+        let mut ops = prologue;
+        for op in &old_ops {
+            let opop = &op.deref(ctx).get_op(ctx);
+            if let Some(local_get_op) = opop.downcast_ref::<wasm::LocalGetOp>() {
+                let index = u32::from(local_get_op.get_index(ctx));
+                ops.extend(self.load_local(ctx, index)?);
+            } else if let Some(local_set_op) = opop.downcast_ref::<wasm::LocalSetOp>() {
+                let index = u32::from(local_set_op.get_index(ctx));
+                ops.extend(self.store_local(ctx, index)?);
+            } else if let Some(local_tee_op) = opop.downcast_ref::<wasm::LocalTeeOp>() {
+                let index_attr = local_tee_op.get_index(ctx);
+                let index = index_attr
+                    .downcast_ref::<IntegerAttr>()
+                    .map(|attr| apint_to_i32(attr.clone().into()) as u32)
+                    .ok_or_else(|| anyhow!("local.tee index is not an IntegerAttr"))?;
+                // store and load it back to leave the value on the stack
+                ops.extend(self.store_local(ctx, index)?);
+                ops.extend(self.load_local(ctx, index)?);
+            } else if opop.is::<wasm::ReturnOp>() {
+                ops.extend(self.epilogue(ctx));
+                ops.push(*op);
+            } else if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+                self.rewrite_block(ctx, block_op.get_block(ctx), Vec::new())?;
+                ops.push(*op);
+            } else if let Some(loop_op) = opop.downcast_ref::<wasm::LoopOp>() {
+                self.rewrite_block(ctx, loop_op.get_block(ctx), Vec::new())?;
+                ops.push(*op);
+            } else {
+                ops.push(*op);
+            }
+        }
+        for op in old_ops {
+            op.unlink(ctx);
+        }
+        for op in ops {
+            op.insert_at_back(block, ctx);
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use pliron::with_context::AttachContext;
+
+    use super::*;
+    use crate::tests_util::as_wasm_module;
+    use crate::tests_util::run_wasm_passes;
+    use crate::wasm::explicit_func_args_pass::WasmExplicitFuncArgsPass;
+
+    fn run(wat: &str) -> (Context, wasm::ModuleOp) {
+        let mut ctx = Context::default();
+        let module_op = run_wasm_passes(
+            &mut ctx,
+            wat,
+            vec![
+                Box::<WasmExplicitFuncArgsPass>::default(),
+                Box::new(WasmLocalsToMemPass::new(0x1000.into())),
+            ],
+        );
+        let module_op = as_wasm_module(&ctx, module_op);
+        (ctx, module_op)
+    }
+
+    /// Render the ops of the function (including the nested ones), constants as decimals.
+    fn render_func(ctx: &Context, module_op: &wasm::ModuleOp, name: &str) -> Vec<String> {
+        let func_op = module_op.get_func(ctx, &name.to_string().into()).unwrap();
+        let mut ops = Vec::new();
+        for op in func_op.op_iter(ctx) {
+            op.walk(ctx, WalkOrder::PreOrder, &mut |op| {
+                let opop = &op.deref(ctx).get_op(ctx);
+                if let Some(const_op) = opop.downcast_ref::<wasm::ConstantOp>() {
+                    let value = const_op.get_value(ctx);
+                    let int_attr = value.downcast_ref::<IntegerAttr>().unwrap();
+                    ops.push(format!(
+                        "wasm.const {}",
+                        apint_to_i32(int_attr.clone().into())
+                    ));
+                } else {
+                    ops.push(op.deref(ctx).with_ctx(ctx).to_string());
+                }
+                WalkResult::Advance
+            });
+        }
+        ops
+    }
+
+    #[test]
+    fn locals_are_zeroed_in_the_start_func_frame() {
+        let (ctx, module_op) = run(r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 7
+        local.set 0
+        local.get 0
+        drop)
+)"#);
+        assert_eq!(
+            render_func(&ctx, &module_op, "main"),
+            vec![
+                // base pointer initialization
+                "wasm.const 4096",
+                "wasm.global.set 0",
+                // frame allocation
+                "wasm.global.get 0",
+                "wasm.const -8",
+                "wasm.add",
+                "wasm.global.set 0",
+                // local 0 = 0
+                "wasm.const 0",
+                "wasm.global.get 0",
+                "wasm.const 0",
+                "wasm.add",
+                "ozk.swap 1",
+                "wasm.store I32",
+                // local.set 0
+                "wasm.const 7",
+                "wasm.global.get 0",
+                "wasm.const 0",
+                "wasm.add",
+                "ozk.swap 1",
+                "wasm.store I32",
+                // local.get 0
+                "wasm.global.get 0",
+                "wasm.const 0",
+                "wasm.add",
+                "wasm.load I32",
+                "wasm.drop",
+                // frame release
+                "wasm.global.get 0",
+                "wasm.const 8",
+                "wasm.add",
+                "wasm.global.set 0",
+                "wasm.return",
+            ]
+        );
+        assert!(module_op
+            .get_func(&ctx, &"main".to_string().into())
+            .unwrap()
+            .get_locals(&ctx)
+            .is_empty());
+    }
+
+    #[test]
+    fn params_are_stored_from_the_stack() {
+        let (ctx, module_op) = run(r#"
+(module
+    (start $main)
+    (func $f (param i32 i64) (result i32)
+        local.get 0)
+    (func $main
+        i32.const 1
+        i64.const 2
+        call $f
+        drop)
+)"#);
+        assert_eq!(
+            render_func(&ctx, &module_op, "f"),
+            vec![
+                "wasm.global.get 0",
+                "wasm.const -16",
+                "wasm.add",
+                "wasm.global.set 0",
+                // the last argument is on top of the stack
+                "wasm.global.get 0",
+                "wasm.const 8",
+                "wasm.add",
+                "ozk.swap 1",
+                "wasm.store I64",
+                "wasm.global.get 0",
+                "wasm.const 0",
+                "wasm.add",
+                "ozk.swap 1",
+                "wasm.store I32",
+                "wasm.global.get 0",
+                "wasm.const 0",
+                "wasm.add",
+                "wasm.load I32",
+                "wasm.global.get 0",
+                "wasm.const 16",
+                "wasm.add",
+                "wasm.global.set 0",
+                "wasm.return",
+            ]
+        );
+    }
+
+    #[test]
+    fn every_return_releases_the_frame() {
+        let (ctx, module_op) = run(r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        (block
+            local.get 0
+            br_if 0
+            return))
+)"#);
+        let ops = render_func(&ctx, &module_op, "main");
+        let releases = ops
+            .windows(4)
+            .filter(|window| {
+                window
+                    == &[
+                        "wasm.global.get 0",
+                        "wasm.const 8",
+                        "wasm.add",
+                        "wasm.global.set 0",
+                    ]
+            })
+            .count();
+        let returns = ops.iter().filter(|op| *op == "wasm.return").count();
+        assert_eq!(returns, 2);
+        assert_eq!(releases, returns);
+    }
+
+    #[test]
+    fn base_pointer_is_added_after_the_module_globals() {
+        let (ctx, module_op) = run(r#"
+(module
+    (start $main)
+    (global $g (mut i32) i32.const 42)
+    (func $main
+        (local i32)
+        local.get 0
+        global.set $g)
+)"#);
+        assert!(module_op.get_global_type(&ctx, 1.into()).is_some());
+        let ops = render_func(&ctx, &module_op, "main");
+        assert_eq!(ops[1], "wasm.global.set 1");
+        // the module global is intact
+        assert!(ops.contains(&"wasm.global.set 0".to_string()));
+    }
+
+    #[test]
+    fn module_without_locals_is_intact() {
+        let (ctx, module_op) = run(r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 1
+        drop)
+)"#);
+        assert!(module_op.get_global_type(&ctx, 0.into()).is_none());
+        assert_eq!(
+            render_func(&ctx, &module_op, "main"),
+            vec!["wasm.const 1", "wasm.drop", "wasm.return"]
+        );
+    }
+}
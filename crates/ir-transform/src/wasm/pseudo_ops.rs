@@ -0,0 +1,642 @@
+//! Expansion of the pseudo ops, i.e. the ops a target can't execute natively.
+//!
+//! Every pseudo op is replaced with an `ozk.call` of a helper function built from the ops the
+//! target has. The helper is added to the module once, on the first expansion. Run before
+//! `dce-unused-functions`, so that the helpers called only from the unreachable functions
+//! (or inlined everywhere) are removed.
+//!
+//! Expects the arguments in the locals (i.e. runs before `wasm-explicit-func-args`).
+
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::RelativeDepth;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::dialects::builtin::types::IntegerType;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
+use pliron::rewrite::RewritePatternSet;
+
+/// An op that can be expanded into a helper function call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PseudoOp {
+    /// `a - b` as `a + b * -1`
+    I32Sub,
+    /// `a != b` as `(a == b) == 0`
+    I32Ne,
+    /// `a > b` as `b < a` (unsigned)
+    I32GtU,
+    /// `a >= b` as `b <= a` (unsigned)
+    I32GeU,
+    /// Signed `a < b` as the unsigned one with the flipped sign bits
+    I32LtS,
+    /// Signed `a > b` as the unsigned `b < a` with the flipped sign bits
+    I32GtS,
+    /// Signed `a <= b` as the unsigned one with the flipped sign bits
+    I32LeS,
+    /// Signed `a >= b` as the unsigned `b <= a` with the flipped sign bits
+    I32GeS,
+    /// Unsigned `a / b` as the shift-subtract long division
+    I32DivU,
+    /// Signed `a / b` as the long division of the absolute values
+    I32DivS,
+    /// Unsigned `a % b` as the shift-subtract long division
+    I32RemU,
+    /// Signed `a % b` as the long division of the absolute values
+    I32RemS,
+    /// The number of set bits as the parallel (SWAR) bit count
+    I32Popcnt,
+}
+
+impl PseudoOp {
+    /// Name of the helper function
+    pub fn helper_name(&self) -> &'static str {
+        match self {
+            PseudoOp::I32Sub => "ozk_pseudo_i32_sub",
+            PseudoOp::I32Ne => "ozk_pseudo_i32_ne",
+            PseudoOp::I32GtU => "ozk_pseudo_i32_gt_u",
+            PseudoOp::I32GeU => "ozk_pseudo_i32_ge_u",
+            PseudoOp::I32LtS => "ozk_pseudo_i32_lt_s",
+            PseudoOp::I32GtS => "ozk_pseudo_i32_gt_s",
+            PseudoOp::I32LeS => "ozk_pseudo_i32_le_s",
+            PseudoOp::I32GeS => "ozk_pseudo_i32_ge_s",
+            PseudoOp::I32DivU => "ozk_pseudo_i32_div_u",
+            PseudoOp::I32DivS => "ozk_pseudo_i32_div_s",
+            PseudoOp::I32RemU => "ozk_pseudo_i32_rem_u",
+            PseudoOp::I32RemS => "ozk_pseudo_i32_rem_s",
+            PseudoOp::I32Popcnt => "ozk_pseudo_i32_popcnt",
+        }
+    }
+
+    /// Returns true if the op is this pseudo op.
+    fn matches(&self, ctx: &Context, op: Ptr<Operation>) -> bool {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let ty = match self {
+            PseudoOp::I32Sub => opop
+                .downcast_ref::<wasm::SubOp>()
+                .map(|op| op.get_type(ctx)),
+            PseudoOp::I32Ne => opop.downcast_ref::<wasm::NeOp>().map(|op| op.get_type(ctx)),
+            PseudoOp::I32GtU => opop
+                .downcast_ref::<wasm::GtUOp>()
+                .map(|op| op.get_type(ctx)),
+            PseudoOp::I32GeU => opop
+                .downcast_ref::<wasm::GeUOp>()
+                .map(|op| op.get_type(ctx)),
+            PseudoOp::I32LtS => opop
+                .downcast_ref::<wasm::LtSOp>()
+                .map(|op| op.get_type(ctx)),
+            PseudoOp::I32GtS => opop
+                .downcast_ref::<wasm::GtSOp>()
+                .map(|op| op.get_type(ctx)),
+            PseudoOp::I32LeS => opop
+                .downcast_ref::<wasm::LeSOp>()
+                .map(|op| op.get_type(ctx)),
+            PseudoOp::I32GeS => opop
+                .downcast_ref::<wasm::GeSOp>()
+                .map(|op| op.get_type(ctx)),
+            PseudoOp::I32DivU => opop
+                .downcast_ref::<wasm::DivUOp>()
+                .map(|op| op.get_type(ctx)),
+            PseudoOp::I32DivS => opop
+                .downcast_ref::<wasm::DivSOp>()
+                .map(|op| op.get_type(ctx)),
+            PseudoOp::I32RemU => opop
+                .downcast_ref::<wasm::RemUOp>()
+                .map(|op| op.get_type(ctx)),
+            PseudoOp::I32RemS => opop
+                .downcast_ref::<wasm::RemSOp>()
+                .map(|op| op.get_type(ctx)),
+            PseudoOp::I32Popcnt => opop
+                .downcast_ref::<wasm::PopcntOp>()
+                .map(|op| op.get_type(ctx)),
+        };
+        ty.map_or(false, |ty| is_i32(ctx, ty))
+    }
+
+    /// Type of the helper function, every pseudo op but popcnt is a binary i32 op.
+    fn helper_type(&self, ctx: &mut Context) -> Ptr<TypeObj> {
+        let i32_ty = i32_type(ctx);
+        match self {
+            PseudoOp::I32Popcnt => FunctionType::get(ctx, vec![i32_ty], vec![i32_ty]),
+            PseudoOp::I32Sub
+            | PseudoOp::I32Ne
+            | PseudoOp::I32GtU
+            | PseudoOp::I32GeU
+            | PseudoOp::I32LtS
+            | PseudoOp::I32GtS
+            | PseudoOp::I32LeS
+            | PseudoOp::I32GeS
+            | PseudoOp::I32DivU
+            | PseudoOp::I32DivS
+            | PseudoOp::I32RemU
+            | PseudoOp::I32RemS => FunctionType::get(ctx, vec![i32_ty, i32_ty], vec![i32_ty]),
+        }
+    }
+
+    /// Locals of the helper function besides the parameters.
+    fn helper_locals(&self, ctx: &mut Context) -> Vec<Ptr<TypeObj>> {
+        match self {
+            PseudoOp::I32DivU | PseudoOp::I32DivS | PseudoOp::I32RemU | PseudoOp::I32RemS => {
+                vec![i32_type(ctx); (DIV_STEP - 1) as usize]
+            }
+            PseudoOp::I32Sub
+            | PseudoOp::I32Ne
+            | PseudoOp::I32GtU
+            | PseudoOp::I32GeU
+            | PseudoOp::I32LtS
+            | PseudoOp::I32GtS
+            | PseudoOp::I32LeS
+            | PseudoOp::I32GeS
+            | PseudoOp::I32Popcnt => vec![],
+        }
+    }
+
+    /// Body of the helper function. The operands are in the locals 0 (`a`) and 1 (`b`).
+    fn helper_body(&self, ctx: &mut Context) -> Vec<Ptr<Operation>> {
+        let i32_ty = i32_type(ctx);
+        let mut ops = Vec::new();
+        match self {
+            PseudoOp::I32Sub => {
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation());
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, 1).get_operation());
+                ops.push(wasm::ConstantOp::new_i32_unlinked(ctx, -1).get_operation());
+                ops.push(wasm::MulOp::new_unlinked(ctx, i32_ty).get_operation());
+                ops.push(wasm::AddOp::new_unlinked(ctx, i32_ty).get_operation());
+            }
+            PseudoOp::I32Ne => {
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation());
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, 1).get_operation());
+                ops.push(wasm::EqOp::new_unlinked(ctx, i32_ty).get_operation());
+                ops.push(wasm::ConstantOp::new_i32_unlinked(ctx, 0).get_operation());
+                ops.push(wasm::EqOp::new_unlinked(ctx, i32_ty).get_operation());
+            }
+            PseudoOp::I32GtU => {
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, 1).get_operation());
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation());
+                ops.push(wasm::LtUOp::new_unlinked(ctx, i32_ty).get_operation());
+            }
+            PseudoOp::I32GeU => {
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, 1).get_operation());
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation());
+                ops.push(wasm::LeUOp::new_unlinked(ctx, i32_ty).get_operation());
+            }
+            PseudoOp::I32LtS => {
+                ops.extend(flip_sign_bit(ctx, 0));
+                ops.extend(flip_sign_bit(ctx, 1));
+                ops.push(wasm::LtUOp::new_unlinked(ctx, i32_ty).get_operation());
+            }
+            PseudoOp::I32GtS => {
+                ops.extend(flip_sign_bit(ctx, 1));
+                ops.extend(flip_sign_bit(ctx, 0));
+                ops.push(wasm::LtUOp::new_unlinked(ctx, i32_ty).get_operation());
+            }
+            PseudoOp::I32LeS => {
+                ops.extend(flip_sign_bit(ctx, 0));
+                ops.extend(flip_sign_bit(ctx, 1));
+                ops.push(wasm::LeUOp::new_unlinked(ctx, i32_ty).get_operation());
+            }
+            PseudoOp::I32GeS => {
+                ops.extend(flip_sign_bit(ctx, 1));
+                ops.extend(flip_sign_bit(ctx, 0));
+                ops.push(wasm::LeUOp::new_unlinked(ctx, i32_ty).get_operation());
+            }
+            PseudoOp::I32DivU => {
+                ops.extend(unsigned_div(ctx, 0, 1));
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, DIV_QUOTIENT).get_operation());
+            }
+            PseudoOp::I32RemU => {
+                ops.extend(unsigned_div(ctx, 0, 1));
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, DIV_REMAINDER).get_operation());
+            }
+            PseudoOp::I32DivS => {
+                ops.extend(signed_div(ctx));
+                // the quotient is negative if the signs of the operands differ
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, DIV_QUOTIENT).get_operation());
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation());
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, 1).get_operation());
+                ops.push(wasm::XorOp::new_unlinked(ctx, i32_ty).get_operation());
+                ops.extend(apply_sign(ctx));
+            }
+            PseudoOp::I32RemS => {
+                ops.extend(signed_div(ctx));
+                // the remainder has the sign of the dividend
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, DIV_REMAINDER).get_operation());
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation());
+                ops.extend(apply_sign(ctx));
+            }
+            PseudoOp::I32Popcnt => ops.extend(popcnt(ctx)),
+        }
+        ops.push(wasm::ReturnOp::new_unlinked(ctx).get_operation());
+        ops
+    }
+
+    /// Build the helper function.
+    fn build_helper(&self, ctx: &mut Context) -> wasm::FuncOp {
+        let ty = self.helper_type(ctx);
+        let block = BasicBlock::new(ctx, Some("entry".into()), vec![]);
+        for op in self.helper_body(ctx) {
+            op.insert_at_back(block, ctx);
+        }
+        let locals = self.helper_locals(ctx);
+        wasm::FuncOp::new_unlinked_with_block(ctx, self.helper_name().into(), ty, block, locals)
+    }
+}
+
+/// Push the local with the sign bit flipped (maps the signed order onto the unsigned one).
+fn flip_sign_bit(ctx: &mut Context, local_index: u32) -> Vec<Ptr<Operation>> {
+    let i32_ty = i32_type(ctx);
+    vec![
+        wasm::LocalGetOp::new_unlinked(ctx, local_index).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, i32::MIN).get_operation(),
+        wasm::XorOp::new_unlinked(ctx, i32_ty).get_operation(),
+    ]
+}
+
+// Locals of the division helpers (after the parameters `a` and `b`).
+/// Absolute value of `a` (signed division only)
+const DIV_ABS_A: u32 = 2;
+/// Absolute value of `b` (signed division only)
+const DIV_ABS_B: u32 = 3;
+const DIV_QUOTIENT: u32 = 4;
+const DIV_REMAINDER: u32 = 5;
+/// Index of the dividend bit brought down on the current step
+const DIV_BIT: u32 = 6;
+/// 1 if the divisor is subtracted on the current step
+const DIV_STEP: u32 = 7;
+
+/// Long division of the unsigned `dividend` by `divisor` (locals), one bit per iteration.
+/// Leaves the quotient and the remainder in the [DIV_QUOTIENT] and [DIV_REMAINDER] locals.
+/// The result of the division by zero is unspecified (Wasm traps).
+fn unsigned_div(ctx: &mut Context, dividend: u32, divisor: u32) -> Vec<Ptr<Operation>> {
+    let i32_ty = i32_type(ctx);
+    let mut ops = Vec::new();
+    for (local, value) in [(DIV_QUOTIENT, 0), (DIV_REMAINDER, 0), (DIV_BIT, 32)] {
+        ops.push(wasm::ConstantOp::new_i32_unlinked(ctx, value).get_operation());
+        ops.push(wasm::LocalSetOp::new_unlinked(ctx, local).get_operation());
+    }
+    let block_type = FunctionType::get(ctx, vec![], vec![]);
+    let loop_op = wasm::LoopOp::new_unlinked(ctx, block_type);
+    let body = vec![
+        // bit -= 1
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_BIT).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 1).get_operation(),
+        wasm::SubOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalSetOp::new_unlinked(ctx, DIV_BIT).get_operation(),
+        // the remainder with its top bit set is above any divisor after the shift
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_REMAINDER).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 31).get_operation(),
+        wasm::ShrUOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalSetOp::new_unlinked(ctx, DIV_STEP).get_operation(),
+        // remainder = (remainder << 1) | ((dividend >> bit) & 1)
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_REMAINDER).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 1).get_operation(),
+        wasm::ShlOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, dividend).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_BIT).get_operation(),
+        wasm::ShrUOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 1).get_operation(),
+        wasm::AndOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::OrOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalSetOp::new_unlinked(ctx, DIV_REMAINDER).get_operation(),
+        // step |= divisor <= remainder
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_STEP).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, divisor).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_REMAINDER).get_operation(),
+        wasm::LeUOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::OrOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalSetOp::new_unlinked(ctx, DIV_STEP).get_operation(),
+        // remainder -= divisor & -step
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_REMAINDER).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, divisor).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 0).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_STEP).get_operation(),
+        wasm::SubOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::AndOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::SubOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalSetOp::new_unlinked(ctx, DIV_REMAINDER).get_operation(),
+        // quotient = (quotient << 1) | step
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_QUOTIENT).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 1).get_operation(),
+        wasm::ShlOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_STEP).get_operation(),
+        wasm::OrOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalSetOp::new_unlinked(ctx, DIV_QUOTIENT).get_operation(),
+        // continue until the last bit
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_BIT).get_operation(),
+        wasm::BrIfOp::new_unlinked(ctx, RelativeDepth::from(0)).get_operation(),
+    ];
+    let loop_block = loop_op.get_block(ctx);
+    for op in body {
+        op.insert_at_back(loop_block, ctx);
+    }
+    ops.push(loop_op.get_operation());
+    ops
+}
+
+/// Long division of the absolute values of `a` and `b`. The overflow (`i32::MIN / -1`)
+/// wraps around instead of trapping.
+fn signed_div(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let mut ops = Vec::new();
+    for (local, abs_local) in [(0, DIV_ABS_A), (1, DIV_ABS_B)] {
+        ops.push(wasm::LocalGetOp::new_unlinked(ctx, local).get_operation());
+        ops.push(wasm::LocalGetOp::new_unlinked(ctx, local).get_operation());
+        ops.extend(apply_sign(ctx));
+        ops.push(wasm::LocalSetOp::new_unlinked(ctx, abs_local).get_operation());
+    }
+    ops.extend(unsigned_div(ctx, DIV_ABS_A, DIV_ABS_B));
+    ops
+}
+
+/// Pops `s` and `v`, pushes `v` negated if `s` is negative: `(v ^ m) - m`, where `m = s >> 31`.
+fn apply_sign(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let i32_ty = i32_type(ctx);
+    vec![
+        wasm::ConstantOp::new_i32_unlinked(ctx, 31).get_operation(),
+        wasm::ShrSOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalTeeOp::new_unlinked(ctx, DIV_STEP).get_operation(),
+        wasm::XorOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, DIV_STEP).get_operation(),
+        wasm::SubOp::new_unlinked(ctx, i32_ty).get_operation(),
+    ]
+}
+
+/// The bit count of the local 0 summed in the 2, 4 and 8 bit fields, the bytes are summed
+/// by the multiplication.
+fn popcnt(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let i32_ty = i32_type(ctx);
+    vec![
+        // x -= (x >> 1) & 0x55555555
+        wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 1).get_operation(),
+        wasm::ShrUOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 0x5555_5555).get_operation(),
+        wasm::AndOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::SubOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalSetOp::new_unlinked(ctx, 0).get_operation(),
+        // x = (x & 0x33333333) + ((x >> 2) & 0x33333333)
+        wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 0x3333_3333).get_operation(),
+        wasm::AndOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 2).get_operation(),
+        wasm::ShrUOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 0x3333_3333).get_operation(),
+        wasm::AndOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::AddOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::LocalSetOp::new_unlinked(ctx, 0).get_operation(),
+        // x = (x + (x >> 4)) & 0x0f0f0f0f
+        wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 4).get_operation(),
+        wasm::ShrUOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::AddOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 0x0f0f_0f0f).get_operation(),
+        wasm::AndOp::new_unlinked(ctx, i32_ty).get_operation(),
+        // (x * 0x01010101) >> 24
+        wasm::ConstantOp::new_i32_unlinked(ctx, 0x0101_0101).get_operation(),
+        wasm::MulOp::new_unlinked(ctx, i32_ty).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 24).get_operation(),
+        wasm::ShrUOp::new_unlinked(ctx, i32_ty).get_operation(),
+    ]
+}
+
+fn is_i32(ctx: &Context, ty: Ptr<TypeObj>) -> bool {
+    ty.deref(ctx)
+        .downcast_ref::<IntegerType>()
+        .map_or(false, |int_ty| int_ty.get_width() == 32)
+}
+
+/// Replaces the pseudo ops declared by the target with the helper function calls.
+#[derive(Default)]
+pub struct WasmExpandPseudoOpsPass {
+    pseudo_ops: Vec<PseudoOp>,
+}
+
+impl WasmExpandPseudoOpsPass {
+    pub fn new(pseudo_ops: Vec<PseudoOp>) -> Self {
+        Self { pseudo_ops }
+    }
+}
+
+impl Pass for WasmExpandPseudoOpsPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::new(ExpandPseudoOps {
+            pseudo_ops: self.pseudo_ops.clone(),
+        }));
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "wasm-expand-pseudo-ops",
+        description: "Replace the ops the target lacks with the helper function calls",
+        constructor: || Box::new(WasmExpandPseudoOpsPass::new(vec![
+            PseudoOp::I32Sub,
+            PseudoOp::I32Ne,
+            PseudoOp::I32GtU,
+            PseudoOp::I32GeU,
+            PseudoOp::I32LtS,
+            PseudoOp::I32GtS,
+            PseudoOp::I32LeS,
+            PseudoOp::I32GeS,
+            PseudoOp::I32DivU,
+            PseudoOp::I32DivS,
+            PseudoOp::I32RemU,
+            PseudoOp::I32RemS,
+            PseudoOp::I32Popcnt,
+        ])),
+    }
+}
+
+struct ExpandPseudoOps {
+    pseudo_ops: Vec<PseudoOp>,
+}
+
+impl RewritePattern for ExpandPseudoOps {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(module_op) = opop.downcast_ref::<wasm::ModuleOp>() else {
+            return Ok(false);
+        };
+        let mut expansions: Vec<(Ptr<Operation>, PseudoOp)> = Vec::new();
+        module_op
+            .get_operation()
+            .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+                if let Some(pseudo_op) = self.pseudo_ops.iter().find(|p| p.matches(ctx, op)) {
+                    expansions.push((op, *pseudo_op));
+                }
+                WalkResult::Advance
+            });
+        if expansions.is_empty() {
+            return Ok(false);
+        }
+        for (op, pseudo_op) in expansions {
+            let helper_sym: FuncSym = pseudo_op.helper_name().into();
+            if module_op.get_func(ctx, &helper_sym).is_none() {
+                let helper = pseudo_op.build_helper(ctx);
+                module_op.append_function(ctx, helper);
+            }
+            let helper_type = pseudo_op.helper_type(ctx);
+            let func_type = helper_type
+                .deref(ctx)
+                .downcast_ref::<FunctionType>()
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("helper type is not a function type"))?;
+            let call_op = ozk::CallOp::new_unlinked(ctx, helper_sym, func_type);
+            rewriter.replace_op_with(ctx, op, call_op.get_operation())?;
+        }
+        Ok(true)
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dce::DceUnusedFunctionsPass;
+    use crate::tests_util::as_wasm_module;
+    use crate::tests_util::func_op_names;
+    use crate::tests_util::render_func;
+    use crate::tests_util::run_wasm_passes;
+
+    fn run(wat: &str, pseudo_ops: Vec<PseudoOp>) -> (Context, wasm::ModuleOp) {
+        let mut ctx = Context::default();
+        let module_op = run_wasm_passes(
+            &mut ctx,
+            wat,
+            vec![
+                Box::new(WasmExpandPseudoOpsPass::new(pseudo_ops)),
+                Box::<DceUnusedFunctionsPass>::default(),
+            ],
+        );
+        let module_op = as_wasm_module(&ctx, module_op);
+        (ctx, module_op)
+    }
+
+    #[test]
+    fn sub_becomes_helper_call() {
+        let (ctx, module_op) = run(
+            r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 7
+        i32.const 5
+        i32.sub
+        i32.const 1
+        i32.sub
+        drop
+        return)
+)"#,
+            vec![PseudoOp::I32Sub],
+        );
+        let main_ops = render_func(&ctx, &module_op, "main");
+        let calls = main_ops
+            .iter()
+            .filter(|op| op.contains("ozk.call") && op.contains("ozk_pseudo_i32_sub"))
+            .count();
+        assert_eq!(calls, 2);
+        assert!(!main_ops.iter().any(|op| op.starts_with("wasm.sub")));
+        let helper_ops = render_func(&ctx, &module_op, "ozk_pseudo_i32_sub");
+        assert_eq!(helper_ops.len(), 6);
+        assert!(helper_ops[3].starts_with("wasm.mul"));
+        assert!(helper_ops[4].starts_with("wasm.add"));
+    }
+
+    #[test]
+    fn only_declared_ops_are_expanded() {
+        let (ctx, module_op) = run(
+            r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 7
+        i32.const 5
+        i32.sub
+        drop
+        return)
+)"#,
+            vec![PseudoOp::I32Ne],
+        );
+        let main_ops = render_func(&ctx, &module_op, "main");
+        assert!(main_ops.iter().any(|op| op.starts_with("wasm.sub")));
+        assert!(module_op
+            .get_func(&ctx, &PseudoOp::I32Sub.helper_name().into())
+            .is_none());
+    }
+
+    #[test]
+    fn div_and_popcnt_become_helper_calls() {
+        let (ctx, module_op) = run(
+            r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 7
+        i32.const 5
+        i32.div_s
+        i32.popcnt
+        drop
+        return)
+)"#,
+            vec![PseudoOp::I32DivS, PseudoOp::I32Popcnt],
+        );
+        let main_ops = func_op_names(&ctx, &module_op, "main");
+        assert!(!main_ops
+            .iter()
+            .any(|op| op == "wasm.div_s" || op == "wasm.popcnt"));
+        let div_func = module_op
+            .get_func(&ctx, &PseudoOp::I32DivS.helper_name().into())
+            .unwrap();
+        assert_eq!(div_func.get_locals(&ctx).len(), 6);
+        let div_ops = func_op_names(&ctx, &module_op, "ozk_pseudo_i32_div_s");
+        assert_eq!(div_ops.iter().filter(|op| *op == "wasm.loop").count(), 1);
+        let popcnt_ops = func_op_names(&ctx, &module_op, "ozk_pseudo_i32_popcnt");
+        assert!(!popcnt_ops.iter().any(|op| op == "wasm.loop"));
+    }
+
+    #[test]
+    fn helpers_of_unreachable_funcs_are_removed() {
+        let (ctx, module_op) = run(
+            r#"
+(module
+    (start $main)
+    (func $main
+        return)
+    (func $unused
+        i32.const 7
+        i32.const 5
+        i32.lt_s
+        drop
+        return)
+)"#,
+            vec![PseudoOp::I32LtS],
+        );
+        assert!(module_op.get_func(&ctx, &"unused".into()).is_none());
+        assert!(module_op
+            .get_func(&ctx, &PseudoOp::I32LtS.helper_name().into())
+            .is_none());
+    }
+}
@@ -27,6 +27,14 @@ impl Pass for WasmCallOpToOzkCallOpPass {
     }
 }
 
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "wasm-resolve-call-op",
+        description: "Replace wasm.call (by function index) with ozk.call (by function symbol)",
+        constructor: || Box::<WasmCallOpToOzkCallOpPass>::default(),
+    }
+}
+
 #[derive(Default)]
 pub struct WasmCallOpToOzkCallOp;
 
@@ -0,0 +1,585 @@
+//! Keep the operand stack of every frame (function body, `block` or `loop`) within the top
+//! [ADDRESSABLE_STACK_DEPTH] values, the part of the Miden and Triton VM operand stack
+//! that the instructions can reach.
+//!
+//! When an op would leave more than [ADDRESSABLE_STACK_DEPTH] values in the frame, the bottom
+//! value of the frame is moved to the top (`ozk.movup`) and spilled to a new local. When an op
+//! needs more values than are left in the frame, the last spilled value is filled back from its
+//! local and moved below the frame values (`ozk.movdn`). The values are consumed in LIFO order,
+//! so the bottom value is the one needed last and every value is spilled and filled at most once.
+//! All the spilled values are filled back before a branch or the end of the frame.
+//!
+//! The stack depth is computed with [stack_effects] (the functions with an op of unknown stack
+//! effect are left as is). Expects the arguments on the stack (i.e. runs after
+//! `wasm-explicit-func-args`), the new locals are lowered along with the rest of the locals.
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::ord_n::Ord16;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::i64_type;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::typed_stack::stack_effects;
+use ozk_wasm_dialect::typed_stack::StackEffect;
+use ozk_wasm_dialect::typed_stack::StackType;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
+use pliron::rewrite::RewritePatternSet;
+
+/// Number of the values on top of the operand stack addressable by the VM instructions
+pub const ADDRESSABLE_STACK_DEPTH: usize = 16;
+
+#[derive(Default)]
+pub struct WasmSpillStackPass;
+
+impl Pass for WasmSpillStackPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::<WasmSpillStack>::default());
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "wasm-spill-stack",
+        description: "Spill the operand stack values beyond the top 16 addressable ones to locals",
+        constructor: || Box::<WasmSpillStackPass>::default(),
+    }
+}
+
+#[derive(Default)]
+struct WasmSpillStack;
+
+impl RewritePattern for WasmSpillStack {
+    fn match_and_rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        _rewriter: &mut dyn PatternRewriter,
+    ) -> Result<bool, anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(module_op) = opop.downcast_ref::<wasm::ModuleOp>().cloned() else {
+            return Ok(false);
+        };
+        let func_ops: Vec<wasm::FuncOp> = module_op
+            .get_body(ctx, 0)
+            .deref(ctx)
+            .iter(ctx)
+            .filter_map(|op| {
+                op.deref(ctx)
+                    .get_op(ctx)
+                    .downcast_ref::<wasm::FuncOp>()
+                    .cloned()
+            })
+            .collect();
+        let mut changed = false;
+        for func_op in func_ops {
+            let Some(effects) = stack_effects(ctx, &module_op, &func_op)? else {
+                continue;
+            };
+            let func_type = func_op.get_type(ctx);
+            let params: Vec<Ptr<TypeObj>> = func_type.get_inputs().clone();
+            let mut locals = func_op.get_locals(ctx);
+            let mut stack_types = Vec::new();
+            for ty in &params {
+                stack_types.push(
+                    StackType::from_type(ctx, *ty)
+                        .ok_or_else(|| anyhow!("unsupported function parameter type"))?,
+                );
+            }
+            let mut spiller = Spiller {
+                effects,
+                body_in_ozk: func_op.has_body_in_ozk(ctx),
+                first_spill_local: (params.len() + locals.len()) as u32,
+                spill_local_types: Vec::new(),
+                free_spill_locals: Vec::new(),
+                frames: Vec::new(),
+            };
+            let entry_values = if func_op.has_args_on_stack(ctx) {
+                // the first argument is on top of the stack
+                stack_types.into_iter().rev().collect()
+            } else {
+                Vec::new()
+            };
+            let ops: Vec<Ptr<Operation>> = func_op.op_iter(ctx).collect();
+            spiller.spill_frame(ctx, func_op.get_entry_block(ctx), ops, entry_values)?;
+            if spiller.spill_local_types.is_empty() {
+                continue;
+            }
+            for ty in spiller.spill_local_types {
+                locals.push(local_type(ctx, ty)?);
+            }
+            func_op.set_locals(ctx, locals);
+            changed = true;
+        }
+        Ok(changed)
+    }
+}
+
+fn local_type(ctx: &mut Context, ty: StackType) -> Result<Ptr<TypeObj>, anyhow::Error> {
+    match ty {
+        StackType::I32 => Ok(i32_type(ctx)),
+        StackType::I64 => Ok(i64_type(ctx)),
+        StackType::Felt => Err(anyhow!("spilling field elements is not supported")),
+    }
+}
+
+/// Values of a frame (function body, `block` or `loop`)
+struct FrameStack {
+    /// Values in the addressable part of the stack (the last one is on top)
+    values: Vec<StackType>,
+    /// Locals holding the spilled values (the last one is right below `values`)
+    spilled: Vec<(u32, StackType)>,
+}
+
+/// Where to insert the spill/fill ops
+#[derive(Clone, Copy)]
+enum InsertPoint {
+    Before(Ptr<Operation>),
+    AtEnd(Ptr<BasicBlock>),
+}
+
+impl InsertPoint {
+    fn insert(&self, ctx: &mut Context, ops: Vec<Ptr<Operation>>) {
+        for op in ops {
+            match self {
+                InsertPoint::Before(before) => op.insert_before(ctx, *before),
+                InsertPoint::AtEnd(block) => op.insert_at_back(*block, ctx),
+            }
+        }
+    }
+}
+
+struct Spiller {
+    effects: HashMap<Ptr<Operation>, StackEffect>,
+    /// Add `ozk` locals access instead of the Wasm one (see [wasm::FuncOp::has_body_in_ozk])
+    body_in_ozk: bool,
+    /// Index of the first new local
+    first_spill_local: u32,
+    /// Types of the new locals
+    spill_local_types: Vec<StackType>,
+    /// New locals not holding a spilled value
+    free_spill_locals: Vec<(u32, StackType)>,
+    /// Enclosing frames, the current one is the last
+    frames: Vec<FrameStack>,
+}
+
+enum BranchTarget {
+    Frame(usize),
+    Function,
+}
+
+impl Spiller {
+    fn frame(&mut self) -> Result<&mut FrameStack, anyhow::Error> {
+        self.frames
+            .last_mut()
+            .ok_or_else(|| anyhow!("no stack frame"))
+    }
+
+    fn spill_frame(
+        &mut self,
+        ctx: &mut Context,
+        block: Ptr<BasicBlock>,
+        ops: Vec<Ptr<Operation>>,
+        values: Vec<StackType>,
+    ) -> Result<(), anyhow::Error> {
+        self.frames.push(FrameStack {
+            values,
+            spilled: Vec::new(),
+        });
+        if self.spill_ops(ctx, ops)? {
+            self.fill(ctx, InsertPoint::AtEnd(block), usize::MAX)?;
+        }
+        let frame = self.frames.pop().ok_or_else(|| anyhow!("no stack frame"))?;
+        // the rest of the frame is unreachable after a branch
+        self.free_spill_locals.extend(frame.spilled);
+        Ok(())
+    }
+
+    /// Returns false if the end of the frame is unreachable.
+    fn spill_ops(
+        &mut self,
+        ctx: &mut Context,
+        ops: Vec<Ptr<Operation>>,
+    ) -> Result<bool, anyhow::Error> {
+        for op in ops {
+            let Some(effect) = self.effects.get(&op).cloned() else {
+                // unreachable code
+                return Ok(false);
+            };
+            let at = InsertPoint::Before(op);
+            let opop = op.deref(ctx).get_op(ctx);
+            let branch_target = if let Some(br_op) = opop.downcast_ref::<wasm::BrOp>() {
+                Some(BranchTarget::Frame(
+                    u32::from(br_op.get_relative_depth(ctx)) as usize,
+                ))
+            } else if let Some(br_if_op) = opop.downcast_ref::<wasm::BrIfOp>() {
+                Some(BranchTarget::Frame(
+                    u32::from(br_if_op.get_relative_depth(ctx)) as usize,
+                ))
+            } else if let Some(br_op) = opop.downcast_ref::<ozk::BrOp>() {
+                Some(BranchTarget::Frame(br_op.get_relative_depth(ctx) as usize))
+            } else if let Some(br_if_op) = opop.downcast_ref::<ozk::BrIfOp>() {
+                Some(BranchTarget::Frame(
+                    br_if_op.get_relative_depth(ctx) as usize
+                ))
+            } else if opop.is::<wasm::ReturnOp>() || opop.is::<ozk::ReturnOp>() {
+                Some(BranchTarget::Function)
+            } else {
+                None
+            };
+            let nested: Option<(Ptr<BasicBlock>, Vec<Ptr<Operation>>)> =
+                if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+                    Some((block_op.get_block(ctx), block_op.op_iter(ctx).collect()))
+                } else if let Some(loop_op) = opop.downcast_ref::<wasm::LoopOp>() {
+                    Some((loop_op.get_block(ctx), loop_op.op_iter(ctx).collect()))
+                } else if let Some(block_op) = opop.downcast_ref::<ozk::BlockOp>() {
+                    Some((block_op.get_block(ctx), block_op.op_iter(ctx).collect()))
+                } else if let Some(loop_op) = opop.downcast_ref::<ozk::LoopOp>() {
+                    Some((loop_op.get_block(ctx), loop_op.op_iter(ctx).collect()))
+                } else {
+                    None
+                };
+            if let Some(target) = branch_target {
+                self.check_exited_frames(target)?;
+                self.fill(ctx, at, usize::MAX)?;
+            } else {
+                self.fill(ctx, at, effect.popped)?;
+                self.make_room(ctx, at, effect.popped, effect.pushed.len())?;
+            }
+            let frame = self.frame()?;
+            let height = frame.values.len();
+            let inputs = frame.values.split_off(height.saturating_sub(effect.popped));
+            if let Some((block, ops)) = nested {
+                self.spill_frame(ctx, block, ops, inputs)?;
+            }
+            let frame = self.frame()?;
+            frame.values.extend(effect.pushed);
+            let unconditional = opop.is::<wasm::BrOp>()
+                || opop.is::<wasm::ReturnOp>()
+                || opop.is::<ozk::BrOp>()
+                || opop.is::<ozk::ReturnOp>();
+            if unconditional {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// The values of the frames exited by a branch are dropped, so their spilled values
+    /// cannot be left behind.
+    fn check_exited_frames(&self, target: BranchTarget) -> Result<(), anyhow::Error> {
+        let exited = match target {
+            BranchTarget::Frame(depth) => depth,
+            BranchTarget::Function => self.frames.len().saturating_sub(1),
+        };
+        let has_spilled = self
+            .frames
+            .iter()
+            .rev()
+            .skip(1)
+            .take(exited)
+            .any(|frame| !frame.spilled.is_empty());
+        if has_spilled {
+            return Err(anyhow!(
+                "branching out of a block with the stack values spilled below it is not supported"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fill the spilled values back until there are `count` values in the current frame
+    /// (or no spilled values left).
+    fn fill(
+        &mut self,
+        ctx: &mut Context,
+        at: InsertPoint,
+        count: usize,
+    ) -> Result<(), anyhow::Error> {
+        loop {
+            let frame = self.frame()?;
+            if frame.values.len() >= count {
+                return Ok(());
+            }
+            let Some((local, ty)) = frame.spilled.pop() else {
+                return Ok(());
+            };
+            let below = frame.values.len();
+            frame.values.insert(0, ty);
+            let mut ops = vec![self.local_get(ctx, local)];
+            if below > 0 {
+                ops.push(ozk::MovDnOp::new_unlinked(ctx, stack_index(below)?).get_operation());
+            }
+            at.insert(ctx, ops);
+            self.free_spill_locals.push((local, ty));
+        }
+    }
+
+    /// Spill the bottom values of the current frame until an op that pops `popped` and pushes
+    /// `pushed` values leaves at most [ADDRESSABLE_STACK_DEPTH] values.
+    fn make_room(
+        &mut self,
+        ctx: &mut Context,
+        at: InsertPoint,
+        popped: usize,
+        pushed: usize,
+    ) -> Result<(), anyhow::Error> {
+        loop {
+            let height = self.frame()?.values.len();
+            if height.saturating_sub(popped) + pushed <= ADDRESSABLE_STACK_DEPTH {
+                return Ok(());
+            }
+            if height <= popped {
+                return Err(anyhow!(
+                    "an op with more than {ADDRESSABLE_STACK_DEPTH} operands is not supported"
+                ));
+            }
+            let bottom = stack_index(height - 1)?;
+            let ty = self.frame()?.values.remove(0);
+            let local = self.alloc_local(ty);
+            at.insert(
+                ctx,
+                vec![
+                    ozk::MovUpOp::new_unlinked(ctx, bottom).get_operation(),
+                    self.local_set(ctx, local),
+                ],
+            );
+            self.frame()?.spilled.push((local, ty));
+        }
+    }
+
+    fn local_get(&self, ctx: &mut Context, local: u32) -> Ptr<Operation> {
+        if self.body_in_ozk {
+            ozk::LocalGetOp::new_unlinked(ctx, local).get_operation()
+        } else {
+            wasm::LocalGetOp::new_unlinked(ctx, local).get_operation()
+        }
+    }
+
+    fn local_set(&self, ctx: &mut Context, local: u32) -> Ptr<Operation> {
+        if self.body_in_ozk {
+            ozk::LocalSetOp::new_unlinked(ctx, local).get_operation()
+        } else {
+            wasm::LocalSetOp::new_unlinked(ctx, local).get_operation()
+        }
+    }
+
+    fn alloc_local(&mut self, ty: StackType) -> u32 {
+        if let Some(pos) = self
+            .free_spill_locals
+            .iter()
+            .position(|(_, free_ty)| *free_ty == ty)
+        {
+            return self.free_spill_locals.swap_remove(pos).0;
+        }
+        self.spill_local_types.push(ty);
+        self.first_spill_local + self.spill_local_types.len() as u32 - 1
+    }
+}
+
+fn stack_index(index: usize) -> Result<Ord16, anyhow::Error> {
+    Ord16::try_from(index).map_err(|_| {
+        anyhow!("stack index {index} is out of the top {ADDRESSABLE_STACK_DEPTH} stack values")
+    })
+}
+
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::panic)]
+#[cfg(test)]
+mod tests {
+    use ozk_ozk_dialect::attributes::apint_to_i32;
+    use pliron::dialects::builtin::attributes::IntegerAttr;
+    use pliron::with_context::AttachContext;
+
+    use super::*;
+    use crate::tests_util::as_wasm_module;
+    use crate::tests_util::run_wasm_passes;
+    use crate::wasm::explicit_func_args_pass::WasmExplicitFuncArgsPass;
+
+    fn run(wat: &str) -> (Context, wasm::ModuleOp) {
+        let mut ctx = Context::default();
+        let module_op = run_wasm_passes(
+            &mut ctx,
+            wat,
+            vec![
+                Box::<WasmExplicitFuncArgsPass>::default(),
+                Box::<WasmSpillStackPass>::default(),
+            ],
+        );
+        let module_op = as_wasm_module(&ctx, module_op);
+        (ctx, module_op)
+    }
+
+    /// Evaluates i32 stack ops, checking that no frame holds more than
+    /// [ADDRESSABLE_STACK_DEPTH] values.
+    struct Eval {
+        stack: Vec<i32>,
+        locals: HashMap<u32, i32>,
+        spill_ops: usize,
+    }
+
+    impl Eval {
+        fn run(&mut self, ctx: &Context, ops: Vec<Ptr<Operation>>) {
+            let frame_start = self.stack.len();
+            for op in ops {
+                let opop = op.deref(ctx).get_op(ctx);
+                if let Some(const_op) = opop.downcast_ref::<wasm::ConstantOp>() {
+                    let value = const_op.get_value(ctx);
+                    let int_attr = value.downcast_ref::<IntegerAttr>().unwrap();
+                    self.stack.push(apint_to_i32(int_attr.clone().into()));
+                } else if opop.is::<wasm::SubOp>() {
+                    let b = self.stack.pop().unwrap();
+                    let a = self.stack.pop().unwrap();
+                    self.stack.push(a.wrapping_sub(b));
+                } else if let Some(set_op) = opop.downcast_ref::<wasm::LocalSetOp>() {
+                    let value = self.stack.pop().unwrap();
+                    self.locals.insert(set_op.get_index(ctx).into(), value);
+                } else if let Some(get_op) = opop.downcast_ref::<wasm::LocalGetOp>() {
+                    let index: u32 = get_op.get_index(ctx).into();
+                    self.stack.push(self.locals[&index]);
+                } else if let Some(movup_op) = opop.downcast_ref::<ozk::MovUpOp>() {
+                    let index: usize = movup_op.get_index(ctx).into();
+                    let value = self.stack.remove(self.stack.len() - 1 - index);
+                    self.stack.push(value);
+                    self.spill_ops += 1;
+                } else if let Some(movdn_op) = opop.downcast_ref::<ozk::MovDnOp>() {
+                    let index: usize = movdn_op.get_index(ctx).into();
+                    let value = self.stack.pop().unwrap();
+                    self.stack.insert(self.stack.len() - index, value);
+                } else if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+                    self.run(ctx, block_op.op_iter(ctx).collect());
+                } else if opop.is::<wasm::ReturnOp>() {
+                    return;
+                } else {
+                    panic!("unexpected op {}", op.deref(ctx).with_ctx(ctx));
+                }
+                assert!(self.stack.len() - frame_start <= ADDRESSABLE_STACK_DEPTH);
+            }
+        }
+    }
+
+    fn eval(ctx: &Context, module_op: &wasm::ModuleOp, name: &str) -> (Vec<i32>, usize) {
+        let func_op = module_op.get_func(ctx, &name.to_string().into()).unwrap();
+        let mut eval = Eval {
+            stack: Vec::new(),
+            locals: HashMap::new(),
+            spill_ops: 0,
+        };
+        eval.run(ctx, func_op.op_iter(ctx).collect());
+        (eval.stack, eval.spill_ops)
+    }
+
+    /// `v1 - (v2 - (v3 - ...))` for the values pushed in order
+    fn sub_chain(values: impl DoubleEndedIterator<Item = i32>) -> i32 {
+        values.rev().reduce(|acc, v| v.wrapping_sub(acc)).unwrap()
+    }
+
+    fn consts(values: impl Iterator<Item = i32>) -> String {
+        values
+            .map(|v| format!("i32.const {v}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn subs(count: usize) -> String {
+        vec!["i32.sub"; count].join("\n")
+    }
+
+    #[test]
+    fn spill_and_fill_in_func_body() {
+        let wat = format!(
+            r#"
+(module
+    (start $main)
+    (func $f (result i32)
+        {}
+        {}
+        return)
+    (func $main
+        call $f
+        drop
+        return)
+)"#,
+            consts(1..=20),
+            subs(19)
+        );
+        let (ctx, module_op) = run(&wat);
+        let (stack, spill_ops) = eval(&ctx, &module_op, "f");
+        assert_eq!(stack, vec![sub_chain(1..=20)]);
+        assert_eq!(spill_ops, 4);
+        let func_op = module_op.get_func(&ctx, &"f".to_string().into()).unwrap();
+        // a local for each of the values spilled at the same time
+        assert_eq!(func_op.get_locals(&ctx).len(), 4);
+    }
+
+    #[test]
+    fn frames_are_spilled_separately() {
+        let wat = format!(
+            r#"
+(module
+    (start $main)
+    (func $f (result i32)
+        {}
+        (block (result i32)
+            {}
+            {})
+        {}
+        return)
+    (func $main
+        call $f
+        drop
+        return)
+)"#,
+            consts(1..=16),
+            consts(17..=34),
+            subs(17),
+            subs(16)
+        );
+        let (ctx, module_op) = run(&wat);
+        let (stack, spill_ops) = eval(&ctx, &module_op, "f");
+        let inner = sub_chain(17..=34);
+        assert_eq!(stack, vec![sub_chain((1..=16).chain([inner]))]);
+        // one outer value to make room for the block result, two values in the block
+        assert_eq!(spill_ops, 3);
+    }
+
+    #[test]
+    fn shallow_stack_is_left_as_is() {
+        let wat = format!(
+            r#"
+(module
+    (start $main)
+    (func $f (result i32)
+        {}
+        {}
+        return)
+    (func $main
+        call $f
+        drop
+        return)
+)"#,
+            consts(1..=16),
+            subs(15)
+        );
+        let (ctx, module_op) = run(&wat);
+        let (stack, spill_ops) = eval(&ctx, &module_op, "f");
+        assert_eq!(stack, vec![sub_chain(1..=16)]);
+        assert_eq!(spill_ops, 0);
+    }
+}
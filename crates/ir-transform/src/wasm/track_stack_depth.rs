@@ -42,6 +42,14 @@ impl Pass for WasmTrackStackDepthPass {
     }
 }
 
+inventory::submit! {
+    crate::pass_registry::PassRegistration {
+        name: "wasm-track-stack-depth",
+        description: "Annotate Wasm ops with the operand stack depth (reserving space for locals)",
+        constructor: || Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+    }
+}
+
 pub struct WasmWriteStackDepth {
     pub reserve_space_for_locals: bool,
 }
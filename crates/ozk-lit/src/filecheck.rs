@@ -0,0 +1,310 @@
+//! A minimal FileCheck: matches `CHECK:`, `CHECK-NEXT:` and `CHECK-NOT:` directives
+//! against the printed IR.
+//!
+//! Patterns are plain substrings (leading and trailing whitespace ignored) matched line by line.
+//! `CHECK:` matches the first line after the previous match, `CHECK-NEXT:` must match the line
+//! right after the previous match and `CHECK-NOT:` must not match any line between the
+//! surrounding positive matches (or till the end of the output).
+
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckKind {
+    Check,
+    CheckNext,
+    CheckNot,
+}
+
+impl CheckKind {
+    fn prefix(&self) -> &'static str {
+        match self {
+            CheckKind::Check => "CHECK:",
+            CheckKind::CheckNext => "CHECK-NEXT:",
+            CheckKind::CheckNot => "CHECK-NOT:",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckDirective {
+    pub kind: CheckKind,
+    pub pattern: String,
+    /// 1-based line number of the directive in the test file
+    pub line: usize,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FileCheckError {
+    #[error("line {line}: empty {prefix} pattern")]
+    EmptyPattern { line: usize, prefix: &'static str },
+    #[error("no CHECK directives found")]
+    NoChecks,
+    #[error("line {line}: CHECK-NEXT without a previous match")]
+    NextWithoutMatch { line: usize },
+    #[error("line {line}: CHECK: {pattern:?} not found in the output")]
+    NotFound { line: usize, pattern: String },
+    #[error("line {line}: CHECK-NEXT: {pattern:?} does not match the next output line {actual:?}")]
+    NextMismatch {
+        line: usize,
+        pattern: String,
+        actual: Option<String>,
+    },
+    #[error("line {line}: CHECK-NOT: {pattern:?} found in output line {output_line}: {output:?}")]
+    Excluded {
+        line: usize,
+        pattern: String,
+        output_line: usize,
+        output: String,
+    },
+}
+
+/// Collect the check directives from the test file source.
+pub fn parse_check_directives(source: &str) -> Result<Vec<CheckDirective>, FileCheckError> {
+    let mut directives = Vec::new();
+    for (idx, line) in source.lines().enumerate() {
+        for kind in [CheckKind::CheckNext, CheckKind::CheckNot, CheckKind::Check] {
+            if let Some(pos) = line.find(kind.prefix()) {
+                let pattern = line[pos + kind.prefix().len()..].trim();
+                if pattern.is_empty() {
+                    return Err(FileCheckError::EmptyPattern {
+                        line: idx + 1,
+                        prefix: kind.prefix(),
+                    });
+                }
+                directives.push(CheckDirective {
+                    kind,
+                    pattern: pattern.to_string(),
+                    line: idx + 1,
+                });
+                break;
+            }
+        }
+    }
+    if directives.is_empty() {
+        return Err(FileCheckError::NoChecks);
+    }
+    Ok(directives)
+}
+
+/// Match the `output` against the `directives`.
+pub fn check_output(output: &str, directives: &[CheckDirective]) -> Result<(), FileCheckError> {
+    let lines: Vec<&str> = output.lines().collect();
+    let mut next_line = 0;
+    let mut last_match: Option<usize> = None;
+    let mut pending_nots: Vec<&CheckDirective> = Vec::new();
+    for directive in directives {
+        match directive.kind {
+            CheckKind::CheckNot => pending_nots.push(directive),
+            CheckKind::Check => {
+                let found = lines[next_line..]
+                    .iter()
+                    .position(|l| l.contains(&directive.pattern))
+                    .map(|pos| pos + next_line)
+                    .ok_or_else(|| FileCheckError::NotFound {
+                        line: directive.line,
+                        pattern: directive.pattern.clone(),
+                    })?;
+                check_nots(&pending_nots, &lines, next_line, found)?;
+                pending_nots.clear();
+                last_match = Some(found);
+                next_line = found + 1;
+            }
+            CheckKind::CheckNext => {
+                let Some(prev) = last_match else {
+                    return Err(FileCheckError::NextWithoutMatch {
+                        line: directive.line,
+                    });
+                };
+                let expected = prev + 1;
+                match lines.get(expected) {
+                    Some(l) if l.contains(&directive.pattern) => (),
+                    Some(_) | None => {
+                        return Err(FileCheckError::NextMismatch {
+                            line: directive.line,
+                            pattern: directive.pattern.clone(),
+                            actual: lines.get(expected).map(|l| l.to_string()),
+                        })
+                    }
+                }
+                // nothing lies between the previous match and this one
+                pending_nots.clear();
+                last_match = Some(expected);
+                next_line = expected + 1;
+            }
+        }
+    }
+    check_nots(&pending_nots, &lines, next_line, lines.len())
+}
+
+fn check_nots(
+    nots: &[&CheckDirective],
+    lines: &[&str],
+    start: usize,
+    end: usize,
+) -> Result<(), FileCheckError> {
+    for not in nots {
+        if let Some(pos) = lines[start..end]
+            .iter()
+            .position(|l| l.contains(&not.pattern))
+        {
+            return Err(FileCheckError::Excluded {
+                line: not.line,
+                pattern: not.pattern.clone(),
+                output_line: start + pos + 1,
+                output: lines[start + pos].to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    const OUTPUT: &str = r#"wasm.module @module_name {
+  block_1_0():
+    wasm.func @main() -> () {
+      entry():
+        wasm.const 0x9: si32
+        wasm.const 0x1000: si32
+        ozk.swap 1
+        wasm.store I64
+        wasm.return
+    }
+}"#;
+
+    fn check(checks: &str) -> Result<(), FileCheckError> {
+        check_output(OUTPUT, &parse_check_directives(checks).unwrap())
+    }
+
+    #[test]
+    fn parse_directives() {
+        let directives = parse_check_directives(
+            r#"
+;; RUN: ozk-opt %s
+;; CHECK: wasm.func @main
+;; CHECK-NEXT:   entry():
+;; CHECK-NOT: wasm.global.set
+"#,
+        )
+        .unwrap();
+        expect![[r#"
+            [
+                CheckDirective {
+                    kind: Check,
+                    pattern: "wasm.func @main",
+                    line: 3,
+                },
+                CheckDirective {
+                    kind: CheckNext,
+                    pattern: "entry():",
+                    line: 4,
+                },
+                CheckDirective {
+                    kind: CheckNot,
+                    pattern: "wasm.global.set",
+                    line: 5,
+                },
+            ]"#]]
+        .assert_eq(&format!("{directives:#?}"));
+        assert_eq!(
+            parse_check_directives(";; CHECK:  ").err(),
+            Some(FileCheckError::EmptyPattern {
+                line: 1,
+                prefix: "CHECK:"
+            })
+        );
+        assert_eq!(
+            parse_check_directives(";; RUN: ozk-opt %s").err(),
+            Some(FileCheckError::NoChecks)
+        );
+    }
+
+    #[test]
+    fn check_in_order() {
+        check(
+            r#"
+;; CHECK: wasm.const 0x9
+;; CHECK: wasm.store
+;; CHECK: wasm.return
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            check(
+                r#"
+;; CHECK: wasm.store
+;; CHECK: wasm.const 0x9
+"#,
+            )
+            .err(),
+            Some(FileCheckError::NotFound {
+                line: 3,
+                pattern: "wasm.const 0x9".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn check_next() {
+        check(
+            r#"
+;; CHECK: ozk.swap 1
+;; CHECK-NEXT: wasm.store I64
+;; CHECK-NEXT: wasm.return
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            check(
+                r#"
+;; CHECK: wasm.const 0x9
+;; CHECK-NEXT: ozk.swap 1
+"#,
+            )
+            .err(),
+            Some(FileCheckError::NextMismatch {
+                line: 3,
+                pattern: "ozk.swap 1".to_string(),
+                actual: Some("        wasm.const 0x1000: si32".to_string())
+            })
+        );
+        assert_eq!(
+            check(";; CHECK-NEXT: wasm.return").err(),
+            Some(FileCheckError::NextWithoutMatch { line: 1 })
+        );
+    }
+
+    #[test]
+    fn check_not() {
+        check(
+            r#"
+;; CHECK: wasm.func @main
+;; CHECK-NOT: wasm.load
+;; CHECK: wasm.return
+;; CHECK-NOT: wasm.const
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            check(
+                r#"
+;; CHECK: wasm.const 0x9
+;; CHECK-NOT: ozk.swap
+;; CHECK: wasm.return
+"#,
+            )
+            .err(),
+            Some(FileCheckError::Excluded {
+                line: 3,
+                pattern: "ozk.swap".to_string(),
+                output_line: 7,
+                output: "        ozk.swap 1".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Lit-style test runner for OmniZK passes.
+//!
+//! A test file (`.wat` or `.ozk`, both hold a Wasm text module) carries its own directives in
+//! comments, e.g.:
+//! ```text
+//! ;; RUN: ozk-opt %s --pass-pipeline=wasm-globals-to-mem --verify-each
+//! ;; CHECK: wasm.func @main
+//! ;; CHECK-NEXT: entry():
+//! ;; CHECK-NOT: wasm.global.set
+//! ```
+//! Every `RUN:` line runs the `ozk-opt` driver in-process and the printed IR is matched
+//! against the check directives (see [`filecheck`]).
+
+// Coding conventions
+// #![deny(unsafe_code)]
+#![deny(non_upper_case_globals)]
+#![deny(non_camel_case_types)]
+#![deny(non_snake_case)]
+#![deny(unused_mut)]
+// #![deny(dead_code)]
+#![deny(unused_imports)]
+// #![deny(missing_docs)]
+// Clippy exclusions
+#![deny(rustdoc::broken_intra_doc_links)]
+#![deny(clippy::wildcard_enum_match_arm)]
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+// #![deny(clippy::todo)]
+#![deny(clippy::unimplemented)]
+#![deny(clippy::panic)]
+
+pub mod filecheck;
+pub mod run_line;
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use ozk_opt::run_opt;
+use ozk_opt::OptError;
+use thiserror::Error;
+
+use crate::filecheck::check_output;
+use crate::filecheck::parse_check_directives;
+use crate::filecheck::FileCheckError;
+use crate::run_line::parse_run_lines;
+use crate::run_line::RunLineError;
+
+/// Extensions of the test files picked up by [`discover_test_files`].
+pub const TEST_FILE_EXTENSIONS: [&str; 2] = ["wat", "ozk"];
+
+#[derive(Debug, Error)]
+pub enum LitError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    RunLine(#[from] RunLineError),
+    #[error("{0}")]
+    FileCheck(#[from] FileCheckError),
+    #[error("line {line}: ozk-opt failed: {err}")]
+    Opt { line: usize, err: OptError },
+    #[error("line {line}: {err}\noutput:\n{output}")]
+    Check {
+        line: usize,
+        err: FileCheckError,
+        output: String,
+    },
+}
+
+/// Recursively find test files (with a `RUN:` directive) in `dir`, sorted by path.
+pub fn discover_test_files(dir: &Path) -> Result<Vec<PathBuf>, LitError> {
+    let mut files = Vec::new();
+    collect_test_files(dir, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+fn collect_test_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), LitError> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_test_files(&path, files)?;
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map_or(false, |ext| TEST_FILE_EXTENSIONS.contains(&ext))
+            && std::fs::read_to_string(&path)?.contains("RUN:")
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Run all `RUN:` directives of the test file and check the output.
+pub fn run_test_file(path: &Path) -> Result<(), LitError> {
+    let source = std::fs::read_to_string(path)?;
+    run_test_source(&source)
+}
+
+/// Run all `RUN:` directives of the test file source and check the output.
+pub fn run_test_source(source: &str) -> Result<(), LitError> {
+    let run_lines = parse_run_lines(source)?;
+    let directives = parse_check_directives(source)?;
+    for run_line in run_lines {
+        let output = run_opt(
+            source.as_bytes(),
+            &run_line.pass_pipeline,
+            run_line.verify_each,
+        )
+        .map_err(|err| LitError::Opt {
+            line: run_line.line,
+            err,
+        })?;
+        check_output(&output, &directives).map_err(|err| LitError::Check {
+            line: run_line.line,
+            err,
+            output,
+        })?;
+    }
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! `RUN:` directives.
+//!
+//! The only supported tool is `ozk-opt`, which is run in-process:
+//! `RUN: ozk-opt %s --pass-pipeline=<pass>[,<pass>...] [--verify-each] | FileCheck %s`
+//! (the `| FileCheck %s` suffix is optional, the output is always checked).
+
+use thiserror::Error;
+
+const RUN_PREFIX: &str = "RUN:";
+const PASS_PIPELINE_FLAG: &str = "--pass-pipeline=";
+const VERIFY_EACH_FLAG: &str = "--verify-each";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunLine {
+    /// Comma-separated pass names for `ozk-opt`
+    pub pass_pipeline: String,
+    /// Verify the IR after every pass
+    pub verify_each: bool,
+    /// 1-based line number of the directive in the test file
+    pub line: usize,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RunLineError {
+    #[error("no RUN: directive found")]
+    NoRunLine,
+    #[error("line {line}: unsupported RUN: command {command:?}")]
+    UnsupportedCommand { line: usize, command: String },
+}
+
+/// Collect the `RUN:` directives from the test file source.
+pub fn parse_run_lines(source: &str) -> Result<Vec<RunLine>, RunLineError> {
+    let mut run_lines = Vec::new();
+    for (idx, line) in source.lines().enumerate() {
+        let Some(pos) = line.find(RUN_PREFIX) else {
+            continue;
+        };
+        let command = line[pos + RUN_PREFIX.len()..].trim();
+        let unsupported = || RunLineError::UnsupportedCommand {
+            line: idx + 1,
+            command: command.to_string(),
+        };
+        let (opt_command, filecheck_command) = match command.split_once('|') {
+            Some((opt, filecheck)) => (opt, Some(filecheck)),
+            None => (command, None),
+        };
+        if let Some(filecheck) = filecheck_command {
+            if filecheck.split_whitespace().collect::<Vec<_>>() != ["FileCheck", "%s"] {
+                return Err(unsupported());
+            }
+        }
+        let mut args = opt_command.split_whitespace();
+        if args.next() != Some("ozk-opt") {
+            return Err(unsupported());
+        }
+        let mut pass_pipeline = String::new();
+        let mut verify_each = false;
+        for arg in args {
+            if arg == "%s" {
+                continue;
+            }
+            if arg == VERIFY_EACH_FLAG {
+                verify_each = true;
+                continue;
+            }
+            let Some(pipeline) = arg.strip_prefix(PASS_PIPELINE_FLAG) else {
+                return Err(unsupported());
+            };
+            pass_pipeline = pipeline.to_string();
+        }
+        run_lines.push(RunLine {
+            pass_pipeline,
+            verify_each,
+            line: idx + 1,
+        });
+    }
+    if run_lines.is_empty() {
+        return Err(RunLineError::NoRunLine);
+    }
+    Ok(run_lines)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_run() {
+        assert_eq!(
+            parse_run_lines(
+                r#"
+;; RUN: ozk-opt %s --pass-pipeline=wasm-track-stack-depth,valida-track-pc --verify-each | FileCheck %s
+;; RUN: ozk-opt %s
+"#
+            )
+            .unwrap(),
+            vec![
+                RunLine {
+                    pass_pipeline: "wasm-track-stack-depth,valida-track-pc".to_string(),
+                    verify_each: true,
+                    line: 2
+                },
+                RunLine {
+                    pass_pipeline: "".to_string(),
+                    verify_each: false,
+                    line: 3
+                },
+            ]
+        );
+        assert_eq!(
+            parse_run_lines(";; CHECK: wasm.func").err(),
+            Some(RunLineError::NoRunLine)
+        );
+        assert_eq!(
+            parse_run_lines(";; RUN: opt %s --pass-pipeline=canonicalize").err(),
+            Some(RunLineError::UnsupportedCommand {
+                line: 1,
+                command: "opt %s --pass-pipeline=canonicalize".to_string()
+            })
+        );
+        assert_eq!(
+            parse_run_lines(";; RUN: ozk-opt %s | grep wasm").err(),
+            Some(RunLineError::UnsupportedCommand {
+                line: 1,
+                command: "ozk-opt %s | grep wasm".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use std::path::Path;
+
+use ozk_lit::discover_test_files;
+use ozk_lit::run_test_file;
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn lit_tests() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("lit");
+    let files = discover_test_files(&dir).unwrap();
+    assert!(!files.is_empty(), "no lit tests found in {}", dir.display());
+    let failures: Vec<String> = files
+        .iter()
+        .filter_map(|file| {
+            run_test_file(file)
+                .err()
+                .map(|err| format!("{}: {}", file.display(), err))
+        })
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "{} of {} lit tests failed:\n{}",
+        failures.len(),
+        files.len(),
+        failures.join("\n\n")
+    );
+}
@@ -0,0 +1,47 @@
+;; RUN: ozk-opt %s --pass-pipeline=wasm-lower-to-ozk,ozk-legalize-i64 --verify-each | FileCheck %s
+
+;; CHECK: wasm.func @add
+;; CHECK-NEXT: entry():
+;; CHECK-NEXT: ozk.local.get 0
+;; CHECK-NEXT: ozk.local.get 1
+;; CHECK-NEXT: ozk.local.get 2
+;; CHECK-NEXT: ozk.local.get 3
+;; CHECK-NEXT: ozk.local.set 7
+;; CHECK-NEXT: ozk.local.set 6
+;; CHECK-NEXT: ozk.local.set 5
+;; CHECK-NEXT: ozk.local.set 4
+;; CHECK-NEXT: ozk.local.get 4
+;; CHECK-NEXT: ozk.local.get 6
+;; CHECK-NEXT: ozk.wrapping_add si32
+;; CHECK-NEXT: ozk.local.tee 8
+;; CHECK-NEXT: ozk.local.get 8
+;; CHECK-NEXT: ozk.local.get 4
+;; CHECK-NEXT: ozk.lt_u si32
+;; CHECK-NEXT: ozk.local.get 5
+;; CHECK-NEXT: ozk.wrapping_add si32
+;; CHECK-NEXT: ozk.local.get 7
+;; CHECK-NEXT: ozk.wrapping_add si32
+;; CHECK-NEXT: ozk.return
+;; CHECK: wasm.func @main
+;; CHECK: ozk.constant 0x2: si32
+;; CHECK-NEXT: ozk.constant 0x0: si32
+;; CHECK-NEXT: ozk.constant 0x0: si32
+;; CHECK-NEXT: ozk.constant 0x1: si32
+;; CHECK-NEXT: ozk.call add
+;; CHECK-NEXT: ozk.drop
+;; CHECK-NEXT: ozk.drop
+;; CHECK-NOT: si64
+(module
+    (start $main)
+    (func $add (param i64 i64) (result i64)
+        local.get 0
+        local.get 1
+        i64.add
+        return)
+    (func $main
+        i64.const 2
+        i64.const 0x100000000
+        call $add
+        drop
+        return)
+)
@@ -0,0 +1,35 @@
+;; RUN: ozk-opt %s --pass-pipeline=wasm-lower-to-ozk --verify-each | FileCheck %s
+
+;; CHECK: wasm.func @add
+;; CHECK-NEXT: entry():
+;; CHECK-NEXT: ozk.local.get 0
+;; CHECK-NEXT: ozk.local.get 1
+;; CHECK-NEXT: ozk.wrapping_add si32
+;; CHECK-NEXT: ozk.return
+;; CHECK: wasm.func @main
+;; CHECK-NEXT: entry():
+;; CHECK-NEXT: ozk.block
+;; CHECK: ozk.constant 0x3: si32
+;; CHECK-NEXT: ozk.constant 0x4: si32
+;; CHECK-NEXT: ozk.call add
+;; CHECK-NEXT: ozk.eqz si32
+;; CHECK-NEXT: ozk.br_if 0
+;; CHECK: ozk.return
+;; CHECK-NOT: wasm.block
+(module
+    (start $main)
+    (func $add (param i32 i32) (result i32)
+        get_local 0
+        get_local 1
+        i32.add
+        return)
+    (func $main
+        block
+            i32.const 3
+            i32.const 4
+            call $add
+            i32.eqz
+            br_if 0
+        end
+        return)
+)
@@ -0,0 +1,20 @@
+;; RUN: ozk-opt %s --pass-pipeline=wasm-lower-to-ozk,wasm-track-stack-depth,valida-lower-arith,valida-lower-func,valida-lower-module,valida-track-pc | FileCheck %s
+
+;; CHECK: valida.program {
+;; CHECK: valida.jalsym -28(fp) main -28 0 0
+;; CHECK: valida.func @add pc=4 {
+;; CHECK: valida.func @main pc=9 {
+;; CHECK-NOT: wasm.
+(module
+    (start $main)
+    (func $add (param i32 i32) (result i32)
+        get_local 0
+        get_local 1
+        i32.add
+        return)
+    (func $main
+        i32.const 3
+        i32.const 4
+        call $add
+        return)
+)
@@ -0,0 +1,23 @@
+;; RUN: ozk-opt %s --pass-pipeline=wasm-canonicalize --verify-each | FileCheck %s
+
+;; CHECK: wasm.func @main
+;; CHECK: wasm.local.get 0
+;; CHECK-NEXT: wasm.const 0x2: si32
+;; CHECK-NEXT: wasm.shl
+;; CHECK-NEXT: wasm.local.tee
+;; CHECK-NEXT: wasm.return
+;; CHECK-NOT: wasm.mul
+(module
+    (start $main)
+    (func $main (param i32) (result i32)
+        (local i32)
+        local.get 0
+        i32.const 2
+        i32.const 2
+        i32.mul
+        i32.mul
+        i32.const 0
+        i32.add
+        local.set 1
+        local.get 1)
+)
@@ -0,0 +1,17 @@
+;; RUN: ozk-opt %s --pass-pipeline=dce-unused-functions --verify-each | FileCheck %s
+
+;; CHECK-NOT: wasm.func @unused
+;; CHECK: wasm.func @used
+;; CHECK-NOT: wasm.func @unused
+;; CHECK: wasm.func @main
+(module
+    (start $main)
+    (func $unused
+        call $used
+        return)
+    (func $used
+        return)
+    (func $main
+        call $used
+        return)
+)
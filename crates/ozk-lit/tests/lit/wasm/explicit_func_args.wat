@@ -0,0 +1,23 @@
+;; RUN: ozk-opt %s --pass-pipeline=wasm-explicit-func-args | FileCheck %s
+
+;; CHECK: wasm.func @add
+;; CHECK-NEXT: entry():
+;; CHECK-NEXT: wasm.local.set 0x1: ui32
+;; CHECK-NEXT: wasm.local.set 0x0: ui32
+;; CHECK-NEXT: wasm.local.get 0
+;; CHECK: wasm.func @main
+;; CHECK-NEXT: entry():
+;; CHECK-NEXT: wasm.const 0x3: si32
+(module
+    (start $main)
+    (func $add (param i32 i32) (result i32)
+        get_local 0
+        get_local 1
+        i32.add
+        return)
+    (func $main
+        i32.const 3
+        i32.const 4
+        call $add
+        return)
+)
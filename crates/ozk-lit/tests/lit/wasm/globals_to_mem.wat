@@ -0,0 +1,21 @@
+;; RUN: ozk-opt %s --pass-pipeline=wasm-globals-to-mem --verify-each | FileCheck %s
+
+;; CHECK: wasm.func @main() -> () {
+;; CHECK-NOT: wasm.global.set
+;; CHECK: wasm.const 0x9: si32
+;; CHECK-NEXT: wasm.const
+;; CHECK-NEXT: ozk.swap 1
+;; CHECK-NEXT: wasm.store I32
+;; CHECK-NEXT: wasm.const
+;; CHECK-NEXT: wasm.load I32
+;; CHECK-NEXT: wasm.return
+;; CHECK-NOT: wasm.global.get
+(module
+    (global $MyGlobalVal (mut i32) i32.const 42)
+    (start $main)
+    (func $main
+        i32.const 9
+        global.set $MyGlobalVal
+        global.get $MyGlobalVal
+        return)
+)
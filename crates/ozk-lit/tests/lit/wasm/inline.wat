@@ -0,0 +1,20 @@
+;; RUN: ozk-opt %s --pass-pipeline=wasm-inline --verify-each | FileCheck %s
+
+;; CHECK: wasm.func @main
+;; CHECK-NOT: wasm.call
+;; CHECK: wasm.add
+;; CHECK: wasm.return
+(module
+    (start $main)
+    (func $add (param i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        i32.add
+        return)
+    (func $main
+        i32.const 1
+        i32.const 2
+        call $add
+        drop
+        return)
+)
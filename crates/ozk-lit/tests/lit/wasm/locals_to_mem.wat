@@ -0,0 +1,22 @@
+;; RUN: ozk-opt %s --pass-pipeline=wasm-explicit-func-args,wasm-locals-to-mem,wasm-globals-to-mem --verify-each | FileCheck %s
+
+;; CHECK: wasm.func @inc
+;; CHECK-NOT: wasm.local
+;; CHECK: ozk.swap 1
+;; CHECK-NEXT: wasm.store I32
+;; CHECK: wasm.load I32
+;; CHECK: wasm.func @main
+;; CHECK-NOT: wasm.local
+;; CHECK-NOT: wasm.global
+(module
+    (start $main)
+    (func $inc (param i32) (result i32)
+        local.get 0
+        i32.const 1
+        i32.add)
+    (func $main
+        (local i32)
+        i32.const 41
+        call $inc
+        local.set 0)
+)
@@ -0,0 +1,53 @@
+;; RUN: ozk-opt %s --pass-pipeline=wasm-explicit-func-args,wasm-spill-stack --verify-each | FileCheck %s
+
+;; CHECK: wasm.func @sum
+;; CHECK-COUNT-16: wasm.const
+;; CHECK-NEXT: ozk.movup 15
+;; CHECK-NEXT: wasm.local.set 0x0: ui32
+;; CHECK-NEXT: wasm.const 0x11: si32
+;; CHECK-COUNT-15: wasm.add
+;; CHECK-NEXT: wasm.local.get 0
+;; CHECK-NEXT: ozk.movdn 1
+;; CHECK-NEXT: wasm.add
+(module
+    (start $main)
+    (func $sum (result i32)
+        i32.const 1
+        i32.const 2
+        i32.const 3
+        i32.const 4
+        i32.const 5
+        i32.const 6
+        i32.const 7
+        i32.const 8
+        i32.const 9
+        i32.const 10
+        i32.const 11
+        i32.const 12
+        i32.const 13
+        i32.const 14
+        i32.const 15
+        i32.const 16
+        i32.const 17
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        i32.add
+        return)
+    (func $main
+        call $sum
+        drop
+        return)
+)
@@ -0,0 +1,17 @@
+use ozk_frontend_wasm::WasmError;
+use ozk_ir_transform::pass_registry::PassRegistryError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum OptError {
+    #[error("Invalid pass pipeline: {0}")]
+    PassPipeline(#[from] PassRegistryError),
+    #[error("Unsupported input: expected a Wasm module (text or binary), the dialect IR cannot be parsed (no IR parser in pliron)")]
+    UnsupportedInput,
+    #[error("Failed to parse Wasm: {0}")]
+    Wat(#[from] wat::Error),
+    #[error("Wasm frontend error: {0:?}")]
+    Frontend(#[from] WasmError),
+    #[error("{0:?}")]
+    Pass(anyhow::Error),
+}
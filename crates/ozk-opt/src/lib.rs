@@ -0,0 +1,105 @@
+//! `ozk-opt` runs a pipeline of registered IR passes on a module and prints the result.
+//!
+//! The input is a Wasm module (text or binary) which is parsed into the wasm dialect.
+//!
+//! Loading the dialect IR from a file is not supported: the pinned pliron version has no IR
+//! parser (the ops are only printed), so the IR printed by `ozk-opt` cannot be read back and
+//! is rejected. To inspect the input of a pass, run `ozk-opt` on the Wasm module with the
+//! passes before it instead.
+//! Passes are looked up by name in the [`ozk_ir_transform::pass_registry`].
+
+// Coding conventions
+// #![deny(unsafe_code)]
+#![deny(non_upper_case_globals)]
+#![deny(non_camel_case_types)]
+#![deny(non_snake_case)]
+#![deny(unused_mut)]
+// #![deny(dead_code)]
+#![deny(unused_imports)]
+// #![deny(missing_docs)]
+// Clippy exclusions
+#![deny(rustdoc::broken_intra_doc_links)]
+#![deny(clippy::wildcard_enum_match_arm)]
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+// #![deny(clippy::todo)]
+#![deny(clippy::unimplemented)]
+#![deny(clippy::panic)]
+
+mod error;
+
+pub use crate::error::*;
+
+use ozk_frontend_wasm::WasmFrontendConfig;
+use ozk_ir_transform::pass_manager::PassManager;
+use ozk_ir_transform::pass_registry::parse_pass_pipeline;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::with_context::AttachContext;
+
+/// Parse the module `source` (Wasm text or binary), run the passes from the comma-separated
+/// `pass_pipeline` on it and return the printed IR.
+/// If `verify_each` is true, the IR is verified after every pass.
+pub fn run_opt(source: &[u8], pass_pipeline: &str, verify_each: bool) -> Result<String, OptError> {
+    if !is_wasm_input(source) {
+        return Err(OptError::UnsupportedInput);
+    }
+    let mut pass_manager = PassManager::new();
+    pass_manager.set_verify_after_each_pass(verify_each);
+    for pass in parse_pass_pipeline(pass_pipeline)? {
+        pass_manager.add_pass(pass);
+    }
+    let wasm = wat::parse_bytes(source)?;
+    let mut ctx = Context::default();
+    let frontend_config = WasmFrontendConfig::default();
+    frontend_config.register(&mut ctx);
+    ozk_wasm_dialect::register(&mut ctx);
+    ozk_ozk_dialect::register(&mut ctx);
+    ozk_miden_dialect::register(&mut ctx);
+    ozk_valida_dialect::register(&mut ctx);
+    ozk_ssa_dialect::register(&mut ctx);
+    let wasm_module_op = ozk_frontend_wasm::parse_module(&mut ctx, &wasm, &frontend_config)?;
+    // passes might replace the module op, so keep it inside a wrapper module
+    let wrapper_module = builtin::ops::ModuleOp::new(&mut ctx, "wrapper");
+    wasm_module_op
+        .get_operation()
+        .insert_at_back(wrapper_module.get_body(&ctx, 0), &mut ctx);
+    pass_manager
+        .run(&mut ctx, wrapper_module.get_operation())
+        .map_err(OptError::Pass)?;
+    let printed = wrapped_ops(&ctx, wrapper_module)
+        .into_iter()
+        .map(|op| op.with_ctx(&ctx).to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    Ok(printed)
+}
+
+/// True if `source` is a Wasm binary or looks like a Wasm text module (starts with `(`
+/// after the whitespace and the `;;` comments).
+fn is_wasm_input(source: &[u8]) -> bool {
+    const WASM_MAGIC: &[u8] = b"\0asm";
+    if source.starts_with(WASM_MAGIC) {
+        return true;
+    }
+    let Ok(text) = std::str::from_utf8(source) else {
+        return false;
+    };
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with(";;"))
+        .map_or(false, |line| line.starts_with('('))
+}
+
+fn wrapped_ops(ctx: &Context, wrapper_module: builtin::ops::ModuleOp) -> Vec<Ptr<Operation>> {
+    wrapper_module
+        .get_body(ctx, 0)
+        .deref(ctx)
+        .iter(ctx)
+        .collect()
+}
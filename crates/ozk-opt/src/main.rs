@@ -0,0 +1,62 @@
+//! `ozk-opt` command line driver.
+//!
+//! Usage: `ozk-opt [--pass-pipeline=<pass>[,<pass>...]] [--verify-each] [--list-passes] <input.wat|input.wasm>`
+//!
+//! Only Wasm modules (text or binary) are accepted as input, the dialect IR cannot be loaded
+//! (see the [ozk_opt] crate docs).
+
+use std::process::ExitCode;
+
+use ozk_ir_transform::pass_registry::registered_passes;
+use ozk_opt::run_opt;
+
+const USAGE: &str = "usage: ozk-opt [--pass-pipeline=<pass>[,<pass>...]] [--verify-each] [--list-passes] <input.wat|input.wasm>
+the input must be a Wasm module (text or binary); loading the dialect IR (e.g. the output
+of ozk-opt) is not supported since the pliron version in use has no IR parser";
+
+fn main() -> ExitCode {
+    let mut pass_pipeline = String::new();
+    let mut verify_each = false;
+    let mut input_path = None;
+    for arg in std::env::args().skip(1) {
+        if let Some(pipeline) = arg.strip_prefix("--pass-pipeline=") {
+            pass_pipeline = pipeline.to_string();
+        } else if arg == "--verify-each" {
+            verify_each = true;
+        } else if arg == "--list-passes" {
+            for reg in registered_passes() {
+                println!("{:<32}{}", reg.name, reg.description);
+            }
+            return ExitCode::SUCCESS;
+        } else if arg == "--help" || arg == "-h" {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        } else if arg.starts_with("--") || input_path.is_some() {
+            eprintln!("unexpected argument: {arg}\n{USAGE}");
+            return ExitCode::FAILURE;
+        } else {
+            input_path = Some(arg);
+        }
+    }
+    let Some(input_path) = input_path else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let source = match std::fs::read(&input_path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("failed to read {input_path}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match run_opt(&source, &pass_pipeline, verify_each) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
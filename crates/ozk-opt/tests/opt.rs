@@ -0,0 +1,72 @@
+#![allow(clippy::unwrap_used)]
+
+use expect_test::expect;
+use ozk_opt::run_opt;
+use ozk_opt::OptError;
+
+const ADD_WAT: &str = r#"
+(module
+    (start $main)
+    (func $add (param i32 i32) (result i32)
+        get_local 0
+        get_local 1
+        i32.add
+        return)
+    (func $main
+        i32.const 3
+        i32.const 4
+        call $add
+        return)
+)
+"#;
+
+#[test]
+fn valida_pipeline_by_pass_names() {
+    let output = run_opt(
+        ADD_WAT.as_bytes(),
+        "wasm-lower-to-ozk,wasm-track-stack-depth,valida-lower-arith,valida-lower-func,valida-lower-module,valida-track-pc",
+        true,
+    )
+    .unwrap();
+    expect![[r#"
+        valida.program {
+          entry():
+            valida.imm32 -24(fp) 0 0 0 28
+            valida.jalsym -28(fp) main -28 0 0
+            valida.sw 0 4(fp) -20(fp) 0 0
+            valida.exit
+          block_5_2():
+            valida.func @add pc=4 {
+              entry():
+                valida.sw 0 -4(fp) 12(fp) 0 0
+                valida.sw 0 -8(fp) 16(fp) 0 0
+                valida.add -4(fp) -8(fp) -4(fp) 0 0
+                valida.sw 0 16(fp) -4(fp) 0 0
+                valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+            }
+            valida.func @main pc=9 {
+              entry():
+                valida.imm32 -4(fp) 0 0 0 3
+                valida.imm32 -8(fp) 0 0 0 4
+                valida.imm32 -16(fp) 0 0 0 20
+                valida.jalsym -20(fp) add -20 0 0
+                valida.sw 0 8(fp) -4(fp) 0 0
+                valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+            }
+        }"#]]
+    .assert_eq(&output);
+}
+
+#[test]
+fn unknown_pass() {
+    let err = run_opt(ADD_WAT.as_bytes(), "wasm-track-stack-depth,no-such-pass", false).unwrap_err();
+    assert!(matches!(err, OptError::PassPipeline(_)));
+    expect!["Invalid pass pipeline: unknown pass: no-such-pass"].assert_eq(&err.to_string());
+}
+
+#[test]
+fn non_wasm_input() {
+    let ir = run_opt(ADD_WAT.as_bytes(), "wasm-track-stack-depth", false).unwrap();
+    let err = run_opt(ir.as_bytes(), "wasm-track-stack-depth", false).unwrap_err();
+    assert!(matches!(err, OptError::UnsupportedInput));
+}
@@ -41,3 +41,9 @@ pub(crate) fn secret_input() -> u64 {
     #[allow(clippy::unwrap_used)]
     SECRET_INPUT.with(|v| v.borrow_mut().pop().unwrap())
 }
+
+pub(crate) fn secret_input_slice(buf: &mut [u64]) {
+    for value in buf.iter_mut() {
+        *value = secret_input();
+    }
+}
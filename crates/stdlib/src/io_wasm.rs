@@ -2,6 +2,7 @@ extern "C" {
     fn ozk_stdlib_pub_input() -> u64;
     fn ozk_stdlib_pub_output(x: u64);
     fn ozk_stdlib_secret_input() -> u64;
+    fn ozk_stdlib_secret_input_slice(ptr: *mut u64, len: usize);
 }
 
 pub fn pub_input() -> u64 {
@@ -15,3 +16,7 @@ pub fn pub_output(x: u64) {
 pub fn secret_input() -> u64 {
     unsafe { ozk_stdlib_secret_input() }
 }
+
+pub fn secret_input_slice(buf: &mut [u64]) {
+    unsafe { ozk_stdlib_secret_input_slice(buf.as_mut_ptr(), buf.len()) }
+}
@@ -82,3 +82,15 @@ pub fn secret_input() -> u64 {
     #[cfg(target_arch = "wasm32")]
     return io_wasm::secret_input();
 }
+
+/// Fill the slice with the next secret inputs (the same as calling [secret_input] for
+/// each element, but read in bulk).
+#[no_mangle]
+pub fn secret_input_slice(buf: &mut [u64]) {
+    #[cfg(feature = "std")]
+    #[cfg(not(target_arch = "wasm32"))]
+    return io_native::secret_input_slice(buf);
+
+    #[cfg(target_arch = "wasm32")]
+    return io_wasm::secret_input_slice(buf);
+}